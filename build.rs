@@ -0,0 +1,8 @@
+// Passes the target triple on to `TargetInfo::current`, since it's only
+// available to build scripts.
+fn main() {
+    if let Ok(target) = std::env::var("TARGET") {
+        println!("cargo:rustc-env=TYPE_LAYOUT_TARGET={}", target);
+    }
+    println!("cargo:rerun-if-changed=build.rs");
+}
@@ -0,0 +1,133 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::metadata::{cargo, toml_string, Metadata};
+use crate::Options;
+
+/// The program that prints the layouts. `{lib_name}` is replaced with the
+/// name of the package's library.
+///
+/// Linking the library is enough to add its types to the registry, but it
+/// has to be used for Rust to link it at all.
+const MAIN: &str = r#"use type_layout::LayoutReport;
+
+use {lib_name} as _;
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let format = args.next().unwrap_or_default();
+    let filters: Vec<String> = args.collect();
+
+    let mut registrations: Vec<_> = type_layout::registry::iter()
+        .filter(|registration| {
+            filters.is_empty()
+                || filters
+                    .iter()
+                    .any(|filter| registration.name.contains(filter.as_str()))
+        })
+        .collect();
+    registrations.sort_by_key(|registration| registration.name);
+
+    let mut report = LayoutReport::new();
+    for registration in registrations {
+        report.push((registration.type_layout)());
+    }
+
+    match format.as_str() {
+        "json" => print!("{}", report.to_json()),
+        "csv" => print!("{}", report.to_csv()),
+        "html" => print!("{}", report.to_html()),
+        "dot" => print!("{}", report.to_dot()),
+        _ => print!("{}", report),
+    }
+}
+"#;
+
+/// A package in the target directory that depends on the package whose types
+/// are printed.
+pub struct Harness {
+    dir: PathBuf,
+}
+
+impl Harness {
+    /// Writes the harness's manifest and source, leaving anything that's
+    /// already there alone when it hasn't changed, so that Cargo doesn't
+    /// rebuild it.
+    pub fn write(metadata: &Metadata, options: &Options) -> Result<Harness, String> {
+        let dir = metadata
+            .target_directory
+            .join("type-layout")
+            .join("harness");
+        fs::create_dir_all(dir.join("src"))
+            .map_err(|err| format!("could not create {}: {}", dir.display(), err))?;
+
+        let features = if options.all_features {
+            &metadata.features
+        } else {
+            &options.features
+        };
+        let features: Vec<String> = features.iter().map(|name| toml_string(name)).collect();
+
+        let manifest = format!(
+            "[package]\n\
+             name = \"type-layout-harness\"\n\
+             version = \"0.0.0\"\n\
+             edition = \"2018\"\n\
+             publish = false\n\
+             \n\
+             [workspace]\n\
+             \n\
+             [dependencies]\n\
+             {} = {{ path = {}, default-features = {}, features = [{}] }}\n\
+             type-layout = {{ {}, features = [\"registry\", \"serde1\"] }}\n",
+            metadata.package,
+            toml_string(&metadata.package_dir.to_string_lossy()),
+            !options.no_default_features,
+            features.join(", "),
+            metadata.type_layout_dependency,
+        );
+
+        write_if_changed(&dir.join("Cargo.toml"), &manifest)?;
+        write_if_changed(
+            &dir.join("src").join("main.rs"),
+            &MAIN.replace("{lib_name}", &metadata.lib_name),
+        )?;
+
+        // Starting from the workspace's lock file keeps the versions of the
+        // package's dependencies the same as in its own builds.
+        let lock_file = metadata.workspace_root.join("Cargo.lock");
+        if lock_file.exists() && !dir.join("Cargo.lock").exists() {
+            fs::copy(&lock_file, dir.join("Cargo.lock"))
+                .map_err(|err| format!("could not copy {}: {}", lock_file.display(), err))?;
+        }
+
+        Ok(Harness { dir })
+    }
+
+    /// Builds and runs the harness, returning its exit code.
+    pub fn run(&self, metadata: &Metadata, options: &Options) -> Result<i32, String> {
+        let status = Command::new(cargo())
+            .arg("run")
+            .arg("--quiet")
+            .arg("--manifest-path")
+            .arg(self.dir.join("Cargo.toml"))
+            .arg("--target-dir")
+            .arg(&metadata.target_directory)
+            .arg("--")
+            .arg(options.format.as_deref().unwrap_or("table"))
+            .args(&options.filters)
+            .status()
+            .map_err(|err| format!("could not run cargo: {}", err))?;
+
+        Ok(status.code().unwrap_or(1))
+    }
+}
+
+fn write_if_changed(path: &Path, contents: &str) -> Result<(), String> {
+    if fs::read_to_string(path).ok().as_deref() == Some(contents) {
+        return Ok(());
+    }
+
+    fs::write(path, contents).map_err(|err| format!("could not write {}: {}", path.display(), err))
+}
@@ -0,0 +1,226 @@
+//! `cargo type-layout` prints the layout of every type in a crate that
+//! derives `TypeLayout`.
+//!
+//! It builds a small program next to the crate's build artifacts that links
+//! the crate, reads the layouts from type-layout's registry, and prints them,
+//! so the crate doesn't need any code of its own to support it. Generic types
+//! aren't in the registry, so they aren't printed.
+//!
+//! ```text
+//! cargo type-layout                      # every type, as tables
+//! cargo type-layout Header Packet        # types whose names contain either
+//! cargo type-layout --format json > layouts.json
+//! ```
+
+mod harness;
+mod metadata;
+
+use std::env;
+use std::ffi::OsString;
+use std::process;
+
+use crate::harness::Harness;
+use crate::metadata::Metadata;
+
+const USAGE: &str = "\
+Prints the layout of every type in a crate that derives TypeLayout.
+
+Usage: cargo type-layout [OPTIONS] [FILTER]...
+
+Arguments:
+  [FILTER]...  Only print types whose names contain one of these
+
+Options:
+      --format <FORMAT>       table, json, csv, html, or dot [default: table]
+  -p, --package <NAME>        Package to print the types of
+      --manifest-path <PATH>  Path to the package's Cargo.toml
+  -F, --features <FEATURES>   Features of the package to enable
+      --all-features          Enable all features of the package
+      --no-default-features   Don't enable the package's default features
+  -h, --help                  Print this message
+";
+
+/// The formats the harness can print, which are named after the
+/// `LayoutReport` methods that render them.
+const FORMATS: &[&str] = &["table", "json", "csv", "html", "dot"];
+
+/// What to print, from the command line.
+#[derive(Debug, Default)]
+pub struct Options {
+    pub format: Option<String>,
+    pub package: Option<String>,
+    pub manifest_path: Option<OsString>,
+    pub features: Vec<String>,
+    pub all_features: bool,
+    pub no_default_features: bool,
+    pub filters: Vec<String>,
+}
+
+fn main() {
+    let mut args: Vec<String> = env::args().skip(1).collect();
+
+    // Cargo passes the name of the subcommand as the first argument.
+    if args.first().map(String::as_str) == Some("type-layout") {
+        args.remove(0);
+    }
+
+    let code = match parse_args(args).and_then(run) {
+        Ok(code) => code,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            1
+        }
+    };
+
+    process::exit(code);
+}
+
+fn run(options: Options) -> Result<i32, String> {
+    let metadata = Metadata::load(&options)?;
+    let harness = Harness::write(&metadata, &options)?;
+    harness.run(&metadata, &options)
+}
+
+fn parse_args(args: Vec<String>) -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value)),
+            _ => (arg.clone(), None),
+        };
+        let inline_value = inline_value.map(str::to_string);
+
+        let mut value = || {
+            inline_value
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("{} needs a value", flag))
+        };
+
+        match flag.as_str() {
+            "-h" | "--help" => {
+                print!("{}", USAGE);
+                process::exit(0);
+            }
+            "--format" => {
+                let format = value()?;
+                if !FORMATS.contains(&format.as_str()) {
+                    return Err(format!(
+                        "unknown format `{}`, expected one of {}",
+                        format,
+                        FORMATS.join(", ")
+                    ));
+                }
+                options.format = Some(format);
+            }
+            "-p" | "--package" => options.package = Some(value()?),
+            "--manifest-path" => options.manifest_path = Some(value()?.into()),
+            "-F" | "--features" => {
+                let features = value()?;
+                options.features.extend(
+                    features
+                        .split(|ch: char| ch == ',' || ch.is_whitespace())
+                        .filter(|feature| !feature.is_empty())
+                        .map(str::to_string),
+                );
+            }
+            "--all-features" => options.all_features = true,
+            "--no-default-features" => options.no_default_features = true,
+            _ if arg.starts_with('-') => {
+                return Err(format!("unknown option `{}`\n\n{}", arg, USAGE));
+            }
+            _ => options.filters.push(arg),
+        }
+    }
+
+    Ok(options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Options, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()).collect())
+    }
+
+    #[test]
+    fn defaults() {
+        let options = parse(&[]).unwrap();
+
+        assert_eq!(options.format, None);
+        assert_eq!(options.package, None);
+        assert!(options.features.is_empty());
+        assert!(!options.all_features && !options.no_default_features);
+        assert!(options.filters.is_empty());
+    }
+
+    #[test]
+    fn values_inline_and_separate() {
+        let options = parse(&[
+            "--format=json",
+            "-p",
+            "app",
+            "--manifest-path",
+            "app/Cargo.toml",
+            "Header",
+            "Packet",
+        ])
+        .unwrap();
+
+        assert_eq!(options.format.as_deref(), Some("json"));
+        assert_eq!(options.package.as_deref(), Some("app"));
+        assert_eq!(
+            options.manifest_path,
+            Some(OsString::from("app/Cargo.toml"))
+        );
+        assert_eq!(options.filters, ["Header", "Packet"]);
+
+        let options = parse(&["--package=app", "--format", "csv"]).unwrap();
+        assert_eq!(options.package.as_deref(), Some("app"));
+        assert_eq!(options.format.as_deref(), Some("csv"));
+    }
+
+    #[test]
+    fn features() {
+        let options = parse(&[
+            "-F",
+            "a,b",
+            "--features=c d",
+            "--features",
+            " e, ",
+            "--all-features",
+            "--no-default-features",
+        ])
+        .unwrap();
+
+        assert_eq!(options.features, ["a", "b", "c", "d", "e"]);
+        assert!(options.all_features);
+        assert!(options.no_default_features);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            parse(&["--format", "yaml"]).unwrap_err(),
+            "unknown format `yaml`, expected one of table, json, csv, html, dot"
+        );
+        assert_eq!(
+            parse(&["--package"]).unwrap_err(),
+            "--package needs a value"
+        );
+        assert_eq!(parse(&["-p"]).unwrap_err(), "-p needs a value");
+        assert_eq!(
+            parse(&["--verbose"]).unwrap_err(),
+            format!("unknown option `--verbose`\n\n{}", USAGE)
+        );
+
+        // Only long options take their value after `=`.
+        assert_eq!(
+            parse(&["-p=app"]).unwrap_err(),
+            format!("unknown option `-p=app`\n\n{}", USAGE)
+        );
+    }
+}
@@ -0,0 +1,384 @@
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde_json::Value;
+
+use crate::Options;
+
+/// What the harness needs to know about the package and its workspace, from
+/// `cargo metadata`.
+pub struct Metadata {
+    /// The name of the package to print the types of.
+    pub package: String,
+
+    /// The name of the package's library, as it's written in Rust code.
+    pub lib_name: String,
+
+    /// The directory holding the package's `Cargo.toml`.
+    pub package_dir: PathBuf,
+
+    /// Every feature the package has, for `--all-features`.
+    pub features: Vec<String>,
+
+    /// How the package depends on type-layout, as the inside of a TOML
+    /// inline table.
+    pub type_layout_dependency: String,
+
+    pub workspace_root: PathBuf,
+    pub target_directory: PathBuf,
+}
+
+impl Metadata {
+    pub fn load(options: &Options) -> Result<Metadata, String> {
+        let mut command = Command::new(cargo());
+        command.args(["metadata", "--format-version", "1"]);
+        if let Some(manifest_path) = &options.manifest_path {
+            command.arg("--manifest-path").arg(manifest_path);
+        }
+
+        let output = command
+            .output()
+            .map_err(|err| format!("could not run cargo metadata: {}", err))?;
+        if !output.status.success() {
+            return Err(format!(
+                "cargo metadata failed:\n{}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+
+        let metadata: Value = serde_json::from_slice(&output.stdout)
+            .map_err(|err| format!("could not read cargo metadata: {}", err))?;
+
+        Metadata::from_json(&metadata, options)
+    }
+
+    /// Reads the output of `cargo metadata --format-version 1`.
+    fn from_json(metadata: &Value, options: &Options) -> Result<Metadata, String> {
+        let package = find_package(metadata, options)?;
+        let package_name = string(package, "name")?;
+        let package_id = string(package, "id")?;
+
+        let lib_name = package["targets"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|target| {
+                target["kind"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .any(|kind| kind == "lib" || kind == "rlib")
+            })
+            .and_then(|target| target["name"].as_str())
+            .ok_or_else(|| format!("package `{}` has no library", package_name))?
+            .replace('-', "_");
+
+        let manifest_path = PathBuf::from(string(package, "manifest_path")?);
+        let package_dir = manifest_path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+
+        let type_layout = find_type_layout(metadata, package_id).ok_or_else(|| {
+            format!(
+                "package `{}` doesn't depend on type-layout, so it has no layouts to print",
+                package_name
+            )
+        })?;
+
+        Ok(Metadata {
+            package: package_name.to_string(),
+            lib_name,
+            package_dir,
+            features: package["features"]
+                .as_object()
+                .map(|features| features.keys().cloned().collect())
+                .unwrap_or_default(),
+            type_layout_dependency: dependency_table(type_layout)?,
+            workspace_root: PathBuf::from(string(metadata, "workspace_root")?),
+            target_directory: PathBuf::from(string(metadata, "target_directory")?),
+        })
+    }
+}
+
+/// The path to Cargo, which Cargo sets when it runs a subcommand.
+pub fn cargo() -> PathBuf {
+    env::var_os("CARGO")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("cargo"))
+}
+
+/// Finds the package named with `--package`, or else the package whose
+/// manifest was used, which is missing for virtual workspaces.
+fn find_package<'a>(metadata: &'a Value, options: &Options) -> Result<&'a Value, String> {
+    let members: Vec<&Value> = metadata["workspace_members"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|id| {
+            metadata["packages"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .find(|package| package["id"] == *id)
+        })
+        .collect();
+
+    if let Some(name) = &options.package {
+        return members
+            .into_iter()
+            .find(|package| package["name"] == name.as_str())
+            .ok_or_else(|| format!("package `{}` isn't in the workspace", name));
+    }
+
+    let root = &metadata["resolve"]["root"];
+    if let Some(package) = members.iter().find(|package| package["id"] == *root) {
+        return Ok(package);
+    }
+
+    match members.as_slice() {
+        [package] => Ok(package),
+        _ => Err("the workspace has several packages, choose one with --package".to_string()),
+    }
+}
+
+/// Finds the type-layout package that `package_id` depends on directly.
+fn find_type_layout<'a>(metadata: &'a Value, package_id: &str) -> Option<&'a Value> {
+    let node = metadata["resolve"]["nodes"]
+        .as_array()?
+        .iter()
+        .find(|node| node["id"] == package_id)?;
+
+    node["dependencies"]
+        .as_array()?
+        .iter()
+        .filter_map(|id| {
+            metadata["packages"]
+                .as_array()?
+                .iter()
+                .find(|package| package["id"] == *id)
+        })
+        .find(|package| package["name"] == "type-layout")
+}
+
+/// Writes a dependency on the same type-layout that the package uses, so
+/// that the harness and the package share one registry.
+fn dependency_table(type_layout: &Value) -> Result<String, String> {
+    let version = string(type_layout, "version")?;
+
+    let source = match type_layout["source"].as_str() {
+        Some(source) => source,
+        None => {
+            let manifest_path = Path::new(string(type_layout, "manifest_path")?);
+            let dir = manifest_path.parent().unwrap_or(manifest_path);
+            return Ok(format!("path = {}", toml_string(&dir.to_string_lossy())));
+        }
+    };
+
+    if let Some(git) = source.strip_prefix("git+") {
+        // Git sources look like `git+https://host/repo?branch=main#<commit>`.
+        let (url, commit) = git.split_once('#').unwrap_or((git, ""));
+        let url = url.split('?').next().unwrap_or(url);
+        return Ok(format!(
+            "git = {}, rev = {}",
+            toml_string(url),
+            toml_string(commit)
+        ));
+    }
+
+    Ok(format!(
+        "version = {}",
+        toml_string(&format!("={}", version))
+    ))
+}
+
+fn string<'a>(value: &'a Value, key: &str) -> Result<&'a str, String> {
+    value[key]
+        .as_str()
+        .ok_or_else(|| format!("cargo metadata has no `{}`", key))
+}
+
+/// Quotes a string for a TOML file.
+pub fn toml_string(text: &str) -> String {
+    let mut quoted = String::from("\"");
+
+    for ch in text.chars() {
+        match ch {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            ch => quoted.push(ch),
+        }
+    }
+
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    const APP: &str = "app 0.1.0 (path+file:///work/app)";
+    const TOOLS: &str = "tools 0.1.0 (path+file:///work/tools)";
+
+    fn package(id: &str, name: &str, dir: &str) -> Value {
+        json!({
+            "id": id,
+            "name": name,
+            "version": "0.1.0",
+            "source": null,
+            "manifest_path": format!("{}/Cargo.toml", dir),
+            "targets": [
+                { "kind": ["bin"], "name": name },
+                { "kind": ["lib"], "name": format!("{}-lib", name) },
+            ],
+            "features": { "default": ["extra"], "extra": [] },
+        })
+    }
+
+    /// The metadata of a workspace whose members all depend on a
+    /// type-layout from `source`.
+    fn metadata(members: &[&str], root: Option<&str>, source: Value) -> Value {
+        let type_layout_id = "type-layout 0.2.0";
+        let mut packages = vec![json!({
+            "id": type_layout_id,
+            "name": "type-layout",
+            "version": "0.2.0",
+            "source": source,
+            "manifest_path": "/work/type-layout/Cargo.toml",
+            "targets": [{ "kind": ["lib"], "name": "type-layout" }],
+            "features": {},
+        })];
+        let mut nodes = vec![json!({ "id": type_layout_id, "dependencies": [] })];
+
+        for &id in members {
+            let name = id.split(' ').next().unwrap();
+            packages.push(package(id, name, &format!("/work/{}", name)));
+            nodes.push(json!({ "id": id, "dependencies": [type_layout_id] }));
+        }
+
+        json!({
+            "packages": packages,
+            "workspace_members": members,
+            "resolve": { "root": root, "nodes": nodes },
+            "workspace_root": "/work",
+            "target_directory": "/work/target",
+        })
+    }
+
+    fn options(package: Option<&str>) -> Options {
+        Options {
+            package: package.map(str::to_string),
+            ..Options::default()
+        }
+    }
+
+    fn load(metadata: &Value, package: Option<&str>) -> Result<Metadata, String> {
+        Metadata::from_json(metadata, &options(package))
+    }
+
+    #[test]
+    fn path_source() {
+        let metadata = load(&metadata(&[APP], Some(APP), Value::Null), None).unwrap();
+
+        assert_eq!(metadata.package, "app");
+        assert_eq!(metadata.lib_name, "app_lib");
+        assert_eq!(metadata.package_dir, Path::new("/work/app"));
+        assert_eq!(metadata.features, ["default", "extra"]);
+        assert_eq!(
+            metadata.type_layout_dependency,
+            "path = \"/work/type-layout\""
+        );
+        assert_eq!(metadata.workspace_root, Path::new("/work"));
+        assert_eq!(metadata.target_directory, Path::new("/work/target"));
+    }
+
+    #[test]
+    fn registry_source() {
+        let source = json!("registry+https://github.com/rust-lang/crates.io-index");
+        let metadata = load(&metadata(&[APP], Some(APP), source), None).unwrap();
+
+        assert_eq!(metadata.type_layout_dependency, "version = \"=0.2.0\"");
+    }
+
+    #[test]
+    fn git_source() {
+        let source = json!("git+https://github.com/LPGhatguy/type-layout?branch=main#0123abcd");
+        let metadata = load(&metadata(&[APP], Some(APP), source), None).unwrap();
+
+        assert_eq!(
+            metadata.type_layout_dependency,
+            "git = \"https://github.com/LPGhatguy/type-layout\", rev = \"0123abcd\""
+        );
+    }
+
+    #[test]
+    fn virtual_workspace() {
+        let workspace = metadata(&[APP, TOOLS], None, Value::Null);
+
+        assert_eq!(
+            load(&workspace, None).err().unwrap(),
+            "the workspace has several packages, choose one with --package"
+        );
+        assert_eq!(load(&workspace, Some("tools")).unwrap().package, "tools");
+
+        // With one member, there's nothing to choose between.
+        let single = metadata(&[TOOLS], None, Value::Null);
+        assert_eq!(load(&single, None).unwrap().package, "tools");
+    }
+
+    #[test]
+    fn root_package_in_workspace() {
+        let workspace = metadata(&[APP, TOOLS], Some(TOOLS), Value::Null);
+
+        assert_eq!(load(&workspace, None).unwrap().package, "tools");
+        assert_eq!(load(&workspace, Some("app")).unwrap().package, "app");
+    }
+
+    #[test]
+    fn package_not_in_workspace() {
+        let workspace = metadata(&[APP, TOOLS], Some(APP), Value::Null);
+
+        // type-layout is in the metadata, but it isn't a member.
+        for name in ["missing", "type-layout"] {
+            assert_eq!(
+                load(&workspace, Some(name)).err().unwrap(),
+                format!("package `{}` isn't in the workspace", name)
+            );
+        }
+    }
+
+    #[test]
+    fn no_type_layout_dependency() {
+        let mut workspace = metadata(&[APP], Some(APP), Value::Null);
+        workspace["resolve"]["nodes"][1]["dependencies"] = json!([]);
+
+        assert_eq!(
+            load(&workspace, None).err().unwrap(),
+            "package `app` doesn't depend on type-layout, so it has no layouts to print"
+        );
+    }
+
+    #[test]
+    fn no_library() {
+        let mut workspace = metadata(&[APP], Some(APP), Value::Null);
+        workspace["packages"][1]["targets"] = json!([{ "kind": ["bin"], "name": "app" }]);
+
+        assert_eq!(
+            load(&workspace, None).err().unwrap(),
+            "package `app` has no library"
+        );
+    }
+
+    #[test]
+    fn quoting() {
+        assert_eq!(toml_string("plain"), "\"plain\"");
+        assert_eq!(
+            toml_string(r#"C:\dir\"quoted""#),
+            r#""C:\\dir\\\"quoted\"""#
+        );
+    }
+}
@@ -0,0 +1,134 @@
+//! Writes layouts as JSON files, for external tools and CI jobs that compare
+//! layouts between builds without running the program.
+//!
+//! This module is only available with the `serde1` feature. Each layout is
+//! written with [`TypeLayoutInfo::to_json`] to a file named after the type,
+//! like `Header.json`, so that a changed layout shows up as a changed file.
+//!
+//! Layouts are usually written from a build script. A build script can't see
+//! the crate it builds, so the types go in a crate of their own, which the
+//! package with the build script lists under `[build-dependencies]`:
+//!
+//! ```rust,ignore
+//! // build.rs
+//! fn main() {
+//!     type_layout::artifacts::write(&[
+//!         protocol::Header::type_layout(),
+//!         protocol::Packet::type_layout(),
+//!     ])
+//!     .unwrap();
+//! }
+//! ```
+//!
+//! The files are written to the directory in the `TYPE_LAYOUT_ARTIFACT_DIR`
+//! environment variable, or else to `type-layout` inside Cargo's `OUT_DIR`.
+//!
+//! Build scripts run on the machine doing the build, so when cross-compiling,
+//! these are the layouts for that machine rather than for the target. Types
+//! with `#[layout(embed)]` carry their layout for the target in the compiled
+//! binary instead; see the [`embedded`](crate::embedded) module.
+
+use alloc::string::String;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::TypeLayoutInfo;
+
+/// The environment variable naming the directory that [`write`] writes to.
+pub const DIR_ENV_VAR: &str = "TYPE_LAYOUT_ARTIFACT_DIR";
+
+/// Writes each layout to a JSON file in the directory named by
+/// [`DIR_ENV_VAR`], or else in `$OUT_DIR/type-layout`, creating it if needed.
+/// Returns the directory.
+///
+/// When `OUT_DIR` is set, which means this is running in a build script, this
+/// also tells Cargo to run the build script again when [`DIR_ENV_VAR`]
+/// changes.
+pub fn write(layouts: &[TypeLayoutInfo]) -> io::Result<PathBuf> {
+    let out_dir = env::var_os("OUT_DIR");
+
+    if out_dir.is_some() {
+        std::println!("cargo:rerun-if-env-changed={}", DIR_ENV_VAR);
+    }
+
+    let dir = match (env::var_os(DIR_ENV_VAR), out_dir) {
+        (Some(dir), _) => PathBuf::from(dir),
+        (None, Some(out_dir)) => Path::new(&out_dir).join("type-layout"),
+        (None, None) => {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                std::format!(
+                    "neither {} nor OUT_DIR is set, so there's nowhere to write layouts",
+                    DIR_ENV_VAR
+                ),
+            ))
+        }
+    };
+
+    write_to(&dir, layouts)?;
+    Ok(dir)
+}
+
+/// Writes each layout in the [registry](crate::registry), like [`write`].
+///
+/// This function is only available with the `registry` feature.
+#[cfg(feature = "registry")]
+pub fn write_registered() -> io::Result<PathBuf> {
+    let layouts: alloc::vec::Vec<TypeLayoutInfo> = crate::registry::iter()
+        .map(|registration| (registration.type_layout)())
+        .collect();
+
+    write(&layouts)
+}
+
+/// Writes each layout to a JSON file in `dir`, creating it if needed.
+///
+/// ```rust
+/// use type_layout::{artifacts, TypeLayout, TypeLayoutInfo};
+///
+/// #[derive(TypeLayout)]
+/// #[repr(C)]
+/// struct Header {
+///     kind: u8,
+///     len: u32,
+/// }
+///
+/// # let dir = std::env::temp_dir().join(format!("type-layout-artifacts-{}", std::process::id()));
+/// artifacts::write_to(&dir, &[Header::type_layout()]).unwrap();
+///
+/// let json = std::fs::read_to_string(dir.join("Header.json")).unwrap();
+/// assert_eq!(TypeLayoutInfo::from_json(&json).unwrap().size, 8);
+/// # std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+pub fn write_to<P: AsRef<Path>>(dir: P, layouts: &[TypeLayoutInfo]) -> io::Result<()> {
+    let dir = dir.as_ref();
+    fs::create_dir_all(dir)?;
+
+    for layout in layouts {
+        let path = dir.join(file_name(layout));
+        fs::write(path, layout.to_json())?;
+    }
+
+    Ok(())
+}
+
+/// The name of a layout's file. Characters that file systems may not allow,
+/// like the `<` and `>` of generic types, become `_`.
+fn file_name(layout: &TypeLayoutInfo) -> String {
+    let mut name: String = layout
+        .full_name()
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || ch == '_' || ch == '-' {
+                ch
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    name.push_str(".json");
+    name
+}
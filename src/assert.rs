@@ -0,0 +1,480 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::env;
+#[cfg(feature = "std")]
+use std::fs;
+#[cfg(feature = "std")]
+use std::path::Path;
+
+use crate::TypeLayoutInfo;
+
+#[cfg(feature = "std")]
+pub(crate) const UPDATE_ENV_VAR: &str = "TYPE_LAYOUT_UPDATE";
+
+/// Whether the `TYPE_LAYOUT_UPDATE` environment variable asks for recorded
+/// layouts to be overwritten.
+#[cfg(feature = "std")]
+pub(crate) fn update_requested() -> bool {
+    env::var_os(UPDATE_ENV_VAR).map_or(false, |value| value != "0")
+}
+
+/// Asserts that a type's rendered layout matches the expected table.
+///
+/// Trailing whitespace and leading or trailing blank lines are ignored. On a
+/// mismatch, the panic message contains a line-by-line diff, with lines only
+/// in the expected table prefixed by `-` and lines only in the actual table
+/// prefixed by `+`.
+///
+/// Without an expected table, the table is compared against a snapshot file
+/// instead, at `layout-snapshots/<module>__<type name>.txt` in the directory
+/// of the calling crate, where `<module>` is the path of the module the
+/// assertion is in, with `::` written as `__`. Keying snapshots by module
+/// keeps types with the same name in different modules apart. Assertions in
+/// the crate's root module leave out the module, like `Foo.txt`. The first
+/// run writes the snapshot, which is meant to be
+/// checked into version control. To accept a change, run the tests again with
+/// the `TYPE_LAYOUT_UPDATE` environment variable set to `1`. Snapshot files
+/// need the `std` feature.
+///
+/// ```rust,no_run
+/// use type_layout::{assert_layout_snapshot, TypeLayout};
+///
+/// #[derive(TypeLayout)]
+/// #[repr(C)]
+/// struct Foo {
+///     a: u8,
+///     b: u32,
+/// }
+///
+/// # #[cfg(feature = "std")]
+/// assert_layout_snapshot!(Foo);
+/// ```
+///
+/// ```rust
+/// use type_layout::{assert_layout_snapshot, TypeLayout};
+///
+/// #[derive(TypeLayout)]
+/// #[repr(C)]
+/// struct Foo {
+///     a: u8,
+///     b: u32,
+/// }
+///
+/// assert_layout_snapshot!(Foo, r"
+/// Foo (size 8, alignment 4)
+/// | Offset | Name      | Size |
+/// | ------ | --------- | ---- |
+/// | 0      | a         | 1    |
+/// | 1      | [padding] | 3    |
+/// | 4      | b         | 4    |
+/// Option<Foo> is 12 bytes (no niche)
+/// ");
+/// ```
+///
+/// ```rust,should_panic
+/// use type_layout::{assert_layout_snapshot, TypeLayout};
+///
+/// #[derive(TypeLayout)]
+/// #[repr(C)]
+/// struct Foo {
+///     a: u8,
+///     b: u32,
+/// }
+///
+/// assert_layout_snapshot!(Foo, r"
+/// Foo (size 8, alignment 4)
+/// | Offset | Name | Size |
+/// | ------ | ---- | ---- |
+/// | 0      | a    | 1    |
+/// | 1      | b    | 4    |
+/// ");
+/// ```
+#[macro_export]
+macro_rules! assert_layout_snapshot {
+    ($ty:ty $(,)?) => {
+        $crate::__assert_layout_snapshot_file(
+            &<$ty as $crate::TypeLayout>::type_layout(),
+            ::core::env!("CARGO_MANIFEST_DIR"),
+            ::core::module_path!(),
+        )
+    };
+
+    ($ty:ty, $expected:expr $(,)?) => {
+        $crate::__assert_layout_snapshot(&<$ty as $crate::TypeLayout>::type_layout(), $expected)
+    };
+}
+
+/// Asserts properties of a type's layout.
+///
+/// Each property is one of:
+///
+/// - `size == N`, the size of the type
+/// - `align == N`, the alignment of the type
+/// - `offset(field) == N`, the offset of a field
+/// - `size(field) == N`, the size of a field
+///
+/// ```rust
+/// use type_layout::{assert_layout, TypeLayout};
+///
+/// #[derive(TypeLayout)]
+/// #[repr(C)]
+/// struct Foo {
+///     a: u8,
+///     b: u32,
+/// }
+///
+/// assert_layout!(Foo, size == 8, align == 4, offset(b) == 4, size(a) == 1);
+/// ```
+///
+/// ```rust,should_panic
+/// use type_layout::{assert_layout, TypeLayout};
+///
+/// #[derive(TypeLayout)]
+/// #[repr(C)]
+/// struct Foo {
+///     a: u8,
+///     b: u32,
+/// }
+///
+/// assert_layout!(Foo, offset(b) == 1);
+/// ```
+///
+/// With a colon after the type instead of a comma, the properties are checked
+/// at compile time instead, and the macro can be used outside of a function.
+/// The type doesn't need to implement `TypeLayout` in this form. Each property
+/// is one of `size == N`, `align == N`, or `field @ N` for the offset of a
+/// field. Checking offsets at compile time needs Rust 1.65 or newer.
+///
+/// ```rust
+/// use type_layout::assert_layout;
+///
+/// #[repr(C)]
+/// struct Foo {
+///     a: u8,
+///     b: u32,
+/// }
+///
+/// assert_layout!(Foo: size == 8, align == 4, a @ 0, b @ 4);
+/// ```
+///
+/// ```compile_fail
+/// use type_layout::assert_layout;
+///
+/// #[repr(C)]
+/// struct Foo {
+///     a: u8,
+///     b: u32,
+/// }
+///
+/// assert_layout!(Foo: b @ 1);
+/// ```
+#[macro_export]
+macro_rules! assert_layout {
+    ($ty:ty, $($check:tt)+) => {{
+        let layout = <$ty as $crate::TypeLayout>::type_layout();
+        $crate::assert_layout!(@check layout, $($check)+);
+    }};
+
+    ($ty:path: $($check:tt)+) => {
+        $crate::assert_layout!(@const $ty, $($check)+);
+    };
+
+    // Alongside each assertion, a mismatched array length makes rustc print
+    // the actual value.
+    (@const $ty:path, size == $expected:expr $(, $($rest:tt)*)?) => {
+        const _: () = ::core::assert!(
+            ::core::mem::size_of::<$ty>() == $expected,
+            ::core::concat!(
+                "size of `", ::core::stringify!($ty), "` is not ", ::core::stringify!($expected),
+            ),
+        );
+        const _: [(); $expected] = [(); ::core::mem::size_of::<$ty>()];
+        $( $crate::assert_layout!(@const $ty, $($rest)*); )?
+    };
+
+    (@const $ty:path, align == $expected:expr $(, $($rest:tt)*)?) => {
+        const _: () = ::core::assert!(
+            ::core::mem::align_of::<$ty>() == $expected,
+            ::core::concat!(
+                "alignment of `", ::core::stringify!($ty), "` is not ",
+                ::core::stringify!($expected),
+            ),
+        );
+        const _: [(); $expected] = [(); ::core::mem::align_of::<$ty>()];
+        $( $crate::assert_layout!(@const $ty, $($rest)*); )?
+    };
+
+    (@const $ty:path, $field:tt @ $expected:expr $(, $($rest:tt)*)?) => {
+        const _: () = ::core::assert!(
+            $crate::__offset_of!($ty, $field) == $expected,
+            ::core::concat!(
+                "offset of `", ::core::stringify!($ty), "::", ::core::stringify!($field),
+                "` is not ", ::core::stringify!($expected),
+            ),
+        );
+        const _: [(); $expected] = [(); $crate::__offset_of!($ty, $field)];
+        $( $crate::assert_layout!(@const $ty, $($rest)*); )?
+    };
+
+    (@const $ty:path $(,)?) => {};
+
+    (@check $layout:ident, size == $expected:expr $(, $($rest:tt)*)?) => {
+        ::core::assert_eq!(
+            $layout.size,
+            $expected,
+            "size of `{}`",
+            $layout.full_name(),
+        );
+        $( $crate::assert_layout!(@check $layout, $($rest)*); )?
+    };
+
+    (@check $layout:ident, align == $expected:expr $(, $($rest:tt)*)?) => {
+        ::core::assert_eq!(
+            $layout.alignment,
+            $expected,
+            "alignment of `{}`",
+            $layout.full_name(),
+        );
+        $( $crate::assert_layout!(@check $layout, $($rest)*); )?
+    };
+
+    (@check $layout:ident, offset($field:ident) == $expected:expr $(, $($rest:tt)*)?) => {
+        ::core::assert_eq!(
+            $crate::__expect_field(&$layout, ::core::stringify!($field)).offset(),
+            $expected,
+            "offset of `{}::{}`",
+            $layout.full_name(),
+            ::core::stringify!($field),
+        );
+        $( $crate::assert_layout!(@check $layout, $($rest)*); )?
+    };
+
+    (@check $layout:ident, size($field:ident) == $expected:expr $(, $($rest:tt)*)?) => {
+        ::core::assert_eq!(
+            $crate::__expect_field(&$layout, ::core::stringify!($field)).size(),
+            $expected,
+            "size of `{}::{}`",
+            $layout.full_name(),
+            ::core::stringify!($field),
+        );
+        $( $crate::assert_layout!(@check $layout, $($rest)*); )?
+    };
+
+    (@check $layout:ident $(,)?) => {};
+}
+
+#[doc(hidden)]
+pub fn __expect_field<'a>(layout: &'a TypeLayoutInfo, name: &str) -> &'a crate::Field {
+    layout
+        .field(name)
+        .unwrap_or_else(|| panic!("`{}` has no field named `{}`", layout.full_name(), name))
+}
+
+#[doc(hidden)]
+#[track_caller]
+pub fn __assert_layout_snapshot(layout: &TypeLayoutInfo, expected: &str) {
+    let actual = layout.to_string();
+    let actual = normalize_lines(&actual);
+    let expected = normalize_lines(expected);
+
+    if actual != expected {
+        panic!(
+            "layout of `{}` does not match the snapshot:\n{}",
+            layout.full_name(),
+            diff_lines(&expected, &actual)
+        );
+    }
+}
+
+#[cfg(feature = "std")]
+#[doc(hidden)]
+#[track_caller]
+pub fn __assert_layout_snapshot_file(
+    layout: &TypeLayoutInfo,
+    manifest_dir: &str,
+    module_path: &str,
+) {
+    let path = Path::new(manifest_dir)
+        .join("layout-snapshots")
+        .join(format!(
+            "{}.txt",
+            snapshot_file_stem(module_path, &layout.full_name())
+        ));
+    let actual = layout.to_string();
+
+    if update_requested() || !path.exists() {
+        let written = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| fs::write(&path, &actual));
+
+        if let Err(err) = written {
+            panic!("could not write {}: {}", path.display(), err);
+        }
+
+        return;
+    }
+
+    let expected = fs::read_to_string(&path)
+        .unwrap_or_else(|err| panic!("could not read {}: {}", path.display(), err));
+    let actual = normalize_lines(&actual);
+    let expected = normalize_lines(&expected);
+
+    if actual != expected {
+        panic!(
+            "layout of `{}` does not match the snapshot in {}; set {}=1 to update it:\n{}",
+            layout.full_name(),
+            path.display(),
+            UPDATE_ENV_VAR,
+            diff_lines(&expected, &actual)
+        );
+    }
+}
+
+/// Turns a type's name and the path of the module it's asserted in into a
+/// file name, by replacing characters that aren't allowed in file names on
+/// some platforms, like the `<` and `>` of generics. The crate's name is left
+/// out of the module path, since each crate has its own snapshot directory.
+#[cfg(feature = "std")]
+fn snapshot_file_stem(module_path: &str, name: &str) -> String {
+    let module = module_path.split_once("::").map(|(_, module)| module);
+    let stem = match module {
+        Some(module) => format!("{}::{}", module, name),
+        None => name.to_string(),
+    };
+
+    stem.chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '_' | '-' => c,
+            _ => '_',
+        })
+        .collect()
+}
+
+pub(crate) fn normalize_lines(text: &str) -> Vec<&str> {
+    let mut lines: Vec<&str> = text.lines().map(str::trim_end).collect();
+
+    while lines.last() == Some(&"") {
+        lines.pop();
+    }
+
+    let leading_blank = lines.iter().take_while(|line| line.is_empty()).count();
+    lines.drain(..leading_blank);
+
+    lines
+}
+
+/// Renders a diff between two lists of lines using their longest common
+/// subsequence.
+pub(crate) fn diff_lines(old: &[&str], new: &[&str]) -> String {
+    let mut output = String::new();
+
+    for op in diff_ops(old, new) {
+        match op {
+            DiffOp::Same(i, _) => output.push_str(&format!("  {}\n", old[i])),
+            DiffOp::Removed(i) => output.push_str(&format!("- {}\n", old[i])),
+            DiffOp::Added(j) => output.push_str(&format!("+ {}\n", new[j])),
+        }
+    }
+
+    output
+}
+
+/// One step of a diff, holding indices into the old and new lists.
+#[derive(Clone, Copy)]
+pub(crate) enum DiffOp {
+    Same(usize, usize),
+    Removed(usize),
+    Added(usize),
+}
+
+/// Finds the steps that turn `old` into `new` using their longest common
+/// subsequence, preferring removals before additions.
+pub(crate) fn diff_ops<T: PartialEq>(old: &[T], new: &[T]) -> Vec<DiffOp> {
+    // common[i][j] is the length of the longest common subsequence of
+    // old[i..] and new[j..].
+    let mut common = vec![vec![0; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut ops = Vec::new();
+    let (mut i, mut j) = (0, 0);
+
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            ops.push(DiffOp::Same(i, j));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || common[i + 1][j] >= common[i][j + 1]) {
+            ops.push(DiffOp::Removed(i));
+            i += 1;
+        } else {
+            ops.push(DiffOp::Added(j));
+            j += 1;
+        }
+    }
+
+    ops
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    fn packed(fields: &[(&'static str, usize)]) -> TypeLayoutInfo {
+        let mut builder = TypeLayoutInfo::builder("Packed").packed(1);
+        for &(name, size) in fields {
+            builder = builder.field(name, "u8", size, 1);
+        }
+        builder.build().unwrap()
+    }
+
+    #[test]
+    fn file_stem_includes_module() {
+        assert_eq!(snapshot_file_stem("my_crate", "Foo"), "Foo");
+        assert_eq!(
+            snapshot_file_stem("my_crate::net::wire", "Foo"),
+            "net__wire__Foo"
+        );
+        assert_eq!(
+            snapshot_file_stem("my_crate::net", "Buffer<u8, 4>"),
+            "net__Buffer_u8__4_"
+        );
+    }
+
+    #[test]
+    fn same_name_in_different_modules() {
+        let dir = env::temp_dir().join(format!(
+            "type-layout-snapshots-{}-same-name",
+            std::process::id()
+        ));
+        let manifest_dir = dir.to_str().unwrap();
+
+        let small = packed(&[("a", 1), ("b", 4)]);
+        let large = packed(&[("a", 1), ("b", 4), ("c", 5)]);
+
+        // Each run writes its snapshot, then checks against it.
+        for _ in 0..2 {
+            __assert_layout_snapshot_file(&small, manifest_dir, "my_crate::offsets");
+            __assert_layout_snapshot_file(&large, manifest_dir, "my_crate::hygiene");
+        }
+
+        let snapshots = dir.join("layout-snapshots");
+        let small_snapshot = fs::read_to_string(snapshots.join("offsets__Packed.txt")).unwrap();
+        let large_snapshot = fs::read_to_string(snapshots.join("hygiene__Packed.txt")).unwrap();
+        assert!(small_snapshot.starts_with("Packed (size 5, alignment 1)"));
+        assert!(large_snapshot.starts_with("Packed (size 10, alignment 1)"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,421 @@
+use alloc::borrow::Cow;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{Field, TypeLayout, TypeLayoutInfo};
+
+/// Compares a struct generated by [bindgen](https://crates.io/crates/bindgen)
+/// with a hand-written Rust type meant to have the same layout, field by
+/// field. See [`CompatReport`].
+///
+/// bindgen can be told to derive `TypeLayout` for the types it generates
+/// with a `ParseCallbacks` that returns it from `add_derives`.
+pub fn bindgen_compat<Bindgen: TypeLayout, Rust: TypeLayout>() -> CompatReport {
+    CompatReport::new(Bindgen::type_layout(), Rust::type_layout())
+}
+
+/// How a struct generated by bindgen and a hand-written Rust type with the
+/// same layout line up, created by [`bindgen_compat`] or
+/// [`CompatReport::new`].
+///
+/// Fields are paired by name, and fields whose names don't match are paired
+/// in the order they appear in. Trailing underscores and `r#` are ignored in
+/// names, since bindgen adds an underscore to C names that are Rust keywords.
+/// Zero-sized fields, like bindgen's `_bitfield_align_1`, and the padding
+/// fields bindgen adds for `#pragma pack` are left out.
+///
+/// Displaying the report shows the fields side by side, followed by every
+/// issue.
+///
+/// ```rust
+/// use type_layout::{bindgen_compat, CompatIssue, TypeLayout};
+///
+/// mod bindings {
+///     // struct header {
+///     //     uint8_t kind;
+///     //     uint32_t len;
+///     //     uint8_t type;
+///     // };
+///     #[derive(type_layout::TypeLayout)]
+///     #[repr(C)]
+///     #[allow(non_camel_case_types)]
+///     pub struct header {
+///         pub kind: u8,
+///         pub len: u32,
+///         pub type_: u8,
+///     }
+/// }
+///
+/// #[derive(TypeLayout)]
+/// #[repr(C)]
+/// struct Header {
+///     kind: u8,
+///     length: u32,
+///     r#type: u16,
+/// }
+///
+/// let report = bindgen_compat::<bindings::header, Header>();
+/// assert!(!report.is_compatible());
+/// assert!(report.issues.contains(&CompatIssue::FieldSize {
+///     bindgen_name: "type_".into(),
+///     rust_name: "type".into(),
+///     bindgen: 1,
+///     rust: 2,
+/// }));
+///
+/// println!("{}", report);
+/// // prints:
+/// // header (bindgen) vs Header: incompatible
+/// // | Offset | bindgen | Size | Offset | Rust   | Size |
+/// // | ------ | ------- | ---- | ------ | ------ | ---- |
+/// // | 0      | kind    | 1    | 0      | kind   | 1    |
+/// // | 4      | len     | 4    | 4      | length | 4    |
+/// // | 8      | type_   | 1    | 8      | type   | 2    |
+/// // `len` is named `length` in Rust
+/// // `type_` has size 1 in bindgen and 2 in Rust
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct CompatReport {
+    /// The layouts that were compared.
+    pub bindgen: TypeLayoutInfo,
+    pub rust: TypeLayoutInfo,
+
+    /// The fields of each layout that were paired with each other, or with
+    /// nothing if the other layout has no matching field.
+    pub pairs: Vec<(Option<Field>, Option<Field>)>,
+
+    /// Every difference between the layouts. Changes to the type come first,
+    /// followed by fields in the order of the bindgen layout.
+    pub issues: Vec<CompatIssue>,
+}
+
+/// One difference found by [`CompatReport`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub enum CompatIssue {
+    Size {
+        bindgen: usize,
+        rust: usize,
+    },
+    Alignment {
+        bindgen: usize,
+        rust: usize,
+    },
+    FieldOffset {
+        bindgen_name: Cow<'static, str>,
+        rust_name: Cow<'static, str>,
+        bindgen: usize,
+        rust: usize,
+    },
+    FieldSize {
+        bindgen_name: Cow<'static, str>,
+        rust_name: Cow<'static, str>,
+        bindgen: usize,
+        rust: usize,
+    },
+
+    /// Fields in the same position have different names. This doesn't make
+    /// the types incompatible, but may mean that the fields are out of order.
+    FieldName {
+        bindgen: Cow<'static, str>,
+        rust: Cow<'static, str>,
+    },
+
+    /// A field of the bindgen layout has no counterpart in the Rust type.
+    MissingField {
+        name: Cow<'static, str>,
+        offset: usize,
+        size: usize,
+    },
+
+    /// A field of the Rust type has no counterpart in the bindgen layout.
+    ExtraField {
+        name: Cow<'static, str>,
+        offset: usize,
+        size: usize,
+    },
+}
+
+impl CompatIssue {
+    /// Whether this issue makes the types incompatible, which is the case for
+    /// every issue except different names.
+    pub fn is_incompatible(&self) -> bool {
+        !matches!(self, CompatIssue::FieldName { .. })
+    }
+}
+
+impl fmt::Display for CompatIssue {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CompatIssue::Size { bindgen, rust } => write!(
+                formatter,
+                "size is {} in bindgen and {} in Rust",
+                bindgen, rust
+            ),
+            CompatIssue::Alignment { bindgen, rust } => write!(
+                formatter,
+                "alignment is {} in bindgen and {} in Rust",
+                bindgen, rust
+            ),
+            CompatIssue::FieldOffset {
+                bindgen_name,
+                bindgen,
+                rust,
+                ..
+            } => write!(
+                formatter,
+                "`{}` is at offset {} in bindgen and {} in Rust",
+                bindgen_name, bindgen, rust
+            ),
+            CompatIssue::FieldSize {
+                bindgen_name,
+                bindgen,
+                rust,
+                ..
+            } => write!(
+                formatter,
+                "`{}` has size {} in bindgen and {} in Rust",
+                bindgen_name, bindgen, rust
+            ),
+            CompatIssue::FieldName { bindgen, rust } => {
+                write!(formatter, "`{}` is named `{}` in Rust", bindgen, rust)
+            }
+            CompatIssue::MissingField { name, offset, size } => write!(
+                formatter,
+                "`{}` at offset {} with size {} is missing from Rust",
+                name, offset, size
+            ),
+            CompatIssue::ExtraField { name, offset, size } => write!(
+                formatter,
+                "`{}` at offset {} with size {} is only in Rust",
+                name, offset, size
+            ),
+        }
+    }
+}
+
+impl CompatReport {
+    /// Compares the layout of a struct generated by bindgen with the layout
+    /// of a Rust type.
+    pub fn new(bindgen: TypeLayoutInfo, rust: TypeLayoutInfo) -> Self {
+        let pairs = pair_fields(&bindgen, &rust);
+        let mut issues = Vec::new();
+
+        if bindgen.size != rust.size {
+            issues.push(CompatIssue::Size {
+                bindgen: bindgen.size,
+                rust: rust.size,
+            });
+        }
+
+        if bindgen.alignment != rust.alignment {
+            issues.push(CompatIssue::Alignment {
+                bindgen: bindgen.alignment,
+                rust: rust.alignment,
+            });
+        }
+
+        for pair in &pairs {
+            match pair {
+                (Some(bindgen_field), Some(rust_field)) => {
+                    let bindgen_name = field_name(bindgen_field);
+                    let rust_name = field_name(rust_field);
+
+                    if normalize(&bindgen_name) != normalize(&rust_name) {
+                        issues.push(CompatIssue::FieldName {
+                            bindgen: bindgen_name.clone(),
+                            rust: rust_name.clone(),
+                        });
+                    }
+
+                    if bindgen_field.offset() != rust_field.offset() {
+                        issues.push(CompatIssue::FieldOffset {
+                            bindgen_name: bindgen_name.clone(),
+                            rust_name: rust_name.clone(),
+                            bindgen: bindgen_field.offset(),
+                            rust: rust_field.offset(),
+                        });
+                    }
+
+                    if bindgen_field.size() != rust_field.size() {
+                        issues.push(CompatIssue::FieldSize {
+                            bindgen_name,
+                            rust_name,
+                            bindgen: bindgen_field.size(),
+                            rust: rust_field.size(),
+                        });
+                    }
+                }
+                (Some(field), None) => issues.push(CompatIssue::MissingField {
+                    name: field_name(field),
+                    offset: field.offset(),
+                    size: field.size(),
+                }),
+                (None, Some(field)) => issues.push(CompatIssue::ExtraField {
+                    name: field_name(field),
+                    offset: field.offset(),
+                    size: field.size(),
+                }),
+                (None, None) => {}
+            }
+        }
+
+        CompatReport {
+            bindgen,
+            rust,
+            pairs,
+            issues,
+        }
+    }
+
+    /// Whether the Rust type can be used in place of the bindgen struct,
+    /// meaning that every issue is a difference in names.
+    pub fn is_compatible(&self) -> bool {
+        !self.issues.iter().any(CompatIssue::is_incompatible)
+    }
+}
+
+impl fmt::Display for CompatReport {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let verdict = if self.is_compatible() {
+            "compatible"
+        } else {
+            "incompatible"
+        };
+
+        writeln!(
+            formatter,
+            "{} (bindgen) vs {}: {}",
+            self.bindgen.full_name(),
+            self.rust.full_name(),
+            verdict
+        )?;
+
+        let cells = |field: &Option<Field>| match field {
+            Some(field) => [
+                field.offset().to_string(),
+                field_name(field).into_owned(),
+                field.size().to_string(),
+            ],
+            None => [String::new(), String::new(), String::new()],
+        };
+
+        let rows: Vec<[String; 6]> = self
+            .pairs
+            .iter()
+            .map(|(bindgen, rust)| {
+                let [a, b, c] = cells(bindgen);
+                let [d, e, f] = cells(rust);
+                [a, b, c, d, e, f]
+            })
+            .collect();
+
+        let headings = ["Offset", "bindgen", "Size", "Offset", "Rust", "Size"];
+        let mut widths = headings.map(str::len);
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.len());
+            }
+        }
+
+        write_row(formatter, &widths, &headings)?;
+        write_row(formatter, &widths, &widths.map(|width| "-".repeat(width)))?;
+        for row in &rows {
+            write_row(formatter, &widths, row)?;
+        }
+
+        for issue in &self.issues {
+            writeln!(formatter, "{}", issue)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Pairs the fields of the two layouts, by name and then by position. The
+/// pairs are in the order of the bindgen layout's fields, followed by the
+/// fields only the Rust type has.
+fn pair_fields(
+    bindgen: &TypeLayoutInfo,
+    rust: &TypeLayoutInfo,
+) -> Vec<(Option<Field>, Option<Field>)> {
+    let bindgen_fields = abi_fields(bindgen);
+    let rust_fields = abi_fields(rust);
+
+    let mut partners: Vec<Option<usize>> = bindgen_fields
+        .iter()
+        .map(|field| {
+            let name = normalize(&field_name(field)).to_string();
+            rust_fields
+                .iter()
+                .position(|other| normalize(&field_name(other)) == name)
+        })
+        .collect();
+
+    // Pair the remaining fields in order.
+    let unpaired: Vec<usize> = (0..rust_fields.len())
+        .filter(|index| !partners.contains(&Some(*index)))
+        .collect();
+    let mut unpaired = unpaired.into_iter();
+    for partner in &mut partners {
+        if partner.is_none() {
+            *partner = unpaired.next();
+        }
+    }
+
+    let mut pairs: Vec<_> = bindgen_fields
+        .iter()
+        .zip(&partners)
+        .map(|(field, partner)| {
+            let rust_field = partner.map(|index| Field::clone(rust_fields[index]));
+            (Some(Field::clone(field)), rust_field)
+        })
+        .collect();
+
+    for (index, field) in rust_fields.iter().enumerate() {
+        if !partners.contains(&Some(index)) {
+            pairs.push((None, Some(Field::clone(field))));
+        }
+    }
+
+    pairs
+}
+
+/// The fields that affect a type's ABI: every field with a size, except the
+/// padding fields bindgen generates.
+fn abi_fields(layout: &TypeLayoutInfo) -> Vec<&Field> {
+    layout
+        .fields
+        .iter()
+        .filter(|field| match field {
+            Field::Field { name, size, .. } => *size > 0 && !name.starts_with("__bindgen_padding_"),
+            Field::Padding { .. } => false,
+        })
+        .collect()
+}
+
+fn field_name(field: &Field) -> Cow<'static, str> {
+    match field {
+        Field::Field { name, .. } => name.clone(),
+        Field::Padding { .. } => Cow::Borrowed("[padding]"),
+    }
+}
+
+/// A field name without `r#` or trailing underscores.
+fn normalize(name: &str) -> &str {
+    name.trim_start_matches("r#").trim_end_matches('_')
+}
+
+fn write_row<S: AsRef<str>>(
+    formatter: &mut fmt::Formatter,
+    widths: &[usize],
+    cells: &[S],
+) -> fmt::Result {
+    for (width, cell) in widths.iter().zip(cells) {
+        write!(formatter, "| {:<width$} ", cell.as_ref(), width = width)?;
+    }
+
+    writeln!(formatter, "|")
+}
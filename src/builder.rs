@@ -0,0 +1,180 @@
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+
+use crate::{Field, LayoutError, Repr, TypeLayoutInfo};
+
+/// Builds the layout of a `#[repr(C)]` struct from a list of fields, for
+/// types that don't have a Rust definition, like structs from a C or C++
+/// library. Created by [`TypeLayoutInfo::builder`].
+///
+/// Fields are placed one after the other the way `#[repr(C)]` places them,
+/// with padding inserted before each field to align it and after the last
+/// field to round the size up to the struct's alignment.
+///
+/// ```rust
+/// use type_layout::{TypeLayout, TypeLayoutInfo};
+///
+/// // struct header {
+/// //     uint8_t kind;
+/// //     uint32_t len;
+/// //     uint16_t flags;
+/// // };
+/// let header = TypeLayoutInfo::builder("header")
+///     .field("kind", "u8", 1, 1)
+///     .field("len", "u32", 4, 4)
+///     .field("flags", "u16", 2, 2)
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(header.size, 12);
+/// assert_eq!(header.field("flags").unwrap().offset(), 8);
+///
+/// #[derive(TypeLayout)]
+/// #[repr(C)]
+/// struct Header {
+///     kind: u8,
+///     len: u32,
+///     flags: u16,
+/// }
+///
+/// assert!(header.diff(&Header::type_layout()).is_empty());
+/// ```
+#[derive(Debug, Clone)]
+pub struct TypeLayoutBuilder {
+    name: Cow<'static, str>,
+    fields: Vec<Field>,
+    packed: Option<usize>,
+    align: Option<usize>,
+    error: Option<LayoutError>,
+}
+
+impl TypeLayoutInfo {
+    /// Starts building the layout of a `#[repr(C)]` struct with the given
+    /// name. See [`TypeLayoutBuilder`].
+    pub fn builder(name: impl Into<Cow<'static, str>>) -> TypeLayoutBuilder {
+        TypeLayoutBuilder {
+            name: name.into(),
+            fields: Vec::new(),
+            packed: None,
+            align: None,
+            error: None,
+        }
+    }
+}
+
+impl TypeLayoutBuilder {
+    /// Adds a field with the given type name, size, and alignment after the
+    /// fields added so far.
+    pub fn field(
+        mut self,
+        name: impl Into<Cow<'static, str>>,
+        ty: impl Into<Cow<'static, str>>,
+        size: usize,
+        alignment: usize,
+    ) -> Self {
+        self.check_alignment(alignment);
+
+        self.fields.push(Field::Field {
+            name: name.into(),
+            ty: ty.into(),
+            resolved_ty: None,
+            type_id: None,
+            offset: 0,
+            size,
+            alignment: Some(alignment),
+            array: None,
+            needs_drop: false,
+            option_size: None,
+            nested: None,
+            contended: false,
+            niche: false,
+        });
+
+        self
+    }
+
+    /// Lowers the alignment of every field to at most `packed`, like
+    /// `#[repr(C, packed(N))]`.
+    pub fn packed(mut self, packed: usize) -> Self {
+        self.check_alignment(packed);
+        self.packed = Some(packed);
+        self
+    }
+
+    /// Raises the alignment of the struct to at least `align`, like
+    /// `#[repr(C, align(N))]`.
+    pub fn align(mut self, align: usize) -> Self {
+        self.check_alignment(align);
+        self.align = Some(align);
+        self
+    }
+
+    /// Lays out the fields. Fails if an alignment isn't a power of two, or if
+    /// the struct is too large.
+    pub fn build(self) -> Result<TypeLayoutInfo, LayoutError> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+
+        let mut fields = self.fields;
+        let mut end: usize = 0;
+        let mut alignment = 1;
+        let mut natural_alignment = 1;
+
+        for field in &mut fields {
+            if let Field::Field {
+                offset,
+                size,
+                alignment: Some(field_alignment),
+                ..
+            } = field
+            {
+                natural_alignment = natural_alignment.max(*field_alignment);
+                if let Some(packed) = self.packed {
+                    *field_alignment = (*field_alignment).min(packed);
+                }
+
+                *offset = crate::compose::round_up(end, *field_alignment)?;
+                end = offset.checked_add(*size).ok_or(LayoutError::Overflow)?;
+                alignment = alignment.max(*field_alignment);
+            }
+        }
+
+        if let Some(align) = self.align {
+            alignment = alignment.max(align);
+        }
+
+        let size = crate::compose::round_up(end, alignment)?;
+        crate::compose::check_size(size, alignment)?;
+
+        Ok(TypeLayoutInfo {
+            name: self.name,
+            generics: Vec::new(),
+            size,
+            alignment,
+            natural_alignment,
+            needs_drop: false,
+            option_size: None,
+            is_union: false,
+            repr: Repr {
+                c: true,
+                packed: self.packed,
+                align: self.align,
+                ..Repr::default()
+            },
+            guaranteed: true,
+            fields: crate::__insert_padding(fields, size),
+            variants: Vec::new(),
+            unsized_tail: None,
+            tag: None,
+            target: None,
+        })
+    }
+
+    /// Remembers the first invalid alignment, which `build` reports.
+    fn check_alignment(&mut self, alignment: usize) {
+        if !alignment.is_power_of_two() && self.error.is_none() {
+            self.error = Some(LayoutError::InvalidAlignment(alignment));
+        }
+    }
+}
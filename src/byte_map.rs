@@ -0,0 +1,211 @@
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{Field, TypeLayoutInfo};
+
+/// The labels given to fields, in order. Fields after these share `#`.
+const LABELS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+
+impl TypeLayoutInfo {
+    /// Renders the layout as a grid of bytes, `row_width` to a row, with the
+    /// bytes of each field marked by the field's letter and padding shown as
+    /// `.`. Over-aligned fields and padding scattered between fields stand
+    /// out more than in the table that `Display` renders.
+    ///
+    /// Nested fields are shown as their own fields, named like
+    /// `outer.inner`. Bytes shared by more than one field, like the fields of
+    /// a union, are shown as `*`, and each variant of an enum gets its own
+    /// grid.
+    ///
+    /// ```rust
+    /// use type_layout::TypeLayout;
+    ///
+    /// #[derive(TypeLayout)]
+    /// #[repr(C)]
+    /// struct Header {
+    ///     kind: u8,
+    ///     len: u32,
+    ///     flags: [u16; 2],
+    ///     checksum: u64,
+    /// }
+    ///
+    /// print!("{}", Header::type_layout().byte_map(16));
+    /// // prints:
+    /// // Header (size 24, alignment 8)
+    /// //      0  1  2  3  4  5  6  7  8  9 10 11 12 13 14 15
+    /// //  0   A  .  .  .  B  B  B  B  C  C  C  C  .  .  .  .
+    /// // 16   D  D  D  D  D  D  D  D
+    /// // A = kind
+    /// // B = len
+    /// // C = flags
+    /// // D = checksum
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Rendering panics if `row_width` is zero.
+    pub fn byte_map(&self, row_width: usize) -> ByteMap<'_> {
+        ByteMap {
+            layout: self,
+            row_width,
+        }
+    }
+}
+
+/// Renders a layout as a grid of bytes. Created by
+/// [`TypeLayoutInfo::byte_map`].
+pub struct ByteMap<'a> {
+    layout: &'a TypeLayoutInfo,
+    row_width: usize,
+}
+
+impl fmt::Display for ByteMap<'_> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        assert!(
+            self.row_width > 0,
+            "byte map row width must be greater than zero"
+        );
+
+        let layout = self.layout;
+        let kind = if layout.is_union { "union, " } else { "" };
+        writeln!(
+            formatter,
+            "{} ({}size {}, alignment {})",
+            layout.full_name(),
+            kind,
+            layout.size,
+            layout.alignment
+        )?;
+
+        if layout.variants.is_empty() {
+            write_map(formatter, layout, &layout.fields, self.row_width)?;
+        } else {
+            for variant in &layout.variants {
+                writeln!(
+                    formatter,
+                    "Variant {} = {}",
+                    variant.name, variant.discriminant
+                )?;
+                write_map(formatter, layout, &variant.fields, self.row_width)?;
+            }
+        }
+
+        if let Some(tail) = &layout.unsized_tail {
+            writeln!(
+                formatter,
+                "followed by {}: {} at offset {}",
+                tail.name, tail.ty, tail.offset
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Draws the grid for one set of fields, which is either the fields of a
+/// struct or those of one variant of an enum, followed by its legend.
+fn write_map(
+    formatter: &mut fmt::Formatter,
+    layout: &TypeLayoutInfo,
+    fields: &[Field],
+    row_width: usize,
+) -> fmt::Result {
+    let mut leaves = Vec::new();
+    collect_leaves(fields, 0, "", &mut leaves);
+
+    let mut bytes = vec![b'.'; layout.size];
+    for (index, leaf) in leaves.iter().enumerate() {
+        let label = LABELS.get(index).copied().unwrap_or(b'#');
+
+        for byte in bytes.iter_mut().skip(leaf.offset).take(leaf.field.size()) {
+            *byte = if *byte == b'.' { label } else { b'*' };
+        }
+    }
+
+    let column_width = format!("{}", row_width - 1).len().max(1) + 1;
+    let offset_width = format!("{}", layout.size.saturating_sub(1) / row_width * row_width).len();
+
+    write!(formatter, "{:width$}", "", width = offset_width + 1)?;
+    for column in 0..row_width.min(layout.size.max(1)) {
+        write!(formatter, "{:>width$}", column, width = column_width)?;
+    }
+    writeln!(formatter)?;
+
+    for (row, chunk) in bytes.chunks(row_width).enumerate() {
+        write!(
+            formatter,
+            "{:>width$} ",
+            row * row_width,
+            width = offset_width
+        )?;
+        for byte in chunk {
+            write!(formatter, "{:>width$}", *byte as char, width = column_width)?;
+        }
+        writeln!(formatter)?;
+    }
+
+    for (index, leaf) in leaves.iter().enumerate().take(LABELS.len()) {
+        writeln!(formatter, "{} = {}", LABELS[index] as char, leaf.name)?;
+    }
+
+    if leaves.len() > LABELS.len() {
+        writeln!(formatter, "# = {} more fields", leaves.len() - LABELS.len())?;
+    }
+
+    Ok(())
+}
+
+/// A field with no nested fields of its own, found by [`collect_leaves`].
+pub(crate) struct Leaf<'a> {
+    /// The field's name, like `outer.inner` for a nested field.
+    pub name: Cow<'a, str>,
+
+    /// The field's offset from the start of the outermost type.
+    pub offset: usize,
+
+    pub field: &'a Field,
+}
+
+/// Flattens fields into the fields that have no nested fields of their own.
+/// Zero-sized fields are left out, since they have no bytes to show.
+pub(crate) fn collect_leaves<'a>(
+    fields: &'a [Field],
+    base: usize,
+    prefix: &str,
+    leaves: &mut Vec<Leaf<'a>>,
+) {
+    for field in fields {
+        if let Field::Field {
+            name,
+            offset,
+            size,
+            array,
+            nested,
+            ..
+        } = field
+        {
+            let name = if prefix.is_empty() {
+                Cow::Borrowed(name.as_ref())
+            } else {
+                Cow::Owned(format!("{}.{}", prefix, name))
+            };
+
+            // The nested layout of an array only covers its first element,
+            // so arrays are shown whole.
+            match nested {
+                Some(nested) if !nested.fields.is_empty() && array.is_none() => {
+                    collect_leaves(&nested.fields, base + offset, &name, leaves);
+                }
+                _ if *size > 0 => leaves.push(Leaf {
+                    name,
+                    offset: base + offset,
+                    field,
+                }),
+                _ => {}
+            }
+        }
+    }
+}
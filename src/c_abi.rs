@@ -0,0 +1,697 @@
+//! Computes the layout a C compiler would give a struct, without compiling
+//! any C.
+//!
+//! Structs are described with [`CStruct`] as a list of fields with C types,
+//! and laid out for a [`CTarget`], which decides the size and alignment of
+//! each primitive type. The result is a [`TypeLayoutInfo`], so it can be
+//! rendered or compared with the layout of a Rust type using
+//! [`TypeLayoutInfo::diff`]. [`TypeLayoutInfo::check_against_c_abi`] does
+//! this for the fields of a Rust type, to check that C sees the same layout.
+//!
+//! Bitfields aren't supported.
+//!
+//! ```rust
+//! use type_layout::c_abi::{CStruct, CTarget, CType};
+//!
+//! // struct sample {
+//! //     char tag;
+//! //     double value;
+//! // };
+//! let sample = CStruct::new("sample")
+//!     .field("tag", CType::Char)
+//!     .field("value", CType::Double);
+//!
+//! // 32-bit System V targets only align `double` to 4 bytes in structs.
+//! assert_eq!(sample.layout(CTarget::I686_SYSV).unwrap().size, 12);
+//! assert_eq!(sample.layout(CTarget::I686_MSVC).unwrap().size, 16);
+//!
+//! // #pragma pack(2)
+//! let packed = sample.clone().pack(2).layout(CTarget::X86_64_SYSV).unwrap();
+//! assert_eq!(packed.field("value").unwrap().offset(), 2);
+//!
+//! println!("{}", sample.layout(CTarget::X86_64_SYSV).unwrap());
+//! // prints:
+//! // sample (size 16, alignment 8)
+//! // | Offset | Name      | Size |
+//! // | ------ | --------- | ---- |
+//! // | 0      | tag       | 1    |
+//! // | 1      | [padding] | 7    |
+//! // | 8      | value     | 8    |
+//! ```
+
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{Field, LayoutError, TypeLayoutInfo};
+
+#[cfg(feature = "c-parser")]
+pub use crate::c_parse::{parse_struct, CParseError};
+
+/// The rules a C compiler follows when laying out structs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CAbi {
+    /// The System V ABI, used by GCC and Clang on Linux, macOS, and the BSDs.
+    SystemV,
+
+    /// The ABI used by MSVC, and by Clang and MinGW when targeting Windows.
+    Msvc,
+}
+
+/// A target to compute C layouts for: its ABI and the size of a pointer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CTarget {
+    pub abi: CAbi,
+
+    /// The size of a pointer in bytes, which also decides the size of `long`
+    /// on System V targets.
+    pub pointer_width: usize,
+}
+
+impl CTarget {
+    pub const X86_64_SYSV: CTarget = CTarget {
+        abi: CAbi::SystemV,
+        pointer_width: 8,
+    };
+
+    pub const X86_64_MSVC: CTarget = CTarget {
+        abi: CAbi::Msvc,
+        pointer_width: 8,
+    };
+
+    pub const I686_SYSV: CTarget = CTarget {
+        abi: CAbi::SystemV,
+        pointer_width: 4,
+    };
+
+    pub const I686_MSVC: CTarget = CTarget {
+        abi: CAbi::Msvc,
+        pointer_width: 4,
+    };
+}
+
+/// The type of a field of a [`CStruct`]. Signed and unsigned integers have
+/// the same layout, so only one of each is listed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CType {
+    Bool,
+    Char,
+    Short,
+    Int,
+    Long,
+    LongLong,
+    Float,
+    Double,
+    LongDouble,
+
+    /// Any pointer, including function pointers.
+    Pointer,
+
+    /// A type with a size and alignment that don't depend on the target,
+    /// like `uint32_t` or a type from another library.
+    Custom {
+        name: Cow<'static, str>,
+        size: usize,
+        alignment: usize,
+    },
+
+    /// A nested struct, usually laid out by [`CStruct::layout`] for the same
+    /// target. Its fields are shown indented below the field.
+    Struct(Box<TypeLayoutInfo>),
+
+    /// An array of the given length.
+    Array(Box<CType>, usize),
+}
+
+impl CType {
+    /// The size and alignment of this type on the given target, when it's a
+    /// field of a struct.
+    pub fn size_align(&self, target: CTarget) -> (usize, usize) {
+        let pointer = target.pointer_width;
+        let sysv32 = target.abi == CAbi::SystemV && pointer == 4;
+
+        match self {
+            CType::Bool | CType::Char => (1, 1),
+            CType::Short => (2, 2),
+            CType::Int | CType::Float => (4, 4),
+            CType::Long => match target.abi {
+                CAbi::SystemV => (pointer, pointer),
+                CAbi::Msvc => (4, 4),
+            },
+            // On 32-bit System V targets, 8-byte types are only aligned to 4
+            // bytes inside structs.
+            CType::LongLong | CType::Double if sysv32 => (8, 4),
+            CType::LongLong | CType::Double => (8, 8),
+            CType::LongDouble => match target.abi {
+                CAbi::SystemV if sysv32 => (12, 4),
+                CAbi::SystemV => (16, 16),
+                CAbi::Msvc => (8, 8),
+            },
+            CType::Pointer => (pointer, pointer),
+            CType::Custom {
+                size, alignment, ..
+            } => (*size, *alignment),
+            CType::Struct(layout) => (layout.size, layout.alignment),
+            CType::Array(elem, len) => {
+                let (size, alignment) = elem.size_align(target);
+                (size.saturating_mul(*len), alignment)
+            }
+        }
+    }
+
+    /// The type as it's written in C, like `unsigned long` or `char[4]`.
+    pub fn name(&self) -> Cow<'static, str> {
+        match self {
+            CType::Bool => Cow::Borrowed("_Bool"),
+            CType::Char => Cow::Borrowed("char"),
+            CType::Short => Cow::Borrowed("short"),
+            CType::Int => Cow::Borrowed("int"),
+            CType::Long => Cow::Borrowed("long"),
+            CType::LongLong => Cow::Borrowed("long long"),
+            CType::Float => Cow::Borrowed("float"),
+            CType::Double => Cow::Borrowed("double"),
+            CType::LongDouble => Cow::Borrowed("long double"),
+            CType::Pointer => Cow::Borrowed("void *"),
+            CType::Custom { name, .. } => name.clone(),
+            CType::Struct(layout) => Cow::Owned(format!("struct {}", layout.name)),
+            CType::Array(elem, len) => Cow::Owned(format!("{}[{}]", elem.name(), len)),
+        }
+    }
+}
+
+/// A C struct, described by the types of its fields in order. See the
+/// [module documentation](self).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CStruct {
+    name: Cow<'static, str>,
+    fields: Vec<(Cow<'static, str>, CType)>,
+    pack: Option<usize>,
+    align: Option<usize>,
+}
+
+impl CStruct {
+    pub fn new(name: impl Into<Cow<'static, str>>) -> Self {
+        CStruct {
+            name: name.into(),
+            fields: Vec::new(),
+            pack: None,
+            align: None,
+        }
+    }
+
+    /// Adds a field after the fields added so far.
+    pub fn field(mut self, name: impl Into<Cow<'static, str>>, ty: CType) -> Self {
+        self.fields.push((name.into(), ty));
+        self
+    }
+
+    /// Lowers the alignment of every field to at most `pack`, like
+    /// `#pragma pack(N)`.
+    pub fn pack(mut self, pack: usize) -> Self {
+        self.pack = Some(pack);
+        self
+    }
+
+    /// Raises the alignment of the struct to at least `align`, like
+    /// `__attribute__((aligned(N)))` or `__declspec(align(N))`.
+    pub fn align(mut self, align: usize) -> Self {
+        self.align = Some(align);
+        self
+    }
+
+    /// Lays out the struct for the given target.
+    ///
+    /// A struct without any fields has a size of 0 with the System V ABI,
+    /// as GCC and Clang give it in C. MSVC doesn't allow empty structs in C,
+    /// so they're given the size they have in C++, which is their alignment.
+    pub fn layout(&self, target: CTarget) -> Result<TypeLayoutInfo, LayoutError> {
+        let mut builder = TypeLayoutInfo::builder(self.name.clone());
+
+        for (name, ty) in &self.fields {
+            let (size, alignment) = ty.size_align(target);
+            builder = builder.field(name.clone(), ty.name(), size, alignment);
+        }
+
+        if let Some(pack) = self.pack {
+            builder = builder.packed(pack);
+        }
+        if let Some(align) = self.align {
+            builder = builder.align(align);
+        }
+
+        let mut layout = builder.build()?;
+        if self.fields.is_empty() && target.abi == CAbi::Msvc {
+            layout.size = layout.alignment;
+            layout.fields = crate::__insert_padding(Vec::new(), layout.size);
+        }
+
+        // Show the fields of nested structs below them.
+        for field in &mut layout.fields {
+            if let Field::Field { name, nested, .. } = field {
+                let ty = self
+                    .fields
+                    .iter()
+                    .find(|(field_name, _)| field_name == name);
+                if let Some((_, CType::Struct(inner))) = ty {
+                    *nested = Some(inner.clone());
+                }
+            }
+        }
+
+        Ok(layout)
+    }
+}
+
+/// The first place where a Rust type's layout differs from the layout a C
+/// compiler gives the same fields. Returned by
+/// [`TypeLayoutInfo::check_against_c_abi`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CAbiMismatch {
+    /// The type isn't `#[repr(C)]`, so the compiler is free to reorder its
+    /// fields and C can't rely on any of them.
+    NotReprC { name: Cow<'static, str> },
+
+    /// A field is at a different offset than C would put it at. Fields of
+    /// nested types are named like `outer.inner`.
+    FieldOffset {
+        name: Cow<'static, str>,
+        expected: usize,
+        actual: usize,
+    },
+
+    /// The type has a different size than the C struct.
+    Size { expected: usize, actual: usize },
+
+    /// The type has a different alignment than the C struct.
+    Alignment { expected: usize, actual: usize },
+}
+
+impl fmt::Display for CAbiMismatch {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CAbiMismatch::NotReprC { name } => {
+                write!(formatter, "{} is not #[repr(C)]", name)
+            }
+            CAbiMismatch::FieldOffset {
+                name,
+                expected,
+                actual,
+            } => write!(
+                formatter,
+                "`{}` is at offset {}, but C puts it at offset {}",
+                name, actual, expected
+            ),
+            CAbiMismatch::Size { expected, actual } => {
+                write!(formatter, "size is {}, but C makes it {}", actual, expected)
+            }
+            CAbiMismatch::Alignment { expected, actual } => write!(
+                formatter,
+                "alignment is {}, but C makes it {}",
+                actual, expected
+            ),
+        }
+    }
+}
+
+impl TypeLayoutInfo {
+    /// Lays out this type's fields the way a C compiler following `abi`
+    /// would for the current target, and returns the first difference from
+    /// the type's actual layout. `None` means that a C struct with the same
+    /// fields, in the same order, matches the type byte for byte.
+    ///
+    /// Fields with primitive types, like `u64` or `f64`, are laid out with
+    /// the size and alignment C gives the matching C type, like `long long`
+    /// or `double`. Other fields keep their own size and alignment, and
+    /// nested fields are checked the same way. Only structs are laid out;
+    /// for enums and unions, only their repr is checked.
+    ///
+    /// ```rust
+    /// use type_layout::c_abi::{CAbi, CAbiMismatch};
+    /// use type_layout::TypeLayout;
+    ///
+    /// #[derive(TypeLayout)]
+    /// #[repr(C)]
+    /// struct Sample {
+    ///     tag: u8,
+    ///     value: f64,
+    ///     count: u16,
+    /// }
+    ///
+    /// #[derive(TypeLayout)]
+    /// struct Native {
+    ///     tag: u8,
+    ///     value: f64,
+    /// }
+    ///
+    /// assert_eq!(Sample::type_layout().check_against_c_abi(CAbi::SystemV), None);
+    /// assert_eq!(
+    ///     Native::type_layout().check_against_c_abi(CAbi::SystemV),
+    ///     Some(CAbiMismatch::NotReprC {
+    ///         name: "Native".into()
+    ///     }),
+    /// );
+    /// ```
+    pub fn check_against_c_abi(&self, abi: CAbi) -> Option<CAbiMismatch> {
+        let target = CTarget {
+            abi,
+            pointer_width: core::mem::size_of::<usize>(),
+        };
+
+        c_layout_of(self, target, "").err()
+    }
+}
+
+/// Lays out the fields of `layout` like C would, returning the C layout if
+/// it matches. `prefix` is prepended to the names of mismatched fields.
+fn c_layout_of(
+    layout: &TypeLayoutInfo,
+    target: CTarget,
+    prefix: &str,
+) -> Result<TypeLayoutInfo, CAbiMismatch> {
+    if !layout.repr.c && !layout.repr.transparent {
+        return Err(CAbiMismatch::NotReprC {
+            name: Cow::Owned(layout.full_name().into_owned()),
+        });
+    }
+
+    // A transparent type is laid out like its only field, and enums and
+    // unions aren't laid out here.
+    if layout.repr.transparent || layout.is_union || !layout.variants.is_empty() {
+        return Ok(layout.clone());
+    }
+
+    let fields: Vec<&Field> = layout
+        .fields
+        .iter()
+        .filter(|field| !field.is_padding())
+        .collect();
+
+    let mut c_struct = CStruct::new(layout.name.clone());
+    for field in &fields {
+        let (name, ty) = match field {
+            Field::Field {
+                name,
+                ty,
+                resolved_ty,
+                size,
+                alignment,
+                array,
+                nested,
+                ..
+            } => {
+                let ty = resolved_ty.as_ref().unwrap_or(ty);
+                let alignment = alignment.unwrap_or(1);
+
+                let c_type = match (nested, array) {
+                    (Some(nested), array) => {
+                        let prefix = crate::nested_prefix(&format!("{}{}", prefix, name), array);
+                        let nested = CType::Struct(Box::new(c_layout_of(nested, target, &prefix)?));
+                        match array {
+                            Some(array) => CType::Array(Box::new(nested), array.len),
+                            None => nested,
+                        }
+                    }
+                    (None, Some(array)) => {
+                        let elem = c_type_of(&array.elem_ty, array.stride, alignment);
+                        CType::Array(Box::new(elem), array.len)
+                    }
+                    (None, None) => c_type_of(ty, *size, alignment),
+                };
+
+                (name, c_type)
+            }
+            Field::Padding { .. } => continue,
+        };
+
+        c_struct = c_struct.field(name.clone(), ty);
+    }
+
+    if let Some(packed) = layout.repr.packed {
+        c_struct = c_struct.pack(packed);
+    }
+    if let Some(align) = layout.repr.align {
+        c_struct = c_struct.align(align);
+    }
+
+    let c_layout = match c_struct.layout(target) {
+        Ok(c_layout) => c_layout,
+        Err(_) => return Ok(layout.clone()),
+    };
+
+    let c_fields = c_layout.fields.iter().filter(|field| !field.is_padding());
+    for (field, c_field) in fields.iter().zip(c_fields) {
+        if field.offset() != c_field.offset() {
+            let name = match field {
+                Field::Field { name, .. } => name,
+                Field::Padding { .. } => continue,
+            };
+
+            return Err(CAbiMismatch::FieldOffset {
+                name: Cow::Owned(format!("{}{}", prefix, name)),
+                expected: c_field.offset(),
+                actual: field.offset(),
+            });
+        }
+    }
+
+    if c_layout.size != layout.size {
+        return Err(CAbiMismatch::Size {
+            expected: c_layout.size,
+            actual: layout.size,
+        });
+    }
+
+    if c_layout.alignment != layout.alignment {
+        return Err(CAbiMismatch::Alignment {
+            expected: c_layout.alignment,
+            actual: layout.alignment,
+        });
+    }
+
+    Ok(c_layout)
+}
+
+/// The C type matching a Rust type, given by its name. Types that aren't
+/// primitives keep their size and alignment.
+fn c_type_of(ty: &str, size: usize, alignment: usize) -> CType {
+    match ty {
+        "bool" => CType::Bool,
+        "u8" | "i8" => CType::Char,
+        "u16" | "i16" => CType::Short,
+        "u32" | "i32" => CType::Int,
+        "u64" | "i64" => CType::LongLong,
+        "f32" => CType::Float,
+        "f64" => CType::Double,
+        "usize" | "isize" => CType::Pointer,
+        _ => CType::Custom {
+            name: Cow::Owned(String::from(ty)),
+            size,
+            alignment,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use super::*;
+
+    const TARGETS: [CTarget; 4] = [
+        CTarget::X86_64_SYSV,
+        CTarget::X86_64_MSVC,
+        CTarget::I686_SYSV,
+        CTarget::I686_MSVC,
+    ];
+
+    fn offset(layout: &TypeLayoutInfo, name: &str) -> usize {
+        layout.field(name).unwrap().offset()
+    }
+
+    #[test]
+    fn primitive_sizes() {
+        // The size and alignment of each type on x86_64 System V, x86_64
+        // MSVC, i686 System V, and i686 MSVC, in that order.
+        let expected = [
+            (CType::Bool, [(1, 1); 4]),
+            (CType::Char, [(1, 1); 4]),
+            (CType::Short, [(2, 2); 4]),
+            (CType::Int, [(4, 4); 4]),
+            (CType::Float, [(4, 4); 4]),
+            (CType::Long, [(8, 8), (4, 4), (4, 4), (4, 4)]),
+            (CType::LongLong, [(8, 8), (8, 8), (8, 4), (8, 8)]),
+            (CType::Double, [(8, 8), (8, 8), (8, 4), (8, 8)]),
+            (CType::LongDouble, [(16, 16), (8, 8), (12, 4), (8, 8)]),
+            (CType::Pointer, [(8, 8), (8, 8), (4, 4), (4, 4)]),
+        ];
+
+        for (ty, sizes) in &expected {
+            for (target, size) in TARGETS.iter().zip(sizes) {
+                assert_eq!(
+                    ty.size_align(*target),
+                    *size,
+                    "{} on {:?}",
+                    ty.name(),
+                    target
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn i686_sysv_aligns_8_byte_types_to_4() {
+        for ty in [CType::Double, CType::LongLong] {
+            let sample = CStruct::new("sample")
+                .field("tag", CType::Char)
+                .field("value", ty);
+
+            let layout = sample.layout(CTarget::I686_SYSV).unwrap();
+            assert_eq!((layout.size, layout.alignment), (12, 4));
+            assert_eq!(offset(&layout, "value"), 4);
+
+            let layout = sample.layout(CTarget::I686_MSVC).unwrap();
+            assert_eq!((layout.size, layout.alignment), (16, 8));
+            assert_eq!(offset(&layout, "value"), 8);
+        }
+
+        let array = CType::Array(Box::new(CType::Double), 3);
+        assert_eq!(array.size_align(CTarget::I686_SYSV), (24, 4));
+        assert_eq!(array.name(), "double[3]");
+    }
+
+    #[test]
+    fn long_double() {
+        let sample = CStruct::new("sample")
+            .field("tag", CType::Char)
+            .field("value", CType::LongDouble);
+
+        let layout = sample.layout(CTarget::I686_SYSV).unwrap();
+        assert_eq!((layout.size, layout.alignment), (16, 4));
+        assert_eq!(offset(&layout, "value"), 4);
+
+        let layout = sample.layout(CTarget::X86_64_SYSV).unwrap();
+        assert_eq!((layout.size, layout.alignment), (32, 16));
+        assert_eq!(offset(&layout, "value"), 16);
+
+        let layout = sample.layout(CTarget::X86_64_MSVC).unwrap();
+        assert_eq!((layout.size, layout.alignment), (16, 8));
+    }
+
+    #[test]
+    fn msvc_long_is_4_bytes() {
+        let sample = CStruct::new("sample")
+            .field("tag", CType::Char)
+            .field("value", CType::Long);
+
+        let layout = sample.layout(CTarget::X86_64_MSVC).unwrap();
+        assert_eq!((layout.size, layout.alignment), (8, 4));
+        assert_eq!(offset(&layout, "value"), 4);
+
+        let layout = sample.layout(CTarget::X86_64_SYSV).unwrap();
+        assert_eq!((layout.size, layout.alignment), (16, 8));
+        assert_eq!(offset(&layout, "value"), 8);
+    }
+
+    #[test]
+    fn empty_structs() {
+        let empty = CStruct::new("empty");
+
+        for target in [CTarget::X86_64_SYSV, CTarget::I686_SYSV] {
+            let layout = empty.layout(target).unwrap();
+            assert_eq!((layout.size, layout.alignment), (0, 1));
+            assert!(layout.fields.is_empty());
+        }
+
+        for target in [CTarget::X86_64_MSVC, CTarget::I686_MSVC] {
+            let layout = empty.layout(target).unwrap();
+            assert_eq!((layout.size, layout.alignment), (1, 1));
+            assert_eq!(layout.padding_bytes(), 1);
+        }
+
+        let layout = empty.clone().align(8).layout(CTarget::X86_64_MSVC).unwrap();
+        assert_eq!((layout.size, layout.alignment), (8, 8));
+
+        let layout = empty.align(8).layout(CTarget::X86_64_SYSV).unwrap();
+        assert_eq!((layout.size, layout.alignment), (0, 8));
+    }
+
+    #[test]
+    fn pack_and_align() {
+        let sample = CStruct::new("sample")
+            .field("tag", CType::Char)
+            .field("value", CType::Double);
+
+        let packed = sample.clone().pack(2).layout(CTarget::X86_64_SYSV).unwrap();
+        assert_eq!((packed.size, packed.alignment), (10, 2));
+        assert_eq!(offset(&packed, "value"), 2);
+
+        // Packing only ever lowers alignment.
+        let loose = sample
+            .clone()
+            .pack(16)
+            .layout(CTarget::X86_64_SYSV)
+            .unwrap();
+        assert_eq!((loose.size, loose.alignment), (16, 8));
+
+        // Packing places the fields, then the struct's alignment is raised,
+        // which only adds tail padding.
+        let both = sample
+            .clone()
+            .pack(1)
+            .align(16)
+            .layout(CTarget::X86_64_SYSV)
+            .unwrap();
+        assert_eq!((both.size, both.alignment), (16, 16));
+        assert_eq!(offset(&both, "value"), 1);
+
+        // Aligning to less than the fields need does nothing.
+        let weak = sample.align(2).layout(CTarget::X86_64_SYSV).unwrap();
+        assert_eq!((weak.size, weak.alignment), (16, 8));
+
+        let invalid = CStruct::new("invalid")
+            .field("tag", CType::Char)
+            .pack(3)
+            .layout(CTarget::X86_64_SYSV);
+        assert_eq!(invalid.unwrap_err(), LayoutError::InvalidAlignment(3));
+    }
+
+    #[test]
+    fn nested_structs() {
+        let point = CStruct::new("point")
+            .field("x", CType::Short)
+            .field("y", CType::Int)
+            .layout(CTarget::X86_64_SYSV)
+            .unwrap();
+
+        let shape = CStruct::new("shape")
+            .field("kind", CType::Char)
+            .field("origin", CType::Struct(Box::new(point)))
+            .field("points", CType::Array(Box::new(CType::Pointer), 2))
+            .layout(CTarget::X86_64_SYSV)
+            .unwrap();
+
+        let table = shape
+            .display_with(crate::TableOptions::new().types(true))
+            .to_string();
+        let lines: Vec<_> = table.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "shape (size 32, alignment 8)",
+                "| Offset | Name        | Type         | Size |",
+                "| ------ | ----------- | ------------ | ---- |",
+                "| 0      | kind        | char         | 1    |",
+                "| 1      | [padding]   |              | 3    |",
+                "| 4      | origin      | struct point | 8    |",
+                "| 4      |   x         | short        | 2    |",
+                "| 6      |   [padding] |              | 2    |",
+                "| 8      |   y         | int          | 4    |",
+                "| 12     | [padding]   |              | 4    |",
+                "| 16     | points      | void *[2]    | 16   |",
+            ]
+        );
+    }
+}
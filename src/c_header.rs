@@ -0,0 +1,410 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use crate::{Field, TypeLayoutInfo};
+
+impl TypeLayoutInfo {
+    /// Generates a C header declaring a struct with this exact layout, for
+    /// the C side of an FFI boundary.
+    ///
+    /// Padding is spelled out as `uint8_t _padN[...]` members, so the C
+    /// compiler doesn't need to insert any, and `_Static_assert`s check the
+    /// size, alignment, and the offset of each field when the header is
+    /// compiled. Fields with primitive or pointer types get the matching C
+    /// type. Other fields become byte arrays with the same size and
+    /// alignment, except for nested fields, whose structs are declared first.
+    ///
+    /// Enums are declared as their tag followed by the bytes of the payload.
+    /// The header is for the target the layout was computed on, and needs a
+    /// C11 compiler.
+    ///
+    /// ```rust
+    /// use type_layout::TypeLayout;
+    ///
+    /// #[derive(TypeLayout)]
+    /// #[repr(C)]
+    /// struct Packet {
+    ///     kind: u8,
+    ///     len: u32,
+    ///     payload: [u16; 4],
+    /// }
+    ///
+    /// print!("{}", Packet::type_layout().to_c_header());
+    /// // prints:
+    /// // #include <stddef.h>
+    /// // #include <stdint.h>
+    /// //
+    /// // struct Packet {
+    /// //     uint8_t kind;
+    /// //     uint8_t _pad0[3];
+    /// //     uint32_t len;
+    /// //     uint16_t payload[4];
+    /// // };
+    /// //
+    /// // _Static_assert(sizeof(struct Packet) == 16, "wrong size for Packet");
+    /// // _Static_assert(_Alignof(struct Packet) == 4, "wrong alignment for Packet");
+    /// // _Static_assert(offsetof(struct Packet, kind) == 0, "wrong offset for Packet.kind");
+    /// // _Static_assert(offsetof(struct Packet, len) == 4, "wrong offset for Packet.len");
+    /// // _Static_assert(offsetof(struct Packet, payload) == 8, "wrong offset for Packet.payload");
+    /// ```
+    pub fn to_c_header(&self) -> String {
+        let mut header = String::from("#include <stddef.h>\n#include <stdint.h>\n");
+        let mut declared = Vec::new();
+        declare(&mut header, self, &mut declared);
+        header
+    }
+}
+
+/// One member of a generated struct.
+struct Member {
+    declaration: String,
+    alignment: usize,
+
+    /// The member's name and offset, for members whose offset is checked.
+    checked: Option<(String, usize)>,
+}
+
+/// Declares a struct for `layout`, after the structs of its nested fields.
+/// `declared` holds the names of the structs that have been declared so far.
+fn declare(out: &mut String, layout: &TypeLayoutInfo, declared: &mut Vec<String>) {
+    let name = identifier(&layout.full_name());
+    if declared.contains(&name) {
+        return;
+    }
+
+    for field in &layout.fields {
+        if let Field::Field {
+            nested: Some(nested),
+            ..
+        } = field
+        {
+            declare(out, nested, declared);
+        }
+    }
+
+    declared.push(name.clone());
+
+    let keyword = struct_keyword(layout);
+    let mut members = if layout.variants.is_empty() {
+        field_members(layout)
+    } else {
+        enum_members(layout)
+    };
+
+    // Raise the alignment of the struct to that of the Rust type, which is
+    // more than that of its members with `#[repr(align(N))]`, or when an
+    // enum's payload is more aligned than its tag.
+    let members_alignment = members.iter().map(|member| member.alignment).max();
+    if let Some(first) = members.first_mut() {
+        if members_alignment.unwrap_or(1) < layout.alignment {
+            first.declaration = format!("_Alignas({}) {}", layout.alignment, first.declaration);
+        }
+    }
+
+    out.push('\n');
+    if let Some(packed) = layout.repr.packed {
+        let _ = writeln!(out, "#pragma pack(push, {})", packed);
+    }
+
+    let _ = writeln!(out, "{} {} {{", keyword, name);
+    for member in &members {
+        let _ = writeln!(out, "    {};", member.declaration);
+    }
+    let _ = writeln!(out, "}};");
+
+    if layout.repr.packed.is_some() {
+        let _ = writeln!(out, "#pragma pack(pop)");
+    }
+
+    let _ = writeln!(out);
+    let _ = writeln!(
+        out,
+        "_Static_assert(sizeof({} {}) == {}, \"wrong size for {}\");",
+        keyword, name, layout.size, name
+    );
+    let _ = writeln!(
+        out,
+        "_Static_assert(_Alignof({} {}) == {}, \"wrong alignment for {}\");",
+        keyword, name, layout.alignment, name
+    );
+
+    for (member, offset) in members.iter().filter_map(|member| member.checked.as_ref()) {
+        let _ = writeln!(
+            out,
+            "_Static_assert(offsetof({} {}, {}) == {}, \"wrong offset for {}.{}\");",
+            keyword, name, member, offset, name, member
+        );
+    }
+}
+
+fn struct_keyword(layout: &TypeLayoutInfo) -> &'static str {
+    if layout.is_union {
+        "union"
+    } else {
+        "struct"
+    }
+}
+
+/// The members of a struct or union, including padding before an unsized
+/// tail, which becomes a flexible array member.
+fn field_members(layout: &TypeLayoutInfo) -> Vec<Member> {
+    let mut members = Vec::new();
+    let mut padding = 0;
+    let mut opaque = 0;
+    let tail_offset = layout.unsized_tail.as_ref().map(|tail| tail.offset);
+
+    for field in &layout.fields {
+        match field {
+            Field::Padding { offset, size, .. } => {
+                // Union members all start at offset 0, so padding isn't a
+                // member of its own.
+                if layout.is_union {
+                    continue;
+                }
+
+                // Padding after the start of an unsized tail is covered by
+                // the tail, which C places at the same offset.
+                let size = match tail_offset {
+                    Some(tail_offset) if *offset >= tail_offset => continue,
+                    Some(tail_offset) => (*size).min(tail_offset - offset),
+                    None => *size,
+                };
+
+                members.push(Member {
+                    declaration: format!("uint8_t _pad{}[{}]", padding, size),
+                    alignment: 1,
+                    checked: None,
+                });
+                padding += 1;
+            }
+            Field::Field {
+                name,
+                ty,
+                resolved_ty,
+                offset,
+                size,
+                alignment,
+                array,
+                nested,
+                ..
+            } => {
+                let ty = resolved_ty.as_ref().unwrap_or(ty);
+                let alignment = alignment.unwrap_or(1);
+
+                let member_name = if ty.is_empty() {
+                    opaque += 1;
+                    format!("_opaque{}", opaque - 1)
+                } else {
+                    identifier(name)
+                };
+
+                let declaration = match (nested, array) {
+                    (Some(nested), Some(array)) => format!(
+                        "{} {} {}[{}]",
+                        struct_keyword(nested),
+                        identifier(&nested.full_name()),
+                        member_name,
+                        array.len
+                    ),
+                    (Some(nested), None) => format!(
+                        "{} {} {}",
+                        struct_keyword(nested),
+                        identifier(&nested.full_name()),
+                        member_name
+                    ),
+                    (None, Some(array)) => match c_type_name(&array.elem_ty, array.stride) {
+                        Some(elem) => declarator(elem, &format!("{}[{}]", member_name, array.len)),
+                        None => opaque_member(&member_name, *size, alignment, ty),
+                    },
+                    (None, None) => match c_type_name(ty, *size) {
+                        Some(c_type) => declarator(c_type, &member_name),
+                        None => opaque_member(&member_name, *size, alignment, ty),
+                    },
+                };
+
+                members.push(Member {
+                    declaration,
+                    alignment,
+                    checked: Some((member_name, *offset)),
+                });
+            }
+        }
+    }
+
+    if let Some(tail) = &layout.unsized_tail {
+        let name = identifier(&tail.name);
+        let elem = match tail.ty.as_ref() {
+            "str" => Some("uint8_t"),
+            ty if ty.starts_with('[') && ty.ends_with(']') => {
+                c_type_name(&ty[1..ty.len() - 1], tail.stride.unwrap_or(0))
+            }
+            _ => None,
+        };
+
+        let (declaration, alignment) = match elem {
+            Some(elem) => (
+                declarator(elem, &format!("{}[]", name)),
+                tail.stride.unwrap_or(1),
+            ),
+            None => (format!("uint8_t {}[] /* {} */", name, tail.ty), 1),
+        };
+
+        members.push(Member {
+            declaration,
+            alignment,
+            checked: Some((name, tail.offset)),
+        });
+    }
+
+    members
+}
+
+/// The members of an enum: its tag, and the bytes of the payload after it.
+fn enum_members(layout: &TypeLayoutInfo) -> Vec<Member> {
+    let mut members = Vec::new();
+
+    let tag = match &layout.tag {
+        Some(tag) => tag,
+        None => return members,
+    };
+
+    let tag_type = match tag.ty.as_ref() {
+        "C enum" => "int",
+        ty => c_type_name(ty, tag.size).unwrap_or("int"),
+    };
+    members.push(Member {
+        declaration: format!("{} tag", tag_type),
+        alignment: tag.size,
+        checked: Some((String::from("tag"), tag.offset)),
+    });
+
+    let tag_end = tag.offset + tag.size;
+    if tag.payload_offset > tag_end {
+        members.push(Member {
+            declaration: format!("uint8_t _pad0[{}]", tag.payload_offset - tag_end),
+            alignment: 1,
+            checked: None,
+        });
+    }
+
+    if layout.size > tag.payload_offset {
+        members.push(Member {
+            declaration: format!("uint8_t payload[{}]", layout.size - tag.payload_offset),
+            alignment: 1,
+            checked: Some((String::from("payload"), tag.payload_offset)),
+        });
+    }
+
+    members
+}
+
+/// Declares `name` with a C type, keeping `*` next to the name.
+fn declarator(c_type: &str, name: &str) -> String {
+    if c_type.ends_with('*') {
+        format!("{}{}", c_type, name)
+    } else {
+        format!("{} {}", c_type, name)
+    }
+}
+
+/// A byte array standing in for a field whose type has no C equivalent.
+fn opaque_member(name: &str, size: usize, alignment: usize, ty: &str) -> String {
+    let comment = if ty.is_empty() {
+        String::new()
+    } else {
+        format!(" /* {} */", ty.replace("*/", "* /"))
+    };
+
+    if alignment > 1 {
+        format!(
+            "_Alignas({}) uint8_t {}[{}]{}",
+            alignment, name, size, comment
+        )
+    } else {
+        format!("uint8_t {}[{}]{}", name, size, comment)
+    }
+}
+
+/// The C type for a Rust type of the given size, if there's one with the
+/// same layout.
+fn c_type_name(ty: &str, size: usize) -> Option<&'static str> {
+    let c_type = match ty {
+        "u8" => "uint8_t",
+        "i8" => "int8_t",
+        "u16" => "uint16_t",
+        "i16" => "int16_t",
+        "u32" | "char" => "uint32_t",
+        "i32" => "int32_t",
+        "u64" => "uint64_t",
+        "i64" => "int64_t",
+        "usize" => "uintptr_t",
+        "isize" => "intptr_t",
+        "f32" => "float",
+        "f64" => "double",
+        "bool" => "_Bool",
+        _ if is_pointer(ty) && size == core::mem::size_of::<usize>() => "void *",
+        _ => return None,
+    };
+
+    Some(c_type)
+}
+
+/// Whether a type is a thin pointer, or an `Option` of one.
+pub(crate) fn is_pointer(ty: &str) -> bool {
+    let ty = ty
+        .strip_prefix("core::option::Option<")
+        .or_else(|| ty.strip_prefix("Option<"))
+        .unwrap_or(ty);
+
+    ty.starts_with('*')
+        || ty.starts_with('&')
+        || ty.starts_with("fn(")
+        || ty.starts_with("unsafe ")
+        || ty.starts_with("extern ")
+        || ty.starts_with("core::ptr::non_null::NonNull<")
+        || ty.starts_with("NonNull<")
+        || ty.starts_with("alloc::boxed::Box<")
+        || ty.starts_with("Box<")
+}
+
+/// Turns a Rust name into a C identifier.
+fn identifier(name: &str) -> String {
+    let mut identifier = sanitize(name);
+    if is_c_keyword(&identifier) {
+        identifier.insert(0, '_');
+    }
+
+    identifier
+}
+
+/// Turns a Rust name into an identifier for C-like languages, replacing
+/// characters that can't appear in one with underscores. Keywords are left
+/// to the caller.
+pub(crate) fn sanitize(name: &str) -> String {
+    let mut identifier: String = name
+        .chars()
+        .map(|ch| if ch.is_ascii_alphanumeric() { ch } else { '_' })
+        .collect();
+
+    while identifier.ends_with('_') && identifier.len() > 1 {
+        identifier.pop();
+    }
+
+    if identifier.starts_with(|ch: char| ch.is_ascii_digit()) {
+        identifier.insert(0, '_');
+    }
+
+    identifier
+}
+
+fn is_c_keyword(name: &str) -> bool {
+    [
+        "auto", "break", "case", "char", "const", "continue", "default", "do", "double", "else",
+        "enum", "extern", "float", "for", "goto", "if", "inline", "int", "long", "register",
+        "restrict", "return", "short", "signed", "sizeof", "static", "struct", "switch", "typedef",
+        "union", "unsigned", "void", "volatile", "while",
+    ]
+    .contains(&name)
+}
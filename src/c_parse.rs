@@ -0,0 +1,940 @@
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::c_abi::{CStruct, CTarget, CType};
+use crate::{LayoutError, TypeLayoutInfo};
+
+/// An error from [`parse_struct`](crate::c_abi::parse_struct).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CParseError {
+    /// A token that can't appear where it was found.
+    UnexpectedToken {
+        found: String,
+        expected: &'static str,
+    },
+
+    /// The source ended in the middle of a declaration.
+    UnexpectedEnd,
+
+    /// A type that isn't a primitive C type, a type from `<stdint.h>` or
+    /// `<stddef.h>`, or a struct declared earlier in the source.
+    UnknownType(String),
+
+    /// A C feature the parser doesn't handle, like bitfields.
+    Unsupported(&'static str),
+
+    /// The source doesn't declare any struct.
+    NoStruct,
+
+    /// Laying out a struct failed.
+    Layout(LayoutError),
+}
+
+impl fmt::Display for CParseError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CParseError::UnexpectedToken { found, expected } => {
+                write!(formatter, "expected {}, found `{}`", expected, found)
+            }
+            CParseError::UnexpectedEnd => write!(formatter, "unexpected end of input"),
+            CParseError::UnknownType(ty) => write!(formatter, "unknown type `{}`", ty),
+            CParseError::Unsupported(feature) => {
+                write!(formatter, "{} are not supported", feature)
+            }
+            CParseError::NoStruct => write!(formatter, "no struct was declared"),
+            CParseError::Layout(error) => write!(formatter, "{}", error),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CParseError {}
+
+impl From<LayoutError> for CParseError {
+    fn from(error: LayoutError) -> Self {
+        CParseError::Layout(error)
+    }
+}
+
+/// Parses C struct declarations and lays out the last one for `target`.
+///
+/// The source can declare several structs, with or without `typedef`, and
+/// later structs can have fields of earlier ones. Fields can have primitive
+/// types, types from `<stdint.h>` and `<stddef.h>`, pointers, function
+/// pointers, and arrays. `#pragma pack`, `__attribute__((packed))`, and
+/// `__attribute__((aligned(N)))` are followed. Comments and other
+/// preprocessor lines are skipped, but macros aren't expanded.
+///
+/// Field types keep their C names, so diffing the result against a Rust type
+/// reports a [`FieldRetyped`](crate::LayoutChange::FieldRetyped) change for
+/// most fields. The other changes show where the layouts differ.
+///
+/// ```rust
+/// use type_layout::c_abi::{parse_struct, CTarget};
+/// use type_layout::{LayoutChange, TypeLayout};
+///
+/// let c_header = parse_struct(
+///     "struct header {
+///         uint8_t kind;
+///         uint32_t len;
+///         uint16_t flags;
+///     };",
+///     CTarget::X86_64_SYSV,
+/// )
+/// .unwrap();
+///
+/// assert_eq!(c_header.size, 12);
+/// assert_eq!(c_header.field("flags").unwrap().offset(), 8);
+///
+/// #[derive(TypeLayout)]
+/// #[repr(C)]
+/// struct Header {
+///     kind: u8,
+///     flags: u16,
+///     len: u32,
+/// }
+///
+/// let diff = c_header.diff(&Header::type_layout());
+/// let changes: Vec<_> = diff
+///     .changes
+///     .iter()
+///     .filter(|change| !matches!(change, LayoutChange::FieldRetyped { .. }))
+///     .collect();
+///
+/// for change in changes {
+///     println!("{}", change);
+/// }
+/// // prints:
+/// // size changed from 12 to 8
+/// // padding changed from 5 to 1 bytes
+/// // `flags` moved from offset 8 to 2
+/// ```
+pub fn parse_struct(source: &str, target: CTarget) -> Result<TypeLayoutInfo, CParseError> {
+    let tokens = tokenize(source)?;
+    let mut parser = Parser {
+        tokens,
+        position: 0,
+        target,
+        structs: Vec::new(),
+        pack: None,
+        pack_stack: Vec::new(),
+    };
+
+    let mut last = None;
+    while let Some(token) = parser.peek() {
+        match token {
+            Token::Pack(action) => {
+                let action = *action;
+                parser.position += 1;
+                parser.apply_pack(action);
+            }
+            Token::Punct(';') => parser.position += 1,
+            _ => last = Some(parser.declaration()?),
+        }
+    }
+
+    last.ok_or(CParseError::NoStruct)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token<'a> {
+    Ident(&'a str),
+    Number(usize),
+    Punct(char),
+    Pack(PackAction),
+}
+
+/// The effect of a `#pragma pack` line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PackAction {
+    Set(Option<usize>),
+    Push(Option<usize>),
+    Pop,
+}
+
+impl fmt::Display for Token<'_> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Token::Ident(ident) => write!(formatter, "{}", ident),
+            Token::Number(number) => write!(formatter, "{}", number),
+            Token::Punct(punct) => write!(formatter, "{}", punct),
+            Token::Pack(_) => write!(formatter, "#pragma pack"),
+        }
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token<'_>>, CParseError> {
+    let mut tokens = Vec::new();
+    let mut rest = source;
+    let mut line_start = true;
+
+    while let Some(ch) = rest.chars().next() {
+        if ch == '\n' {
+            line_start = true;
+            rest = &rest[1..];
+        } else if ch.is_whitespace() {
+            rest = &rest[ch.len_utf8()..];
+        } else if rest.starts_with("//") {
+            rest = rest.find('\n').map_or("", |end| &rest[end..]);
+        } else if rest.starts_with("/*") {
+            let end = rest.find("*/").ok_or(CParseError::UnexpectedEnd)?;
+            rest = &rest[end + 2..];
+        } else if ch == '#' && line_start {
+            let end = rest.find('\n').unwrap_or(rest.len());
+            if let Some(action) = pragma_pack(&rest[1..end])? {
+                tokens.push(Token::Pack(action));
+            }
+            rest = &rest[end..];
+        } else if ch.is_ascii_alphabetic() || ch == '_' {
+            let end = rest
+                .find(|ch: char| !ch.is_ascii_alphanumeric() && ch != '_')
+                .unwrap_or(rest.len());
+            tokens.push(Token::Ident(&rest[..end]));
+            rest = &rest[end..];
+            line_start = false;
+        } else if ch.is_ascii_digit() {
+            let end = rest
+                .find(|ch: char| !ch.is_ascii_alphanumeric())
+                .unwrap_or(rest.len());
+            tokens.push(Token::Number(parse_number(&rest[..end])?));
+            rest = &rest[end..];
+            line_start = false;
+        } else {
+            tokens.push(Token::Punct(ch));
+            rest = &rest[ch.len_utf8()..];
+            line_start = false;
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Parses an integer literal, ignoring suffixes like `u` and `UL`.
+fn parse_number(literal: &str) -> Result<usize, CParseError> {
+    let digits = literal.trim_end_matches(['u', 'U', 'l', 'L']);
+    let parsed = match digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+    {
+        Some(hex) => usize::from_str_radix(hex, 16),
+        None if digits.len() > 1 && digits.starts_with('0') => {
+            usize::from_str_radix(&digits[1..], 8)
+        }
+        None => digits.parse(),
+    };
+
+    parsed.map_err(|_| CParseError::UnexpectedToken {
+        found: String::from(literal),
+        expected: "an integer",
+    })
+}
+
+/// Parses the directive after a `#`, returning its action if it's
+/// `#pragma pack`. Other directives are skipped.
+fn pragma_pack(directive: &str) -> Result<Option<PackAction>, CParseError> {
+    let mut words = directive.split_whitespace();
+    if words.next() != Some("pragma") {
+        return Ok(None);
+    }
+
+    let rest: String = words.collect();
+    let arguments = match rest.strip_prefix("pack") {
+        Some(arguments) => arguments,
+        None => return Ok(None),
+    };
+
+    let arguments = arguments
+        .strip_prefix('(')
+        .and_then(|arguments| arguments.strip_suffix(')'))
+        .ok_or(CParseError::UnexpectedToken {
+            found: String::from(directive.trim()),
+            expected: "`#pragma pack(...)`",
+        })?;
+
+    let mut arguments = arguments.split(',');
+    let action = match arguments.next() {
+        Some("") | None => PackAction::Set(None),
+        Some("push") => PackAction::Push(arguments.next().map(parse_number).transpose()?),
+        Some("pop") => PackAction::Pop,
+        Some(pack) => PackAction::Set(Some(parse_number(pack)?)),
+    };
+
+    Ok(Some(action))
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token<'a>>,
+    position: usize,
+    target: CTarget,
+
+    /// The structs declared so far, with every name they can be referred to
+    /// by: their tag, and the names given to them by `typedef`.
+    structs: Vec<(Vec<String>, TypeLayoutInfo)>,
+
+    pack: Option<usize>,
+    pack_stack: Vec<Option<usize>>,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&Token<'a>> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Result<Token<'a>, CParseError> {
+        let token = *self.peek().ok_or(CParseError::UnexpectedEnd)?;
+        self.position += 1;
+        Ok(token)
+    }
+
+    fn eat(&mut self, expected: Token<'_>) -> bool {
+        if self.peek() == Some(&expected) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_punct(&mut self, punct: char, expected: &'static str) -> Result<(), CParseError> {
+        match self.next()? {
+            Token::Punct(found) if found == punct => Ok(()),
+            found => Err(unexpected(found, expected)),
+        }
+    }
+
+    fn expect_ident(&mut self, expected: &'static str) -> Result<&'a str, CParseError> {
+        match self.next()? {
+            Token::Ident(ident) => Ok(ident),
+            found => Err(unexpected(found, expected)),
+        }
+    }
+
+    fn expect_number(&mut self) -> Result<usize, CParseError> {
+        match self.next()? {
+            Token::Number(number) => Ok(number),
+            found => Err(unexpected(found, "an integer")),
+        }
+    }
+
+    fn apply_pack(&mut self, action: PackAction) {
+        match action {
+            PackAction::Set(pack) => self.pack = pack,
+            PackAction::Push(pack) => {
+                self.pack_stack.push(self.pack);
+                if pack.is_some() {
+                    self.pack = pack;
+                }
+            }
+            PackAction::Pop => self.pack = self.pack_stack.pop().flatten(),
+        }
+    }
+
+    /// Parses `[typedef] struct [name] { fields } [names];` and lays out the
+    /// struct.
+    fn declaration(&mut self) -> Result<TypeLayoutInfo, CParseError> {
+        let typedef = self.eat(Token::Ident("typedef"));
+
+        match self.next()? {
+            Token::Ident("struct") => {}
+            Token::Ident("union") => return Err(CParseError::Unsupported("unions")),
+            found => return Err(unexpected(found, "a struct declaration")),
+        }
+
+        let mut attributes = self.attributes()?;
+
+        let tag = match self.peek() {
+            Some(Token::Ident(name)) => {
+                let name = *name;
+                self.position += 1;
+                Some(name)
+            }
+            _ => None,
+        };
+
+        let mut fields = Vec::new();
+        self.expect_punct('{', "`{`")?;
+        while !self.eat(Token::Punct('}')) {
+            fields.extend(self.field()?);
+        }
+
+        let more = self.attributes()?;
+        attributes.packed |= more.packed;
+        attributes.aligned = attributes.aligned.max(more.aligned);
+
+        let mut names: Vec<String> = tag
+            .map(|tag| format!("struct {}", tag))
+            .into_iter()
+            .collect();
+        if typedef {
+            loop {
+                names.push(String::from(self.expect_ident("a typedef name")?));
+                if !self.eat(Token::Punct(',')) {
+                    break;
+                }
+            }
+        }
+        self.expect_punct(';', "`;`")?;
+
+        let name = match (tag, names.last()) {
+            (Some(tag), _) => String::from(tag),
+            (None, Some(name)) => name.clone(),
+            (None, None) => return Err(CParseError::Unsupported("anonymous structs")),
+        };
+
+        let mut c_struct = CStruct::new(name);
+        for (name, ty) in fields {
+            c_struct = c_struct.field(name, ty);
+        }
+
+        match (attributes.packed, self.pack) {
+            (true, _) => c_struct = c_struct.pack(1),
+            (false, Some(pack)) => c_struct = c_struct.pack(pack),
+            (false, None) => {}
+        }
+        if let Some(aligned) = attributes.aligned {
+            c_struct = c_struct.align(aligned);
+        }
+
+        let layout = c_struct.layout(self.target)?;
+        self.structs.push((names, layout.clone()));
+        Ok(layout)
+    }
+
+    /// Parses any number of `__attribute__((...))`.
+    fn attributes(&mut self) -> Result<Attributes, CParseError> {
+        let mut attributes = Attributes::default();
+
+        while self.eat(Token::Ident("__attribute__")) {
+            self.expect_punct('(', "`(`")?;
+            self.expect_punct('(', "`(`")?;
+
+            loop {
+                match self.expect_ident("an attribute")? {
+                    "packed" | "__packed__" => attributes.packed = true,
+                    "aligned" | "__aligned__" => {
+                        self.expect_punct('(', "`(`")?;
+                        let aligned = self.expect_number()?;
+                        self.expect_punct(')', "`)`")?;
+                        attributes.aligned = attributes.aligned.max(Some(aligned));
+                    }
+                    _ => {
+                        return Err(CParseError::Unsupported(
+                            "attributes other than `packed` and `aligned`",
+                        ))
+                    }
+                }
+
+                if !self.eat(Token::Punct(',')) {
+                    break;
+                }
+            }
+
+            self.expect_punct(')', "`)`")?;
+            self.expect_punct(')', "`)`")?;
+        }
+
+        Ok(attributes)
+    }
+
+    /// Parses a field declaration, which can declare several fields of the
+    /// same base type, like `int a, *b, c[4];`.
+    fn field(&mut self) -> Result<Vec<(String, CType)>, CParseError> {
+        let base = self.base_type()?;
+        let mut fields = Vec::new();
+
+        loop {
+            fields.push(self.declarator(&base)?);
+
+            match self.next()? {
+                Token::Punct(',') => continue,
+                Token::Punct(';') => break,
+                Token::Punct(':') => return Err(CParseError::Unsupported("bitfields")),
+                found => return Err(unexpected(found, "`,` or `;`")),
+            }
+        }
+
+        Ok(fields)
+    }
+
+    /// Parses the type specifiers at the start of a field declaration, like
+    /// `unsigned long`, `uint32_t`, or `struct foo`.
+    fn base_type(&mut self) -> Result<BaseType, CParseError> {
+        let mut words = Vec::new();
+
+        while let Some(Token::Ident(word)) = self.peek() {
+            let word = *word;
+
+            match word {
+                "const" | "volatile" => {}
+                "signed" | "unsigned" | "char" | "short" | "int" | "long" | "float" | "double"
+                | "_Bool" | "bool" | "void" => words.push(word),
+                "struct" if words.is_empty() => {
+                    self.position += 1;
+                    let name = self.expect_ident("a struct name")?;
+                    return self.named_type(&format!("struct {}", name));
+                }
+                _ if words.is_empty() => {
+                    self.position += 1;
+                    return self.named_type(word);
+                }
+                _ => break,
+            }
+
+            self.position += 1;
+        }
+
+        let spelling = words.join(" ");
+        let longs = words.iter().filter(|word| **word == "long").count();
+        let has = |name: &str| words.contains(&name);
+
+        let ty = if words.is_empty() {
+            return match self.peek() {
+                Some(token) => Err(unexpected(*token, "a type")),
+                None => Err(CParseError::UnexpectedEnd),
+            };
+        } else if has("void") {
+            None
+        } else if has("_Bool") || has("bool") {
+            Some(CType::Bool)
+        } else if has("char") {
+            Some(CType::Char)
+        } else if has("short") {
+            Some(CType::Short)
+        } else if has("float") {
+            Some(CType::Float)
+        } else if has("double") && longs > 0 {
+            Some(CType::LongDouble)
+        } else if has("double") {
+            Some(CType::Double)
+        } else if longs >= 2 {
+            Some(CType::LongLong)
+        } else if longs == 1 {
+            Some(CType::Long)
+        } else {
+            Some(CType::Int)
+        };
+
+        Ok(BaseType {
+            spelling: Cow::Owned(spelling),
+            ty,
+        })
+    }
+
+    /// Finds a type by name: a type from `<stdint.h>` or `<stddef.h>`, or a
+    /// struct declared earlier.
+    fn named_type(&mut self, name: &str) -> Result<BaseType, CParseError> {
+        let like = match name {
+            "int8_t" | "uint8_t" => Some(CType::Char),
+            "int16_t" | "uint16_t" => Some(CType::Short),
+            "int32_t" | "uint32_t" => Some(CType::Int),
+            "int64_t" | "uint64_t" => Some(CType::LongLong),
+            "size_t" | "ssize_t" | "ptrdiff_t" | "intptr_t" | "uintptr_t" => Some(CType::Pointer),
+            _ => None,
+        };
+
+        if let Some(like) = like {
+            let (size, alignment) = like.size_align(self.target);
+            return Ok(BaseType {
+                spelling: Cow::Owned(String::from(name)),
+                ty: Some(CType::Custom {
+                    name: Cow::Owned(String::from(name)),
+                    size,
+                    alignment,
+                }),
+            });
+        }
+
+        let declared = self
+            .structs
+            .iter()
+            .find(|(names, _)| names.iter().any(|declared| declared == name));
+
+        match declared {
+            Some((_, layout)) => Ok(BaseType {
+                spelling: Cow::Owned(String::from(name)),
+                ty: Some(CType::Struct(Box::new(layout.clone()))),
+            }),
+            None => Err(CParseError::UnknownType(String::from(name))),
+        }
+    }
+
+    /// Parses the name of a field and the pointers and arrays around it, like
+    /// `*name`, `name[4][2]`, or `(*callback)(int)`.
+    fn declarator(&mut self, base: &BaseType) -> Result<(String, CType), CParseError> {
+        let mut pointers = 0;
+        while self.eat(Token::Punct('*')) {
+            pointers += 1;
+            while self.eat(Token::Ident("const")) || self.eat(Token::Ident("volatile")) {}
+        }
+
+        let (name, function_pointer) = if self.eat(Token::Punct('(')) {
+            self.expect_punct('*', "`*`")?;
+            let name = self.expect_ident("a field name")?;
+            self.expect_punct(')', "`)`")?;
+            self.skip_parameters()?;
+            (name, true)
+        } else {
+            (self.expect_ident("a field name")?, false)
+        };
+
+        let mut lengths = Vec::new();
+        while self.eat(Token::Punct('[')) {
+            lengths.push(self.expect_number()?);
+            self.expect_punct(']', "`]`")?;
+        }
+
+        let mut ty = if function_pointer || pointers > 0 {
+            let (size, alignment) = CType::Pointer.size_align(self.target);
+            let name = if function_pointer {
+                Cow::Borrowed("function pointer")
+            } else {
+                Cow::Owned(format!("{} {}", base.spelling, "*".repeat(pointers)))
+            };
+
+            CType::Custom {
+                name,
+                size,
+                alignment,
+            }
+        } else {
+            match &base.ty {
+                Some(CType::Struct(layout)) => CType::Struct(layout.clone()),
+                Some(like) => {
+                    let (size, alignment) = like.size_align(self.target);
+                    CType::Custom {
+                        name: base.spelling.clone(),
+                        size,
+                        alignment,
+                    }
+                }
+                None => return Err(CParseError::UnknownType(String::from("void"))),
+            }
+        };
+
+        for length in lengths.into_iter().rev() {
+            ty = CType::Array(Box::new(ty), length);
+        }
+
+        Ok((String::from(name), ty))
+    }
+
+    /// Skips the parameter list of a function pointer.
+    fn skip_parameters(&mut self) -> Result<(), CParseError> {
+        self.expect_punct('(', "`(`")?;
+
+        let mut depth = 1;
+        while depth > 0 {
+            match self.next()? {
+                Token::Punct('(') => depth += 1,
+                Token::Punct(')') => depth -= 1,
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The attributes of a struct.
+#[derive(Default)]
+struct Attributes {
+    packed: bool,
+    aligned: Option<usize>,
+}
+
+/// The type named by the specifiers of a field declaration, before any
+/// pointers or arrays. `ty` is `None` for `void`.
+struct BaseType {
+    spelling: Cow<'static, str>,
+    ty: Option<CType>,
+}
+
+fn unexpected(found: Token<'_>, expected: &'static str) -> CParseError {
+    CParseError::UnexpectedToken {
+        found: format!("{}", found),
+        expected,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use super::*;
+
+    fn parse(source: &str) -> TypeLayoutInfo {
+        parse_struct(source, CTarget::X86_64_SYSV).unwrap()
+    }
+
+    fn error(source: &str) -> CParseError {
+        parse_struct(source, CTarget::X86_64_SYSV).unwrap_err()
+    }
+
+    /// The offset and size of each field.
+    fn fields(layout: &TypeLayoutInfo) -> Vec<(&str, usize, usize)> {
+        layout
+            .fields
+            .iter()
+            .filter_map(|field| match field {
+                crate::Field::Field {
+                    name, offset, size, ..
+                } => Some((&**name, *offset, *size)),
+                crate::Field::Padding { .. } => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn primitive_spellings() {
+        let layout = parse(
+            "struct s {
+                signed char a;
+                unsigned short int b;
+                const unsigned c;
+                long d;
+                unsigned long long int e;
+                long double f;
+                _Bool g;
+                volatile float h;
+                double i;
+            };",
+        );
+
+        assert_eq!(
+            fields(&layout),
+            [
+                ("a", 0, 1),
+                ("b", 2, 2),
+                ("c", 4, 4),
+                ("d", 8, 8),
+                ("e", 16, 8),
+                ("f", 32, 16),
+                ("g", 48, 1),
+                ("h", 52, 4),
+                ("i", 56, 8),
+            ]
+        );
+        assert_eq!((layout.size, layout.alignment), (64, 16));
+    }
+
+    #[test]
+    fn stdint_and_pointers() {
+        let layout = parse(
+            "struct s {
+                uint8_t kind;
+                int64_t id;
+                size_t len;
+                const char *name, **argv;
+                void (*callback)(void *, int (*)(int));
+                int16_t flags;
+            };",
+        );
+
+        assert_eq!(
+            fields(&layout),
+            [
+                ("kind", 0, 1),
+                ("id", 8, 8),
+                ("len", 16, 8),
+                ("name", 24, 8),
+                ("argv", 32, 8),
+                ("callback", 40, 8),
+                ("flags", 48, 2),
+            ]
+        );
+
+        let i686 = parse_struct("struct s { char c; size_t len; };", CTarget::I686_SYSV).unwrap();
+        assert_eq!(i686.size, 8);
+    }
+
+    #[test]
+    fn arrays_of_arrays() {
+        let layout = parse("struct s { short m[2][3]; char c; int v[0x2u]; };");
+
+        assert_eq!(fields(&layout), [("m", 0, 12), ("c", 12, 1), ("v", 16, 8)]);
+        assert_eq!(layout.size, 24);
+    }
+
+    #[test]
+    fn typedefs_and_nested_structs() {
+        let layout = parse(
+            "typedef struct point { int x, y; } point_t, vec2_t;
+            typedef struct { double w; } weight;
+            struct shape {
+                char kind;
+                struct point origin;
+                vec2_t size;
+                weight weights[2];
+            };",
+        );
+
+        assert_eq!(&*layout.name, "shape");
+        assert_eq!(
+            fields(&layout),
+            [
+                ("kind", 0, 1),
+                ("origin", 4, 8),
+                ("size", 12, 8),
+                ("weights", 24, 16),
+            ]
+        );
+    }
+
+    #[test]
+    fn lays_out_the_last_struct() {
+        let layout = parse("struct a { char c; }; struct b { int i; };;");
+        assert_eq!(&*layout.name, "b");
+    }
+
+    #[test]
+    fn comments_and_preprocessor_lines() {
+        let layout = parse(
+            "#include <stdint.h>
+            #define LEN 4 // not expanded
+            /* struct ignored { int x; }; */
+            struct s {
+                // a line comment
+                uint32_t a; /* a block
+                comment */ uint8_t b;
+            };",
+        );
+
+        assert_eq!(fields(&layout), [("a", 0, 4), ("b", 4, 1)]);
+    }
+
+    #[test]
+    fn pragma_pack() {
+        let source = "
+            #pragma pack(push, 1)
+            struct packed { char c; int i; };
+            #pragma pack(pop)
+            struct normal { char c; int i; };
+            #pragma pack(2)
+            struct two { char c; int i; };
+            #pragma pack()
+            struct reset { char c; int i; };
+        ";
+
+        for (name, size) in [("packed", 5), ("normal", 8), ("two", 6), ("reset", 8)] {
+            let last = format!("struct last {{ struct {} inner; }};", name);
+            let layout = parse(&format!("{}{}", source, last));
+            assert_eq!(layout.size, size, "struct {}", name);
+        }
+    }
+
+    #[test]
+    fn attributes() {
+        let packed = parse("struct __attribute__((packed)) s { char c; int i; };");
+        assert_eq!(packed.size, 5);
+
+        let after = parse("struct s { char c; int i; } __attribute__((__packed__));");
+        assert_eq!(after.size, 5);
+
+        let aligned = parse("struct s { char c; } __attribute__((packed, aligned(16)));");
+        assert_eq!((aligned.size, aligned.alignment), (16, 16));
+    }
+
+    #[test]
+    fn unexpected_token() {
+        let err = error("struct s { int 5; };");
+        assert_eq!(
+            err,
+            CParseError::UnexpectedToken {
+                found: "5".to_string(),
+                expected: "a field name",
+            }
+        );
+        assert_eq!(err.to_string(), "expected a field name, found `5`");
+
+        assert_eq!(
+            error("struct s { int a } ;").to_string(),
+            "expected `,` or `;`, found `}`"
+        );
+        assert_eq!(
+            error("enum e { A };").to_string(),
+            "expected a struct declaration, found `enum`"
+        );
+        assert_eq!(
+            error("struct s { int a[08]; };").to_string(),
+            "expected an integer, found `08`"
+        );
+        assert_eq!(
+            error("#pragma pack 1\nstruct s { int a; };").to_string(),
+            "expected `#pragma pack(...)`, found `pragma pack 1`"
+        );
+    }
+
+    #[test]
+    fn unexpected_end() {
+        for source in [
+            "struct s { int a;",
+            "struct s { int a; }",
+            "struct s { int",
+            "struct s { int a; }; /* unterminated",
+        ] {
+            assert_eq!(error(source), CParseError::UnexpectedEnd, "{}", source);
+        }
+
+        assert_eq!(
+            CParseError::UnexpectedEnd.to_string(),
+            "unexpected end of input"
+        );
+    }
+
+    #[test]
+    fn unknown_type() {
+        let err = error("struct s { my_int a; };");
+        assert_eq!(err, CParseError::UnknownType("my_int".to_string()));
+        assert_eq!(err.to_string(), "unknown type `my_int`");
+
+        // Structs have to be declared before they're used.
+        assert_eq!(
+            error("struct s { struct later a; };"),
+            CParseError::UnknownType("struct later".to_string())
+        );
+        assert_eq!(
+            error("struct s { void a; };"),
+            CParseError::UnknownType("void".to_string())
+        );
+    }
+
+    #[test]
+    fn unsupported() {
+        let err = error("struct s { unsigned a : 3; };");
+        assert_eq!(err, CParseError::Unsupported("bitfields"));
+        assert_eq!(err.to_string(), "bitfields are not supported");
+
+        assert_eq!(
+            error("union u { int a; };"),
+            CParseError::Unsupported("unions")
+        );
+        assert_eq!(
+            error("struct { int a; };"),
+            CParseError::Unsupported("anonymous structs")
+        );
+        assert_eq!(
+            error("struct __attribute__((deprecated)) s { int a; };"),
+            CParseError::Unsupported("attributes other than `packed` and `aligned`")
+        );
+    }
+
+    #[test]
+    fn no_struct() {
+        for source in ["", "// nothing here", "#include <stddef.h>\n;"] {
+            assert_eq!(error(source), CParseError::NoStruct);
+        }
+
+        assert_eq!(CParseError::NoStruct.to_string(), "no struct was declared");
+    }
+
+    #[test]
+    fn layout_errors() {
+        let err = error("struct s { int a; } __attribute__((aligned(3)));");
+        assert_eq!(err, CParseError::Layout(LayoutError::InvalidAlignment(3)));
+        assert_eq!(err.to_string(), "alignment 3 is not a power of two");
+    }
+}
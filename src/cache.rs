@@ -0,0 +1,51 @@
+use alloc::boxed::Box;
+use core::any::TypeId;
+use core::ptr;
+use core::sync::atomic::{AtomicPtr, Ordering};
+use std::collections::HashMap;
+use std::sync::{Mutex, Once};
+
+use crate::TypeLayoutInfo;
+
+type Cache = Mutex<HashMap<TypeId, &'static TypeLayoutInfo>>;
+
+/// Returns the cached layout of the type with the given `TypeId`, computing
+/// and leaking it with `compute` the first time it's asked for.
+pub(crate) fn cached(
+    type_id: TypeId,
+    compute: impl FnOnce() -> TypeLayoutInfo,
+) -> &'static TypeLayoutInfo {
+    if let Some(layout) = lock().get(&type_id) {
+        return layout;
+    }
+
+    // The lock isn't held while computing the layout, so two threads may both
+    // compute it. Only the first one to finish is kept.
+    let layout = compute();
+    let mut cache = lock();
+    let cached = cache
+        .entry(type_id)
+        .or_insert_with(|| Box::leak(Box::new(layout)));
+
+    cached
+}
+
+fn lock() -> std::sync::MutexGuard<'static, HashMap<TypeId, &'static TypeLayoutInfo>> {
+    // `Mutex::new` isn't const on our minimum supported Rust version, so the
+    // cache is created on first use instead of in a static.
+    static INIT: Once = Once::new();
+    static CACHE: AtomicPtr<Cache> = AtomicPtr::new(ptr::null_mut());
+
+    INIT.call_once(|| {
+        let cache = Box::new(Mutex::new(HashMap::new()));
+        CACHE.store(Box::into_raw(cache), Ordering::Release);
+    });
+
+    // SAFETY: `call_once` has stored a pointer from `Box::into_raw` by now,
+    // which is never freed or replaced.
+    let cache = unsafe { &*CACHE.load(Ordering::Acquire) };
+
+    // A panic can't leave the map in an inconsistent state, so a poisoned
+    // lock is still usable.
+    cache.lock().unwrap_or_else(|err| err.into_inner())
+}
@@ -0,0 +1,400 @@
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::Range;
+
+use crate::{Field, TableOptions, TypeLayoutInfo};
+
+/// The most common cache line size, used on x86-64 and most ARM cores. Some
+/// targets differ; Apple's M-series chips use 128-byte lines, for example.
+pub const DEFAULT_CACHE_LINE_SIZE: usize = 64;
+
+impl TypeLayoutInfo {
+    /// Groups this type's fields by the cache lines they occupy, assuming the
+    /// type starts at the beginning of a cache line.
+    ///
+    /// ```rust
+    /// use type_layout::{TypeLayout, DEFAULT_CACHE_LINE_SIZE};
+    ///
+    /// #[derive(TypeLayout)]
+    /// #[repr(C)]
+    /// struct Shared {
+    ///     head: [u8; 56],
+    ///     middle: [u8; 12],
+    ///     tail: u32,
+    /// }
+    ///
+    /// let report = Shared::type_layout().cache_line_report(DEFAULT_CACHE_LINE_SIZE);
+    ///
+    /// assert_eq!(report.lines.len(), 2);
+    /// assert_eq!(report.lines[0].fields, ["head", "middle"]);
+    /// assert_eq!(report.lines[1].fields, ["middle", "tail"]);
+    ///
+    /// let straddling: Vec<_> = report.straddling().map(|field| &field.name).collect();
+    /// assert_eq!(straddling, ["middle"]);
+    ///
+    /// println!("{}", report);
+    /// // prints:
+    /// // Shared in 64-byte cache lines
+    /// // Line 0 (bytes 0..64): head, middle
+    /// // Line 1 (bytes 64..72): middle, tail
+    /// // `middle` straddles lines 0 and 1
+    /// ```
+    ///
+    /// Fields larger than a cache line can't avoid crossing boundaries, so
+    /// they're reported as spanning several lines instead:
+    ///
+    /// ```rust
+    /// use type_layout::TypeLayout;
+    ///
+    /// #[derive(TypeLayout)]
+    /// #[repr(C)]
+    /// struct Buffer {
+    ///     len: u64,
+    ///     data: [u8; 200],
+    /// }
+    ///
+    /// let report = Buffer::type_layout().cache_line_report(64);
+    /// let data = &report.fields[1];
+    ///
+    /// assert_eq!((data.first_line, data.line_count), (0, 4));
+    /// assert!(!data.straddles);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `line_size` is zero.
+    pub fn cache_line_report(&self, line_size: usize) -> CacheLineReport {
+        assert!(line_size > 0, "cache line size must be greater than zero");
+
+        let line_count = (self.size + line_size - 1) / line_size;
+        let mut lines: Vec<CacheLine> = (0..line_count)
+            .map(|index| CacheLine {
+                index,
+                start: index * line_size,
+                end: ((index + 1) * line_size).min(self.size),
+                fields: Vec::new(),
+            })
+            .collect();
+
+        let mut fields = Vec::new();
+
+        for field in &self.fields {
+            let (name, offset, size, contended) = match field {
+                Field::Field {
+                    name,
+                    ty,
+                    resolved_ty,
+                    offset,
+                    size,
+                    contended,
+                    ..
+                } => {
+                    let ty = resolved_ty.as_ref().unwrap_or(ty);
+                    (name, *offset, *size, *contended || is_atomic(ty))
+                }
+                Field::Padding { .. } => continue,
+            };
+
+            let first_line = offset / line_size;
+            let last_line = (offset + size.max(1) - 1) / line_size;
+
+            for line in &mut lines[first_line.min(line_count)..(last_line + 1).min(line_count)] {
+                line.fields.push(name.clone());
+            }
+
+            fields.push(CacheLineField {
+                name: name.clone(),
+                offset,
+                size,
+                first_line,
+                line_count: last_line - first_line + 1,
+                straddles: size <= line_size && first_line != last_line,
+                contended,
+            });
+        }
+
+        CacheLineReport {
+            name: Cow::Owned(self.full_name().into_owned()),
+            line_size,
+            lines,
+            fields,
+        }
+    }
+
+    /// Renders the layout table like `Display` does, with a separator row
+    /// before each row that starts on a new cache line.
+    ///
+    /// ```rust
+    /// use type_layout::TypeLayout;
+    ///
+    /// #[derive(TypeLayout)]
+    /// #[repr(C)]
+    /// struct Counters {
+    ///     reads: [u64; 8],
+    ///     writes: [u64; 8],
+    /// }
+    ///
+    /// println!("{}", Counters::type_layout().with_cache_lines(64));
+    /// // prints:
+    /// // Counters (size 128, alignment 8)
+    /// // | Offset | Name                        | Size |
+    /// // | ------ | --------------------------- | ---- |
+    /// // | 0      | reads: [u64; 8] (stride 8)  | 64   |
+    /// // | ====== | =========================== | ==== |
+    /// // | 64     | writes: [u64; 8] (stride 8) | 64   |
+    /// // Option<Counters> is 136 bytes (no niche)
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Rendering panics if `line_size` is zero.
+    pub fn with_cache_lines(&self, line_size: usize) -> WithCacheLines<'_> {
+        WithCacheLines {
+            layout: self,
+            line_size,
+        }
+    }
+}
+
+/// How a type's fields are spread across cache lines. Created by
+/// [`TypeLayoutInfo::cache_line_report`].
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct CacheLineReport {
+    pub name: Cow<'static, str>,
+    pub line_size: usize,
+
+    /// Every cache line the type occupies, in order.
+    pub lines: Vec<CacheLine>,
+
+    /// Where each field of the type falls, in order of offset.
+    pub fields: Vec<CacheLineField>,
+}
+
+impl CacheLineReport {
+    /// Fields that would fit in a single cache line, but cross a boundary
+    /// between two.
+    pub fn straddling(&self) -> impl Iterator<Item = &CacheLineField> {
+        self.fields.iter().filter(|field| field.straddles)
+    }
+
+    /// Cache lines shared by more than one contended field. When different
+    /// threads write to fields on the same line, each write invalidates the
+    /// line for every other thread, even though the fields are independent.
+    ///
+    /// Fields are contended when their type is an atomic, like `AtomicU64`, or
+    /// when they're marked with `#[layout(contended)]`.
+    ///
+    /// ```rust
+    /// use std::sync::atomic::AtomicU64;
+    /// use type_layout::TypeLayout;
+    ///
+    /// #[derive(TypeLayout)]
+    /// #[repr(C)]
+    /// struct Queue {
+    ///     head: AtomicU64,
+    ///     tail: AtomicU64,
+    ///     #[layout(contended)]
+    ///     stats: [u64; 4],
+    ///     capacity: u64,
+    /// }
+    ///
+    /// let report = Queue::type_layout().cache_line_report(64);
+    /// let warnings = report.false_sharing();
+    ///
+    /// assert_eq!(warnings.len(), 1);
+    /// assert_eq!(warnings[0].line, 0);
+    /// assert_eq!(warnings[0].fields, ["head", "tail", "stats"]);
+    ///
+    /// println!("{}", report);
+    /// // prints:
+    /// // Queue in 64-byte cache lines
+    /// // Line 0 (bytes 0..56): head, tail, stats, capacity
+    /// // warning: contended fields `head`, `tail`, `stats` share line 0, which can cause false sharing
+    /// ```
+    pub fn false_sharing(&self) -> Vec<FalseSharing> {
+        self.lines
+            .iter()
+            .filter_map(|line| {
+                let fields: Vec<_> = self
+                    .fields
+                    .iter()
+                    .filter(|field| field.contended && field.lines().contains(&line.index))
+                    .map(|field| field.name.clone())
+                    .collect();
+
+                if fields.len() > 1 {
+                    Some(FalseSharing {
+                        line: line.index,
+                        fields,
+                    })
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+/// A cache line shared by several contended fields. Created by
+/// [`CacheLineReport::false_sharing`].
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct FalseSharing {
+    /// The index of the shared cache line.
+    pub line: usize,
+
+    /// The names of the contended fields on the line.
+    pub fields: Vec<Cow<'static, str>>,
+}
+
+impl fmt::Display for FalseSharing {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let fields: Vec<_> = self
+            .fields
+            .iter()
+            .map(|field| format!("`{}`", field))
+            .collect();
+
+        write!(
+            formatter,
+            "warning: contended fields {} share line {}, which can cause false sharing",
+            fields.join(", "),
+            self.line
+        )
+    }
+}
+
+/// One cache line occupied by a type.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct CacheLine {
+    pub index: usize,
+
+    /// The range of the type's bytes that fall in this line. The last line of
+    /// a type can be shorter than the line size.
+    pub start: usize,
+    pub end: usize,
+
+    /// The names of the fields with at least one byte in this line.
+    pub fields: Vec<Cow<'static, str>>,
+}
+
+/// The cache lines occupied by one field.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct CacheLineField {
+    pub name: Cow<'static, str>,
+    pub offset: usize,
+    pub size: usize,
+    pub first_line: usize,
+    pub line_count: usize,
+
+    /// Whether the field crosses a cache line boundary even though it's small
+    /// enough to fit in one line.
+    pub straddles: bool,
+
+    /// Whether the field is an atomic or is marked with
+    /// `#[layout(contended)]`, so that sharing a cache line with another such
+    /// field could cause false sharing.
+    #[cfg_attr(feature = "serde1", serde(default))]
+    pub contended: bool,
+}
+
+impl CacheLineField {
+    /// The indices of the cache lines this field occupies.
+    ///
+    /// ```rust
+    /// use type_layout::TypeLayout;
+    ///
+    /// #[derive(TypeLayout)]
+    /// #[repr(C)]
+    /// struct Packet {
+    ///     header: [u8; 60],
+    ///     checksum: [u8; 8],
+    ///     body: [u8; 128],
+    /// }
+    ///
+    /// let report = Packet::type_layout().cache_line_report(64);
+    /// let lines: Vec<_> = report.fields.iter().map(|field| field.lines()).collect();
+    /// assert_eq!(lines, [0..1, 0..2, 1..4]);
+    /// ```
+    pub fn lines(&self) -> Range<usize> {
+        self.first_line..self.first_line + self.line_count
+    }
+}
+
+impl fmt::Display for CacheLineReport {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            formatter,
+            "{} in {}-byte cache lines",
+            self.name, self.line_size
+        )?;
+
+        for line in &self.lines {
+            writeln!(
+                formatter,
+                "Line {} (bytes {}..{}): {}",
+                line.index,
+                line.start,
+                line.end,
+                line.fields.join(", ")
+            )?;
+        }
+
+        for field in &self.fields {
+            if field.straddles {
+                writeln!(
+                    formatter,
+                    "`{}` straddles lines {} and {}",
+                    field.name,
+                    field.first_line,
+                    field.first_line + field.line_count - 1
+                )?;
+            } else if field.line_count > 1 {
+                writeln!(
+                    formatter,
+                    "`{}` spans {} lines",
+                    field.name, field.line_count
+                )?;
+            }
+        }
+
+        for warning in self.false_sharing() {
+            writeln!(formatter, "{}", warning)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Renders a layout table with cache line separators. Created by
+/// [`TypeLayoutInfo::with_cache_lines`].
+pub struct WithCacheLines<'a> {
+    layout: &'a TypeLayoutInfo,
+    line_size: usize,
+}
+
+impl fmt::Display for WithCacheLines<'_> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        assert!(
+            self.line_size > 0,
+            "cache line size must be greater than zero"
+        );
+
+        self.layout
+            .write_table(formatter, &TableOptions::new().cache_lines(self.line_size))
+    }
+}
+
+/// Whether a type name names one of the atomic types in `std::sync::atomic`,
+/// like `AtomicU64` or `core::sync::atomic::AtomicPtr<T>`.
+fn is_atomic(ty: &str) -> bool {
+    let path = ty.split('<').next().unwrap_or(ty);
+    let name = path.rsplit("::").next().unwrap_or(path).trim();
+
+    name.starts_with("Atomic")
+}
@@ -0,0 +1,382 @@
+use alloc::borrow::Cow;
+use alloc::borrow::ToOwned;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::alloc::Layout;
+use core::fmt;
+
+use crate::{Field, Repr, TypeLayoutInfo};
+
+/// An error from converting or composing layouts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayoutError {
+    /// The alignment isn't a power of two, which can only happen for a
+    /// `TypeLayoutInfo` that was built by hand.
+    InvalidAlignment(usize),
+
+    /// The size overflowed, or exceeded `isize::MAX` once rounded up to the
+    /// alignment.
+    Overflow,
+
+    /// The layout has an unsized tail, so nothing can be placed after it.
+    Unsized,
+}
+
+impl fmt::Display for LayoutError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LayoutError::InvalidAlignment(alignment) => {
+                write!(formatter, "alignment {} is not a power of two", alignment)
+            }
+            LayoutError::Overflow => write!(formatter, "layout size overflowed"),
+            LayoutError::Unsized => {
+                write!(formatter, "layout ends in an unsized field")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for LayoutError {}
+
+impl TypeLayoutInfo {
+    /// Converts this layout to a [`Layout`] for use with allocator APIs.
+    ///
+    /// ```rust
+    /// use type_layout::TypeLayout;
+    ///
+    /// #[derive(TypeLayout)]
+    /// #[repr(C)]
+    /// struct Header {
+    ///     kind: u8,
+    ///     len: u32,
+    /// }
+    ///
+    /// let layout = Header::type_layout().alloc_layout().unwrap();
+    /// assert_eq!(layout, std::alloc::Layout::new::<Header>());
+    /// ```
+    pub fn alloc_layout(&self) -> Result<Layout, LayoutError> {
+        if !self.alignment.is_power_of_two() {
+            return Err(LayoutError::InvalidAlignment(self.alignment));
+        }
+
+        Layout::from_size_align(self.size, self.alignment).map_err(|_| LayoutError::Overflow)
+    }
+
+    /// Creates the layout of `count` instances of this type laid out one
+    /// after another, like [`Layout::repeat`]. Fields of the `i`th instance
+    /// are named `[i].field`.
+    ///
+    /// Every instance of a zero-sized type is at offset 0, so only the fields
+    /// of the first are listed. `count` has no limit for those, like it
+    /// doesn't for arrays of them.
+    ///
+    /// Returns the new layout and the distance between instances in bytes.
+    pub fn repeat(&self, count: usize) -> Result<(TypeLayoutInfo, usize), LayoutError> {
+        self.alloc_layout()?;
+        self.check_sized()?;
+
+        let stride = round_up(self.size, self.alignment)?;
+        let size = stride.checked_mul(count).ok_or(LayoutError::Overflow)?;
+        check_size(size, self.alignment)?;
+
+        let listed = if stride == 0 { count.min(1) } else { count };
+
+        let mut fields = Vec::new();
+        for index in 0..listed {
+            let base = stride * index;
+            for field in self.opaque_fields() {
+                fields.push(field.rebased(base, |name| format!("[{}].{}", index, name)));
+            }
+        }
+
+        let repeated = TypeLayoutInfo {
+            name: Cow::Owned(format!("[{}; {}]", self.full_name(), count)),
+            generics: Vec::new(),
+            size,
+            alignment: self.alignment,
+            natural_alignment: self.natural_alignment,
+            needs_drop: self.needs_drop && count > 0,
+            option_size: None,
+            is_union: false,
+            repr: Repr::default(),
+            guaranteed: self.guaranteed,
+            fields: crate::__insert_padding(fields, size),
+            variants: Vec::new(),
+            unsized_tail: None,
+            tag: None,
+            target: self.target.clone(),
+        };
+
+        Ok((repeated, stride))
+    }
+
+    /// Creates the layout of this type followed by `next`, like
+    /// [`Layout::extend`]. The fields of `next` are moved to the offset it
+    /// ends up at.
+    ///
+    /// Like [`Layout::extend`], no padding is added after `next`; use
+    /// [`TypeLayoutInfo::pad_to_align`] to add it.
+    ///
+    /// Returns the new layout and the offset of `next` within it.
+    ///
+    /// ```rust
+    /// use type_layout::TypeLayout;
+    ///
+    /// #[derive(TypeLayout)]
+    /// #[repr(C)]
+    /// struct Header {
+    ///     len: u16,
+    /// }
+    ///
+    /// #[derive(TypeLayout)]
+    /// #[repr(C)]
+    /// struct Item {
+    ///     value: u32,
+    /// }
+    ///
+    /// let (items, _) = Item::type_layout().repeat(2).unwrap();
+    /// let (buffer, offset) = Header::type_layout().extend(&items).unwrap();
+    ///
+    /// assert_eq!(offset, 4);
+    /// assert_eq!(buffer.size, 12);
+    /// assert_eq!(buffer.field("[1].value").unwrap().offset(), 8);
+    /// ```
+    pub fn extend(&self, next: &TypeLayoutInfo) -> Result<(TypeLayoutInfo, usize), LayoutError> {
+        self.alloc_layout()?;
+        self.check_sized()?;
+        next.alloc_layout()?;
+        next.check_sized()?;
+
+        let offset = round_up(self.size, next.alignment)?;
+        let size = offset.checked_add(next.size).ok_or(LayoutError::Overflow)?;
+        let alignment = self.alignment.max(next.alignment);
+        check_size(size, alignment)?;
+
+        let second = next
+            .opaque_fields()
+            .into_iter()
+            .map(|field| field.rebased(offset, |name| name.to_owned()));
+        let fields = self.opaque_fields().into_iter().chain(second).collect();
+
+        let extended = TypeLayoutInfo {
+            name: Cow::Owned(format!("{} + {}", self.full_name(), next.full_name())),
+            generics: Vec::new(),
+            size,
+            alignment,
+            natural_alignment: self.natural_alignment.max(next.natural_alignment),
+            needs_drop: self.needs_drop || next.needs_drop,
+            option_size: None,
+            is_union: false,
+            // `extend` places fields the same way `#[repr(C)]` does.
+            repr: Repr {
+                c: true,
+                ..Repr::default()
+            },
+            guaranteed: self.guaranteed && next.guaranteed,
+            fields: crate::__insert_padding(fields, size),
+            variants: Vec::new(),
+            unsized_tail: None,
+            tag: None,
+            target: if self.target == next.target {
+                self.target.clone()
+            } else {
+                None
+            },
+        };
+
+        Ok((extended, offset))
+    }
+
+    /// Rounds the size of this layout up to a multiple of its alignment,
+    /// adding trailing padding, like [`Layout::pad_to_align`].
+    pub fn pad_to_align(&self) -> Result<TypeLayoutInfo, LayoutError> {
+        self.alloc_layout()?;
+
+        let size = round_up(self.size, self.alignment)?;
+        check_size(size, self.alignment)?;
+
+        let fields = self.opaque_fields();
+
+        Ok(TypeLayoutInfo {
+            name: self.name.clone(),
+            generics: self.generics.clone(),
+            size,
+            alignment: self.alignment,
+            natural_alignment: self.natural_alignment,
+            needs_drop: self.needs_drop,
+            option_size: None,
+            is_union: false,
+            repr: self.repr.clone(),
+            guaranteed: self.guaranteed,
+            fields: crate::__insert_padding(fields, size),
+            variants: Vec::new(),
+            unsized_tail: self.unsized_tail.clone(),
+            tag: None,
+            target: self.target.clone(),
+        })
+    }
+}
+
+impl TypeLayoutInfo {
+    fn check_sized(&self) -> Result<(), LayoutError> {
+        match self.unsized_tail {
+            Some(_) => Err(LayoutError::Unsized),
+            None => Ok(()),
+        }
+    }
+
+    /// The fields of this layout without padding. The variants of an enum and
+    /// the overlapping fields of a union can't be combined with other fields,
+    /// so enums and unions become a single field covering the whole type.
+    fn opaque_fields(&self) -> Vec<Field> {
+        if self.variants.is_empty() && !self.is_union {
+            return self
+                .fields
+                .iter()
+                .filter(|field| !field.is_padding())
+                .cloned()
+                .collect();
+        }
+
+        vec![Field::Field {
+            name: Cow::Owned(self.full_name().into_owned()),
+            ty: Cow::Owned(self.full_name().into_owned()),
+            resolved_ty: None,
+            type_id: None,
+            offset: 0,
+            size: self.size,
+            alignment: Some(self.alignment),
+            array: None,
+            needs_drop: self.needs_drop,
+            option_size: self.option_size,
+            nested: None,
+            contended: false,
+            niche: false,
+        }]
+    }
+}
+
+impl Field {
+    /// Copies this field, moved `base` bytes further into the type and
+    /// renamed by `rename`.
+    fn rebased(&self, base: usize, rename: impl Fn(&str) -> String) -> Field {
+        let mut field = self.clone();
+
+        match &mut field {
+            Field::Field { name, offset, .. } => {
+                *name = Cow::Owned(rename(name));
+                *offset += base;
+            }
+            Field::Padding { offset, .. } => *offset += base,
+        }
+
+        field
+    }
+}
+
+pub(crate) fn round_up(size: usize, alignment: usize) -> Result<usize, LayoutError> {
+    let rounded = size
+        .checked_add(alignment - 1)
+        .ok_or(LayoutError::Overflow)?
+        / alignment
+        * alignment;
+
+    Ok(rounded)
+}
+
+pub(crate) fn check_size(size: usize, alignment: usize) -> Result<(), LayoutError> {
+    Layout::from_size_align(size, alignment)
+        .map(|_| ())
+        .map_err(|_| LayoutError::Overflow)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::UnsizedTail;
+
+    fn item() -> TypeLayoutInfo {
+        TypeLayoutInfo::builder("Item")
+            .field("value", "u32", 4, 4)
+            .field("flag", "bool", 1, 1)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn repeat_names_and_offsets() {
+        let (items, stride) = item().repeat(2).unwrap();
+
+        assert_eq!(stride, 8);
+        assert_eq!(items.name, "[Item; 2]");
+        assert_eq!((items.size, items.alignment), (16, 4));
+        assert_eq!(items.field("[0].flag").unwrap().offset(), 4);
+        assert_eq!(items.field("[1].value").unwrap().offset(), 8);
+        assert_eq!(items.field("[1].flag").unwrap().offset(), 12);
+        assert_eq!(items.fields.last().unwrap().offset(), 13);
+        assert!(items.fields.last().unwrap().is_padding());
+    }
+
+    #[test]
+    fn repeat_none() {
+        let (items, stride) = item().repeat(0).unwrap();
+
+        assert_eq!(stride, 8);
+        assert_eq!(items.size, 0);
+        assert!(items.fields.is_empty());
+    }
+
+    #[test]
+    fn repeat_overflow() {
+        assert_eq!(item().repeat(usize::MAX), Err(LayoutError::Overflow));
+
+        // Fits in a `usize`, but not in an `isize`.
+        assert_eq!(
+            item().repeat(isize::MAX as usize / 8 + 1),
+            Err(LayoutError::Overflow)
+        );
+    }
+
+    #[test]
+    fn repeat_invalid_alignment() {
+        let mut layout = item();
+        layout.alignment = 3;
+
+        assert_eq!(layout.repeat(2), Err(LayoutError::InvalidAlignment(3)));
+    }
+
+    #[test]
+    fn repeat_unsized() {
+        let mut layout = TypeLayoutInfo::builder("Buffer")
+            .field("len", "usize", 8, 8)
+            .build()
+            .unwrap();
+        layout.unsized_tail = Some(UnsizedTail {
+            name: "data".into(),
+            ty: "[u8]".into(),
+            offset: 8,
+            stride: Some(1),
+        });
+
+        assert_eq!(layout.repeat(2), Err(LayoutError::Unsized));
+    }
+
+    #[test]
+    fn repeat_zero_sized() {
+        let marker = TypeLayoutInfo::builder("Marker")
+            .field("phantom", "PhantomData<u64>", 0, 8)
+            .build()
+            .unwrap();
+
+        let (markers, stride) = marker.repeat(usize::MAX).unwrap();
+
+        assert_eq!(stride, 0);
+        assert_eq!((markers.size, markers.alignment), (0, 8));
+        assert_eq!(markers.fields.len(), 1);
+        assert_eq!(markers.fields[0].offset(), 0);
+        assert!(markers.field("[0].phantom").is_some());
+    }
+}
@@ -0,0 +1,173 @@
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+
+use crate::{Field, Repr, TargetInfo, TypeLayoutInfo};
+
+/// A type whose layout is available as a constant, without running any code
+/// or allocating.
+///
+/// This trait is implemented by `#[derive(TypeLayout)]` on structs with
+/// `#[layout(const_layout)]`. Computing field offsets in a const context needs
+/// Rust 1.65 or newer.
+///
+/// ```rust
+/// use type_layout::{ConstTypeLayout, TypeLayout};
+///
+/// #[derive(TypeLayout)]
+/// #[layout(const_layout)]
+/// #[repr(C)]
+/// struct Register {
+///     control: u8,
+///     status: u32,
+/// }
+///
+/// const STATUS_OFFSET: usize = match Register::LAYOUT.field("status") {
+///     Some(field) => field.offset,
+///     None => panic!("no status field"),
+/// };
+///
+/// assert_eq!(STATUS_OFFSET, 4);
+/// assert_eq!(Register::LAYOUT.padding_bytes(), 3);
+/// assert_eq!(
+///     Register::LAYOUT.to_layout().padding_mask(),
+///     Register::type_layout().padding_mask(),
+/// );
+/// ```
+pub trait ConstTypeLayout {
+    const LAYOUT: ConstLayout;
+}
+
+/// The layout of a type, made only of constants and static slices. See
+/// [`ConstTypeLayout`].
+///
+/// Unlike [`TypeLayoutInfo`], padding isn't listed among the fields, since
+/// building that list would need an allocation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ConstLayout {
+    pub name: &'static str,
+    pub size: usize,
+    pub alignment: usize,
+    pub needs_drop: bool,
+
+    /// Whether the type's field offsets are guaranteed. See
+    /// [`TypeLayoutInfo::guaranteed`].
+    pub guaranteed: bool,
+
+    /// The fields of the type, in the order they're declared.
+    pub fields: &'static [ConstField],
+}
+
+/// One field of a [`ConstLayout`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ConstField {
+    pub name: &'static str,
+
+    /// The type of the field, as written in the source code.
+    pub ty: &'static str,
+    pub offset: usize,
+    pub size: usize,
+    pub alignment: usize,
+    pub needs_drop: bool,
+}
+
+impl ConstLayout {
+    /// Finds the field with the given name, if there is one.
+    pub const fn field(&self, name: &str) -> Option<&ConstField> {
+        let mut index = 0;
+
+        while index < self.fields.len() {
+            if str_eq(self.fields[index].name, name) {
+                return Some(&self.fields[index]);
+            }
+
+            index += 1;
+        }
+
+        None
+    }
+
+    /// The number of bytes of the type that don't belong to any field.
+    pub const fn padding_bytes(&self) -> usize {
+        let mut used = 0;
+        let mut index = 0;
+
+        while index < self.fields.len() {
+            used += self.fields[index].size;
+            index += 1;
+        }
+
+        self.size.saturating_sub(used)
+    }
+
+    /// Converts this layout to a [`TypeLayoutInfo`], which lists padding and
+    /// supports everything else this crate does with layouts.
+    ///
+    /// The result matches the type's `TypeLayout::type_layout()`, except that
+    /// details that can't be computed in a const context, like the size of
+    /// `Option<T>` and `TypeId`s, aren't filled in.
+    pub fn to_layout(&self) -> TypeLayoutInfo {
+        let fields: Vec<Field> = self
+            .fields
+            .iter()
+            .map(|field| Field::Field {
+                name: Cow::Borrowed(field.name),
+                ty: Cow::Borrowed(field.ty),
+                resolved_ty: None,
+                type_id: None,
+                offset: field.offset,
+                size: field.size,
+                alignment: Some(field.alignment),
+                array: None,
+                needs_drop: field.needs_drop,
+                option_size: None,
+                nested: None,
+                contended: false,
+                niche: false,
+            })
+            .collect();
+
+        let natural_alignment = self
+            .fields
+            .iter()
+            .map(|field| field.alignment)
+            .fold(1, usize::max);
+
+        TypeLayoutInfo {
+            name: Cow::Borrowed(self.name),
+            generics: Vec::new(),
+            size: self.size,
+            alignment: self.alignment,
+            natural_alignment,
+            needs_drop: self.needs_drop,
+            option_size: None,
+            is_union: false,
+            repr: Repr::default(),
+            guaranteed: self.guaranteed,
+            fields: crate::__insert_padding(fields, self.size),
+            variants: Vec::new(),
+            unsized_tail: None,
+            tag: None,
+            target: Some(TargetInfo::current()),
+        }
+    }
+}
+
+const fn str_eq(a: &str, b: &str) -> bool {
+    let a = a.as_bytes();
+    let b = b.as_bytes();
+
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let mut index = 0;
+    while index < a.len() {
+        if a[index] != b[index] {
+            return false;
+        }
+
+        index += 1;
+    }
+
+    true
+}
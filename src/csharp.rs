@@ -0,0 +1,326 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use crate::{Field, TypeLayoutInfo};
+
+impl TypeLayoutInfo {
+    /// Generates C# definitions of this type, for calling into a Rust
+    /// `cdylib` with P/Invoke.
+    ///
+    /// The struct is declared with `[StructLayout(LayoutKind.Explicit)]` and
+    /// its size, and each field gets a `[FieldOffset(n)]` attribute, so the C#
+    /// layout matches without relying on the marshaller's own rules. Fields
+    /// with primitive or pointer types get the matching C# type, `bool` is
+    /// declared as a `byte` to keep the struct blittable, and nested fields
+    /// refer to structs declared before this one. Other fields become fixed
+    /// byte buffers of the same size.
+    ///
+    /// Enums are declared as their tag followed by the bytes of the payload.
+    /// An unsized tail can't be declared in C#, so it's left out. The
+    /// definitions are for the target the layout was computed on.
+    ///
+    /// ```rust
+    /// use type_layout::TypeLayout;
+    ///
+    /// #[derive(TypeLayout)]
+    /// #[repr(C)]
+    /// struct Packet {
+    ///     kind: u8,
+    ///     len: u32,
+    ///     payload: [u16; 4],
+    /// }
+    ///
+    /// print!("{}", Packet::type_layout().to_csharp());
+    /// // prints:
+    /// // using System.Runtime.InteropServices;
+    /// //
+    /// // [StructLayout(LayoutKind.Explicit, Size = 16)]
+    /// // public unsafe struct Packet
+    /// // {
+    /// //     [FieldOffset(0)] public byte kind;
+    /// //     [FieldOffset(4)] public uint len;
+    /// //     [FieldOffset(8)] public fixed ushort payload[4];
+    /// // }
+    /// ```
+    pub fn to_csharp(&self) -> String {
+        let mut out = String::from("using System.Runtime.InteropServices;\n");
+        let mut declared = Vec::new();
+        declare(&mut out, self, &mut declared);
+        out
+    }
+}
+
+/// Declares a struct for `layout`, after the structs of its nested fields.
+/// `declared` holds the names of the structs that have been declared so far.
+fn declare(out: &mut String, layout: &TypeLayoutInfo, declared: &mut Vec<String>) {
+    let name = identifier(&layout.full_name());
+    if declared.contains(&name) {
+        return;
+    }
+
+    for field in &layout.fields {
+        if let Field::Field {
+            nested: Some(nested),
+            ..
+        } = field
+        {
+            declare(out, nested, declared);
+        }
+    }
+
+    declared.push(name.clone());
+
+    let members = if layout.variants.is_empty() {
+        field_members(layout)
+    } else {
+        enum_members(layout)
+    };
+
+    let modifiers = if members.iter().any(|member| member.contains(" fixed ")) {
+        "public unsafe struct"
+    } else {
+        "public struct"
+    };
+
+    let _ = writeln!(out);
+    match layout.repr.packed {
+        Some(packed) => {
+            let _ = writeln!(
+                out,
+                "[StructLayout(LayoutKind.Explicit, Size = {}, Pack = {})]",
+                layout.size, packed
+            );
+        }
+        None => {
+            let _ = writeln!(
+                out,
+                "[StructLayout(LayoutKind.Explicit, Size = {})]",
+                layout.size
+            );
+        }
+    }
+
+    let _ = writeln!(out, "{} {}", modifiers, name);
+    let _ = writeln!(out, "{{");
+    for member in &members {
+        let _ = writeln!(out, "    {}", member);
+    }
+    let _ = writeln!(out, "}}");
+}
+
+/// The members of a struct or union. Padding is covered by the struct's
+/// explicit size and the offsets of the fields, so it isn't declared.
+fn field_members(layout: &TypeLayoutInfo) -> Vec<String> {
+    let mut members = Vec::new();
+    let mut opaque = 0;
+
+    for field in &layout.fields {
+        if let Field::Field {
+            name,
+            ty,
+            resolved_ty,
+            offset,
+            size,
+            array,
+            nested,
+            ..
+        } = field
+        {
+            let ty = resolved_ty.as_ref().unwrap_or(ty);
+
+            let member_name = if ty.is_empty() {
+                opaque += 1;
+                format!("_opaque{}", opaque - 1)
+            } else {
+                identifier(name)
+            };
+
+            let declaration = match (nested, array) {
+                // Fixed buffers can't hold structs either.
+                (Some(_), Some(_)) => opaque_member(&member_name, *size, ty),
+                (Some(nested), None) => {
+                    format!("{} {};", identifier(&nested.full_name()), member_name)
+                }
+                (None, Some(array)) => match csharp_type_name(&array.elem_ty, array.stride) {
+                    // Fixed buffers can only hold primitive types.
+                    Some(elem) if elem != "nint" && elem != "nuint" => {
+                        format!("fixed {} {}[{}];", elem, member_name, array.len)
+                    }
+                    _ => opaque_member(&member_name, *size, ty),
+                },
+                (None, None) => match csharp_type_name(ty, *size) {
+                    Some(csharp_type) => format!("{} {};", csharp_type, member_name),
+                    None => opaque_member(&member_name, *size, ty),
+                },
+            };
+
+            members.push(format!("[FieldOffset({})] public {}", offset, declaration));
+        }
+    }
+
+    if let Some(tail) = &layout.unsized_tail {
+        members.push(format!(
+            "// {}: {} at offset {} can't be declared in C#",
+            tail.name, tail.ty, tail.offset
+        ));
+    }
+
+    members
+}
+
+/// The members of an enum: its tag, and the bytes of the payload after it.
+fn enum_members(layout: &TypeLayoutInfo) -> Vec<String> {
+    let mut members = Vec::new();
+
+    let tag = match &layout.tag {
+        Some(tag) => tag,
+        None => return members,
+    };
+
+    let tag_type = match tag.ty.as_ref() {
+        "C enum" => "int",
+        ty => csharp_type_name(ty, tag.size).unwrap_or("int"),
+    };
+    members.push(format!(
+        "[FieldOffset({})] public {} tag;",
+        tag.offset, tag_type
+    ));
+
+    if layout.size > tag.payload_offset {
+        members.push(format!(
+            "[FieldOffset({})] public fixed byte payload[{}];",
+            tag.payload_offset,
+            layout.size - tag.payload_offset
+        ));
+    }
+
+    members
+}
+
+/// A fixed byte buffer standing in for a field whose type has no C#
+/// equivalent.
+fn opaque_member(name: &str, size: usize, ty: &str) -> String {
+    if ty.is_empty() {
+        format!("fixed byte {}[{}];", name, size)
+    } else {
+        format!("fixed byte {}[{}]; // {}", name, size, ty)
+    }
+}
+
+/// The C# type for a Rust type of the given size, if there's a blittable one
+/// with the same layout.
+fn csharp_type_name(ty: &str, size: usize) -> Option<&'static str> {
+    let csharp_type = match ty {
+        "u8" | "bool" => "byte",
+        "i8" => "sbyte",
+        "u16" => "ushort",
+        "i16" => "short",
+        "u32" | "char" => "uint",
+        "i32" => "int",
+        "u64" => "ulong",
+        "i64" => "long",
+        "usize" => "nuint",
+        "isize" => "nint",
+        "f32" => "float",
+        "f64" => "double",
+        _ if crate::c_header::is_pointer(ty) && size == core::mem::size_of::<usize>() => "nint",
+        _ => return None,
+    };
+
+    Some(csharp_type)
+}
+
+/// Turns a Rust name into a C# identifier.
+fn identifier(name: &str) -> String {
+    let mut identifier = crate::c_header::sanitize(name);
+    if is_csharp_keyword(&identifier) {
+        identifier.insert(0, '@');
+    }
+
+    identifier
+}
+
+fn is_csharp_keyword(name: &str) -> bool {
+    [
+        "abstract",
+        "as",
+        "base",
+        "bool",
+        "break",
+        "byte",
+        "case",
+        "catch",
+        "char",
+        "checked",
+        "class",
+        "const",
+        "continue",
+        "decimal",
+        "default",
+        "delegate",
+        "do",
+        "double",
+        "else",
+        "enum",
+        "event",
+        "explicit",
+        "extern",
+        "false",
+        "finally",
+        "fixed",
+        "float",
+        "for",
+        "foreach",
+        "goto",
+        "if",
+        "implicit",
+        "in",
+        "int",
+        "interface",
+        "internal",
+        "is",
+        "lock",
+        "long",
+        "namespace",
+        "new",
+        "null",
+        "object",
+        "operator",
+        "out",
+        "override",
+        "params",
+        "private",
+        "protected",
+        "public",
+        "readonly",
+        "ref",
+        "return",
+        "sbyte",
+        "sealed",
+        "short",
+        "sizeof",
+        "stackalloc",
+        "static",
+        "string",
+        "struct",
+        "switch",
+        "this",
+        "throw",
+        "true",
+        "try",
+        "typeof",
+        "uint",
+        "ulong",
+        "unchecked",
+        "unsafe",
+        "ushort",
+        "using",
+        "virtual",
+        "void",
+        "volatile",
+        "while",
+    ]
+    .contains(&name)
+}
@@ -0,0 +1,145 @@
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::string::String;
+use core::fmt::Write;
+
+use crate::{Field, LayoutReport, PaddingKind, TypeLayoutInfo};
+
+const HEADER: &str = "type,field,field_type,offset,size,kind\n";
+
+impl TypeLayoutInfo {
+    /// Generates CSV with a row for each field and run of padding in this
+    /// type, for auditing layouts in a spreadsheet. See
+    /// [`LayoutReport::to_csv`], which puts the rows of many types in one
+    /// file.
+    ///
+    /// The columns are the type's name, the field's name, its type, offset,
+    /// size, and kind, which is `field`, `padding`, `tail padding`, or
+    /// `unsized tail`. Nested fields and the fields of enum variants are
+    /// named like `outer.inner`, and offsets are from the start of the type.
+    ///
+    /// ```rust
+    /// use type_layout::TypeLayout;
+    ///
+    /// #[derive(TypeLayout)]
+    /// #[repr(C)]
+    /// struct Header {
+    ///     kind: u8,
+    ///     len: u32,
+    ///     flags: [u16; 2],
+    /// }
+    ///
+    /// print!("{}", Header::type_layout().to_csv());
+    /// // prints:
+    /// // type,field,field_type,offset,size,kind
+    /// // Header,kind,u8,0,1,field
+    /// // Header,[padding],,1,3,padding
+    /// // Header,len,u32,4,4,field
+    /// // Header,flags,[u16; 2],8,4,field
+    /// ```
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from(HEADER);
+        write_layout(&mut out, self);
+        out
+    }
+}
+
+impl LayoutReport {
+    /// Generates CSV with a row for each field and run of padding in every
+    /// layout in the report, under one header. See
+    /// [`TypeLayoutInfo::to_csv`].
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from(HEADER);
+        for layout in self.layouts() {
+            write_layout(&mut out, layout);
+        }
+        out
+    }
+}
+
+fn write_layout(out: &mut String, layout: &TypeLayoutInfo) {
+    let full_name = layout.full_name();
+    let type_name = escape(&full_name);
+
+    if layout.variants.is_empty() {
+        write_rows(out, &type_name, &layout.fields, 0, "");
+    } else {
+        for variant in &layout.variants {
+            let prefix = format!("{}.", variant.name);
+            write_rows(out, &type_name, &variant.fields, 0, &prefix);
+        }
+    }
+
+    if let Some(tail) = &layout.unsized_tail {
+        let _ = writeln!(
+            out,
+            "{},{},{},{},,unsized tail",
+            type_name,
+            escape(&tail.name),
+            escape(&tail.ty),
+            tail.offset
+        );
+    }
+}
+
+/// Writes a row for each field, followed by the rows of its nested fields.
+/// `base` is the offset of the fields' parent from the start of the
+/// outermost type.
+fn write_rows(out: &mut String, type_name: &str, fields: &[Field], base: usize, prefix: &str) {
+    for field in fields {
+        let offset = base + field.offset();
+
+        let (name, ty, kind): (Cow<'_, str>, &str, _) = match field {
+            Field::Field {
+                name,
+                ty,
+                resolved_ty,
+                ..
+            } => (
+                Cow::Borrowed(name),
+                resolved_ty.as_ref().unwrap_or(ty),
+                "field",
+            ),
+            Field::Padding {
+                kind: PaddingKind::Interior,
+                ..
+            } => (Cow::Borrowed("[padding]"), "", "padding"),
+            Field::Padding {
+                kind: PaddingKind::Trailing,
+                ..
+            } => (Cow::Borrowed("[tail padding]"), "", "tail padding"),
+        };
+
+        let name = format!("{}{}", prefix, name);
+        let _ = writeln!(
+            out,
+            "{},{},{},{},{},{}",
+            type_name,
+            escape(&name),
+            escape(ty),
+            offset,
+            field.size(),
+            kind
+        );
+
+        if let Field::Field {
+            nested: Some(nested),
+            array,
+            ..
+        } = field
+        {
+            let prefix = crate::nested_prefix(&name, array);
+            write_rows(out, type_name, &nested.fields, offset, &prefix);
+        }
+    }
+}
+
+/// Quotes a value if it contains a comma, quote, or line break, like the
+/// generic arguments of `HashMap<K, V>`.
+fn escape(value: &str) -> Cow<'_, str> {
+    if value.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", value.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(value)
+    }
+}
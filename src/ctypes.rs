@@ -0,0 +1,317 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use crate::{Field, TypeLayoutInfo};
+
+impl TypeLayoutInfo {
+    /// Generates Python [`ctypes`](https://docs.python.org/3/library/ctypes.html)
+    /// classes for this type, for checking structs shared with Rust from
+    /// Python, like structs in shared memory.
+    ///
+    /// Padding is listed in `_fields_` as `_padN` byte arrays, so ctypes
+    /// doesn't need to insert any, and packed structs set `_pack_`. Fields
+    /// with primitive or pointer types get the matching ctypes type, and
+    /// nested fields refer to classes declared before this one. Other fields
+    /// become arrays of the same size and alignment.
+    ///
+    /// Enums are declared as their tag followed by the bytes of the payload,
+    /// and an unsized slice tail becomes an empty array. Alignment raised by
+    /// `#[repr(align(N))]` is set with `_align_`, which needs Python 3.13.
+    /// The classes are for the target the layout was computed on.
+    ///
+    /// ```rust
+    /// use type_layout::TypeLayout;
+    ///
+    /// #[derive(TypeLayout)]
+    /// #[repr(C)]
+    /// struct Packet {
+    ///     kind: u8,
+    ///     len: u32,
+    ///     payload: [u16; 4],
+    /// }
+    ///
+    /// print!("{}", Packet::type_layout().to_ctypes());
+    /// // prints:
+    /// // import ctypes
+    /// //
+    /// //
+    /// // class Packet(ctypes.Structure):
+    /// //     _fields_ = [
+    /// //         ("kind", ctypes.c_uint8),
+    /// //         ("_pad0", ctypes.c_uint8 * 3),
+    /// //         ("len", ctypes.c_uint32),
+    /// //         ("payload", ctypes.c_uint16 * 4),
+    /// //     ]
+    /// ```
+    pub fn to_ctypes(&self) -> String {
+        let mut out = String::from("import ctypes\n");
+        let mut declared = Vec::new();
+        declare(&mut out, self, &mut declared);
+        out
+    }
+}
+
+/// One entry of `_fields_`.
+struct Member {
+    name: String,
+    ty: String,
+    alignment: usize,
+}
+
+/// Declares a class for `layout`, after the classes of its nested fields.
+/// `declared` holds the names of the classes that have been declared so far.
+fn declare(out: &mut String, layout: &TypeLayoutInfo, declared: &mut Vec<String>) {
+    let name = identifier(&layout.full_name());
+    if declared.contains(&name) {
+        return;
+    }
+
+    for field in &layout.fields {
+        if let Field::Field {
+            nested: Some(nested),
+            ..
+        } = field
+        {
+            declare(out, nested, declared);
+        }
+    }
+
+    declared.push(name.clone());
+
+    let members = if layout.variants.is_empty() {
+        field_members(layout)
+    } else {
+        enum_members(layout)
+    };
+
+    let base = if layout.is_union {
+        "ctypes.Union"
+    } else {
+        "ctypes.Structure"
+    };
+
+    let _ = writeln!(out);
+    let _ = writeln!(out);
+    let _ = writeln!(out, "class {}({}):", name, base);
+
+    if let Some(packed) = layout.repr.packed {
+        // Python 3.14 only follows `_pack_` on every platform with MSVC's
+        // layout rules, which match `#[repr(C, packed)]`.
+        let _ = writeln!(out, "    _layout_ = \"ms\"");
+        let _ = writeln!(out, "    _pack_ = {}", packed);
+    }
+
+    let members_alignment = members.iter().map(|member| member.alignment).max();
+    if members_alignment.unwrap_or(1) < layout.alignment {
+        let _ = writeln!(out, "    _align_ = {}", layout.alignment);
+    }
+
+    let _ = writeln!(out, "    _fields_ = [");
+    for member in &members {
+        let _ = writeln!(out, "        (\"{}\", {}),", member.name, member.ty);
+    }
+    let _ = writeln!(out, "    ]");
+}
+
+/// The members of a struct or union, including padding before an unsized
+/// tail, which becomes an empty array at the same offset.
+fn field_members(layout: &TypeLayoutInfo) -> Vec<Member> {
+    let mut members = Vec::new();
+    let mut padding = 0;
+    let mut opaque = 0;
+    let tail_offset = layout.unsized_tail.as_ref().map(|tail| tail.offset);
+
+    for field in &layout.fields {
+        match field {
+            Field::Padding { offset, size, .. } => {
+                // Union members all start at offset 0, so padding isn't a
+                // member of its own.
+                if layout.is_union {
+                    continue;
+                }
+
+                let size = match tail_offset {
+                    Some(tail_offset) if *offset >= tail_offset => continue,
+                    Some(tail_offset) => (*size).min(tail_offset - offset),
+                    None => *size,
+                };
+
+                members.push(Member {
+                    name: format!("_pad{}", padding),
+                    ty: format!("ctypes.c_uint8 * {}", size),
+                    alignment: 1,
+                });
+                padding += 1;
+            }
+            Field::Field {
+                name,
+                ty,
+                resolved_ty,
+                offset,
+                size,
+                alignment,
+                array,
+                nested,
+                ..
+            } => {
+                let ty = resolved_ty.as_ref().unwrap_or(ty);
+                let alignment = alignment.unwrap_or(1);
+
+                let member_name = if ty.is_empty() {
+                    opaque += 1;
+                    format!("_opaque{}", opaque - 1)
+                } else {
+                    identifier(name)
+                };
+
+                let ctype = match (nested, array) {
+                    (Some(nested), Some(array)) => {
+                        format!("{} * {}", identifier(&nested.full_name()), array.len)
+                    }
+                    (Some(nested), None) => identifier(&nested.full_name()),
+                    (None, Some(array)) => match ctypes_type_name(&array.elem_ty, array.stride) {
+                        Some(elem) => format!("{} * {}", elem, array.len),
+                        None => opaque_type(*size, alignment, *offset),
+                    },
+                    (None, None) => match ctypes_type_name(ty, *size) {
+                        Some(ctype) => String::from(ctype),
+                        None => opaque_type(*size, alignment, *offset),
+                    },
+                };
+
+                members.push(Member {
+                    name: member_name,
+                    ty: ctype,
+                    alignment,
+                });
+            }
+        }
+    }
+
+    if let Some(tail) = &layout.unsized_tail {
+        let elem = match tail.ty.as_ref() {
+            "str" => Some("ctypes.c_uint8"),
+            ty if ty.starts_with('[') && ty.ends_with(']') => {
+                ctypes_type_name(&ty[1..ty.len() - 1], tail.stride.unwrap_or(0))
+            }
+            _ => None,
+        };
+
+        let (ty, alignment) = match elem {
+            Some(elem) => (format!("{} * 0", elem), tail.stride.unwrap_or(1)),
+            None => (String::from("ctypes.c_uint8 * 0"), 1),
+        };
+
+        members.push(Member {
+            name: identifier(&tail.name),
+            ty,
+            alignment,
+        });
+    }
+
+    members
+}
+
+/// The members of an enum: its tag, and the bytes of the payload after it.
+fn enum_members(layout: &TypeLayoutInfo) -> Vec<Member> {
+    let mut members = Vec::new();
+
+    let tag = match &layout.tag {
+        Some(tag) => tag,
+        None => return members,
+    };
+
+    let tag_type = match tag.ty.as_ref() {
+        "C enum" => "ctypes.c_int",
+        ty => ctypes_type_name(ty, tag.size).unwrap_or("ctypes.c_int"),
+    };
+    members.push(Member {
+        name: String::from("tag"),
+        ty: String::from(tag_type),
+        alignment: tag.size,
+    });
+
+    let tag_end = tag.offset + tag.size;
+    if tag.payload_offset > tag_end {
+        members.push(Member {
+            name: String::from("_pad0"),
+            ty: format!("ctypes.c_uint8 * {}", tag.payload_offset - tag_end),
+            alignment: 1,
+        });
+    }
+
+    if layout.size > tag.payload_offset {
+        let size = layout.size - tag.payload_offset;
+        let ty = opaque_type(size, layout.alignment, tag.payload_offset);
+        members.push(Member {
+            name: String::from("payload"),
+            alignment: opaque_alignment(size, layout.alignment, tag.payload_offset),
+            ty,
+        });
+    }
+
+    members
+}
+
+/// An array standing in for a field whose type has no ctypes equivalent,
+/// made of the widest integers that keep its alignment.
+fn opaque_type(size: usize, alignment: usize, offset: usize) -> String {
+    let width = opaque_alignment(size, alignment, offset);
+    format!("ctypes.c_uint{} * {}", width * 8, size / width)
+}
+
+fn opaque_alignment(size: usize, alignment: usize, offset: usize) -> usize {
+    [8, 4, 2]
+        .iter()
+        .copied()
+        .find(|&width| width <= alignment && size % width == 0 && offset % width == 0)
+        .unwrap_or(1)
+}
+
+/// The ctypes type for a Rust type of the given size, if there's one with
+/// the same layout.
+fn ctypes_type_name(ty: &str, size: usize) -> Option<&'static str> {
+    let ctype = match ty {
+        "u8" => "ctypes.c_uint8",
+        "i8" => "ctypes.c_int8",
+        "u16" => "ctypes.c_uint16",
+        "i16" => "ctypes.c_int16",
+        "u32" | "char" => "ctypes.c_uint32",
+        "i32" => "ctypes.c_int32",
+        "u64" => "ctypes.c_uint64",
+        "i64" => "ctypes.c_int64",
+        "usize" => "ctypes.c_size_t",
+        "isize" => "ctypes.c_ssize_t",
+        "f32" => "ctypes.c_float",
+        "f64" => "ctypes.c_double",
+        "bool" => "ctypes.c_bool",
+        _ if crate::c_header::is_pointer(ty) && size == core::mem::size_of::<usize>() => {
+            "ctypes.c_void_p"
+        }
+        _ => return None,
+    };
+
+    Some(ctype)
+}
+
+/// Turns a Rust name into a Python identifier.
+fn identifier(name: &str) -> String {
+    let mut identifier = crate::c_header::sanitize(name);
+    if is_python_keyword(&identifier) {
+        identifier.push('_');
+    }
+
+    identifier
+}
+
+fn is_python_keyword(name: &str) -> bool {
+    [
+        "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class",
+        "continue", "def", "del", "elif", "else", "except", "finally", "for", "from", "global",
+        "if", "import", "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return",
+        "try", "while", "with", "yield",
+    ]
+    .contains(&name)
+}
@@ -0,0 +1,899 @@
+use alloc::borrow::Cow;
+use alloc::borrow::ToOwned;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::assert::{diff_ops, DiffOp};
+use crate::{Field, LayoutReport, PaddingKind, TargetInfo, TypeLayoutInfo};
+
+impl TypeLayoutInfo {
+    /// Compares this layout with another layout of the same type, like one
+    /// built for a different target or by a different version of a library.
+    ///
+    /// Fields are matched by name. The fields of enum variants are named like
+    /// `Variant.field`.
+    ///
+    /// ```rust
+    /// use type_layout::{LayoutChange, TypeLayout};
+    ///
+    /// mod v1 {
+    ///     #[derive(type_layout::TypeLayout)]
+    ///     #[repr(C)]
+    ///     pub struct Message {
+    ///         pub id: u32,
+    ///         pub len: u16,
+    ///     }
+    /// }
+    ///
+    /// mod v2 {
+    ///     #[derive(type_layout::TypeLayout)]
+    ///     #[repr(C)]
+    ///     pub struct Message {
+    ///         pub id: u64,
+    ///         pub len: u16,
+    ///         pub flags: u8,
+    ///     }
+    /// }
+    ///
+    /// let diff = v1::Message::type_layout().diff(&v2::Message::type_layout());
+    ///
+    /// assert!(diff.changes.contains(&LayoutChange::Size { old: 8, new: 16 }));
+    /// assert!(diff.changes.contains(&LayoutChange::FieldMoved {
+    ///     name: "len".into(),
+    ///     old: 4,
+    ///     new: 8,
+    /// }));
+    ///
+    /// for change in &diff.changes {
+    ///     println!("{}", change);
+    /// }
+    /// // prints:
+    /// // size changed from 8 to 16
+    /// // alignment changed from 4 to 8
+    /// // padding changed from 2 to 5 bytes
+    /// // `id` changed size from 4 to 8
+    /// // `id` changed type from `u32` to `u64`
+    /// // `len` moved from offset 4 to 8
+    /// // `flags` was added at offset 10 with size 1
+    /// ```
+    pub fn diff(&self, other: &TypeLayoutInfo) -> LayoutDiff {
+        let mut changes = Vec::new();
+
+        if self.size != other.size {
+            changes.push(LayoutChange::Size {
+                old: self.size,
+                new: other.size,
+            });
+        }
+
+        if self.alignment != other.alignment {
+            changes.push(LayoutChange::Alignment {
+                old: self.alignment,
+                new: other.alignment,
+            });
+        }
+
+        let (old_padding, new_padding) = (self.padding_bytes(), other.padding_bytes());
+        if old_padding != new_padding {
+            changes.push(LayoutChange::Padding {
+                old: old_padding,
+                new: new_padding,
+            });
+        }
+
+        let old_fields = named_fields(self);
+        let new_fields = named_fields(other);
+
+        for (name, old) in &old_fields {
+            let new = match new_fields.iter().find(|(new_name, _)| new_name == name) {
+                Some((_, new)) => new,
+                None => {
+                    changes.push(LayoutChange::FieldRemoved {
+                        name: name.clone(),
+                        offset: old.offset(),
+                        size: old.size(),
+                    });
+                    continue;
+                }
+            };
+
+            if old.size() != new.size() {
+                changes.push(LayoutChange::FieldResized {
+                    name: name.clone(),
+                    old: old.size(),
+                    new: new.size(),
+                });
+            }
+
+            if let (Some(old_ty), Some(new_ty)) = (field_type(old), field_type(new)) {
+                if old_ty != new_ty {
+                    changes.push(LayoutChange::FieldRetyped {
+                        name: name.clone(),
+                        old: Cow::Owned(old_ty.to_owned()),
+                        new: Cow::Owned(new_ty.to_owned()),
+                    });
+                }
+            }
+
+            if old.offset() != new.offset() {
+                changes.push(LayoutChange::FieldMoved {
+                    name: name.clone(),
+                    old: old.offset(),
+                    new: new.offset(),
+                });
+            }
+        }
+
+        for (name, new) in &new_fields {
+            if !old_fields.iter().any(|(old_name, _)| old_name == name) {
+                changes.push(LayoutChange::FieldAdded {
+                    name: name.clone(),
+                    offset: new.offset(),
+                    size: new.size(),
+                });
+            }
+        }
+
+        LayoutDiff {
+            name: Cow::Owned(other.full_name().into_owned()),
+            old: self.clone(),
+            new: other.clone(),
+            changes,
+        }
+    }
+}
+
+/// The differences between two layouts of a type. Created by
+/// [`TypeLayoutInfo::diff`].
+///
+/// Its `Display` renders the two layout tables side by side, with a marker
+/// between them on each row that differs: `|` for a row that changed, `<` for
+/// a row only in the old layout, and `>` for a row only in the new one.
+///
+/// ```rust
+/// mod v1 {
+///     #[derive(type_layout::TypeLayout)]
+///     #[repr(C)]
+///     pub struct Message {
+///         pub id: u32,
+///         pub len: u16,
+///     }
+/// }
+///
+/// mod v2 {
+///     #[derive(type_layout::TypeLayout)]
+///     #[repr(C)]
+///     pub struct Message {
+///         pub id: u32,
+///         pub flags: u8,
+///         pub len: u16,
+///     }
+/// }
+///
+/// use type_layout::TypeLayout;
+///
+/// let diff = v1::Message::type_layout().diff(&v2::Message::type_layout());
+/// println!("{}", diff);
+/// // prints:
+/// // Message (size 8, alignment 4)        Message (size 8, alignment 4)
+/// // | Offset | Name           | Size |   | Offset | Name      | Size |
+/// // | ------ | -------------- | ---- |   | ------ | --------- | ---- |
+/// // | 0      | id             | 4    |   | 0      | id        | 4    |
+/// // | 4      | len            | 2    | | | 4      | flags     | 1    |
+/// // | 6      | [tail padding] | 2    | | | 5      | [padding] | 1    |
+/// //                                    > | 6      | len       | 2    |
+/// // `len` moved from offset 4 to 6
+/// // `flags` was added at offset 4 with size 1
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct LayoutDiff {
+    pub name: Cow<'static, str>,
+
+    /// The layouts that were compared.
+    pub old: TypeLayoutInfo,
+    pub new: TypeLayoutInfo,
+
+    /// Every difference between the layouts. Changes to the type come first,
+    /// followed by changes to fields in the order of the old layout, then
+    /// fields that were added.
+    pub changes: Vec<LayoutChange>,
+}
+
+impl LayoutDiff {
+    /// Whether the layouts are the same.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+/// One difference between two layouts of a type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub enum LayoutChange {
+    Size {
+        old: usize,
+        new: usize,
+    },
+    Alignment {
+        old: usize,
+        new: usize,
+    },
+
+    /// The total number of padding bytes changed.
+    Padding {
+        old: usize,
+        new: usize,
+    },
+    FieldAdded {
+        name: Cow<'static, str>,
+        offset: usize,
+        size: usize,
+    },
+    FieldRemoved {
+        name: Cow<'static, str>,
+        offset: usize,
+        size: usize,
+    },
+    FieldMoved {
+        name: Cow<'static, str>,
+        old: usize,
+        new: usize,
+    },
+    FieldResized {
+        name: Cow<'static, str>,
+        old: usize,
+        new: usize,
+    },
+    FieldRetyped {
+        name: Cow<'static, str>,
+        old: Cow<'static, str>,
+        new: Cow<'static, str>,
+    },
+}
+
+impl fmt::Display for LayoutChange {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LayoutChange::Size { old, new } => {
+                write!(formatter, "size changed from {} to {}", old, new)
+            }
+            LayoutChange::Alignment { old, new } => {
+                write!(formatter, "alignment changed from {} to {}", old, new)
+            }
+            LayoutChange::Padding { old, new } => {
+                write!(formatter, "padding changed from {} to {} bytes", old, new)
+            }
+            LayoutChange::FieldAdded { name, offset, size } => write!(
+                formatter,
+                "`{}` was added at offset {} with size {}",
+                name, offset, size
+            ),
+            LayoutChange::FieldRemoved { name, offset, size } => write!(
+                formatter,
+                "`{}` was removed from offset {} with size {}",
+                name, offset, size
+            ),
+            LayoutChange::FieldMoved { name, old, new } => {
+                write!(formatter, "`{}` moved from offset {} to {}", name, old, new)
+            }
+            LayoutChange::FieldResized { name, old, new } => {
+                write!(formatter, "`{}` changed size from {} to {}", name, old, new)
+            }
+            LayoutChange::FieldRetyped { name, old, new } => write!(
+                formatter,
+                "`{}` changed type from `{}` to `{}`",
+                name, old, new
+            ),
+        }
+    }
+}
+
+impl fmt::Display for LayoutDiff {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let old_rows = table_rows(&self.old);
+        let new_rows = table_rows(&self.new);
+
+        let widths = |rows: &[[String; 3]]| {
+            let mut widths = ["Offset".len(), "Name".len(), "Size".len()];
+            for row in rows {
+                for (width, cell) in widths.iter_mut().zip(row) {
+                    *width = (*width).max(cell.len());
+                }
+            }
+            widths
+        };
+        let old_widths = widths(&old_rows);
+        let new_widths = widths(&new_rows);
+
+        // The width of a rendered row, like `| 0 | a | 1 |`.
+        let old_width = old_widths.iter().map(|width| width + 3).sum::<usize>() + 1;
+
+        let header = |layout: &TypeLayoutInfo| {
+            format!(
+                "{} (size {}, alignment {})",
+                layout.full_name(),
+                layout.size,
+                layout.alignment
+            )
+        };
+        let headings = ["Offset", "Name", "Size"].map(String::from);
+        let old_dashes = old_widths.map(|width| "-".repeat(width));
+        let new_dashes = new_widths.map(|width| "-".repeat(width));
+
+        let mut lines = vec![
+            (Some(header(&self.old)), ' ', Some(header(&self.new))),
+            (
+                Some(render_row(&old_widths, &headings)),
+                ' ',
+                Some(render_row(&new_widths, &headings)),
+            ),
+            (
+                Some(render_row(&old_widths, &old_dashes)),
+                ' ',
+                Some(render_row(&new_widths, &new_dashes)),
+            ),
+        ];
+
+        // Runs of removed and added rows are paired up, so that a row that
+        // changed appears on one line.
+        let mut removed = Vec::new();
+        let mut added = Vec::new();
+        let flush = |removed: &mut Vec<usize>, added: &mut Vec<usize>, lines: &mut Vec<_>| {
+            for index in 0..removed.len().max(added.len()) {
+                let old = removed
+                    .get(index)
+                    .map(|&i| render_row(&old_widths, &old_rows[i]));
+                let new = added
+                    .get(index)
+                    .map(|&j| render_row(&new_widths, &new_rows[j]));
+                let marker = match (&old, &new) {
+                    (Some(_), Some(_)) => '|',
+                    (Some(_), None) => '<',
+                    _ => '>',
+                };
+                lines.push((old, marker, new));
+            }
+            removed.clear();
+            added.clear();
+        };
+
+        for op in diff_ops(&old_rows, &new_rows) {
+            match op {
+                DiffOp::Same(i, j) => {
+                    flush(&mut removed, &mut added, &mut lines);
+                    lines.push((
+                        Some(render_row(&old_widths, &old_rows[i])),
+                        ' ',
+                        Some(render_row(&new_widths, &new_rows[j])),
+                    ));
+                }
+                DiffOp::Removed(i) => removed.push(i),
+                DiffOp::Added(j) => added.push(j),
+            }
+        }
+        flush(&mut removed, &mut added, &mut lines);
+
+        for (old, marker, new) in lines {
+            let line = format!(
+                "{:<old_width$} {} {}",
+                old.unwrap_or_default(),
+                marker,
+                new.unwrap_or_default(),
+                old_width = old_width
+            );
+            writeln!(formatter, "{}", line.trim_end())?;
+        }
+
+        for change in &self.changes {
+            match change {
+                LayoutChange::Size { .. }
+                | LayoutChange::Alignment { .. }
+                | LayoutChange::Padding { .. } => {}
+                _ => writeln!(formatter, "{}", change)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl LayoutReport {
+    /// Compares the layouts in this report with those of another report,
+    /// like one built for a different target, to check that types shared
+    /// between the targets are laid out the same way on both.
+    ///
+    /// Types are matched by name, and each pair is compared with
+    /// [`TypeLayoutInfo::diff`]. Reports are usually read with
+    /// [`LayoutReport::from_json`] from files written on each target; see
+    /// [`ReportDiff::from_json`].
+    ///
+    /// ```rust
+    /// use type_layout::{Endianness, LayoutReport, TargetInfo, TypeLayout};
+    ///
+    /// // Stands in for a type whose layout differs between targets.
+    /// mod native {
+    ///     #[derive(type_layout::TypeLayout)]
+    ///     #[repr(C)]
+    ///     pub struct Message {
+    ///         pub id: u32,
+    ///         pub len: u64,
+    ///     }
+    /// }
+    ///
+    /// mod wasm {
+    ///     #[derive(type_layout::TypeLayout)]
+    ///     #[repr(C)]
+    ///     pub struct Message {
+    ///         pub id: u32,
+    ///         pub len: u32,
+    ///     }
+    /// }
+    ///
+    /// let report = |mut layout: type_layout::TypeLayoutInfo, triple, pointer_width| {
+    ///     layout.target = Some(TargetInfo {
+    ///         pointer_width,
+    ///         endianness: Endianness::Little,
+    ///         triple: Some(triple),
+    ///     });
+    ///
+    ///     let mut report = LayoutReport::new();
+    ///     report.push(layout);
+    ///     report
+    /// };
+    ///
+    /// let native = report(native::Message::type_layout(), "x86_64-unknown-linux-gnu".into(), 64);
+    /// let wasm = report(wasm::Message::type_layout(), "wasm32-unknown-unknown".into(), 32);
+    ///
+    /// let diff = native.diff(&wasm);
+    /// assert!(!diff.is_empty());
+    ///
+    /// print!("{}", diff);
+    /// // prints:
+    /// // x86_64-unknown-linux-gnu (64-bit, little endian) -> wasm32-unknown-unknown (32-bit, little endian)
+    /// //
+    /// // Message (size 16, alignment 8)   Message (size 8, alignment 4)
+    /// // | Offset | Name      | Size |   | Offset | Name | Size |
+    /// // | ------ | --------- | ---- |   | ------ | ---- | ---- |
+    /// // | 0      | id        | 4    |   | 0      | id   | 4    |
+    /// // | 4      | [padding] | 4    | | | 4      | len  | 4    |
+    /// // | 8      | len       | 8    | <
+    /// // `len` changed size from 8 to 4
+    /// // `len` changed type from `u64` to `u32`
+    /// // `len` moved from offset 8 to 4
+    /// ```
+    pub fn diff(&self, other: &LayoutReport) -> ReportDiff {
+        let mut changed = Vec::new();
+        let mut removed = Vec::new();
+
+        for old in self.layouts() {
+            let name = old.full_name();
+            match other.layouts().iter().find(|new| new.full_name() == name) {
+                Some(new) => {
+                    let diff = old.diff(new);
+                    if !diff.is_empty() {
+                        changed.push(diff);
+                    }
+                }
+                None => removed.push(old.clone()),
+            }
+        }
+
+        let added = other
+            .layouts()
+            .iter()
+            .filter(|new| {
+                let name = new.full_name();
+                !self.layouts().iter().any(|old| old.full_name() == name)
+            })
+            .cloned()
+            .collect();
+
+        ReportDiff {
+            old_target: target_of(self),
+            new_target: target_of(other),
+            changed,
+            removed,
+            added,
+        }
+    }
+}
+
+/// The first target named by a report's layouts. Layouts in one report are
+/// normally all computed for the same target.
+fn target_of(report: &LayoutReport) -> Option<TargetInfo> {
+    report
+        .layouts()
+        .iter()
+        .find_map(|layout| layout.target.clone())
+}
+
+/// The differences between two reports of layouts, like those of the same
+/// types on two targets. Created by [`LayoutReport::diff`].
+///
+/// Its `Display` names the two targets, then shows each type that differs
+/// like a [`LayoutDiff`], followed by the types that are only in one report.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReportDiff {
+    /// The targets the reports were computed for, if their layouts record
+    /// one.
+    pub old_target: Option<TargetInfo>,
+    pub new_target: Option<TargetInfo>,
+
+    /// The types whose layouts differ, in the order of the old report.
+    /// Types laid out the same way in both reports aren't included.
+    pub changed: Vec<LayoutDiff>,
+
+    /// Types that are only in the old report.
+    pub removed: Vec<TypeLayoutInfo>,
+
+    /// Types that are only in the new report.
+    pub added: Vec<TypeLayoutInfo>,
+}
+
+impl ReportDiff {
+    /// Whether every type is in both reports and laid out the same way in
+    /// each.
+    pub fn is_empty(&self) -> bool {
+        self.changed.is_empty() && self.removed.is_empty() && self.added.is_empty()
+    }
+}
+
+impl fmt::Display for ReportDiff {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let target = |target: &Option<TargetInfo>| match target {
+            Some(target) => target.to_string(),
+            None => "unknown target".to_owned(),
+        };
+        writeln!(
+            formatter,
+            "{} -> {}",
+            target(&self.old_target),
+            target(&self.new_target)
+        )?;
+
+        if self.is_empty() {
+            return writeln!(formatter, "layouts are the same");
+        }
+
+        for diff in &self.changed {
+            writeln!(formatter)?;
+            write!(formatter, "{}", diff)?;
+        }
+
+        if !self.removed.is_empty() || !self.added.is_empty() {
+            writeln!(formatter)?;
+        }
+        for layout in &self.removed {
+            writeln!(
+                formatter,
+                "`{}` is only in the old layouts",
+                layout.full_name()
+            )?;
+        }
+        for layout in &self.added {
+            writeln!(
+                formatter,
+                "`{}` is only in the new layouts",
+                layout.full_name()
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The offset, name, and size of each row in a layout's table. Rows of enum
+/// variants are named like `Variant.field`.
+fn table_rows(layout: &TypeLayoutInfo) -> Vec<[String; 3]> {
+    let fields = layout.fields.iter().map(|field| (None, field));
+    let variant_fields = layout.variants.iter().flat_map(|variant| {
+        variant
+            .fields
+            .iter()
+            .map(move |field| (Some(&variant.name), field))
+    });
+
+    fields
+        .chain(variant_fields)
+        .map(|(variant, field)| {
+            let name = match field {
+                Field::Field { name, .. } => name.as_ref(),
+                Field::Padding {
+                    kind: PaddingKind::Interior,
+                    ..
+                } => "[padding]",
+                Field::Padding {
+                    kind: PaddingKind::Trailing,
+                    ..
+                } => "[tail padding]",
+            };
+            let name = match variant {
+                Some(variant) => format!("{}.{}", variant, name),
+                None => name.to_owned(),
+            };
+
+            [field.offset().to_string(), name, field.size().to_string()]
+        })
+        .collect()
+}
+
+fn render_row(widths: &[usize; 3], cells: &[String; 3]) -> String {
+    let mut row = String::new();
+    for (width, cell) in widths.iter().zip(cells) {
+        row.push_str(&format!("| {:<width$} ", cell, width = width));
+    }
+    row.push('|');
+    row
+}
+
+/// The fields of a layout paired with the names they're compared by.
+fn named_fields(layout: &TypeLayoutInfo) -> Vec<(Cow<'static, str>, &Field)> {
+    let fields = layout.fields.iter().map(|field| (None, field));
+    let variant_fields = layout.variants.iter().flat_map(|variant| {
+        variant
+            .fields
+            .iter()
+            .map(move |field| (Some(&variant.name), field))
+    });
+
+    fields
+        .chain(variant_fields)
+        .filter_map(|(variant, field)| match (variant, field) {
+            (None, Field::Field { name, .. }) => Some((name.clone(), field)),
+            (Some(variant), Field::Field { name, .. }) => {
+                Some((Cow::Owned(format!("{}.{}", variant, name)), field))
+            }
+            (_, Field::Padding { .. }) => None,
+        })
+        .collect()
+}
+
+/// The type of a field as written in the source, which is the same no matter
+/// which target the layout was built for.
+fn field_type(field: &Field) -> Option<&str> {
+    match field {
+        Field::Field { ty, .. } => Some(ty),
+        Field::Padding { .. } => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::boxed::Box;
+
+    use super::*;
+    use crate::{TypeLayoutBuilder, Variant};
+
+    fn header() -> TypeLayoutInfo {
+        TypeLayoutInfo::builder("Header")
+            .field("id", "u32", 4, 4)
+            .field("len", "u16", 2, 2)
+            .build()
+            .unwrap()
+    }
+
+    fn variant(name: &'static str, builder: TypeLayoutBuilder) -> Variant {
+        Variant {
+            name: name.into(),
+            discriminant: 0,
+            fields: builder.build().unwrap().fields,
+        }
+    }
+
+    #[test]
+    fn same_layout() {
+        let diff = header().diff(&header());
+
+        assert!(diff.is_empty());
+        assert_eq!(diff.name, "Header");
+    }
+
+    #[test]
+    fn moved_fields() {
+        let reordered = TypeLayoutInfo::builder("Header")
+            .field("len", "u16", 2, 2)
+            .field("id", "u32", 4, 4)
+            .build()
+            .unwrap();
+
+        // The padding moved too, but there's as much of it.
+        assert_eq!(
+            header().diff(&reordered).changes,
+            vec![
+                LayoutChange::FieldMoved {
+                    name: "id".into(),
+                    old: 0,
+                    new: 4,
+                },
+                LayoutChange::FieldMoved {
+                    name: "len".into(),
+                    old: 4,
+                    new: 0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn added_and_removed_fields() {
+        let renamed = TypeLayoutInfo::builder("Header")
+            .field("id", "u32", 4, 4)
+            .field("length", "u16", 2, 2)
+            .build()
+            .unwrap();
+
+        let diff = header().diff(&renamed);
+        assert_eq!(
+            diff.changes,
+            vec![
+                LayoutChange::FieldRemoved {
+                    name: "len".into(),
+                    offset: 4,
+                    size: 2,
+                },
+                LayoutChange::FieldAdded {
+                    name: "length".into(),
+                    offset: 4,
+                    size: 2,
+                },
+            ]
+        );
+        assert_eq!(
+            diff.to_string(),
+            "\
+Header (size 8, alignment 4)         Header (size 8, alignment 4)
+| Offset | Name           | Size |   | Offset | Name           | Size |
+| ------ | -------------- | ---- |   | ------ | -------------- | ---- |
+| 0      | id             | 4    |   | 0      | id             | 4    |
+| 4      | len            | 2    | | | 4      | length         | 2    |
+| 6      | [tail padding] | 2    |   | 6      | [tail padding] | 2    |
+`len` was removed from offset 4 with size 2
+`length` was added at offset 4 with size 2
+"
+        );
+    }
+
+    #[test]
+    fn changed_padding() {
+        let packed = TypeLayoutInfo::builder("Header")
+            .field("id", "u32", 4, 4)
+            .field("len", "u16", 2, 2)
+            .packed(1)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            header().diff(&packed).changes,
+            vec![
+                LayoutChange::Size { old: 8, new: 6 },
+                LayoutChange::Alignment { old: 4, new: 1 },
+                LayoutChange::Padding { old: 2, new: 0 },
+            ]
+        );
+    }
+
+    #[test]
+    fn changed_variant() {
+        // Enums have no fields of their own, and each variant's padding
+        // extends to the end of the enum. Only bytes that are padding in
+        // every variant count as padding.
+        let event = |width: usize| {
+            let mut layout = TypeLayoutInfo::builder("Event").align(4).build().unwrap();
+            layout.size = 8;
+            layout.variants = vec![
+                variant(
+                    "Quit",
+                    TypeLayoutInfo::builder("Quit")
+                        .field("[tag]", "u8", 1, 1)
+                        .align(8),
+                ),
+                variant(
+                    "Key",
+                    TypeLayoutInfo::builder("Key")
+                        .field("[tag]", "u8", 1, 1)
+                        .field("0", "u32", width, width)
+                        .align(8),
+                ),
+            ];
+            layout
+        };
+
+        assert_eq!(
+            event(4).diff(&event(2)).changes,
+            vec![
+                LayoutChange::Padding { old: 3, new: 5 },
+                LayoutChange::FieldResized {
+                    name: "Key.0".into(),
+                    old: 4,
+                    new: 2,
+                },
+                LayoutChange::FieldMoved {
+                    name: "Key.0".into(),
+                    old: 4,
+                    new: 2,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn changed_nested() {
+        let packet = |inner: TypeLayoutInfo| {
+            let mut layout = TypeLayoutInfo::builder("Packet")
+                .field("header", "Header", inner.size, inner.alignment)
+                .field("crc", "u32", 4, 4)
+                .build()
+                .unwrap();
+            if let Field::Field { nested, .. } = &mut layout.fields[0] {
+                *nested = Some(Box::new(inner));
+            }
+            layout
+        };
+        let wide = TypeLayoutInfo::builder("Header")
+            .field("id", "u64", 8, 8)
+            .field("len", "u16", 2, 2)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            packet(header()).diff(&packet(wide)).changes,
+            vec![
+                LayoutChange::Size { old: 12, new: 24 },
+                LayoutChange::Alignment { old: 4, new: 8 },
+                LayoutChange::Padding { old: 0, new: 4 },
+                LayoutChange::FieldResized {
+                    name: "header".into(),
+                    old: 8,
+                    new: 16,
+                },
+                LayoutChange::FieldMoved {
+                    name: "crc".into(),
+                    old: 8,
+                    new: 16,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn report_diff() {
+        let mut old = LayoutReport::new();
+        old.push(header());
+        old.push(TypeLayoutInfo::builder("Gone").build().unwrap());
+
+        let mut new = LayoutReport::new();
+        new.push(
+            TypeLayoutInfo::builder("Header")
+                .field("id", "u64", 8, 8)
+                .field("len", "u16", 2, 2)
+                .build()
+                .unwrap(),
+        );
+        new.push(TypeLayoutInfo::builder("Fresh").build().unwrap());
+
+        let diff = old.diff(&new);
+        assert!(!diff.is_empty());
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].name, "Header");
+        assert_eq!(diff.removed[0].name, "Gone");
+        assert_eq!(diff.added[0].name, "Fresh");
+
+        let output = diff.to_string();
+        assert!(output.starts_with("unknown target -> unknown target\n"));
+        assert!(output.ends_with(
+            "\n`Gone` is only in the old layouts\n`Fresh` is only in the new layouts\n"
+        ));
+
+        let same = old.diff(&old);
+        assert!(same.is_empty());
+        assert_eq!(
+            same.to_string(),
+            "unknown target -> unknown target\nlayouts are the same\n"
+        );
+    }
+}
@@ -0,0 +1,209 @@
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use crate::{Field, LayoutReport, TypeLayoutInfo};
+
+impl TypeLayoutInfo {
+    /// Generates a [Graphviz](https://graphviz.org/) graph of this type and
+    /// the types of its nested fields, to visualize a hierarchy of structs.
+    /// Render it with `dot -Tsvg`.
+    ///
+    /// Each type is a record listing the offset, name, type, and size of its
+    /// fields and padding, and an edge leads from each field marked
+    /// `#[layout(nested)]` to the record of its type. Each type appears once,
+    /// however many fields contain it.
+    ///
+    /// ```rust
+    /// use type_layout::TypeLayout;
+    ///
+    /// #[derive(TypeLayout)]
+    /// #[repr(C)]
+    /// struct Header {
+    ///     kind: u8,
+    ///     len: u32,
+    /// }
+    ///
+    /// #[derive(TypeLayout)]
+    /// #[repr(C)]
+    /// struct Packet {
+    ///     #[layout(nested)]
+    ///     header: Header,
+    ///     checksum: u32,
+    /// }
+    ///
+    /// print!("{}", Packet::type_layout().to_dot());
+    /// // prints:
+    /// // digraph layout {
+    /// //     node [shape=record, fontname="monospace"];
+    /// //     "Packet" [label="{Packet (size 12, alignment 4)|<f0>0: header: Header (8)|<f1>8: checksum: u32 (4)}"];
+    /// //     "Packet":f0 -> "Header";
+    /// //     "Header" [label="{Header (size 8, alignment 4)|<f0>0: kind: u8 (1)|<f1>1: [padding] (3)|<f2>4: len: u32 (4)}"];
+    /// // }
+    /// ```
+    pub fn to_dot(&self) -> String {
+        render(core::slice::from_ref(self))
+    }
+}
+
+impl LayoutReport {
+    /// Generates a [Graphviz](https://graphviz.org/) graph of every layout in
+    /// the report and the types of their nested fields. See
+    /// [`TypeLayoutInfo::to_dot`].
+    pub fn to_dot(&self) -> String {
+        render(self.layouts())
+    }
+}
+
+fn render(layouts: &[TypeLayoutInfo]) -> String {
+    let mut out = String::from("digraph layout {\n");
+    let _ = writeln!(out, "    node [shape=record, fontname=\"monospace\"];");
+
+    let mut declared = Vec::new();
+    for layout in layouts {
+        declare(&mut out, layout, &mut declared);
+    }
+
+    out.push_str("}\n");
+    out
+}
+
+/// Adds the node for `layout` and its edges, followed by the nodes of the
+/// types of its nested fields. `declared` holds the names of the types that
+/// have nodes so far.
+fn declare<'a>(out: &mut String, layout: &'a TypeLayoutInfo, declared: &mut Vec<Cow<'a, str>>) {
+    let name = layout.full_name();
+    if declared.contains(&name) {
+        return;
+    }
+    declared.push(name.clone());
+
+    let kind = if layout.is_union { "union, " } else { "" };
+    let mut label = format!(
+        "{{{} ({}size {}, alignment {})",
+        escape_record(&name),
+        kind,
+        layout.size,
+        layout.alignment
+    );
+
+    let mut nested_fields = Vec::new();
+    let mut port = 0;
+
+    let variants = layout
+        .variants
+        .iter()
+        .map(|variant| (Some(variant), &variant.fields));
+    let groups: Vec<_> = if layout.variants.is_empty() {
+        alloc::vec![(None, &layout.fields)]
+    } else {
+        variants.collect()
+    };
+
+    for (variant, fields) in groups {
+        if let Some(variant) = variant {
+            let _ = write!(
+                label,
+                "|Variant {} = {}",
+                escape_record(&variant.name),
+                variant.discriminant
+            );
+        }
+
+        for field in fields {
+            let _ = write!(label, "|<f{}>{}: ", port, field.offset());
+
+            match field {
+                Field::Field {
+                    name,
+                    ty,
+                    resolved_ty,
+                    size,
+                    array,
+                    nested,
+                    ..
+                } => {
+                    let ty = match (nested, array) {
+                        (Some(nested), Some(array)) => {
+                            Cow::Owned(format!("[{}; {}]", nested.full_name(), array.len))
+                        }
+                        (Some(nested), None) => nested.full_name(),
+                        (None, _) => Cow::Borrowed(resolved_ty.as_ref().unwrap_or(ty).as_ref()),
+                    };
+                    let _ = write!(
+                        label,
+                        "{}: {} ({})",
+                        escape_record(name),
+                        escape_record(&ty),
+                        size
+                    );
+
+                    if let Some(nested) = nested {
+                        nested_fields.push((port, nested));
+                    }
+                }
+                Field::Padding { size, .. } => {
+                    let _ = write!(label, "[padding] ({})", size);
+                }
+            }
+
+            port += 1;
+        }
+    }
+
+    if let Some(tail) = &layout.unsized_tail {
+        let _ = write!(
+            label,
+            "|{}: {}: {} (unsized)",
+            tail.offset,
+            escape_record(&tail.name),
+            escape_record(&tail.ty)
+        );
+    }
+
+    label.push('}');
+
+    let _ = writeln!(
+        out,
+        "    \"{}\" [label=\"{}\"];",
+        escape_id(&name),
+        escape_id(&label)
+    );
+
+    for (port, nested) in &nested_fields {
+        let _ = writeln!(
+            out,
+            "    \"{}\":f{} -> \"{}\";",
+            escape_id(&name),
+            port,
+            escape_id(&nested.full_name())
+        );
+    }
+
+    for (_, nested) in nested_fields {
+        declare(out, nested, declared);
+    }
+}
+
+/// Escapes the characters that have a meaning in record labels.
+fn escape_record(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for ch in text.chars() {
+        if matches!(ch, '{' | '}' | '|' | '<' | '>') {
+            escaped.push('\\');
+        }
+        escaped.push(ch);
+    }
+
+    escaped
+}
+
+/// Escapes text for a quoted string in the dot language, where only quotes
+/// need escaping. Backslashes are left alone, since record labels use them
+/// for escapes of their own.
+fn escape_id(text: &str) -> String {
+    text.replace('"', "\\\"")
+}
@@ -0,0 +1,789 @@
+//! Reads the layouts of structs from the DWARF debug info of a compiled
+//! binary, to check Rust types against the layouts a C or C++ compiler
+//! actually produced.
+//!
+//! This module is only available with the `dwarf` feature. The binary can be
+//! an executable, a shared library, or a separate debug info file, in any
+//! format the [object](https://crates.io/crates/object) crate reads. It needs
+//! to have been compiled with debug info, like with `-g`, and linked, since
+//! the debug info in object files refers to names that are only filled in by
+//! the linker.
+//!
+//! DWARF records the size of each struct and the offset of each field, but
+//! usually not alignment, so the alignment of types is inferred from their
+//! sizes and the offsets of their fields. Consecutive bitfields are combined
+//! into one field, named like `a, b`, covering the bytes they're stored in.
+//!
+//! ```rust,no_run
+//! use type_layout::TypeLayout;
+//!
+//! #[derive(TypeLayout)]
+//! #[repr(C)]
+//! struct Header {
+//!     kind: u8,
+//!     len: u32,
+//! }
+//!
+//! let binary = std::fs::read("target/debug/libheader.so").unwrap();
+//! let c_header = type_layout::dwarf::layout(&binary, "header").unwrap().unwrap();
+//!
+//! for change in &c_header.diff(&Header::type_layout()).changes {
+//!     println!("{}", change);
+//! }
+//! ```
+
+use std::borrow::Cow;
+use std::boxed::Box;
+use std::convert::TryFrom;
+use std::fmt;
+use std::format;
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+use gimli::{AttributeValue, EndianSlice, RunTimeEndian, UnitOffset};
+use object::{Object, ObjectSection};
+
+use crate::{ArrayInfo, Endianness, Field, Repr, TargetInfo, TypeLayoutInfo};
+
+type Reader<'data> = EndianSlice<'data, RunTimeEndian>;
+type Unit<'data> = gimli::Unit<Reader<'data>>;
+type Dwarf<'data> = gimli::Dwarf<Reader<'data>>;
+
+/// How deeply types can refer to other types before reading stops, which
+/// guards against cycles in malformed debug info.
+const MAX_DEPTH: usize = 64;
+
+/// An error from reading debug info.
+#[derive(Debug)]
+pub enum DwarfError {
+    /// The binary couldn't be parsed.
+    Object(object::Error),
+
+    /// The debug info couldn't be parsed.
+    Dwarf(gimli::Error),
+
+    /// The binary doesn't have a `.debug_info` section.
+    NoDebugInfo,
+}
+
+impl fmt::Display for DwarfError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DwarfError::Object(error) => write!(formatter, "failed to read binary: {}", error),
+            DwarfError::Dwarf(error) => write!(formatter, "failed to read debug info: {}", error),
+            DwarfError::NoDebugInfo => write!(formatter, "binary has no debug info"),
+        }
+    }
+}
+
+impl std::error::Error for DwarfError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DwarfError::Object(error) => Some(error),
+            DwarfError::Dwarf(error) => Some(error),
+            DwarfError::NoDebugInfo => None,
+        }
+    }
+}
+
+impl From<object::Error> for DwarfError {
+    fn from(error: object::Error) -> Self {
+        DwarfError::Object(error)
+    }
+}
+
+impl From<gimli::Error> for DwarfError {
+    fn from(error: gimli::Error) -> Self {
+        DwarfError::Dwarf(error)
+    }
+}
+
+/// Reads the layout of every named struct, class, and union in the binary's
+/// debug info. Types inside namespaces or other types are named like
+/// `outer::Inner`. A type that's described by several compilation units is
+/// only included once.
+pub fn layouts(binary: &[u8]) -> Result<Vec<TypeLayoutInfo>, DwarfError> {
+    let mut layouts: Vec<TypeLayoutInfo> = Vec::new();
+
+    read_units(binary, |dwarf, unit| {
+        let mut tree = unit.entries_tree(None)?;
+        let root = tree.root()?;
+        collect(dwarf, unit, root, "", &mut layouts)
+    })?;
+
+    let target = target_of(&object::File::parse(binary)?);
+    for layout in &mut layouts {
+        set_target(layout, &target);
+    }
+
+    Ok(layouts)
+}
+
+/// The target a binary was compiled for. Binaries don't record their target
+/// triple, so it's left out.
+fn target_of(file: &object::File<'_>) -> TargetInfo {
+    TargetInfo {
+        pointer_width: if file.is_64() { 64 } else { 32 },
+        endianness: if file.is_little_endian() {
+            Endianness::Little
+        } else {
+            Endianness::Big
+        },
+        triple: None,
+    }
+}
+
+/// Sets the target of a layout and the layouts of its nested fields.
+fn set_target(layout: &mut TypeLayoutInfo, target: &TargetInfo) {
+    layout.target = Some(target.clone());
+
+    for field in &mut layout.fields {
+        if let Field::Field {
+            nested: Some(nested),
+            ..
+        } = field
+        {
+            set_target(nested, target);
+        }
+    }
+}
+
+/// Reads the layout of the struct, class, or union with the given name, like
+/// `header` or `net::Header`. See [`layouts`].
+pub fn layout(binary: &[u8], name: &str) -> Result<Option<TypeLayoutInfo>, DwarfError> {
+    Ok(layouts(binary)?
+        .into_iter()
+        .find(|layout| layout.name == name))
+}
+
+/// Loads the debug info of `binary` and calls `visit` with each unit.
+fn read_units(
+    binary: &[u8],
+    mut visit: impl FnMut(&Dwarf<'_>, &Unit<'_>) -> Result<(), DwarfError>,
+) -> Result<(), DwarfError> {
+    let file = object::File::parse(binary)?;
+    if file.section_by_name(".debug_info").is_none() {
+        return Err(DwarfError::NoDebugInfo);
+    }
+
+    let endian = if file.is_little_endian() {
+        RunTimeEndian::Little
+    } else {
+        RunTimeEndian::Big
+    };
+
+    let sections = gimli::DwarfSections::load(|id| -> Result<Cow<'_, [u8]>, DwarfError> {
+        match file.section_by_name(id.name()) {
+            Some(section) => Ok(section.uncompressed_data()?),
+            None => Ok(Cow::Borrowed(&[])),
+        }
+    })?;
+    let dwarf = sections.borrow(|section| EndianSlice::new(section, endian));
+
+    let mut headers = dwarf.units();
+    while let Some(header) = headers.next()? {
+        let unit = dwarf.unit(header)?;
+        visit(&dwarf, &unit)?;
+    }
+
+    Ok(())
+}
+
+/// Adds the layouts of the types under `node` to `layouts`. `scope` is the
+/// name of the namespace or type the node is in, like `outer::`.
+fn collect(
+    dwarf: &Dwarf<'_>,
+    unit: &Unit<'_>,
+    node: gimli::EntriesTreeNode<'_, '_, '_, Reader<'_>>,
+    scope: &str,
+    layouts: &mut Vec<TypeLayoutInfo>,
+) -> Result<(), DwarfError> {
+    let entry = node.entry();
+    let tag = entry.tag();
+    let name = entry_name(dwarf, unit, entry)?;
+
+    let is_record = matches!(
+        tag,
+        gimli::DW_TAG_structure_type | gimli::DW_TAG_class_type | gimli::DW_TAG_union_type
+    );
+
+    let scope = match (&name, tag) {
+        (Some(name), gimli::DW_TAG_namespace) => format!("{}{}::", scope, name),
+        (Some(name), _) if is_record => {
+            let qualified = format!("{}{}", scope, name);
+            if !layouts.iter().any(|layout| layout.name == qualified) {
+                if let Some(mut layout) = record_layout(dwarf, unit, entry.offset(), 0)? {
+                    layout.name = Cow::Owned(qualified.clone());
+                    layouts.push(layout);
+                }
+            }
+            format!("{}::", qualified)
+        }
+        _ => scope.to_string(),
+    };
+
+    let mut children = node.children();
+    while let Some(child) = children.next()? {
+        collect(dwarf, unit, child, &scope, layouts)?;
+    }
+
+    Ok(())
+}
+
+/// Reads the layout of a struct, class, or union. Declarations without a
+/// definition have no layout.
+fn record_layout(
+    dwarf: &Dwarf<'_>,
+    unit: &Unit<'_>,
+    offset: UnitOffset,
+    depth: usize,
+) -> Result<Option<TypeLayoutInfo>, DwarfError> {
+    let mut tree = unit.entries_tree(Some(offset))?;
+    let root = tree.root()?;
+    let entry = root.entry();
+
+    if entry.attr_value(gimli::DW_AT_declaration)?.is_some() {
+        return Ok(None);
+    }
+
+    let size = match udata(entry, gimli::DW_AT_byte_size)? {
+        Some(size) => size,
+        None => return Ok(None),
+    };
+
+    let name = entry_name(dwarf, unit, entry)?.unwrap_or_else(|| String::from("(anonymous)"));
+    let is_union = entry.tag() == gimli::DW_TAG_union_type;
+    let explicit_alignment = udata(entry, gimli::DW_AT_alignment)?;
+
+    let mut fields = Vec::new();
+    let mut bitfields: Option<(String, usize, usize)> = None;
+
+    let mut children = root.children();
+    while let Some(child) = children.next()? {
+        let member = child.entry();
+        let (member_name, is_base) = match member.tag() {
+            gimli::DW_TAG_member if member.attr_value(gimli::DW_AT_external)?.is_some() => continue,
+            gimli::DW_TAG_member => (entry_name(dwarf, unit, member)?, false),
+            gimli::DW_TAG_inheritance => (None, true),
+            _ => continue,
+        };
+
+        let ty = match type_info(dwarf, unit, member, depth + 1)? {
+            Some(ty) => ty,
+            None => continue,
+        };
+
+        let member_name = match (member_name, is_base) {
+            (_, true) => format!("[base] {}", ty.name),
+            (Some(name), false) => name,
+            (None, false) => String::from("(anonymous)"),
+        };
+
+        let location = member_location(member)?;
+
+        if let Some(bit_size) = udata(member, gimli::DW_AT_bit_size)? {
+            let bit_offset = match udata(member, gimli::DW_AT_data_bit_offset)? {
+                Some(bit_offset) => bit_offset,
+                None => legacy_bit_offset(member, location.unwrap_or(0), ty.size, bit_size)?,
+            };
+
+            let start = bit_offset / 8;
+            let end = (bit_offset + bit_size + 7) / 8;
+
+            bitfields = match bitfields.take() {
+                Some((names, group_start, group_end)) if start < group_end => Some((
+                    format!("{}, {}", names, member_name),
+                    group_start,
+                    group_end.max(end),
+                )),
+                Some(group) => {
+                    fields.push(bitfield(group));
+                    Some((member_name, start, end))
+                }
+                None => Some((member_name, start, end)),
+            };
+            continue;
+        }
+
+        if let Some(group) = bitfields.take() {
+            fields.push(bitfield(group));
+        }
+
+        fields.push(Field::Field {
+            name: Cow::Owned(member_name),
+            ty: Cow::Owned(ty.name),
+            resolved_ty: None,
+            type_id: None,
+            offset: location.unwrap_or(0),
+            size: ty.size,
+            alignment: Some(ty.alignment),
+            array: ty.array,
+            needs_drop: false,
+            option_size: None,
+            nested: ty.nested.map(Box::new),
+            contended: false,
+            niche: false,
+        });
+    }
+
+    if let Some(group) = bitfields.take() {
+        fields.push(bitfield(group));
+    }
+
+    let natural_alignment = fields
+        .iter()
+        .filter_map(|field| match field {
+            Field::Field { alignment, .. } => *alignment,
+            Field::Padding { .. } => None,
+        })
+        .max()
+        .unwrap_or(1);
+
+    // Lower the alignment until it's consistent with the size and the
+    // offsets of the fields, which it isn't for packed structs.
+    let mut alignment = natural_alignment;
+    while alignment > 1
+        && (size % alignment != 0
+            || fields.iter().any(|field| match field {
+                Field::Field {
+                    offset,
+                    alignment: Some(field_alignment),
+                    ..
+                } => offset % alignment.min(*field_alignment) != 0,
+                _ => false,
+            }))
+    {
+        alignment /= 2;
+    }
+
+    let packed = if alignment < natural_alignment {
+        Some(alignment)
+    } else {
+        None
+    };
+
+    let alignment = match explicit_alignment {
+        Some(explicit) => explicit.max(alignment),
+        None => alignment,
+    };
+
+    Ok(Some(TypeLayoutInfo {
+        name: Cow::Owned(name),
+        generics: Vec::new(),
+        size,
+        alignment,
+        natural_alignment,
+        needs_drop: false,
+        option_size: None,
+        is_union,
+        repr: Repr {
+            c: true,
+            packed,
+            align: explicit_alignment,
+            ..Repr::default()
+        },
+        guaranteed: true,
+        fields: crate::__insert_padding(fields, size),
+        variants: Vec::new(),
+        unsized_tail: None,
+        tag: None,
+        target: None,
+    }))
+}
+
+/// A field covering the bytes of a run of bitfields.
+fn bitfield((names, start, end): (String, usize, usize)) -> Field {
+    Field::Field {
+        name: Cow::Owned(names),
+        ty: Cow::Borrowed("bitfield"),
+        resolved_ty: None,
+        type_id: None,
+        offset: start,
+        size: end - start,
+        alignment: Some(1),
+        array: None,
+        needs_drop: false,
+        option_size: None,
+        nested: None,
+        contended: false,
+        niche: false,
+    }
+}
+
+/// What a field needs to know about its type.
+struct TypeInfo {
+    name: String,
+    size: usize,
+    alignment: usize,
+    array: Option<ArrayInfo>,
+    nested: Option<TypeLayoutInfo>,
+}
+
+/// Reads the type `entry` refers to with `DW_AT_type`.
+fn type_info(
+    dwarf: &Dwarf<'_>,
+    unit: &Unit<'_>,
+    entry: &gimli::DebuggingInformationEntry<'_, '_, Reader<'_>>,
+    depth: usize,
+) -> Result<Option<TypeInfo>, DwarfError> {
+    match entry.attr_value(gimli::DW_AT_type)? {
+        Some(AttributeValue::UnitRef(offset)) => type_at(dwarf, unit, offset, depth),
+        _ => Ok(None),
+    }
+}
+
+fn type_at(
+    dwarf: &Dwarf<'_>,
+    unit: &Unit<'_>,
+    offset: UnitOffset,
+    depth: usize,
+) -> Result<Option<TypeInfo>, DwarfError> {
+    if depth > MAX_DEPTH {
+        return Ok(None);
+    }
+
+    let entry = unit.entry(offset)?;
+    let name = entry_name(dwarf, unit, &entry)?;
+    let byte_size = udata(&entry, gimli::DW_AT_byte_size)?;
+    let explicit_alignment = udata(&entry, gimli::DW_AT_alignment)?;
+
+    let info = match entry.tag() {
+        gimli::DW_TAG_base_type | gimli::DW_TAG_enumeration_type => {
+            let size = match byte_size {
+                Some(size) => size,
+                None => return Ok(None),
+            };
+
+            TypeInfo {
+                name: name.unwrap_or_else(|| String::from("(anonymous)")),
+                size,
+                alignment: inferred_alignment(size),
+                array: None,
+                nested: None,
+            }
+        }
+        gimli::DW_TAG_pointer_type
+        | gimli::DW_TAG_reference_type
+        | gimli::DW_TAG_rvalue_reference_type
+        | gimli::DW_TAG_ptr_to_member_type => {
+            let size = byte_size.unwrap_or(usize::from(unit.encoding().address_size));
+            let pointee = match type_info(dwarf, unit, &entry, depth + 1)? {
+                Some(pointee) => pointee.name,
+                None => String::from("void"),
+            };
+            let sigil = match entry.tag() {
+                gimli::DW_TAG_reference_type => "&",
+                gimli::DW_TAG_rvalue_reference_type => "&&",
+                _ => "*",
+            };
+
+            TypeInfo {
+                name: format!("{} {}", pointee, sigil),
+                size,
+                alignment: inferred_alignment(size),
+                array: None,
+                nested: None,
+            }
+        }
+        gimli::DW_TAG_typedef
+        | gimli::DW_TAG_const_type
+        | gimli::DW_TAG_volatile_type
+        | gimli::DW_TAG_restrict_type
+        | gimli::DW_TAG_atomic_type => {
+            let mut inner = match type_info(dwarf, unit, &entry, depth + 1)? {
+                Some(inner) => inner,
+                None => return Ok(None),
+            };
+
+            inner.name = match entry.tag() {
+                gimli::DW_TAG_typedef => name.unwrap_or(inner.name),
+                gimli::DW_TAG_const_type => format!("const {}", inner.name),
+                gimli::DW_TAG_volatile_type => format!("volatile {}", inner.name),
+                gimli::DW_TAG_atomic_type => format!("_Atomic {}", inner.name),
+                _ => inner.name,
+            };
+
+            inner
+        }
+        gimli::DW_TAG_array_type => return array_at(dwarf, unit, offset, depth),
+        gimli::DW_TAG_structure_type | gimli::DW_TAG_class_type | gimli::DW_TAG_union_type => {
+            let layout = match record_layout(dwarf, unit, offset, depth)? {
+                Some(layout) => layout,
+                None => return Ok(None),
+            };
+
+            TypeInfo {
+                name: layout.name.to_string(),
+                size: layout.size,
+                alignment: layout.alignment,
+                array: None,
+                nested: Some(layout),
+            }
+        }
+        gimli::DW_TAG_subroutine_type => TypeInfo {
+            name: String::from("function"),
+            size: 0,
+            alignment: 1,
+            array: None,
+            nested: None,
+        },
+        _ => return Ok(None),
+    };
+
+    Ok(Some(TypeInfo {
+        alignment: explicit_alignment.unwrap_or(info.alignment),
+        ..info
+    }))
+}
+
+/// Reads an array type, which has a subrange child for each dimension.
+fn array_at(
+    dwarf: &Dwarf<'_>,
+    unit: &Unit<'_>,
+    offset: UnitOffset,
+    depth: usize,
+) -> Result<Option<TypeInfo>, DwarfError> {
+    let mut tree = unit.entries_tree(Some(offset))?;
+    let root = tree.root()?;
+    let elem = match type_info(dwarf, unit, root.entry(), depth + 1)? {
+        Some(elem) => elem,
+        None => return Ok(None),
+    };
+    let stride = udata(root.entry(), gimli::DW_AT_byte_stride)?.unwrap_or(elem.size);
+
+    let mut lengths = Vec::new();
+    let mut children = root.children();
+    while let Some(child) = children.next()? {
+        let subrange = child.entry();
+        if subrange.tag() != gimli::DW_TAG_subrange_type {
+            continue;
+        }
+
+        let length = match udata(subrange, gimli::DW_AT_count)? {
+            Some(count) => count,
+            None => match udata(subrange, gimli::DW_AT_upper_bound)? {
+                Some(upper_bound) => upper_bound + 1,
+                // Flexible array members have no bounds.
+                None => 0,
+            },
+        };
+        lengths.push(length);
+    }
+
+    if lengths.is_empty() {
+        lengths.push(0);
+    }
+
+    // Build the type from the innermost dimension out, so `int[2][3]` is an
+    // array of 2 `int[3]`s.
+    let mut ty_name = elem.name;
+    let mut dimensions = String::new();
+    let mut size = stride;
+    let mut array = None;
+
+    for (index, &length) in lengths.iter().enumerate().rev() {
+        let elem_ty = format!("{}{}", ty_name, dimensions);
+        let elem_size = size;
+        size = elem_size.saturating_mul(length);
+        dimensions = format!("[{}]{}", length, dimensions);
+
+        if index == 0 {
+            array = Some(ArrayInfo {
+                elem_ty: Cow::Owned(elem_ty),
+                len: length,
+                stride: elem_size,
+            });
+        }
+    }
+    ty_name.push_str(&dimensions);
+
+    Ok(Some(TypeInfo {
+        name: ty_name,
+        size,
+        alignment: elem.alignment,
+        array,
+        nested: None,
+    }))
+}
+
+/// The offset of a member from `DW_AT_data_member_location`, which is either
+/// a constant or an expression that adds a constant.
+fn member_location(
+    entry: &gimli::DebuggingInformationEntry<'_, '_, Reader<'_>>,
+) -> Result<Option<usize>, DwarfError> {
+    let value = match entry.attr_value(gimli::DW_AT_data_member_location)? {
+        Some(value) => value,
+        None => return Ok(None),
+    };
+
+    if let Some(offset) = value.udata_value() {
+        return Ok(usize::try_from(offset).ok());
+    }
+
+    if let AttributeValue::Exprloc(expression) = value {
+        let mut reader = expression.0;
+        if gimli::Reader::read_u8(&mut reader)? == gimli::DW_OP_plus_uconst.0 {
+            let offset = gimli::Reader::read_uleb128(&mut reader)?;
+            return Ok(usize::try_from(offset).ok());
+        }
+    }
+
+    Ok(None)
+}
+
+/// The bit offset of a bitfield described with DWARF 2's `DW_AT_bit_offset`,
+/// which counts from the most significant bit of the storage unit at
+/// `location`. Only little-endian targets are handled.
+fn legacy_bit_offset(
+    entry: &gimli::DebuggingInformationEntry<'_, '_, Reader<'_>>,
+    location: usize,
+    type_size: usize,
+    bit_size: usize,
+) -> Result<usize, DwarfError> {
+    let storage_size = udata(entry, gimli::DW_AT_byte_size)?.unwrap_or(type_size);
+    let from_msb = udata(entry, gimli::DW_AT_bit_offset)?.unwrap_or(0);
+
+    Ok((location * 8 + storage_size * 8).saturating_sub(from_msb + bit_size))
+}
+
+fn entry_name(
+    dwarf: &Dwarf<'_>,
+    unit: &Unit<'_>,
+    entry: &gimli::DebuggingInformationEntry<'_, '_, Reader<'_>>,
+) -> Result<Option<String>, DwarfError> {
+    match entry.attr_value(gimli::DW_AT_name)? {
+        Some(value) => {
+            let name = dwarf.attr_string(unit, value)?;
+            Ok(Some(name.to_string_lossy().into_owned()))
+        }
+        None => Ok(None),
+    }
+}
+
+fn udata(
+    entry: &gimli::DebuggingInformationEntry<'_, '_, Reader<'_>>,
+    attribute: gimli::DwAt,
+) -> Result<Option<usize>, DwarfError> {
+    let value = entry
+        .attr_value(attribute)?
+        .and_then(|value| value.udata_value())
+        .and_then(|value| usize::try_from(value).ok());
+
+    Ok(value)
+}
+
+/// The alignment of a primitive type, which is its size on most targets.
+/// Types with other sizes, like the 12-byte `long double` of 32-bit x86, are
+/// aligned to the largest power of two their size is a multiple of.
+fn inferred_alignment(size: usize) -> usize {
+    match size {
+        0 => 1,
+        size => (1 << size.trailing_zeros()).min(16),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TypeLayout;
+
+    // Generic, so the `layout-tests` feature doesn't give it a snapshot.
+    #[derive(TypeLayout)]
+    #[layout(crate = "crate")]
+    #[repr(C)]
+    struct Probe<T> {
+        kind: u8,
+        len: u32,
+        values: [T; 3],
+        next: *const u8,
+        inner: Inner,
+    }
+
+    #[repr(C)]
+    struct Inner {
+        flag: bool,
+        value: u64,
+    }
+
+    fn fields(layout: &TypeLayoutInfo) -> Vec<(String, usize, usize)> {
+        layout
+            .fields
+            .iter()
+            .filter_map(|field| match field {
+                Field::Field {
+                    name, offset, size, ..
+                } => Some((name.to_string(), *offset, *size)),
+                Field::Padding { .. } => None,
+            })
+            .collect()
+    }
+
+    /// Test binaries are built with debug info, which is kept in the
+    /// executable on Linux.
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn reads_the_test_binary() {
+        // A local of the type makes sure it's in the debug info.
+        let probe = Probe {
+            kind: 1,
+            len: 2,
+            values: [3u16, 4, 5],
+            next: core::ptr::null(),
+            inner: Inner {
+                flag: true,
+                value: 6,
+            },
+        };
+        assert!(probe.inner.flag);
+
+        let binary = std::fs::read(std::env::current_exe().unwrap()).unwrap();
+        let from_dwarf = layout(&binary, "type_layout::dwarf::tests::Probe<u16>")
+            .unwrap()
+            .unwrap();
+        let expected = Probe::<u16>::type_layout();
+
+        assert_eq!(from_dwarf.size, expected.size);
+        assert_eq!(from_dwarf.alignment, expected.alignment);
+        assert_eq!(fields(&from_dwarf), fields(&expected));
+
+        let values = from_dwarf.field("values").unwrap();
+        match values {
+            Field::Field {
+                array: Some(array), ..
+            } => assert_eq!((array.len, array.stride), (3, 2)),
+            _ => panic!("`values` should be an array: {:?}", values),
+        }
+
+        let inner = from_dwarf
+            .fields
+            .iter()
+            .find_map(|field| match field {
+                Field::Field {
+                    name,
+                    nested: Some(nested),
+                    ..
+                } if name == "inner" => Some(nested),
+                _ => None,
+            })
+            .expect("`inner` should be nested");
+        let value_offset =
+            &probe.inner.value as *const u64 as usize - &probe.inner as *const Inner as usize;
+        assert_eq!(
+            fields(inner),
+            [
+                (String::from("flag"), 0, 1),
+                (String::from("value"), value_offset, 8),
+            ]
+        );
+
+        let target = from_dwarf.target.unwrap();
+        assert_eq!(target.pointer_width, core::mem::size_of::<usize>() * 8);
+    }
+
+    #[test]
+    fn not_a_binary() {
+        let error = layouts(b"not a binary").unwrap_err();
+        assert!(matches!(error, DwarfError::Object(_)), "{:?}", error);
+        assert!(error.to_string().starts_with("failed to read binary: "));
+    }
+}
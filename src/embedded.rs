@@ -0,0 +1,565 @@
+//! Layout records embedded in compiled artifacts.
+//!
+//! Types that derive `TypeLayout` with `#[layout(embed)]` place a record of
+//! their layout into a link section of the compiled binary, so that tools can
+//! recover layouts without running any code. The section is named
+//! `.type_layout` on ELF targets, `__DATA,__type_layout` on Apple targets, and
+//! `.tylay` on Windows. [`parse_section`] turns the contents of the section
+//! back into [`TypeLayoutInfo`]s.
+//!
+//! Embedding requires Rust 1.65 or newer, and isn't supported on generic
+//! types.
+//!
+//! # Encoding
+//!
+//! The section contains records back to back. Linkers may place zero bytes
+//! between records, which are skipped. All integers are little-endian, and
+//! strings are a `u32` length followed by that many bytes of UTF-8.
+//!
+//! A record is:
+//!
+//! | Field               | Encoding                      |
+//! | ------------------- | ----------------------------- |
+//! | magic               | the bytes `TLAY`              |
+//! | version             | `u8`, currently 1             |
+//! | flags               | `u8`, see below               |
+//! | size                | `u64`                         |
+//! | alignment           | `u64`                         |
+//! | natural alignment   | `u64`                         |
+//! | name                | string                        |
+//! | field count         | `u32`                         |
+//! | fields              | field count fields            |
+//!
+//! The flags of a record have bit 0 set if the type needs drop, and bit 1 set
+//! if its layout is guaranteed.
+//!
+//! A field is:
+//!
+//! | Field  | Encoding                      |
+//! | ------ | ----------------------------- |
+//! | flags  | `u8`, bit 0 set if needs drop |
+//! | offset | `u64`                         |
+//! | size   | `u64`                         |
+//! | name   | string                        |
+//! | type   | string                        |
+//!
+//! Padding isn't encoded; it's recomputed from the fields when parsing. Future
+//! versions will change the version number rather than the meaning of an
+//! existing version.
+
+use alloc::borrow::{Cow, ToOwned};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::str;
+
+use crate::{Field, Repr, TypeLayoutInfo};
+
+const MAGIC: &[u8; 4] = b"TLAY";
+const VERSION: u8 = 1;
+const FLAG_NEEDS_DROP: u8 = 1;
+const FLAG_GUARANTEED: u8 = 2;
+
+/// An error from parsing an embedded layout section.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// Expected the start of a record, but found something else.
+    BadMagic { offset: usize },
+
+    /// A record uses an encoding version this crate doesn't understand.
+    UnsupportedVersion { offset: usize, version: u8 },
+
+    /// The section ended in the middle of a record.
+    UnexpectedEnd,
+
+    /// A string in a record wasn't valid UTF-8.
+    InvalidUtf8 { offset: usize },
+
+    /// A number in a record doesn't fit in a `usize` on this target.
+    Overflow { offset: usize },
+
+    /// A field in a record ends past the end of its type.
+    FieldOutOfBounds { offset: usize },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::BadMagic { offset } => {
+                write!(formatter, "expected a layout record at offset {}", offset)
+            }
+            ParseError::UnsupportedVersion { offset, version } => write!(
+                formatter,
+                "layout record at offset {} has unsupported version {}",
+                offset, version
+            ),
+            ParseError::UnexpectedEnd => {
+                write!(formatter, "section ended in the middle of a record")
+            }
+            ParseError::InvalidUtf8 { offset } => {
+                write!(formatter, "invalid UTF-8 in string at offset {}", offset)
+            }
+            ParseError::Overflow { offset } => {
+                write!(
+                    formatter,
+                    "number at offset {} does not fit in usize",
+                    offset
+                )
+            }
+            ParseError::FieldOutOfBounds { offset } => write!(
+                formatter,
+                "field at offset {} ends past the end of its type",
+                offset
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {}
+
+/// Parses every layout record in the contents of a `.type_layout` section.
+///
+/// ```rust
+/// use type_layout::{embedded, TypeLayout};
+///
+/// #[derive(TypeLayout)]
+/// #[repr(C)]
+/// struct Header {
+///     kind: u8,
+///     len: u32,
+/// }
+///
+/// let section = embedded::encode(&Header::type_layout());
+/// let layouts = embedded::parse_section(&section).unwrap();
+///
+/// assert_eq!(layouts[0].name, "Header");
+/// assert_eq!(layouts[0].field("len").unwrap().offset(), 4);
+/// ```
+pub fn parse_section(section: &[u8]) -> Result<Vec<TypeLayoutInfo>, ParseError> {
+    let mut reader = Reader {
+        bytes: section,
+        pos: 0,
+    };
+    let mut layouts = Vec::new();
+
+    loop {
+        while reader.bytes.get(reader.pos) == Some(&0) {
+            reader.pos += 1;
+        }
+
+        if reader.pos == reader.bytes.len() {
+            return Ok(layouts);
+        }
+
+        layouts.push(reader.record()?);
+    }
+}
+
+/// Encodes a layout the same way `#[layout(embed)]` does.
+pub fn encode(layout: &TypeLayoutInfo) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(MAGIC);
+    bytes.push(VERSION);
+    bytes.push(record_flags(layout.needs_drop, layout.guaranteed));
+    bytes.extend_from_slice(&(layout.size as u64).to_le_bytes());
+    bytes.extend_from_slice(&(layout.alignment as u64).to_le_bytes());
+    bytes.extend_from_slice(&(layout.natural_alignment as u64).to_le_bytes());
+    encode_str(&mut bytes, &layout.name);
+
+    let fields: Vec<_> = layout
+        .fields
+        .iter()
+        .filter_map(|field| match field {
+            Field::Field {
+                name,
+                ty,
+                offset,
+                size,
+                needs_drop,
+                ..
+            } => Some((name, ty, *offset, *size, *needs_drop)),
+            Field::Padding { .. } => None,
+        })
+        .collect();
+
+    bytes.extend_from_slice(&(fields.len() as u32).to_le_bytes());
+
+    for (name, ty, offset, size, needs_drop) in fields {
+        bytes.push(if needs_drop { FLAG_NEEDS_DROP } else { 0 });
+        bytes.extend_from_slice(&(offset as u64).to_le_bytes());
+        bytes.extend_from_slice(&(size as u64).to_le_bytes());
+        encode_str(&mut bytes, name);
+        encode_str(&mut bytes, ty);
+    }
+
+    bytes
+}
+
+const fn record_flags(needs_drop: bool, guaranteed: bool) -> u8 {
+    let needs_drop = if needs_drop { FLAG_NEEDS_DROP } else { 0 };
+    let guaranteed = if guaranteed { FLAG_GUARANTEED } else { 0 };
+    needs_drop | guaranteed
+}
+
+fn encode_str(bytes: &mut Vec<u8>, text: &str) {
+    bytes.extend_from_slice(&(text.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(text.as_bytes());
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn record(&mut self) -> Result<TypeLayoutInfo, ParseError> {
+        let start = self.pos;
+        if self.take(MAGIC.len())? != MAGIC {
+            return Err(ParseError::BadMagic { offset: start });
+        }
+
+        let version = self.u8()?;
+        if version != VERSION {
+            return Err(ParseError::UnsupportedVersion {
+                offset: start,
+                version,
+            });
+        }
+
+        let flags = self.u8()?;
+        let size = self.usize()?;
+        let alignment = self.usize()?;
+        let natural_alignment = self.usize()?;
+        let name = self.string()?;
+
+        let field_count = self.u32()?;
+        let mut fields = Vec::new();
+
+        for _ in 0..field_count {
+            let field_start = self.pos;
+            let flags = self.u8()?;
+            let offset = self.usize()?;
+            let field_size = self.usize()?;
+            let name = self.string()?;
+            let ty = self.string()?;
+
+            match offset.checked_add(field_size) {
+                Some(end) if end <= size => {}
+                _ => {
+                    return Err(ParseError::FieldOutOfBounds {
+                        offset: field_start,
+                    })
+                }
+            }
+
+            fields.push(Field::Field {
+                name: Cow::Owned(name),
+                ty: Cow::Owned(ty),
+                resolved_ty: None,
+                type_id: None,
+                offset,
+                size: field_size,
+                alignment: None,
+                array: None,
+                needs_drop: flags & FLAG_NEEDS_DROP != 0,
+                option_size: None,
+                nested: None,
+                contended: false,
+                niche: false,
+            });
+        }
+
+        Ok(TypeLayoutInfo {
+            name: Cow::Owned(name),
+            generics: Vec::new(),
+            size,
+            alignment,
+            natural_alignment,
+            needs_drop: flags & FLAG_NEEDS_DROP != 0,
+            option_size: None,
+            is_union: false,
+            repr: Repr::default(),
+            guaranteed: flags & FLAG_GUARANTEED != 0,
+            fields: crate::__insert_padding(fields, size),
+            variants: Vec::new(),
+            unsized_tail: None,
+            tag: None,
+            target: None,
+        })
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], ParseError> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or(ParseError::UnexpectedEnd)?;
+
+        let taken = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(taken)
+    }
+
+    fn u8(&mut self) -> Result<u8, ParseError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, ParseError> {
+        let mut bytes = [0; 4];
+        bytes.copy_from_slice(self.take(4)?);
+        Ok(u32::from_le_bytes(bytes))
+    }
+
+    fn usize(&mut self) -> Result<usize, ParseError> {
+        let offset = self.pos;
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(self.take(8)?);
+
+        let value = u64::from_le_bytes(bytes);
+        if value > usize::MAX as u64 {
+            return Err(ParseError::Overflow { offset });
+        }
+
+        Ok(value as usize)
+    }
+
+    fn string(&mut self) -> Result<String, ParseError> {
+        let len = self.u32()? as usize;
+        let offset = self.pos;
+        let bytes = self.take(len)?;
+
+        str::from_utf8(bytes)
+            .map(str::to_owned)
+            .map_err(|_| ParseError::InvalidUtf8 { offset })
+    }
+}
+
+/// Builds an encoded record in a const context. This is used by
+/// `#[layout(embed)]`, which computes the length of the record ahead of time.
+#[doc(hidden)]
+pub struct Encoder<const N: usize> {
+    bytes: [u8; N],
+    pos: usize,
+}
+
+#[doc(hidden)]
+impl<const N: usize> Encoder<N> {
+    #[allow(clippy::new_without_default)]
+    pub const fn new() -> Self {
+        Self {
+            bytes: [0; N],
+            pos: 0,
+        }
+        .bytes(MAGIC)
+        .u8(VERSION)
+    }
+
+    pub const fn u8(mut self, value: u8) -> Self {
+        self.bytes[self.pos] = value;
+        self.pos += 1;
+        self
+    }
+
+    pub const fn flags(self, needs_drop: bool) -> Self {
+        self.u8(if needs_drop { FLAG_NEEDS_DROP } else { 0 })
+    }
+
+    pub const fn record_flags(self, needs_drop: bool, guaranteed: bool) -> Self {
+        self.u8(record_flags(needs_drop, guaranteed))
+    }
+
+    pub const fn u32(self, value: u32) -> Self {
+        self.bytes(&value.to_le_bytes())
+    }
+
+    pub const fn usize(self, value: usize) -> Self {
+        self.bytes(&(value as u64).to_le_bytes())
+    }
+
+    pub const fn str(self, value: &str) -> Self {
+        self.u32(value.len() as u32).bytes(value.as_bytes())
+    }
+
+    const fn bytes(mut self, value: &[u8]) -> Self {
+        let mut index = 0;
+        while index < value.len() {
+            self = self.u8(value[index]);
+            index += 1;
+        }
+        self
+    }
+
+    pub const fn finish(self) -> [u8; N] {
+        assert!(
+            self.pos == N,
+            "type-layout computed the wrong record length"
+        );
+        self.bytes
+    }
+}
+
+/// The length of an encoded record with the given name and fields, each given
+/// as its name and type.
+#[doc(hidden)]
+pub const fn record_len(name: &str, fields: &[(&str, &str)]) -> usize {
+    let mut len = MAGIC.len() + 1 + 1 + 8 * 3 + 4 + name.len() + 4;
+
+    let mut index = 0;
+    while index < fields.len() {
+        len += 1 + 8 + 8 + 4 + fields[index].0.len() + 4 + fields[index].1.len();
+        index += 1;
+    }
+
+    len
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::format;
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    use super::*;
+
+    fn header() -> TypeLayoutInfo {
+        TypeLayoutInfo::builder("Header")
+            .field("kind", "u8", 1, 1)
+            .field("len", "u32", 4, 4)
+            .build()
+            .unwrap()
+    }
+
+    /// A record for a type of size 8 with a single field.
+    fn record(offset: u64, size: u64) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(VERSION);
+        bytes.push(0);
+        bytes.extend_from_slice(&8u64.to_le_bytes());
+        bytes.extend_from_slice(&4u64.to_le_bytes());
+        bytes.extend_from_slice(&4u64.to_le_bytes());
+        encode_str(&mut bytes, "Bad");
+        bytes.extend_from_slice(&1u32.to_le_bytes());
+
+        bytes.push(0);
+        bytes.extend_from_slice(&offset.to_le_bytes());
+        bytes.extend_from_slice(&size.to_le_bytes());
+        encode_str(&mut bytes, "value");
+        encode_str(&mut bytes, "u16");
+        bytes
+    }
+
+    #[test]
+    fn round_trip_with_zero_bytes_between_records() {
+        let mut section = vec![0, 0];
+        section.extend(encode(&header()));
+        section.extend([0; 5]);
+        section.extend(record(6, 2));
+        section.push(0);
+
+        let layouts = parse_section(&section).unwrap();
+        assert_eq!(layouts.len(), 2);
+        assert_eq!(layouts[0].size, 8);
+        assert_eq!(layouts[0].field("len").unwrap().offset(), 4);
+        assert_eq!(layouts[0].fields.len(), 3);
+        assert_eq!(layouts[1].name, "Bad");
+        assert_eq!(layouts[1].field("value").unwrap().offset(), 6);
+    }
+
+    #[test]
+    fn empty_section() {
+        assert_eq!(parse_section(&[]), Ok(Vec::new()));
+        assert_eq!(parse_section(&[0; 16]), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn field_past_the_end() {
+        // The field starts after the record's header, name, and field count.
+        let field_start = 4 + 1 + 1 + 8 * 3 + 4 + 3 + 4;
+
+        for (offset, size) in [(u64::MAX, 2), (7, 2), (0, 9)] {
+            let error = parse_section(&record(offset, size)).unwrap_err();
+            assert_eq!(
+                error,
+                ParseError::FieldOutOfBounds {
+                    offset: field_start
+                },
+                "offset {}, size {}",
+                offset,
+                size
+            );
+        }
+
+        assert_eq!(
+            ParseError::FieldOutOfBounds { offset: 41 }.to_string(),
+            "field at offset 41 ends past the end of its type"
+        );
+    }
+
+    #[test]
+    fn bad_magic() {
+        let mut section = encode(&header());
+        section.extend(b"TLAX");
+
+        let error = parse_section(&section).unwrap_err();
+        assert_eq!(
+            error,
+            ParseError::BadMagic {
+                offset: section.len() - 4
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            format!("expected a layout record at offset {}", section.len() - 4)
+        );
+    }
+
+    #[test]
+    fn unsupported_version() {
+        let mut section = encode(&header());
+        section[4] = 2;
+
+        let error = parse_section(&section).unwrap_err();
+        assert_eq!(
+            error,
+            ParseError::UnsupportedVersion {
+                offset: 0,
+                version: 2
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "layout record at offset 0 has unsupported version 2"
+        );
+    }
+
+    #[test]
+    fn truncated() {
+        let section = encode(&header());
+
+        for len in [1, 4, 5, 6, 20, section.len() - 1] {
+            assert_eq!(
+                parse_section(&section[..len]),
+                Err(ParseError::UnexpectedEnd),
+                "truncated to {} bytes",
+                len
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        let mut section = record(0, 2);
+        // The first byte of the name, after the header and its length.
+        let name = 4 + 1 + 1 + 8 * 3 + 4;
+        section[name] = 0xff;
+
+        let error = parse_section(&section).unwrap_err();
+        assert_eq!(error, ParseError::InvalidUtf8 { offset: name });
+        assert_eq!(
+            error.to_string(),
+            format!("invalid UTF-8 in string at offset {}", name)
+        );
+    }
+}
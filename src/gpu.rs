@@ -0,0 +1,767 @@
+//! Checks Rust types against the std140 and std430 layouts that GLSL uses
+//! for uniform and storage buffers.
+//!
+//! GPU layouts align vectors to more than their components: a `vec3` is
+//! aligned to 16 bytes, and in std140 the elements of every array are 16
+//! bytes apart. A `#[repr(C)]` struct that looks like the GLSL block often
+//! doesn't match it, so [`TypeLayoutInfo::check_gpu_layout`] lists every
+//! field that's in the wrong place.
+//!
+//! The GLSL type of each field is inferred from its Rust type:
+//!
+//! - `f32`, `i32`, and `u32` are scalars, and `f64` is a `double`.
+//! - Arrays of 2 to 4 scalars, like `[f32; 3]`, are vectors. Other arrays are
+//!   arrays, so `[[f32; 4]; 4]` is a `mat4`, which std140 and std430 lay out
+//!   like an array of columns.
+//! - The vector and matrix types of [glam](https://crates.io/crates/glam),
+//!   like `Vec3` and `Mat4`, are the matching GLSL types.
+//! - Fields marked `#[layout(nested)]` are structs, and their fields are
+//!   checked too.
+//!
+//! ```rust
+//! use type_layout::gpu::GpuLayout;
+//! use type_layout::TypeLayout;
+//!
+//! #[derive(TypeLayout)]
+//! #[repr(C)]
+//! struct Light {
+//!     intensity: f32,
+//!     position: [f32; 3],
+//!     weights: [f32; 2],
+//! }
+//!
+//! let mismatches = Light::type_layout().check_gpu_layout(GpuLayout::Std140);
+//! for mismatch in &mismatches {
+//!     println!("{}", mismatch);
+//! }
+//! // prints:
+//! // `position` is at offset 4, but std140 puts it at offset 16
+//! // size is 24, but std140 makes it 48
+//! ```
+
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{ArrayInfo, Field, Repr, TypeLayoutInfo};
+
+/// The layout rules for a GLSL interface block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GpuLayout {
+    /// The layout of uniform buffers. Arrays and structs are aligned to 16
+    /// bytes.
+    Std140,
+
+    /// The layout of shader storage buffers, and of push constants in
+    /// Vulkan. Like std140, without rounding arrays and structs up to 16
+    /// bytes.
+    Std430,
+}
+
+impl fmt::Display for GpuLayout {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GpuLayout::Std140 => write!(formatter, "std140"),
+            GpuLayout::Std430 => write!(formatter, "std430"),
+        }
+    }
+}
+
+/// A place where a Rust type's layout differs from a GPU layout. Returned by
+/// [`TypeLayoutInfo::check_gpu_layout`]. Fields of nested structs are named
+/// like `outer.inner`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum GpuMismatch {
+    /// A field is at a different offset than the GPU layout puts it at.
+    FieldOffset {
+        layout: GpuLayout,
+        name: Cow<'static, str>,
+        expected: usize,
+        actual: usize,
+    },
+
+    /// The elements of an array field are a different distance apart than in
+    /// the GPU layout, like a `[[f32; 3]; 3]` used as a `mat3`.
+    ArrayStride {
+        layout: GpuLayout,
+        name: Cow<'static, str>,
+        expected: usize,
+        actual: usize,
+    },
+
+    /// The type has a different size than the GPU layout gives it.
+    Size {
+        layout: GpuLayout,
+        expected: usize,
+        actual: usize,
+    },
+
+    /// A field's type has no GLSL equivalent, like `bool`, which is 4 bytes
+    /// in GLSL. The rest of the type is checked as if the field had its Rust
+    /// size and alignment.
+    UnsupportedType {
+        name: Cow<'static, str>,
+        ty: Cow<'static, str>,
+    },
+}
+
+impl fmt::Display for GpuMismatch {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GpuMismatch::FieldOffset {
+                layout,
+                name,
+                expected,
+                actual,
+            } => write!(
+                formatter,
+                "`{}` is at offset {}, but {} puts it at offset {}",
+                name, actual, layout, expected
+            ),
+            GpuMismatch::ArrayStride {
+                layout,
+                name,
+                expected,
+                actual,
+            } => write!(
+                formatter,
+                "`{}` has an array stride of {}, but {} uses {}",
+                name, actual, layout, expected
+            ),
+            GpuMismatch::Size {
+                layout,
+                expected,
+                actual,
+            } => write!(
+                formatter,
+                "size is {}, but {} makes it {}",
+                actual, layout, expected
+            ),
+            GpuMismatch::UnsupportedType { name, ty } => {
+                write!(
+                    formatter,
+                    "`{}` has type `{}`, which has no GLSL equivalent",
+                    name, ty
+                )
+            }
+        }
+    }
+}
+
+impl TypeLayoutInfo {
+    /// Compares the offset of each of this type's fields with the offset a
+    /// GLSL block with the same fields has under `layout`, returning every
+    /// difference. See the [module documentation](crate::gpu) for how the
+    /// GLSL type of each field is chosen.
+    ///
+    /// When a field is in the wrong place, the fields after it are checked
+    /// from where it actually is, so each mistake is only reported once.
+    pub fn check_gpu_layout(&self, layout: GpuLayout) -> Vec<GpuMismatch> {
+        let mut mismatches = Vec::new();
+        check_fields(self, layout, "", &mut mismatches);
+
+        let (expected, _) = gpu_type_of_layout(self).size_align(layout);
+        if expected != self.size {
+            mismatches.push(GpuMismatch::Size {
+                layout,
+                expected,
+                actual: self.size,
+            });
+        }
+
+        mismatches
+    }
+
+    /// Lays out this type's fields the way a GLSL block with the same fields
+    /// is laid out under `layout`, to show how a matching Rust type would be
+    /// padded. Fields with no GLSL equivalent keep their Rust size and
+    /// alignment.
+    ///
+    /// ```rust
+    /// use type_layout::gpu::GpuLayout;
+    /// use type_layout::TypeLayout;
+    ///
+    /// #[derive(TypeLayout)]
+    /// #[repr(C)]
+    /// struct Particle {
+    ///     mass: f32,
+    ///     velocity: [f32; 3],
+    /// }
+    ///
+    /// let std140 = Particle::type_layout().gpu_layout(GpuLayout::Std140);
+    /// println!("{}", std140);
+    /// // prints:
+    /// // Particle (size 32, alignment 16)
+    /// // | Offset | Name                          | Size |
+    /// // | ------ | ----------------------------- | ---- |
+    /// // | 0      | mass                          | 4    |
+    /// // | 4      | [padding]                     | 12   |
+    /// // | 16     | velocity: [f32; 3] (stride 4) | 12   |
+    /// // | 28     | [tail padding]                | 4    |
+    /// ```
+    pub fn gpu_layout(&self, layout: GpuLayout) -> TypeLayoutInfo {
+        gpu_layout_of(self, &gpu_type_of_layout(self), layout)
+    }
+}
+
+/// A GLSL scalar type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Scalar {
+    F32,
+    I32,
+    U32,
+    F64,
+}
+
+impl Scalar {
+    fn size(self) -> usize {
+        match self {
+            Scalar::F64 => 8,
+            _ => 4,
+        }
+    }
+}
+
+/// The GLSL type of a field, as far as its layout is concerned.
+#[derive(Debug, Clone)]
+pub(crate) enum GpuType {
+    Scalar(Scalar),
+
+    /// A vector with the given number of components.
+    Vector(Scalar, usize),
+
+    /// A square matrix with the given number of columns, laid out like an
+    /// array of column vectors.
+    Matrix(Scalar, usize),
+
+    Array(Box<GpuType>, usize),
+
+    Struct(Vec<(Cow<'static, str>, GpuType)>),
+
+    /// A type with no GLSL equivalent, with its Rust size and alignment.
+    Unsupported(usize, usize),
+}
+
+impl GpuType {
+    pub(crate) fn size_align(&self, layout: GpuLayout) -> (usize, usize) {
+        match self {
+            GpuType::Scalar(scalar) => (scalar.size(), scalar.size()),
+            GpuType::Vector(scalar, 2) => (scalar.size() * 2, scalar.size() * 2),
+            GpuType::Vector(scalar, count) => (scalar.size() * count, scalar.size() * 4),
+            GpuType::Matrix(scalar, count) => {
+                GpuType::Array(Box::new(GpuType::Vector(*scalar, *count)), *count)
+                    .size_align(layout)
+            }
+            GpuType::Array(elem, len) => {
+                let stride = self.array_stride(layout).unwrap_or(0);
+                let (_, alignment) = elem.size_align(layout);
+                (stride * len, round_alignment(alignment, layout))
+            }
+            GpuType::Struct(fields) => {
+                let mut end = 0;
+                let mut alignment = 1;
+                for (_, ty) in fields {
+                    let (field_size, field_alignment) = ty.size_align(layout);
+                    end = round_up(end, field_alignment) + field_size;
+                    alignment = alignment.max(field_alignment);
+                }
+
+                let alignment = round_alignment(alignment, layout);
+                (round_up(end, alignment), alignment)
+            }
+            GpuType::Unsupported(size, alignment) => (*size, *alignment),
+        }
+    }
+
+    /// The distance between the elements of an array.
+    pub(crate) fn array_stride(&self, layout: GpuLayout) -> Option<usize> {
+        match self {
+            GpuType::Array(elem, _) => {
+                let (size, alignment) = elem.size_align(layout);
+                let alignment = round_alignment(alignment, layout);
+                Some(round_up(size, alignment))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// std140 rounds the alignment of arrays and structs up to that of a `vec4`.
+fn round_alignment(alignment: usize, layout: GpuLayout) -> usize {
+    match layout {
+        GpuLayout::Std140 => alignment.max(16),
+        GpuLayout::Std430 => alignment,
+    }
+}
+
+pub(crate) fn round_up(offset: usize, alignment: usize) -> usize {
+    (offset + alignment - 1) / alignment * alignment
+}
+
+fn gpu_type_of_layout(layout: &TypeLayoutInfo) -> GpuType {
+    let fields = layout
+        .fields
+        .iter()
+        .filter_map(|field| match field {
+            Field::Field { name, size, .. } if *size > 0 => {
+                Some((name.clone(), gpu_type_of_field(field)))
+            }
+            _ => None,
+        })
+        .collect();
+
+    GpuType::Struct(fields)
+}
+
+pub(crate) fn gpu_type_of_field(field: &Field) -> GpuType {
+    match field {
+        Field::Field {
+            ty,
+            resolved_ty,
+            size,
+            alignment,
+            array,
+            nested,
+            ..
+        } => {
+            let ty = resolved_ty.as_ref().unwrap_or(ty);
+            let alignment = alignment.unwrap_or(1);
+
+            match (nested, array) {
+                (Some(nested), Some(array)) => {
+                    GpuType::Array(Box::new(gpu_type_of_layout(nested)), array.len)
+                }
+                (Some(nested), None) => gpu_type_of_layout(nested),
+                (None, Some(array)) => {
+                    gpu_type_of_array(array).unwrap_or(GpuType::Unsupported(*size, alignment))
+                }
+                (None, None) => gpu_type_of(ty).unwrap_or(GpuType::Unsupported(*size, alignment)),
+            }
+        }
+        Field::Padding { size, .. } => GpuType::Unsupported(*size, 1),
+    }
+}
+
+fn gpu_type_of_array(array: &ArrayInfo) -> Option<GpuType> {
+    let elem = gpu_type_of(&array.elem_ty)?;
+
+    match (elem, array.len) {
+        (GpuType::Scalar(scalar), 2..=4) => Some(GpuType::Vector(scalar, array.len)),
+        (elem, len) => Some(GpuType::Array(Box::new(elem), len)),
+    }
+}
+
+/// The GLSL type of a Rust type written as `ty`.
+fn gpu_type_of(ty: &str) -> Option<GpuType> {
+    let ty = ty.trim();
+
+    let scalar = match ty {
+        "f32" => Some(Scalar::F32),
+        "i32" => Some(Scalar::I32),
+        "u32" => Some(Scalar::U32),
+        "f64" => Some(Scalar::F64),
+        _ => None,
+    };
+    if let Some(scalar) = scalar {
+        return Some(GpuType::Scalar(scalar));
+    }
+
+    // Arrays written in the type, like the element type of `[[f32; 3]; 4]`.
+    if let Some(inner) = ty.strip_prefix('[').and_then(|ty| ty.strip_suffix(']')) {
+        let (elem, len) = inner.rsplit_once(';')?;
+        let len: usize = len.trim().parse().ok()?;
+        let elem = gpu_type_of(elem)?;
+
+        return match (elem, len) {
+            (GpuType::Scalar(scalar), 2..=4) => Some(GpuType::Vector(scalar, len)),
+            (elem, len) => Some(GpuType::Array(Box::new(elem), len)),
+        };
+    }
+
+    // The vector and matrix types of glam, with or without a path.
+    let name = ty.rsplit("::").next().unwrap_or(ty);
+    let (scalar, rest) = match name.as_bytes().first()? {
+        b'D' => (Scalar::F64, &name[1..]),
+        b'I' => (Scalar::I32, &name[1..]),
+        b'U' => (Scalar::U32, &name[1..]),
+        _ => (Scalar::F32, name),
+    };
+
+    let count = |prefix: &str| -> Option<usize> {
+        match rest.strip_prefix(prefix)? {
+            "2" => Some(2),
+            "3" | "3A" => Some(3),
+            "4" => Some(4),
+            _ => None,
+        }
+    };
+
+    if let Some(count) = count("Vec") {
+        return Some(GpuType::Vector(scalar, count));
+    }
+
+    if rest.len() == 4 && matches!(scalar, Scalar::F32 | Scalar::F64) {
+        if let Some(count) = count("Mat") {
+            return Some(GpuType::Matrix(scalar, count));
+        }
+    }
+
+    None
+}
+
+/// Checks the offsets of the fields of `layout`, adding mismatches to
+/// `mismatches`. `prefix` is prepended to the names of mismatched fields.
+fn check_fields(
+    layout: &TypeLayoutInfo,
+    rules: GpuLayout,
+    prefix: &str,
+    mismatches: &mut Vec<GpuMismatch>,
+) {
+    let mut end = 0;
+
+    for field in &layout.fields {
+        let (name, ty, array, nested) = match field {
+            Field::Field {
+                name,
+                ty,
+                size,
+                array,
+                nested,
+                ..
+            } if *size > 0 => (name, ty, array, nested),
+            _ => continue,
+        };
+
+        let qualified = || Cow::Owned(format!("{}{}", prefix, name));
+        let gpu_type = gpu_type_of_field(field);
+        if let GpuType::Unsupported(..) = gpu_type {
+            mismatches.push(GpuMismatch::UnsupportedType {
+                name: qualified(),
+                ty: ty.clone(),
+            });
+        }
+
+        let (size, alignment) = gpu_type.size_align(rules);
+        let expected = round_up(end, alignment);
+        if field.offset() != expected {
+            mismatches.push(GpuMismatch::FieldOffset {
+                layout: rules,
+                name: qualified(),
+                expected,
+                actual: field.offset(),
+            });
+        }
+
+        if let (Some(expected), Some(array)) = (gpu_type.array_stride(rules), array) {
+            if array.stride != expected {
+                mismatches.push(GpuMismatch::ArrayStride {
+                    layout: rules,
+                    name: qualified(),
+                    expected,
+                    actual: array.stride,
+                });
+            }
+        }
+
+        if let Some(nested) = nested {
+            let prefix = crate::nested_prefix(&format!("{}{}", prefix, name), array);
+            check_fields(nested, rules, &prefix, mismatches);
+        }
+
+        end = field.offset() + size;
+    }
+}
+
+/// Builds the layout `gpu_type` has under `rules`, with the names and types
+/// of the fields of `layout`.
+fn gpu_layout_of(layout: &TypeLayoutInfo, gpu_type: &GpuType, rules: GpuLayout) -> TypeLayoutInfo {
+    let gpu_fields = match gpu_type {
+        GpuType::Struct(fields) => fields,
+        _ => unreachable!("gpu_layout_of is only called with structs"),
+    };
+
+    let (size, alignment) = gpu_type.size_align(rules);
+    let rust_fields = layout.fields.iter().filter(|field| match field {
+        Field::Field { size, .. } => *size > 0,
+        Field::Padding { .. } => false,
+    });
+
+    let mut fields = Vec::new();
+    let mut end = 0;
+
+    for (field, (_, field_type)) in rust_fields.zip(gpu_fields) {
+        let (field_size, field_alignment) = field_type.size_align(rules);
+        let offset = round_up(end, field_alignment);
+        end = offset + field_size;
+
+        let mut field = field.clone();
+        if let Field::Field {
+            offset: field_offset,
+            size,
+            alignment,
+            array,
+            nested,
+            ..
+        } = &mut field
+        {
+            *field_offset = offset;
+            *size = field_size;
+            *alignment = Some(field_alignment);
+
+            if let (Some(array), Some(stride)) = (array, field_type.array_stride(rules)) {
+                array.stride = stride;
+            }
+
+            if let Some(inner) = nested {
+                let inner_type = match field_type {
+                    GpuType::Array(elem, _) => elem,
+                    _ => field_type,
+                };
+                **inner = gpu_layout_of(inner, inner_type, rules);
+            }
+        }
+
+        fields.push(field);
+    }
+
+    TypeLayoutInfo {
+        name: layout.name.clone(),
+        generics: layout.generics.clone(),
+        size,
+        alignment,
+        natural_alignment: alignment,
+        needs_drop: false,
+        option_size: None,
+        is_union: false,
+        repr: Repr::default(),
+        guaranteed: true,
+        fields: crate::__insert_padding(fields, size),
+        variants: Vec::new(),
+        unsized_tail: None,
+        tag: None,
+        target: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    use super::*;
+
+    const LAYOUTS: [GpuLayout; 2] = [GpuLayout::Std140, GpuLayout::Std430];
+
+    fn f32_array(len: usize) -> GpuType {
+        GpuType::Array(Box::new(GpuType::Scalar(Scalar::F32)), len)
+    }
+
+    /// Sets `nested` on the field called `name`, like `#[layout(nested)]`.
+    fn nest(mut layout: TypeLayoutInfo, name: &str, inner: TypeLayoutInfo) -> TypeLayoutInfo {
+        for field in &mut layout.fields {
+            if let Field::Field {
+                name: field_name,
+                nested,
+                ..
+            } = field
+            {
+                if field_name == name {
+                    *nested = Some(Box::new(inner.clone()));
+                }
+            }
+        }
+
+        layout
+    }
+
+    #[test]
+    fn array_stride() {
+        // std140 rounds the stride of every array up to 16.
+        let array = f32_array(4);
+        assert_eq!(array.array_stride(GpuLayout::Std140), Some(16));
+        assert_eq!(array.size_align(GpuLayout::Std140), (64, 16));
+
+        assert_eq!(array.array_stride(GpuLayout::Std430), Some(4));
+        assert_eq!(array.size_align(GpuLayout::Std430), (16, 4));
+
+        // Elements already 16 bytes apart stay that way.
+        let vec4s = GpuType::Array(Box::new(GpuType::Vector(Scalar::F32, 4)), 2);
+        for &layout in &LAYOUTS {
+            assert_eq!(vec4s.array_stride(layout), Some(16));
+        }
+
+        assert_eq!(
+            GpuType::Scalar(Scalar::F32).array_stride(GpuLayout::Std140),
+            None
+        );
+    }
+
+    #[test]
+    fn vectors() {
+        for &layout in &LAYOUTS {
+            assert_eq!(GpuType::Vector(Scalar::F32, 2).size_align(layout), (8, 8));
+            assert_eq!(GpuType::Vector(Scalar::F32, 3).size_align(layout), (12, 16));
+            assert_eq!(GpuType::Vector(Scalar::F32, 4).size_align(layout), (16, 16));
+            assert_eq!(GpuType::Vector(Scalar::F64, 3).size_align(layout), (24, 32));
+        }
+    }
+
+    #[test]
+    fn scalar_packs_into_vec3_tail() {
+        let light = TypeLayoutInfo::builder("Light")
+            .field("position", "[f32; 3]", 12, 4)
+            .field("intensity", "f32", 4, 4)
+            .build()
+            .unwrap();
+
+        for &layout in &LAYOUTS {
+            assert_eq!(light.check_gpu_layout(layout), vec![]);
+
+            let gpu = light.gpu_layout(layout);
+            assert_eq!(gpu.field("intensity").unwrap().offset(), 12);
+            assert_eq!((gpu.size, gpu.alignment), (16, 16));
+        }
+    }
+
+    #[test]
+    fn mat3_columns_are_16_bytes_apart() {
+        let columns = GpuType::Array(Box::new(GpuType::Vector(Scalar::F32, 3)), 3);
+        for &layout in &LAYOUTS {
+            assert_eq!(columns.array_stride(layout), Some(16));
+            assert_eq!(GpuType::Matrix(Scalar::F32, 3).size_align(layout), (48, 16));
+        }
+
+        // A `[[f32; 3]; 3]` has 12-byte columns.
+        let mut transform = TypeLayoutInfo::builder("Transform")
+            .field("matrix", "[[f32; 3]; 3]", 36, 4)
+            .build()
+            .unwrap();
+        if let Field::Field { array, .. } = &mut transform.fields[0] {
+            *array = Some(ArrayInfo {
+                elem_ty: "[f32; 3]".into(),
+                len: 3,
+                stride: 12,
+            });
+        }
+
+        assert_eq!(
+            transform.check_gpu_layout(GpuLayout::Std430),
+            vec![
+                GpuMismatch::ArrayStride {
+                    layout: GpuLayout::Std430,
+                    name: "matrix".into(),
+                    expected: 16,
+                    actual: 12,
+                },
+                GpuMismatch::Size {
+                    layout: GpuLayout::Std430,
+                    expected: 48,
+                    actual: 36,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn nested_struct_alignment() {
+        let inner = GpuType::Struct(vec![("x".into(), GpuType::Scalar(Scalar::F32))]);
+        assert_eq!(inner.size_align(GpuLayout::Std140), (16, 16));
+        assert_eq!(inner.size_align(GpuLayout::Std430), (4, 4));
+
+        let inner = TypeLayoutInfo::builder("Inner")
+            .field("x", "f32", 4, 4)
+            .build()
+            .unwrap();
+        let outer = TypeLayoutInfo::builder("Outer")
+            .field("a", "f32", 4, 4)
+            .field("inner", "Inner", 4, 4)
+            .build()
+            .unwrap();
+        let outer = nest(outer, "inner", inner);
+
+        assert_eq!(outer.check_gpu_layout(GpuLayout::Std430), vec![]);
+        assert_eq!(
+            outer.check_gpu_layout(GpuLayout::Std140),
+            vec![
+                GpuMismatch::FieldOffset {
+                    layout: GpuLayout::Std140,
+                    name: "inner".into(),
+                    expected: 16,
+                    actual: 4,
+                },
+                GpuMismatch::Size {
+                    layout: GpuLayout::Std140,
+                    expected: 32,
+                    actual: 8,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn nested_mismatches_are_qualified() {
+        let inner = TypeLayoutInfo::builder("Inner")
+            .field("a", "f32", 4, 4)
+            .field("b", "[f32; 3]", 12, 4)
+            .build()
+            .unwrap();
+        let outer = TypeLayoutInfo::builder("Outer")
+            .field("inner", "Inner", 16, 4)
+            .build()
+            .unwrap();
+        let outer = nest(outer, "inner", inner);
+
+        let mismatches = outer.check_gpu_layout(GpuLayout::Std430);
+        assert_eq!(
+            mismatches[0],
+            GpuMismatch::FieldOffset {
+                layout: GpuLayout::Std430,
+                name: "inner.b".into(),
+                expected: 16,
+                actual: 4,
+            }
+        );
+    }
+
+    #[test]
+    fn bool_is_unsupported() {
+        let flags = TypeLayoutInfo::builder("Flags")
+            .field("enabled", "bool", 1, 1)
+            .field("scale", "f32", 4, 4)
+            .build()
+            .unwrap();
+
+        let mismatches = flags.check_gpu_layout(GpuLayout::Std430);
+        assert_eq!(
+            mismatches,
+            vec![GpuMismatch::UnsupportedType {
+                name: "enabled".into(),
+                ty: "bool".into(),
+            }]
+        );
+        assert_eq!(
+            mismatches[0].to_string(),
+            "`enabled` has type `bool`, which has no GLSL equivalent"
+        );
+    }
+
+    #[test]
+    fn glam_types() {
+        assert!(matches!(
+            gpu_type_of("glam::Vec3A"),
+            Some(GpuType::Vector(Scalar::F32, 3))
+        ));
+        assert!(matches!(
+            gpu_type_of("DMat4"),
+            Some(GpuType::Matrix(Scalar::F64, 4))
+        ));
+        assert!(matches!(
+            gpu_type_of("UVec2"),
+            Some(GpuType::Vector(Scalar::U32, 2))
+        ));
+        assert!(gpu_type_of("IMat4").is_none());
+        assert!(gpu_type_of("bool").is_none());
+    }
+}
@@ -0,0 +1,221 @@
+use crate::{Field, TypeLayoutInfo};
+
+impl TypeLayoutInfo {
+    /// A hash of the shape of this layout: its size and alignment, and the
+    /// offset and size of each field and run of padding, including those of
+    /// enum variants and nested layouts.
+    ///
+    /// Names and types aren't included, so renaming a type or a field keeps
+    /// the same hash, while moving or resizing anything changes it. The hash
+    /// is computed with 64-bit FNV-1a over a fixed encoding, so it's the same
+    /// in every build and process, and across versions of this crate. That
+    /// makes it suitable for embedding in shared memory or file headers so
+    /// that both sides can check that they agree on a layout.
+    ///
+    /// ```rust
+    /// use type_layout::TypeLayout;
+    ///
+    /// #[derive(TypeLayout)]
+    /// #[repr(C)]
+    /// struct Header {
+    ///     magic: u32,
+    ///     version: u16,
+    /// }
+    ///
+    /// #[derive(TypeLayout)]
+    /// #[repr(C)]
+    /// struct Renamed {
+    ///     tag: u32,
+    ///     revision: u16,
+    /// }
+    ///
+    /// #[derive(TypeLayout)]
+    /// #[repr(C)]
+    /// struct Widened {
+    ///     magic: u32,
+    ///     version: u32,
+    /// }
+    ///
+    /// let hash = Header::type_layout().layout_hash();
+    /// assert_eq!(hash, Renamed::type_layout().layout_hash());
+    /// assert_ne!(hash, Widened::type_layout().layout_hash());
+    /// ```
+    pub fn layout_hash(&self) -> u64 {
+        let mut hasher = Fnv1a::new();
+        hash_layout(&mut hasher, self);
+        hasher.finish()
+    }
+}
+
+fn hash_layout(hasher: &mut Fnv1a, layout: &TypeLayoutInfo) {
+    hasher.write_usize(layout.size);
+    hasher.write_usize(layout.alignment);
+    hasher.write_u8(layout.is_union as u8);
+
+    hash_fields(hasher, &layout.fields);
+
+    hasher.write_usize(layout.variants.len());
+    for variant in &layout.variants {
+        hasher.write(&variant.discriminant.to_le_bytes());
+        hash_fields(hasher, &variant.fields);
+    }
+
+    // Only hashed when present, so that sized layouts keep their hashes.
+    if let Some(tail) = &layout.unsized_tail {
+        hasher.write_u8(2);
+        hasher.write_usize(tail.offset);
+        hasher.write_usize(tail.stride.unwrap_or(0));
+    }
+}
+
+fn hash_fields(hasher: &mut Fnv1a, fields: &[Field]) {
+    hasher.write_usize(fields.len());
+
+    for field in fields {
+        match field {
+            Field::Field {
+                offset,
+                size,
+                nested,
+                ..
+            } => {
+                hasher.write_u8(0);
+                hasher.write_usize(*offset);
+                hasher.write_usize(*size);
+
+                match nested {
+                    Some(nested) => {
+                        hasher.write_u8(1);
+                        hash_layout(hasher, nested);
+                    }
+                    None => hasher.write_u8(0),
+                }
+            }
+            Field::Padding { offset, size, .. } => {
+                hasher.write_u8(1);
+                hasher.write_usize(*offset);
+                hasher.write_usize(*size);
+            }
+        }
+    }
+}
+
+/// The 64-bit FNV-1a hash. Unlike the hashers in `std`, its output is
+/// specified, so it can be relied on to never change.
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Self {
+        Fnv1a(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    fn write_u8(&mut self, value: u8) {
+        self.write(&[value]);
+    }
+
+    /// Writes a `usize` as 8 bytes, so that the hash doesn't depend on the
+    /// width of `usize`.
+    fn write_usize(&mut self, value: usize) {
+        self.write(&(value as u64).to_le_bytes());
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::boxed::Box;
+    use alloc::vec;
+
+    use super::*;
+    use crate::Variant;
+
+    fn header() -> TypeLayoutInfo {
+        TypeLayoutInfo::builder("Header")
+            .field("magic", "u32", 4, 4)
+            .field("version", "u16", 2, 2)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn fnv1a_test_vectors() {
+        let hash = |bytes: &[u8]| {
+            let mut hasher = Fnv1a::new();
+            hasher.write(bytes);
+            hasher.finish()
+        };
+
+        assert_eq!(hash(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(hash(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(hash(b"foobar"), 0x8594_4171_f739_67e8);
+    }
+
+    // These hashes are written into files and shared memory, so they must
+    // never change. If one of these tests fails, the encoding changed.
+
+    #[test]
+    fn struct_hash() {
+        assert_eq!(header().layout_hash(), 0x1b3b_bf5b_eae0_4eb9);
+    }
+
+    #[test]
+    fn enum_hash() {
+        let variant =
+            |name: &'static str, discriminant: i128, builder: crate::TypeLayoutBuilder| Variant {
+                name: name.into(),
+                discriminant,
+                fields: builder.build().unwrap().fields,
+            };
+
+        let mut layout = TypeLayoutInfo::builder("Message")
+            .field("[tag]", "u8", 1, 1)
+            .field("payload", "u32", 4, 4)
+            .build()
+            .unwrap();
+        layout.variants = vec![
+            variant(
+                "Ping",
+                0,
+                TypeLayoutInfo::builder("Ping")
+                    .field("[tag]", "u8", 1, 1)
+                    .align(8),
+            ),
+            variant(
+                "Data",
+                -1,
+                TypeLayoutInfo::builder("Data")
+                    .field("[tag]", "u8", 1, 1)
+                    .field("0", "u32", 4, 4),
+            ),
+        ];
+
+        assert_eq!(layout.layout_hash(), 0xb4d0_dd7d_4fc0_f395);
+    }
+
+    #[test]
+    fn nested_hash() {
+        let mut layout = TypeLayoutInfo::builder("Packet")
+            .field("header", "Header", 8, 4)
+            .field("len", "u8", 1, 1)
+            .build()
+            .unwrap();
+        let plain = layout.layout_hash();
+
+        if let Field::Field { nested, .. } = &mut layout.fields[0] {
+            *nested = Some(Box::new(header()));
+        }
+
+        assert_ne!(layout.layout_hash(), plain);
+        assert_eq!(layout.layout_hash(), 0x7da1_2d7c_7dbd_e081);
+    }
+}
@@ -0,0 +1,265 @@
+use alloc::format;
+use alloc::string::String;
+use core::fmt::Write;
+
+use crate::{Field, LayoutReport, TypeLayoutInfo};
+
+impl TypeLayoutInfo {
+    /// Generates a standalone HTML page describing this type's layout. See
+    /// [`LayoutReport::to_html`], which describes several types in one page.
+    pub fn to_html(&self) -> String {
+        render(core::slice::from_ref(self))
+    }
+}
+
+impl LayoutReport {
+    /// Generates a standalone HTML page describing every layout in the
+    /// report, to publish as a CI artifact or open in a browser.
+    ///
+    /// The page starts with a summary of each type's size and padding, and
+    /// follows it with a section for each type, holding a table of its fields
+    /// and a [byte map](TypeLayoutInfo::byte_map). Clicking a column's header
+    /// sorts a table by that column. Styles and scripts are inline, so the
+    /// page is a single file.
+    ///
+    /// ```rust
+    /// use type_layout::{LayoutReport, TypeLayout};
+    ///
+    /// #[derive(TypeLayout)]
+    /// #[repr(C)]
+    /// struct Header {
+    ///     kind: u8,
+    ///     len: u32,
+    /// }
+    ///
+    /// let mut report = LayoutReport::new();
+    /// report.add::<Header>();
+    ///
+    /// let html = report.to_html();
+    /// assert!(html.starts_with("<!DOCTYPE html>"));
+    /// assert!(html.contains("<td>len</td>"));
+    /// ```
+    pub fn to_html(&self) -> String {
+        render(self.layouts())
+    }
+}
+
+const STYLE: &str = "\
+body { font-family: system-ui, sans-serif; margin: 2em; color: #222; }
+table { border-collapse: collapse; margin: 1em 0; }
+th, td { border: 1px solid #ccc; padding: 0.25em 0.75em; text-align: left; }
+th { background: #f0f0f0; cursor: pointer; user-select: none; }
+td.number { text-align: right; font-variant-numeric: tabular-nums; }
+tr.padding td { background: #fde8e8; color: #a33; }
+pre.byte-map { background: #f8f8f8; padding: 1em; display: inline-block; }
+section { margin-top: 3em; }
+";
+
+/// Sorts a table by the column whose header was clicked, numerically when
+/// every cell in the column is a number. Clicking again reverses the order.
+const SCRIPT: &str = "\
+document.querySelectorAll('table.sortable th').forEach((header, column) => {
+  header.addEventListener('click', () => {
+    const body = header.closest('table').tBodies[0];
+    const rows = Array.from(body.rows);
+    const cell = (row) => row.cells[column].textContent.trim();
+    const numeric = rows.every((row) => cell(row) !== '' && !isNaN(cell(row)));
+    const ascending = header.dataset.order !== 'ascending';
+    rows.sort((a, b) => {
+      const order = numeric ? cell(a) - cell(b) : cell(a).localeCompare(cell(b));
+      return ascending ? order : -order;
+    });
+    header.dataset.order = ascending ? 'ascending' : 'descending';
+    rows.forEach((row) => body.appendChild(row));
+  });
+});
+";
+
+fn render(layouts: &[TypeLayoutInfo]) -> String {
+    let mut out = String::new();
+
+    let _ = writeln!(out, "<!DOCTYPE html>");
+    let _ = writeln!(out, "<html lang=\"en\">");
+    let _ = writeln!(out, "<head>");
+    let _ = writeln!(out, "<meta charset=\"utf-8\">");
+    let _ = writeln!(out, "<title>Type layouts</title>");
+    let _ = writeln!(out, "<style>\n{}</style>", STYLE);
+    let _ = writeln!(out, "</head>");
+    let _ = writeln!(out, "<body>");
+    let _ = writeln!(out, "<h1>Type layouts</h1>");
+
+    let _ = writeln!(out, "<table class=\"sortable\">");
+    let _ = writeln!(
+        out,
+        "<thead><tr><th>Type</th><th>Size</th><th>Alignment</th>\
+         <th>Padding</th><th>Padding %</th></tr></thead>"
+    );
+    let _ = writeln!(out, "<tbody>");
+    for (index, layout) in layouts.iter().enumerate() {
+        let _ = writeln!(
+            out,
+            "<tr><td><a href=\"#type-{}\">{}</a></td>{}{}{}{}</tr>",
+            index,
+            escape(&layout.full_name()),
+            number(layout.size),
+            number(layout.alignment),
+            number(layout.padding_bytes()),
+            format_args!(
+                "<td class=\"number\">{:.1}</td>",
+                layout.padding_ratio() * 100.0
+            ),
+        );
+    }
+    let _ = writeln!(out, "</tbody>");
+    let _ = writeln!(out, "</table>");
+
+    for (index, layout) in layouts.iter().enumerate() {
+        write_section(&mut out, index, layout);
+    }
+
+    let _ = writeln!(out, "<script>\n{}</script>", SCRIPT);
+    let _ = writeln!(out, "</body>");
+    let _ = writeln!(out, "</html>");
+
+    out
+}
+
+/// Writes the section for one type: its size, its fields, and its byte map.
+fn write_section(out: &mut String, index: usize, layout: &TypeLayoutInfo) {
+    let _ = writeln!(out, "<section id=\"type-{}\">", index);
+    let _ = writeln!(out, "<h2>{}</h2>", escape(&layout.full_name()));
+
+    let kind = if layout.is_union { "union, " } else { "" };
+    let _ = writeln!(
+        out,
+        "<p>{}size {}, alignment {}, {} bytes of padding</p>",
+        kind,
+        layout.size,
+        layout.alignment,
+        layout.padding_bytes()
+    );
+
+    if !layout.guaranteed {
+        let _ = writeln!(
+            out,
+            "<p><strong>warning:</strong> {} has no <code>#[repr(C)]</code>, \
+             so the compiler may reorder its fields</p>",
+            escape(&layout.full_name())
+        );
+    }
+
+    if layout.variants.is_empty() {
+        write_fields(out, &layout.fields);
+    } else {
+        for variant in &layout.variants {
+            let _ = writeln!(
+                out,
+                "<h3>Variant {} = {}</h3>",
+                escape(&variant.name),
+                variant.discriminant
+            );
+            write_fields(out, &variant.fields);
+        }
+    }
+
+    if let Some(tail) = &layout.unsized_tail {
+        let _ = writeln!(
+            out,
+            "<p>followed by <code>{}: {}</code> at offset {}</p>",
+            escape(&tail.name),
+            escape(&tail.ty),
+            tail.offset
+        );
+    }
+
+    let byte_map = format!("{}", layout.byte_map(16));
+    // The byte map's first line repeats the heading.
+    let byte_map = byte_map.split_once('\n').map_or("", |(_, rest)| rest);
+    let _ = writeln!(out, "<pre class=\"byte-map\">{}</pre>", escape(byte_map));
+    let _ = writeln!(out, "</section>");
+}
+
+fn write_fields(out: &mut String, fields: &[Field]) {
+    let _ = writeln!(out, "<table class=\"sortable\">");
+    let _ = writeln!(
+        out,
+        "<thead><tr><th>Offset</th><th>Name</th><th>Type</th>\
+         <th>Size</th><th>Align</th></tr></thead>"
+    );
+    let _ = writeln!(out, "<tbody>");
+    write_rows(out, fields, 0, "");
+    let _ = writeln!(out, "</tbody>");
+    let _ = writeln!(out, "</table>");
+}
+
+/// Writes a row for each field, followed by the rows of its nested fields,
+/// which are named like `outer.inner`. `base` is the offset of the fields'
+/// parent from the start of the outermost type.
+fn write_rows(out: &mut String, fields: &[Field], base: usize, prefix: &str) {
+    for field in fields {
+        let offset = base + field.offset();
+
+        match field {
+            Field::Field {
+                name,
+                ty,
+                resolved_ty,
+                size,
+                alignment,
+                array,
+                nested,
+                ..
+            } => {
+                let name = format!("{}{}", prefix, name);
+                let alignment =
+                    alignment.map_or(String::new(), |alignment| format!("{}", alignment));
+
+                let _ = writeln!(
+                    out,
+                    "<tr>{}<td>{}</td><td><code>{}</code></td>{}{}</tr>",
+                    number(offset),
+                    escape(&name),
+                    escape(resolved_ty.as_ref().unwrap_or(ty)),
+                    number(*size),
+                    format_args!("<td class=\"number\">{}</td>", alignment),
+                );
+
+                if let Some(nested) = nested {
+                    let prefix = crate::nested_prefix(&name, array);
+                    write_rows(out, &nested.fields, offset, &prefix);
+                }
+            }
+            Field::Padding { size, .. } => {
+                let _ = writeln!(
+                    out,
+                    "<tr class=\"padding\">{}<td>{}[padding]</td><td></td>{}<td></td></tr>",
+                    number(offset),
+                    escape(prefix),
+                    number(*size),
+                );
+            }
+        }
+    }
+}
+
+fn number(value: usize) -> String {
+    format!("<td class=\"number\">{}</td>", value)
+}
+
+/// Escapes text for HTML, or for XML like SVG.
+pub(crate) fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            ch => escaped.push(ch),
+        }
+    }
+
+    escaped
+}
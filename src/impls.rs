@@ -0,0 +1,444 @@
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::any::{type_name, TypeId};
+use core::marker::PhantomData;
+use core::mem::{align_of, needs_drop, size_of, MaybeUninit};
+use core::num::{
+    NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128,
+    NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize,
+};
+use core::ptr::{self, NonNull};
+use core::time::Duration;
+
+use crate::{ArrayInfo, Field, Repr, TargetInfo, TypeLayout, TypeLayoutInfo};
+
+/// Describes a type whose fields can't be described, like a primitive, as a
+/// single `[value]` field covering the whole type. `type_id` is only given for
+/// types that are known to be `'static`.
+pub(crate) fn opaque_layout<T>(type_id: Option<TypeId>) -> TypeLayoutInfo {
+    let size = size_of::<T>();
+    let fields = if size == 0 {
+        Vec::new()
+    } else {
+        vec![Field::Field {
+            name: Cow::Borrowed("[value]"),
+            ty: Cow::Borrowed(type_name::<T>()),
+            resolved_ty: None,
+            type_id,
+            offset: 0,
+            size,
+            alignment: Some(align_of::<T>()),
+            array: None,
+            needs_drop: needs_drop::<T>(),
+            option_size: Some(size_of::<Option<T>>()),
+            nested: None,
+            contended: false,
+            niche: false,
+        }]
+    };
+
+    TypeLayoutInfo {
+        name: Cow::Borrowed(type_name::<T>()),
+        generics: Vec::new(),
+        size,
+        alignment: align_of::<T>(),
+        natural_alignment: align_of::<T>(),
+        needs_drop: needs_drop::<T>(),
+        option_size: Some(size_of::<Option<T>>()),
+        is_union: false,
+        repr: Repr::default(),
+        guaranteed: true,
+        fields,
+        variants: Vec::new(),
+        unsized_tail: None,
+        tag: None,
+        target: Some(TargetInfo::current()),
+    }
+}
+
+macro_rules! impl_static {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl TypeLayout for $ty {
+                fn type_layout() -> TypeLayoutInfo {
+                    opaque_layout::<$ty>(Some(TypeId::of::<$ty>()))
+                }
+            }
+        )*
+    };
+}
+
+impl_static!(
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    f32,
+    f64,
+    bool,
+    char,
+    (),
+    NonZeroU8,
+    NonZeroU16,
+    NonZeroU32,
+    NonZeroU64,
+    NonZeroU128,
+    NonZeroUsize,
+    NonZeroI8,
+    NonZeroI16,
+    NonZeroI32,
+    NonZeroI64,
+    NonZeroI128,
+    NonZeroIsize,
+    String,
+    Duration,
+);
+
+macro_rules! impl_generic {
+    ($($ty:ident<$param:ident $(: ?$unsized:ident)?>),* $(,)?) => {
+        $(
+            impl<$param $(: ?$unsized)?> TypeLayout for $ty<$param> {
+                fn type_layout() -> TypeLayoutInfo {
+                    opaque_layout::<Self>(None)
+                }
+            }
+        )*
+    };
+}
+
+// The layout of `Option<T>` is only specified for a few kinds of `T`, so it's
+// reported as a whole rather than as a tag and a payload.
+impl_generic!(
+    Option<T>,
+    NonNull<T: ?Sized>,
+    PhantomData<T: ?Sized>,
+    Box<T: ?Sized>,
+    Rc<T: ?Sized>,
+    Arc<T: ?Sized>,
+    Vec<T>,
+);
+
+impl<T: ?Sized> TypeLayout for *const T {
+    fn type_layout() -> TypeLayoutInfo {
+        opaque_layout::<Self>(None)
+    }
+}
+
+impl<T: ?Sized> TypeLayout for *mut T {
+    fn type_layout() -> TypeLayoutInfo {
+        opaque_layout::<Self>(None)
+    }
+}
+
+impl<T: ?Sized> TypeLayout for &T {
+    fn type_layout() -> TypeLayoutInfo {
+        opaque_layout::<Self>(None)
+    }
+}
+
+impl<T: ?Sized> TypeLayout for &mut T {
+    fn type_layout() -> TypeLayoutInfo {
+        opaque_layout::<Self>(None)
+    }
+}
+
+impl<T, const N: usize> TypeLayout for [T; N] {
+    fn type_layout() -> TypeLayoutInfo {
+        let mut layout = opaque_layout::<Self>(None);
+
+        if let Some(Field::Field { array, .. }) = layout.fields.first_mut() {
+            *array = Some(ArrayInfo {
+                elem_ty: Cow::Borrowed(type_name::<T>()),
+                len: N,
+                stride: size_of::<T>(),
+            });
+        }
+
+        layout
+    }
+}
+
+/// Describes one element of a tuple, at the given offset.
+fn tuple_field<T>(index: &'static str, offset: usize) -> Field {
+    Field::Field {
+        name: Cow::Borrowed(index),
+        ty: Cow::Borrowed(type_name::<T>()),
+        resolved_ty: None,
+        type_id: None,
+        offset,
+        size: size_of::<T>(),
+        alignment: Some(align_of::<T>()),
+        array: None,
+        needs_drop: needs_drop::<T>(),
+        option_size: Some(size_of::<Option<T>>()),
+        nested: None,
+        contended: false,
+        niche: false,
+    }
+}
+
+macro_rules! impl_tuple {
+    ($($param:ident $index:tt),+) => {
+        impl<$($param),+> TypeLayout for ($($param,)+) {
+            fn type_layout() -> TypeLayoutInfo {
+                let uninit = MaybeUninit::<Self>::uninit();
+                let base = uninit.as_ptr();
+                let mut fields = Vec::new();
+
+                $(
+                    // SAFETY: `addr_of!` finds the address of the element
+                    // without reading it, so it doesn't matter that the tuple
+                    // is uninitialized.
+                    let element = unsafe { ptr::addr_of!((*base).$index) };
+                    let offset = element as usize - base as usize;
+                    fields.push(tuple_field::<$param>(stringify!($index), offset));
+                )+
+
+                let size = size_of::<Self>();
+
+                TypeLayoutInfo {
+                    name: Cow::Borrowed(type_name::<Self>()),
+                    generics: Vec::new(),
+                    size,
+                    alignment: align_of::<Self>(),
+                    natural_alignment: align_of::<Self>(),
+                    needs_drop: needs_drop::<Self>(),
+                    option_size: Some(size_of::<Option<Self>>()),
+                    is_union: false,
+                    repr: Repr::default(),
+                    guaranteed: false,
+                    fields: crate::__insert_padding(fields, size),
+                    variants: Vec::new(),
+                    unsized_tail: None,
+                    tag: None,
+                    target: Some(TargetInfo::current()),
+                }
+            }
+        }
+    };
+}
+
+impl_tuple!(A 0);
+impl_tuple!(A 0, B 1);
+impl_tuple!(A 0, B 1, C 2);
+impl_tuple!(A 0, B 1, C 2, D 3);
+impl_tuple!(A 0, B 1, C 2, D 3, E 4);
+impl_tuple!(A 0, B 1, C 2, D 3, E 4, F 5);
+impl_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+impl_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+impl_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8);
+impl_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9);
+impl_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10);
+impl_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10, L 11);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks a layout against what the compiler reports for `T`. Types
+    /// with a size are one opaque `[value]` field.
+    fn check_opaque<T: TypeLayout>() -> TypeLayoutInfo {
+        let layout = T::type_layout();
+        let name = type_name::<T>();
+
+        assert_eq!(layout.size, size_of::<T>(), "size of {}", name);
+        assert_eq!(layout.alignment, align_of::<T>(), "alignment of {}", name);
+        assert_eq!(
+            layout.option_size,
+            Some(size_of::<Option<T>>()),
+            "size of Option<{}>",
+            name
+        );
+
+        if size_of::<T>() == 0 {
+            assert!(layout.fields.is_empty(), "fields of {}", name);
+        } else {
+            assert_eq!(layout.fields.len(), 1, "fields of {}", name);
+            match &layout.fields[0] {
+                Field::Field {
+                    name: field_name,
+                    offset,
+                    size,
+                    alignment,
+                    ..
+                } => {
+                    assert_eq!(field_name, "[value]");
+                    assert_eq!((*offset, *size), (0, size_of::<T>()), "field of {}", name);
+                    assert_eq!(*alignment, Some(align_of::<T>()), "field of {}", name);
+                }
+                Field::Padding { .. } => panic!("{} has a padding field", name),
+            }
+        }
+
+        layout
+    }
+
+    fn value_type_id(layout: &TypeLayoutInfo) -> Option<TypeId> {
+        match &layout.fields[0] {
+            Field::Field { type_id, .. } => *type_id,
+            Field::Padding { .. } => None,
+        }
+    }
+
+    #[test]
+    fn primitives() {
+        check_opaque::<u8>();
+        check_opaque::<u16>();
+        check_opaque::<u64>();
+        check_opaque::<u128>();
+        check_opaque::<usize>();
+        check_opaque::<i32>();
+        check_opaque::<f64>();
+        check_opaque::<bool>();
+        check_opaque::<char>();
+        check_opaque::<()>();
+        check_opaque::<Duration>();
+
+        let layout = check_opaque::<u32>();
+        assert_eq!(layout.name, "u32");
+        assert_eq!(value_type_id(&layout), Some(TypeId::of::<u32>()));
+    }
+
+    #[test]
+    fn niches() {
+        let layout = check_opaque::<NonZeroU32>();
+        assert_eq!(layout.option_size, Some(4));
+
+        // `Option<T>` is reported as a whole, whatever is inside it, and
+        // isn't known to be `'static`.
+        let layout = check_opaque::<Option<NonZeroU32>>();
+        assert_eq!(layout.size, 4);
+        assert_eq!(value_type_id(&layout), None);
+
+        check_opaque::<Option<u32>>();
+        check_opaque::<Option<Box<u8>>>();
+        check_opaque::<NonNull<u8>>();
+        check_opaque::<NonNull<[u8]>>();
+        check_opaque::<PhantomData<str>>();
+    }
+
+    #[test]
+    fn pointers_and_collections() {
+        check_opaque::<Box<u64>>();
+        check_opaque::<Box<[u8]>>();
+        check_opaque::<Box<dyn core::fmt::Debug>>();
+        check_opaque::<Rc<str>>();
+        check_opaque::<Arc<u8>>();
+        check_opaque::<Vec<u16>>();
+
+        let layout = check_opaque::<String>();
+        assert!(layout.needs_drop);
+        assert!(matches!(
+            layout.fields[0],
+            Field::Field {
+                needs_drop: true,
+                ..
+            }
+        ));
+    }
+
+    /// The names, offsets, and sizes of a layout's fields, leaving out
+    /// padding.
+    fn field_offsets(layout: &TypeLayoutInfo) -> Vec<(&str, usize, usize)> {
+        layout
+            .fields
+            .iter()
+            .filter_map(|field| match field {
+                Field::Field {
+                    name, offset, size, ..
+                } => Some((name.as_ref(), *offset, *size)),
+                Field::Padding { .. } => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn tuple_fields_are_in_offset_order() {
+        let tuple = (1u8, 2u32, 3u16, 4u64);
+        let base = &tuple as *const _ as usize;
+        let offset = |element: *const u8| element as usize - base;
+
+        let mut expected = vec![
+            ("0", offset(&tuple.0 as *const u8), 1),
+            ("1", offset(&tuple.1 as *const u32 as *const u8), 4),
+            ("2", offset(&tuple.2 as *const u16 as *const u8), 2),
+            ("3", offset(&tuple.3 as *const u64 as *const u8), 8),
+        ];
+        expected.sort_by_key(|&(_, offset, _)| offset);
+
+        let layout = <(u8, u32, u16, u64)>::type_layout();
+        assert_eq!(field_offsets(&layout), expected);
+        assert_eq!(layout.size, size_of::<(u8, u32, u16, u64)>());
+        assert_eq!(layout.alignment, align_of::<(u8, u32, u16, u64)>());
+        assert!(!layout.guaranteed);
+    }
+
+    #[test]
+    fn tuple_padding_fills_gaps() {
+        let layout = <(u8, u16)>::type_layout();
+        let covered: usize = layout.fields.iter().map(Field::size).sum();
+        assert_eq!(covered, layout.size);
+
+        let layout = <(u8,)>::type_layout();
+        assert_eq!(field_offsets(&layout), [("0", 0, 1)]);
+        assert_eq!(layout.size, 1);
+    }
+
+    #[test]
+    fn array_stride() {
+        let layout = check_opaque::<[u16; 5]>();
+        assert_eq!((layout.size, layout.alignment), (10, 2));
+        match &layout.fields[0] {
+            Field::Field {
+                array: Some(array), ..
+            } => {
+                assert_eq!(array.elem_ty, "u16");
+                assert_eq!((array.len, array.stride), (5, 2));
+            }
+            field => panic!("expected an array field, found {:?}", field),
+        }
+
+        // The stride includes the padding at the end of each element.
+        let layout = check_opaque::<[(u32, u8); 3]>();
+        assert_eq!(layout.size, 3 * size_of::<(u32, u8)>());
+        match &layout.fields[0] {
+            Field::Field {
+                array: Some(array), ..
+            } => assert_eq!((array.len, array.stride), (3, 8)),
+            field => panic!("expected an array field, found {:?}", field),
+        }
+
+        let layout = <[u64; 0]>::type_layout();
+        assert_eq!((layout.size, layout.alignment), (0, align_of::<u64>()));
+        assert!(layout.fields.is_empty());
+    }
+
+    #[test]
+    fn references_are_pointer_sized() {
+        let pointer = size_of::<usize>();
+
+        assert_eq!(check_opaque::<&u8>().size, pointer);
+        assert_eq!(check_opaque::<&mut u64>().size, pointer);
+        assert_eq!(check_opaque::<*const u32>().size, pointer);
+        assert_eq!(check_opaque::<*mut u32>().size, pointer);
+        assert_eq!(check_opaque::<&u8>().alignment, align_of::<usize>());
+
+        // References to unsized types carry a length or a vtable too.
+        assert_eq!(check_opaque::<&[u8]>().size, 2 * pointer);
+        assert_eq!(check_opaque::<&str>().size, 2 * pointer);
+        assert_eq!(check_opaque::<&dyn core::fmt::Debug>().size, 2 * pointer);
+    }
+}
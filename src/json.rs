@@ -0,0 +1,203 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use std::error::Error;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{LayoutReport, ReportDiff, TypeLayoutInfo};
+
+/// The version of the JSON format written by [`TypeLayoutInfo::to_json`] and
+/// [`LayoutReport::to_json`].
+///
+/// Within a version, the format only changes by gaining fields, and documents
+/// that lack a field read it as its default, so documents written by older
+/// releases of type-layout keep reading the same way. Readers should ignore
+/// fields they don't know about. Any other change, like renaming or removing
+/// a field or changing what a value means, bumps the version.
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+struct LayoutDocument<'a> {
+    schema_version: u32,
+    layout: &'a TypeLayoutInfo,
+}
+
+#[derive(Serialize)]
+struct ReportDocument<'a> {
+    schema_version: u32,
+    layouts: &'a [TypeLayoutInfo],
+}
+
+#[derive(Deserialize)]
+struct Header {
+    schema_version: u32,
+}
+
+#[derive(Deserialize)]
+struct OwnedLayoutDocument {
+    layout: TypeLayoutInfo,
+}
+
+#[derive(Deserialize)]
+struct OwnedReportDocument {
+    layouts: Vec<TypeLayoutInfo>,
+}
+
+impl TypeLayoutInfo {
+    /// Serializes the layout as pretty-printed JSON in a versioned format
+    /// that external tools can depend on. See [`JSON_SCHEMA_VERSION`] for
+    /// what may change between releases.
+    ///
+    /// The document is an object holding the `schema_version` and the
+    /// `layout`, whose fields are named like the fields of
+    /// [`TypeLayoutInfo`]. With the `schemars` feature,
+    /// `schemars::schema_for!(TypeLayoutInfo)` generates a JSON Schema for
+    /// the `layout`.
+    ///
+    /// This method is only available with the `serde1` feature.
+    ///
+    /// ```rust
+    /// use type_layout::{TypeLayout, TypeLayoutInfo};
+    ///
+    /// #[derive(TypeLayout)]
+    /// #[repr(C)]
+    /// struct Header {
+    ///     kind: u8,
+    ///     len: u32,
+    /// }
+    ///
+    /// let json = Header::type_layout().to_json();
+    /// assert!(json.starts_with("{\n  \"schema_version\": 1,"));
+    ///
+    /// let layout = TypeLayoutInfo::from_json(&json).unwrap();
+    /// assert_eq!(layout.size, 8);
+    /// ```
+    pub fn to_json(&self) -> String {
+        let document = LayoutDocument {
+            schema_version: JSON_SCHEMA_VERSION,
+            layout: self,
+        };
+
+        to_string(&document)
+    }
+
+    /// Reads a layout from JSON written by [`to_json`](Self::to_json), by
+    /// this or an older release of type-layout.
+    ///
+    /// This method is only available with the `serde1` feature.
+    pub fn from_json(json: &str) -> Result<TypeLayoutInfo, JsonError> {
+        check_version(json)?;
+
+        let document: OwnedLayoutDocument = serde_json::from_str(json).map_err(JsonError::Json)?;
+        Ok(document.layout)
+    }
+}
+
+impl LayoutReport {
+    /// Serializes every layout in the report as pretty-printed JSON, in the
+    /// same versioned format as [`TypeLayoutInfo::to_json`], except that the
+    /// document holds a list of `layouts`.
+    ///
+    /// This method is only available with the `serde1` feature.
+    pub fn to_json(&self) -> String {
+        let document = ReportDocument {
+            schema_version: JSON_SCHEMA_VERSION,
+            layouts: self.layouts(),
+        };
+
+        to_string(&document)
+    }
+
+    /// Reads a report from JSON written by [`to_json`](Self::to_json), by
+    /// this or an older release of type-layout.
+    ///
+    /// This method is only available with the `serde1` feature.
+    pub fn from_json(json: &str) -> Result<LayoutReport, JsonError> {
+        check_version(json)?;
+
+        let document: OwnedReportDocument = serde_json::from_str(json).map_err(JsonError::Json)?;
+
+        let mut report = LayoutReport::new();
+        for layout in document.layouts {
+            report.push(layout);
+        }
+
+        Ok(report)
+    }
+}
+
+impl ReportDiff {
+    /// Compares two reports written by [`LayoutReport::to_json`], like on
+    /// two targets that exchange the same types, such as a native build and
+    /// a WebAssembly build. See [`LayoutReport::diff`].
+    ///
+    /// This method is only available with the `serde1` feature.
+    ///
+    /// ```rust,no_run
+    /// use type_layout::ReportDiff;
+    ///
+    /// let native = std::fs::read_to_string("layouts/x86_64.json").unwrap();
+    /// let wasm = std::fs::read_to_string("layouts/wasm32.json").unwrap();
+    ///
+    /// let diff = ReportDiff::from_json(&native, &wasm).unwrap();
+    /// assert!(diff.is_empty(), "layouts differ between targets:\n{}", diff);
+    /// ```
+    pub fn from_json(old: &str, new: &str) -> Result<ReportDiff, JsonError> {
+        let old = LayoutReport::from_json(old)?;
+        let new = LayoutReport::from_json(new)?;
+        Ok(old.diff(&new))
+    }
+}
+
+fn to_string<T: Serialize>(document: &T) -> String {
+    // Layouts only hold strings, numbers, and lists, which always serialize.
+    let mut json = serde_json::to_string_pretty(document).unwrap_or_default();
+    json.push('\n');
+    json
+}
+
+/// Reads just the version first, so that documents from newer releases are
+/// reported as such, rather than as whatever error their new format causes.
+fn check_version(json: &str) -> Result<(), JsonError> {
+    let header: Header = serde_json::from_str(json).map_err(JsonError::Json)?;
+
+    if header.schema_version > JSON_SCHEMA_VERSION {
+        return Err(JsonError::UnsupportedVersion(header.schema_version));
+    }
+
+    Ok(())
+}
+
+/// An error from reading a layout from JSON.
+#[derive(Debug)]
+pub enum JsonError {
+    /// The document isn't valid JSON, or doesn't describe a layout.
+    Json(serde_json::Error),
+
+    /// The document was written by a newer release of type-layout, with a
+    /// format this release doesn't understand.
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            JsonError::Json(err) => write!(formatter, "invalid layout JSON: {}", err),
+            JsonError::UnsupportedVersion(version) => write!(
+                formatter,
+                "layout JSON has schema version {}, but only versions up to {} are supported",
+                version, JSON_SCHEMA_VERSION
+            ),
+        }
+    }
+}
+
+impl Error for JsonError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            JsonError::Json(err) => Some(err),
+            JsonError::UnsupportedVersion(_) => None,
+        }
+    }
+}
@@ -9,8 +9,9 @@ that reports:
 - Each field's name, type, offset, and size
 - Padding due to alignment requirements
 
-**type-layout currently only functions on structs with named fields.** This is a
-temporary limitation.
+**type-layout functions on structs, unions, and enums with `#[repr(C)]` or a
+primitive representation like `#[repr(u8)]`.** The layout of other enums is
+unspecified, so they aren't supported.
 
 ## Examples
 
@@ -35,6 +36,7 @@ println!("{}", Foo::type_layout());
 // | 0      | a         | 1    |
 // | 1      | [padding] | 3    |
 // | 4      | b         | 4    |
+// Option<Foo> is 12 bytes (no niche)
 ```
 
 Over-aligned types have trailing padding, which can be a source of bugs in some
@@ -51,154 +53,1962 @@ struct OverAligned {
 
 println!("{}", OverAligned::type_layout());
 // prints:
-// OverAligned (size 128, alignment 128)
-// | Offset | Name      | Size |
-// | ------ | --------- | ---- |
-// | 0      | value     | 1    |
-// | 1      | [padding] | 127  |
+// OverAligned (size 128, alignment 128 (natural 1))
+// | Offset | Name           | Size |
+// | ------ | -------------- | ---- |
+// | 0      | value          | 1    |
+// | 1      | [tail padding] | 127  |
+// Option<OverAligned> is 256 bytes (no niche)
+```
+
+To include a type's layout in its rustdoc output, add `#[document_layout]`,
+which appends a "Layout" section to the type's docs and derives `TypeLayout`
+if the type doesn't already. When the type is `#[repr(C)]` and every field is
+a `u8`, `i8`, `bool`, or an array of them, which are the only types laid out
+the same way on every target, the section contains the full offset and size
+table. Otherwise, it lists each field's name and type, with its offset and
+size written as `offset_of!` and `size_of` expressions.
+Fields behind `#[cfg(...)]` are only listed in builds where they exist.
+
+With `#[document_layout(path = "my_crate::Color")]`, it also generates a
+doctest, compiled only under `#[cfg(doctest)]`, that checks the table against
+`TypeLayout::type_layout()` when you run `cargo test`. Doctests can only reach
+the type through its public path, so it has to be given, and generic types
+can't be checked.
+
+```rust
+use type_layout::document_layout;
+
+/// A color with an alpha channel.
+#[document_layout]
+#[repr(C)]
+pub struct Color {
+    r: u8,
+    g: u8,
+    b: u8,
+    a: u8,
+}
 ```
 
+## Features
+
+- `std` (enabled by default) enables the parts of the crate that need the
+  standard library, like `write_io` and snapshot files. Without it, the crate
+  is `no_std` and only needs `alloc`, which suits embedded targets.
+- `memoffset` (enabled by default) finds field offsets with the
+  [memoffset](https://crates.io/crates/memoffset) crate, which works on older
+  compilers. Without it, `core::mem::offset_of!` is used instead, which
+  requires Rust 1.77 or newer.
+- `serde1` implements Serde's traits for the crate's types and enables the
+  `lockfile` and `artifacts` modules and `to_json`, which writes layouts in a
+  versioned JSON format for other tools to read.
+- `schemars` implements `schemars::JsonSchema` for `TypeLayoutInfo`, `Field`,
+  and the types they contain, which describes the JSON that `serde1` reads
+  and writes. It enables `serde1`.
+- `registry` enables the `registry` module, which lists every type that
+  derives `TypeLayout`. The `cargo type-layout` command, installed with
+  `cargo install cargo-type-layout`, uses it to print the layouts of every
+  type in a crate.
+- `layout-tests` makes `#[derive(TypeLayout)]` generate a test for each
+  non-generic type, named `type_layout_test_<Type>`, which checks the type's
+  layout against a snapshot file with `assert_layout_snapshot!`, named after
+  the type and its module. This catches layout changes from new compilers or
+  targets in CI without writing any tests.
+- `c-parser` enables `c_abi::parse_struct`, which lays out C struct
+  declarations, so they can be compared with Rust types.
+- `dwarf` enables the `dwarf` module, which reads the layouts of structs from
+  the debug info of compiled C and C++ code.
+- `color` enables `TableOptions::color`, which highlights padding in tables
+  printed to a terminal. It needs Rust 1.70 or newer.
+
 ## Minimum Supported Rust Version (MSRV)
 
-type-layout supports Rust 1.34.1 and newer. Until type-layout reaches 1.0,
-changes to the MSRV will require major version bumps. After 1.0, MSRV changes
+type-layout supports Rust 1.60 and newer, with its default features. Some
+features and options need a newer compiler:
+
+- Rust 1.65 for `#[layout(embed)]`, `#[layout(offset_consts)]`,
+  `#[layout(const_layout)]`, `#[layout(offset = N)]`, and checking offsets
+  at compile time with `assert_layout!`.
+- Rust 1.70 for the `color` feature.
+- Rust 1.77 without the `memoffset` feature, which finds offsets with
+  `core::mem::offset_of!` instead.
+
+Until type-layout reaches 1.0, changes to the MSRV will require major version
+bumps. After 1.0, MSRV changes
 will only require minor version bumps, but will need significant justification.
 */
 
-use std::borrow::Cow;
-use std::fmt::{self, Display};
-use std::str;
+#![no_std]
+
+extern crate alloc;
 
-pub use type_layout_derive::TypeLayout;
+#[cfg(feature = "std")]
+extern crate std;
 
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::any::TypeId;
+use core::fmt;
+
+#[cfg(feature = "std")]
+use std::io;
+
+// The `JsonSchema` derive expects these from the standard prelude, which a
+// `no_std` crate doesn't have.
+#[cfg(feature = "schemars")]
+use alloc::{borrow::ToOwned, vec};
+
+mod assert;
+mod bindgen;
+mod builder;
+mod byte_map;
+mod c_header;
+#[cfg(feature = "c-parser")]
+mod c_parse;
+#[cfg(feature = "std")]
+mod cache;
+mod cache_line;
+mod compose;
+mod const_layout;
+mod csharp;
+mod csv;
+mod ctypes;
+mod diff;
+mod dot;
+mod hash;
+mod html;
+mod impls;
+#[cfg(feature = "serde1")]
+mod json;
+mod packet_diagram;
+mod padding;
+mod reorder;
+mod report;
+mod rows;
+mod shader;
+mod svg;
+mod table;
+mod target;
+mod value;
+
+#[cfg(feature = "serde1")]
+pub mod artifacts;
+pub mod c_abi;
+#[cfg(feature = "dwarf")]
+pub mod dwarf;
+pub mod embedded;
+pub mod gpu;
+
+#[cfg(feature = "serde1")]
+pub mod lockfile;
+
+#[cfg(feature = "registry")]
+pub mod registry;
+
+pub use type_layout_derive::{document_layout, TypeLayout};
+
+#[cfg(feature = "memoffset")]
 #[doc(hidden)]
 pub use memoffset;
 
+pub use crate::bindgen::{bindgen_compat, CompatIssue, CompatReport};
+pub use crate::builder::TypeLayoutBuilder;
+pub use crate::byte_map::ByteMap;
+pub use crate::cache_line::{
+    CacheLine, CacheLineField, CacheLineReport, FalseSharing, WithCacheLines,
+    DEFAULT_CACHE_LINE_SIZE,
+};
+pub use crate::compose::LayoutError;
+pub use crate::const_layout::{ConstField, ConstLayout, ConstTypeLayout};
+pub use crate::diff::{LayoutChange, LayoutDiff, ReportDiff};
+#[cfg(feature = "serde1")]
+pub use crate::json::{JsonError, JSON_SCHEMA_VERSION};
+pub use crate::packet_diagram::PacketDiagram;
+pub use crate::padding::{eq_ignoring_padding, PaddingCause, PaddingRegion};
+pub use crate::reorder::{OptimizedOrder, ProjectedSavings};
+pub use crate::report::LayoutReport;
+pub use crate::rows::{FieldRow, PaddingRow, Row};
+pub use crate::table::{DisplayWith, TableOptions};
+pub use crate::target::{Endianness, TargetInfo};
+pub use crate::value::{layout_of_value, ValueLayout};
+
+#[doc(hidden)]
+pub use crate::assert::{__assert_layout_snapshot, __expect_field};
+
+#[cfg(feature = "std")]
+#[doc(hidden)]
+pub use crate::assert::__assert_layout_snapshot_file;
+
+#[cfg(feature = "registry")]
+#[doc(hidden)]
+pub use inventory;
+
+/// Items used by generated code, re-exported so that the code doesn't depend on
+/// whether `std` or `alloc` is available, or how the prelude is named, where
+/// it's expanded.
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::borrow::Cow;
+    pub use alloc::boxed::Box;
+    pub use alloc::format;
+    pub use alloc::vec;
+    pub use alloc::vec::Vec;
+
+    /// Named in the warning that `#[layout(report)]` produces, which shows
+    /// the type's size and alignment as `SIZE` and `ALIGN`. It's never
+    /// constructed. The `u8` makes it a type that isn't FFI-safe, since rustc
+    /// doesn't warn about zero-sized ones.
+    #[allow(dead_code)]
+    pub struct LayoutOf<T: ?Sized, const SIZE: usize, const ALIGN: usize>(
+        core::marker::PhantomData<T>,
+        u8,
+    );
+
+    /// The rows of a type's layout table as `(offset, name, type, size)`,
+    /// with an empty name and type for padding. The doctests that
+    /// `#[document_layout(path = "...")]` generates compare these to the
+    /// table in the type's docs.
+    pub fn document_rows(layout: &crate::TypeLayoutInfo) -> Vec<(usize, &str, &str, usize)> {
+        layout
+            .fields
+            .iter()
+            .map(|field| match field {
+                crate::Field::Field {
+                    offset,
+                    name,
+                    ty,
+                    size,
+                    ..
+                } => (*offset, &**name, &**ty, *size),
+                crate::Field::Padding { offset, size, .. } => (*offset, "", "", *size),
+            })
+            .collect()
+    }
+}
+
+/// Finds the offset of a field. Generated code uses this instead of naming a
+/// particular `offset_of!` so that the `memoffset` feature can choose one.
+#[cfg(feature = "memoffset")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __offset_of {
+    ($parent:path, $field:tt) => {
+        $crate::memoffset::offset_of!($parent, $field)
+    };
+}
+
+#[cfg(not(feature = "memoffset"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __offset_of {
+    ($parent:path, $field:tt) => {
+        ::core::mem::offset_of!($parent, $field)
+    };
+}
+
+#[cfg(not(feature = "registry"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __register_type_layout {
+    ($ty:ident) => {};
+}
+
+/// A type whose layout can be inspected at runtime.
+///
+/// This trait is usually implemented with `#[derive(TypeLayout)]`. The derive
+/// accepts these options through a `#[layout(...)]` attribute on the type:
+///
+/// - `max_padding = N` fails the build if the type contains more than `N`
+///   bytes of padding.
+/// - `max_size = N` fails the build if the type is larger than `N` bytes.
+/// - `size = N` and `align = N` fail the build if the type's size or
+///   alignment isn't exactly `N`.
+/// - `embed` places a record of the type's layout in a link section of the
+///   compiled binary. See the [`embedded`] module.
+/// - `offset_consts` adds an `OFFSET_OF_<FIELD>` associated constant for each
+///   field, holding its offset in bytes. Fields of tuple structs are numbered,
+///   like `OFFSET_OF_0`. The constants are usable in const contexts on Rust
+///   1.65 or newer.
+/// - `const_layout` implements [`ConstTypeLayout`], which describes the type
+///   in a constant that can be used without allocating. It's only supported on
+///   structs, and needs Rust 1.65 or newer.
+/// - `crate = "path"` sets the path generated code uses to reach this crate,
+///   which is `::type_layout` by default. This is needed when type-layout is
+///   re-exported through another crate or renamed in `Cargo.toml`.
+/// - `bound = "..."` replaces the where clause the derive adds to the impl,
+///   which otherwise requires `'static` for `type_id` fields and
+///   `TypeLayout` for `nested` fields. `no_bound` leaves it out instead.
+///
+/// ```rust
+/// use type_layout::TypeLayout;
+///
+/// #[derive(TypeLayout)]
+/// #[layout(max_padding = 3, max_size = 8)]
+/// #[repr(C)]
+/// struct Packet {
+///     kind: u8,
+///     len: u32,
+/// }
+/// ```
+///
+/// ```compile_fail
+/// use type_layout::TypeLayout;
+///
+/// #[derive(TypeLayout)]
+/// #[layout(max_padding = 2)]
+/// #[repr(C)]
+/// struct Packet {
+///     kind: u8,
+///     len: u32,
+/// }
+/// ```
+///
+/// ```rust
+/// use type_layout::TypeLayout;
+///
+/// #[derive(TypeLayout)]
+/// #[layout(size = 16, align = 8)]
+/// #[repr(C)]
+/// struct Entry {
+///     key: u64,
+///     value: u32,
+/// }
+/// ```
+///
+/// ```compile_fail
+/// use type_layout::TypeLayout;
+///
+/// #[derive(TypeLayout)]
+/// #[layout(size = 12)]
+/// #[repr(C)]
+/// struct Entry {
+///     key: u64,
+///     value: u32,
+/// }
+/// ```
+///
+/// Fields of structs accept `#[layout(offset = N)]`, which fails the build if
+/// the field isn't at offset `N`. This is useful for pinning down a struct that
+/// has to match one defined elsewhere, like in C. Like `offset_consts`, it
+/// needs Rust 1.65 or newer.
+///
+/// ```rust
+/// use type_layout::TypeLayout;
+///
+/// #[derive(TypeLayout)]
+/// #[repr(C)]
+/// struct FirmwareHeader {
+///     #[layout(offset = 0)]
+///     magic: u32,
+///     #[layout(offset = 8)]
+///     entry_point: u64,
+/// }
+/// ```
+///
+/// ```compile_fail
+/// use type_layout::TypeLayout;
+///
+/// #[derive(TypeLayout)]
+/// #[repr(C)]
+/// struct FirmwareHeader {
+///     magic: u32,
+///     #[layout(offset = 4)]
+///     entry_point: u64,
+/// }
+/// ```
+///
+/// The layout of a generic type depends on its parameters, so these checks
+/// can't run where the type is defined. They run for each instantiation whose
+/// `type_layout()` is compiled instead, and only in a full build, since
+/// `cargo check` doesn't evaluate them. Instantiations that never have their
+/// `type_layout()` called aren't checked at all:
+///
+/// ```rust
+/// use type_layout::TypeLayout;
+///
+/// #[derive(TypeLayout)]
+/// #[layout(max_size = 8)]
+/// #[repr(C)]
+/// struct Slot<T> {
+///     tag: u8,
+///     value: T,
+/// }
+///
+/// // Larger than 8 bytes, but never checked.
+/// let slot = Slot { tag: 0, value: 0u64 };
+/// println!("{}", Slot::<u32>::type_layout());
+/// ```
+///
+/// ```compile_fail
+/// # use type_layout::TypeLayout;
+/// #
+/// # #[derive(TypeLayout)]
+/// # #[layout(max_size = 8)]
+/// # #[repr(C)]
+/// # struct Slot<T> {
+/// #     tag: u8,
+/// #     value: T,
+/// # }
+/// #
+/// println!("{}", Slot::<u64>::type_layout());
+/// ```
+///
+/// Fields marked with `#[layout(skip)]` are reported as an opaque region
+/// named `[opaque]`, without their name or type, while their bytes are still
+/// accounted for. This allows publishing the layout of a type with private
+/// fields:
+///
+/// ```rust
+/// use type_layout::TypeLayout;
+///
+/// #[derive(TypeLayout)]
+/// #[repr(C)]
+/// struct Session {
+///     id: u32,
+///     #[layout(skip)]
+///     secret_key: [u8; 16],
+/// }
+///
+/// let layout = Session::type_layout();
+/// assert!(layout.field("secret_key").is_none());
+/// assert_eq!(layout.field("[opaque]").unwrap().size(), 16);
+///
+/// println!("{}", layout);
+/// // prints:
+/// // Session (size 20, alignment 4)
+/// // | Offset | Name     | Size |
+/// // | ------ | -------- | ---- |
+/// // | 0      | id       | 4    |
+/// // | 4      | [opaque] | 16   |
+/// // Option<Session> is 24 bytes (no niche)
+/// ```
+///
+/// `#[layout(rename = "...")]` and `#[layout(ty = "...")]` change the name
+/// and type a field is reported under, like to match a struct's definition in
+/// C:
+///
+/// ```rust
+/// use std::cell::Cell;
+/// use type_layout::{TableOptions, TypeLayout};
+///
+/// #[derive(TypeLayout)]
+/// #[repr(C)]
+/// struct Status {
+///     #[layout(rename = "dev_id", ty = "uint16_t")]
+///     device: u16,
+///     #[layout(rename = "err_count", ty = "uint32_t")]
+///     errors: Cell<u32>,
+/// }
+///
+/// let layout = Status::type_layout();
+/// println!("{}", layout.display_with(TableOptions::new().types(true)));
+/// // prints:
+/// // Status (size 8, alignment 4)
+/// // | Offset | Name      | Type     | Size |
+/// // | ------ | --------- | -------- | ---- |
+/// // | 0      | dev_id    | uint16_t | 2    |
+/// // | 2      | [padding] |          | 2    |
+/// // | 4      | err_count | uint32_t | 4    |
+/// // Option<Status> is 12 bytes (no niche)
+/// ```
+///
+/// ```rust
+/// use type_layout::TypeLayout;
+///
+/// #[derive(TypeLayout)]
+/// #[layout(offset_consts)]
+/// #[repr(C)]
+/// struct Packet {
+///     kind: u8,
+///     len: u32,
+/// }
+///
+/// #[derive(TypeLayout)]
+/// #[layout(offset_consts)]
+/// #[repr(C)]
+/// struct Pair(u16, u64);
+///
+/// const LEN_OFFSET: usize = Packet::OFFSET_OF_LEN;
+/// assert_eq!(LEN_OFFSET, 4);
+/// assert_eq!(Pair::OFFSET_OF_1, 8);
+/// ```
+///
+/// Fields whose constants would have the same name are rejected:
+///
+/// ```compile_fail
+/// use type_layout::TypeLayout;
+///
+/// #[derive(TypeLayout)]
+/// #[layout(offset_consts)]
+/// #[allow(non_snake_case)]
+/// struct Mixed {
+///     value: u8,
+///     Value: u8,
+/// }
+/// ```
+///
+/// Generic types are checked once for each set of generic parameters that
+/// `type_layout()` is called with, since their layout isn't known until then.
+///
+/// `#[layout(report)]` shows the type's size and alignment as a warning while
+/// it's compiled, so they appear in the output of `cargo check` without
+/// running anything. There's no stable way for a derive to emit warnings, so
+/// the warning is rustc's `improper_ctypes_definitions`, naming a type that holds the
+/// size and alignment. It's meant to be added while inspecting a type and
+/// removed afterwards, since builds that deny warnings fail with it.
+///
+/// ```rust
+/// use type_layout::TypeLayout;
+///
+/// #[derive(TypeLayout)]
+/// #[layout(report)]
+/// #[repr(C)]
+/// struct Packet {
+///     kind: u8,
+///     len: u32,
+/// }
+/// ```
+///
+/// ```text
+/// warning: `extern` fn uses type `type_layout::__private::LayoutOf<Packet, 8, 4>`, which is not FFI-safe
+///  --> src/lib.rs:4:10
+///   |
+/// 4 | #[layout(report)]
+///   |          ^^^^^^ not FFI-safe
+/// ```
+///
+/// `TypeLayout` is also implemented for primitives and some common types from
+/// `std`, like `NonNull<T>`, `Option<T>`, and `String`, so that they can be
+/// used with generic code and `#[layout(nested)]`. Their fields aren't public,
+/// so each is reported as a single `[value]` field:
+///
+/// ```rust
+/// use std::num::NonZeroU32;
+/// use type_layout::TypeLayout;
+///
+/// let layout = u32::type_layout();
+/// assert_eq!((layout.size, layout.alignment), (4, 4));
+/// assert_eq!(layout.field("[value]").unwrap().size(), 4);
+///
+/// assert_eq!(<Option<NonZeroU32>>::type_layout().size, 4);
+/// assert_eq!(<*const str>::type_layout().size, 2 * std::mem::size_of::<usize>());
+/// ```
+///
+/// With `crate = "..."`, the derive works through a re-export of this crate:
+///
+/// ```rust
+/// mod facade {
+///     pub use type_layout::*;
+/// }
+///
+/// use facade::TypeLayout;
+///
+/// #[derive(TypeLayout)]
+/// #[layout(crate = "facade")]
+/// #[repr(C)]
+/// struct Point {
+///     x: f32,
+///     y: f32,
+/// }
+///
+/// assert_eq!(Point::type_layout().size, 8);
+/// ```
+///
+/// With `bound = "..."`, the impl is bounded by the given predicates rather
+/// than the ones inferred from the fields:
+///
+/// ```rust
+/// use type_layout::TypeLayout;
+///
+/// #[derive(TypeLayout)]
+/// #[layout(bound = "T: TypeLayout + Copy")]
+/// #[repr(C)]
+/// struct Pair<T> {
+///     #[layout(nested)]
+///     first: T,
+///     #[layout(nested)]
+///     second: T,
+/// }
+///
+/// assert_eq!(<Pair<u16>>::type_layout().size, 4);
+/// ```
+///
+/// Tuples of up to 12 elements, arrays, references, and raw pointers implement
+/// `TypeLayout` for any element types. Tuples list their elements in order of
+/// offset, which may differ from the order they're written in:
+///
+/// ```rust
+/// use type_layout::TypeLayout;
+///
+/// let layout = <(u8, u32, u16)>::type_layout();
+/// assert_eq!(layout.size, 8);
+/// assert_eq!(layout.field("1").unwrap().size(), 4);
+///
+/// let array = <[u16; 5]>::type_layout();
+/// assert_eq!((array.size, array.alignment), (10, 2));
+///
+/// assert_eq!(<&[u8]>::type_layout().size, 2 * std::mem::size_of::<usize>());
+/// ```
+///
+/// Only the last field of a struct can be dynamically sized, like `[u8]`,
+/// `str`, or `dyn Trait`; see [`UnsizedTail`]. Unsized fields anywhere else
+/// are rejected with an error pointing at the field:
+///
+/// ```compile_fail
+/// use type_layout::TypeLayout;
+///
+/// #[derive(TypeLayout)]
+/// struct Message {
+///     body: str,
+///     len: usize,
+/// }
+/// ```
+///
+/// So are fields whose type is `impl Trait` or `_`. The type of a field can
+/// be a macro invocation, like `id_type!()`, which the derive can't look
+/// inside of. The field is laid out as whatever the macro expands to, and is
+/// reported with the macro invocation as its type.
 pub trait TypeLayout {
     fn type_layout() -> TypeLayoutInfo;
+
+    /// Returns this type's layout, computing it only the first time it's
+    /// called for each type. This suits code that looks up layouts
+    /// repeatedly, where calling `type_layout()` would rebuild the layout each
+    /// time.
+    ///
+    /// The layouts are kept for the rest of the program. This method is only
+    /// available with the `std` feature.
+    ///
+    /// ```rust
+    /// use type_layout::TypeLayout;
+    ///
+    /// #[derive(TypeLayout)]
+    /// #[repr(C)]
+    /// struct Sample {
+    ///     time: u64,
+    ///     value: f32,
+    /// }
+    ///
+    /// let first = Sample::type_layout_static();
+    /// let second = Sample::type_layout_static();
+    /// assert!(std::ptr::eq(first, second));
+    /// assert_eq!(first.size, 16);
+    /// ```
+    #[cfg(feature = "std")]
+    fn type_layout_static() -> &'static TypeLayoutInfo
+    where
+        Self: 'static,
+    {
+        crate::cache::cached(TypeId::of::<Self>(), Self::type_layout)
+    }
+
+    /// The index in `type_layout().variants` of the variant this value holds.
+    /// The derive implements this for enums. See [`layout_of_value`].
+    #[doc(hidden)]
+    fn __variant_index(&self) -> Option<usize> {
+        None
+    }
+}
+
+/// An object-safe version of [`TypeLayout`], which gets the layout through a
+/// value instead of naming its type. This is implemented for every type that
+/// implements `TypeLayout`, so values of different types can be kept together
+/// and inspected, like in a debugging UI.
+///
+/// Pointers like `Box<T>` and `&T` implement `TypeLayout` too, so `layout()`
+/// has to be called on the value they point to, rather than on the pointer:
+///
+/// ```rust
+/// use type_layout::{DynTypeLayout, TypeLayout};
+///
+/// #[derive(TypeLayout)]
+/// #[repr(C)]
+/// struct Position {
+///     x: f32,
+///     y: f32,
+/// }
+///
+/// #[derive(TypeLayout)]
+/// #[repr(C)]
+/// struct Health {
+///     current: u16,
+///     max: u16,
+/// }
+///
+/// let components: Vec<Box<dyn DynTypeLayout>> = vec![
+///     Box::new(Position { x: 0.0, y: 1.0 }),
+///     Box::new(Health { current: 5, max: 10 }),
+/// ];
+///
+/// let names: Vec<_> = components
+///     .iter()
+///     .map(|component| component.as_ref().layout().name)
+///     .collect();
+/// assert_eq!(names, ["Position", "Health"]);
+///
+/// // This is the layout of the `Box` instead.
+/// assert_eq!(components[0].layout().size, std::mem::size_of::<usize>() * 2);
+/// ```
+pub trait DynTypeLayout {
+    /// Returns the layout of this value's type, like
+    /// [`TypeLayout::type_layout`].
+    fn layout(&self) -> TypeLayoutInfo;
 }
 
-#[derive(Debug)]
+impl<T: TypeLayout + ?Sized> DynTypeLayout for T {
+    fn layout(&self) -> TypeLayoutInfo {
+        T::type_layout()
+    }
+}
+
+/// The layout of a type, as returned by [`TypeLayout::type_layout`].
+///
+/// Layouts can be compared and hashed, which makes it easy to check one
+/// against an expected layout in a test, or to use them as map keys:
+///
+/// ```rust
+/// use std::collections::HashSet;
+/// use type_layout::TypeLayout;
+///
+/// #[derive(TypeLayout)]
+/// #[repr(C)]
+/// struct Point {
+///     x: f32,
+///     y: f32,
+/// }
+///
+/// assert_eq!(Point::type_layout(), Point::type_layout());
+/// assert_ne!(Point::type_layout(), <(f32, f32)>::type_layout());
+///
+/// let seen: HashSet<_> = vec![Point::type_layout(), Point::type_layout()]
+///     .into_iter()
+///     .collect();
+/// assert_eq!(seen.len(), 1);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct TypeLayoutInfo {
     pub name: Cow<'static, str>,
+
+    /// The type and const generic parameters the type was instantiated with,
+    /// like `["u64", "4"]` for `Buffer<u64, 4>`. Lifetimes aren't included.
+    /// See [`TypeLayoutInfo::full_name`].
+    #[cfg_attr(feature = "serde1", serde(default))]
+    pub generics: Vec<Cow<'static, str>>,
     pub size: usize,
     pub alignment: usize,
+
+    /// The alignment the type would have without `#[repr(align(N))]` or
+    /// `#[repr(packed)]`, which is the largest alignment of its fields.
+    pub natural_alignment: usize,
+
+    /// Whether dropping this type runs any code, as reported by
+    /// [`std::mem::needs_drop`]. For generic types, this can depend on the
+    /// generic parameters.
+    ///
+    /// ```rust
+    /// use type_layout::{Field, TypeLayout};
+    ///
+    /// #[derive(TypeLayout)]
+    /// struct Entry<T> {
+    ///     id: u32,
+    ///     name: String,
+    ///     value: T,
+    /// }
+    ///
+    /// fn field_needs_drop(field: Option<&Field>) -> bool {
+    ///     match field {
+    ///         Some(Field::Field { needs_drop, .. }) => *needs_drop,
+    ///         _ => panic!("expected a field"),
+    ///     }
+    /// }
+    ///
+    /// let layout = Entry::<u64>::type_layout();
+    /// assert!(layout.needs_drop);
+    /// assert!(!field_needs_drop(layout.field("id")));
+    /// assert!(field_needs_drop(layout.field("name")));
+    /// assert!(!field_needs_drop(layout.field("value")));
+    ///
+    /// let layout = Entry::<Vec<u8>>::type_layout();
+    /// assert!(field_needs_drop(layout.field("value")));
+    /// ```
+    pub needs_drop: bool,
+
+    /// The size of `Option<Self>`, if it's known. When this is the same as
+    /// `size`, the type has a niche that `Option` stores its tag in. Layouts
+    /// built by hand or read from an embedded section don't know this.
+    #[cfg_attr(feature = "serde1", serde(default))]
+    pub option_size: Option<usize>,
+
+    /// Whether this type is a union. Every field of a union starts at offset
+    /// 0, so its fields overlap each other instead of following one another.
+    ///
+    /// ```rust
+    /// use type_layout::TypeLayout;
+    ///
+    /// #[derive(TypeLayout)]
+    /// #[repr(C)]
+    /// union Value {
+    ///     int: u32,
+    ///     float: f64,
+    ///     bytes: [u8; 3],
+    /// }
+    ///
+    /// let layout = Value::type_layout();
+    /// assert!(layout.is_union);
+    /// assert_eq!(layout.field("float").unwrap().offset(), 0);
+    /// assert_eq!(layout.field("bytes").unwrap().offset(), 0);
+    ///
+    /// println!("{}", layout);
+    /// // prints:
+    /// // Value (union, size 8, alignment 8)
+    /// // | Offset | Name                      | Size |
+    /// // | ------ | ------------------------- | ---- |
+    /// // | 0      | int                       | 4    |
+    /// // | 0      | float                     | 8    |
+    /// // | 0      | bytes: [u8; 3] (stride 1) | 3    |
+    /// // Option<Value> is 16 bytes (no niche)
+    /// ```
+    #[cfg_attr(feature = "serde1", serde(default))]
+    pub is_union: bool,
+
+    /// The type's `#[repr(...)]` attribute, which decides whether its layout
+    /// is guaranteed. See [`Repr`].
+    #[cfg_attr(feature = "serde1", serde(default))]
+    pub repr: Repr,
+
+    /// Whether the order and offsets of the fields are guaranteed by the
+    /// language. Derived types are guaranteed when they're `#[repr(C)]`,
+    /// `#[repr(transparent)]`, or have a primitive representation like
+    /// `#[repr(u8)]`. Otherwise, the compiler is free to reorder fields, and
+    /// may lay the type out differently between compilations, so the offsets
+    /// shouldn't be relied on as an ABI.
+    ///
+    /// `Display` prints a warning for layouts that aren't guaranteed.
+    ///
+    /// ```rust
+    /// use type_layout::TypeLayout;
+    ///
+    /// #[derive(TypeLayout)]
+    /// struct Point {
+    ///     x: u8,
+    ///     y: u32,
+    /// }
+    ///
+    /// let layout = Point::type_layout();
+    /// assert!(!layout.guaranteed);
+    ///
+    /// println!("{}", layout);
+    /// // prints:
+    /// // Point (size 8, alignment 4)
+    /// // | Offset | Name           | Size |
+    /// // | ------ | -------------- | ---- |
+    /// // | 0      | y              | 4    |
+    /// // | 4      | x              | 1    |
+    /// // | 5      | [tail padding] | 3    |
+    /// // Option<Point> is 12 bytes (no niche)
+    /// // warning: Point has no #[repr(C)], so the compiler may reorder its fields
+    /// ```
+    #[cfg_attr(feature = "serde1", serde(default))]
+    pub guaranteed: bool,
+
+    /// The fields of a struct or union, and the padding between and after
+    /// them as `Field::Padding` entries, in order of offset. This is empty for
+    /// enums, whose fields are in `variants`.
+    pub fields: Vec<Field>,
+
+    /// The variants of an enum. This is empty for structs.
+    #[cfg_attr(feature = "serde1", serde(default))]
+    pub variants: Vec<Variant>,
+
+    /// The last field of a struct whose type is dynamically sized, like
+    /// `[T]`, `str`, or `dyn Trait`. Such a field isn't listed in `fields`,
+    /// which only cover the sized fields before it, and `size` is the size of
+    /// a value whose tail is empty. See [`UnsizedTail`].
+    #[cfg_attr(feature = "serde1", serde(default))]
+    pub unsized_tail: Option<UnsizedTail>,
+
+    /// The tag of an enum, which says which variant it holds. This is `None`
+    /// for structs and unions. See [`EnumTag`].
+    #[cfg_attr(feature = "serde1", serde(default))]
+    pub tag: Option<EnumTag>,
+
+    /// The target the layout was computed for, if it's known. Derived
+    /// layouts are for the target being compiled for. See [`TargetInfo`].
+    #[cfg_attr(feature = "serde1", serde(default))]
+    pub target: Option<TargetInfo>,
+}
+
+/// One variant of an enum, along with the layout of the enum when it holds
+/// that variant.
+///
+/// Enums must have `#[repr(C)]` or a primitive representation like
+/// `#[repr(u8)]`, since the layout of other enums is unspecified.
+///
+/// ```rust
+/// use type_layout::TypeLayout;
+///
+/// #[derive(TypeLayout)]
+/// #[repr(u8)]
+/// enum Message {
+///     Quit,
+///     Move { x: i32, y: i32 },
+///     Write(u16, u64),
+/// }
+///
+/// let layout = Message::type_layout();
+/// assert_eq!(layout.size, 16);
+///
+/// let write = layout.variant("Write").unwrap();
+/// assert_eq!(write.discriminant, 2);
+/// assert_eq!(write.field("[tag]").unwrap().size(), 1);
+/// assert_eq!(write.field("0").unwrap().offset(), 2);
+/// assert_eq!(write.field("1").unwrap().offset(), 8);
+///
+/// println!("{}", layout);
+/// // prints:
+/// // Message (size 16, alignment 8)
+/// // Variant Quit = 0
+/// // | Offset | Name           | Size |
+/// // | ------ | -------------- | ---- |
+/// // | 0      | [tag]          | 1    |
+/// // | 1      | [tail padding] | 15   |
+/// // Variant Move = 1
+/// // | Offset | Name           | Size |
+/// // | ------ | -------------- | ---- |
+/// // | 0      | [tag]          | 1    |
+/// // | 1      | [padding]      | 3    |
+/// // | 4      | x              | 4    |
+/// // | 8      | y              | 4    |
+/// // | 12     | [tail padding] | 4    |
+/// // Variant Write = 2
+/// // | Offset | Name      | Size |
+/// // | ------ | --------- | ---- |
+/// // | 0      | [tag]     | 1    |
+/// // | 1      | [padding] | 1    |
+/// // | 2      | 0         | 2    |
+/// // | 4      | [padding] | 4    |
+/// // | 8      | 1         | 8    |
+/// // Option<Message> is 16 bytes (has a niche)
+/// ```
+///
+/// ```compile_fail
+/// use type_layout::TypeLayout;
+///
+/// #[derive(TypeLayout)]
+/// enum Unspecified {
+///     A(u8),
+///     B(u32),
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Variant {
+    pub name: Cow<'static, str>,
+
+    /// The value of the enum's tag when it holds this variant.
+    pub discriminant: i128,
+
+    /// The enum's tag, named `[tag]`, followed by this variant's fields and
+    /// the padding between them. Offsets are from the start of the enum, and
+    /// padding extends to the end of the enum.
     pub fields: Vec<Field>,
 }
 
-#[derive(Debug)]
+/// The tag of an enum, as described by [`TypeLayoutInfo::tag`]. Along with
+/// each variant's [`discriminant`](Variant::discriminant), this is enough to
+/// check an enum against the tagged union it mirrors in C:
+///
+/// ```rust
+/// use type_layout::TypeLayout;
+///
+/// // struct Event {
+/// //     uint8_t tag;
+/// //     union {
+/// //         struct { uint32_t code; } key;
+/// //         struct { int16_t x, y; } mouse;
+/// //     };
+/// // };
+/// #[derive(TypeLayout)]
+/// #[repr(C, u8)]
+/// enum Event {
+///     Key { code: u32 } = 1,
+///     Mouse { x: i16, y: i16 } = 2,
+/// }
+///
+/// let layout = Event::type_layout();
+/// let tag = layout.tag.as_ref().unwrap();
+/// assert_eq!((tag.ty.as_ref(), tag.offset, tag.size), ("u8", 0, 1));
+/// assert_eq!(tag.payload_offset, 4);
+///
+/// let discriminants: Vec<_> = layout
+///     .variants
+///     .iter()
+///     .map(|variant| variant.discriminant)
+///     .collect();
+/// assert_eq!(discriminants, [1, 2]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct EnumTag {
+    /// The tag's type, like `u8`, or `C enum` for `#[repr(C)]` enums without
+    /// a primitive type, whose tag is the size of a C `enum`.
+    pub ty: Cow<'static, str>,
+    pub offset: usize,
+    pub size: usize,
+
+    /// The offset the variants' fields are laid out from. With `#[repr(C)]`,
+    /// this is the offset of the union of variants that follows the tag.
+    /// With only a primitive representation, it's the end of the tag, and
+    /// each variant's fields are aligned from there.
+    pub payload_offset: usize,
+}
+
+impl Variant {
+    /// Finds the field of this variant with the given name, if there is one.
+    pub fn field(&self, name: &str) -> Option<&Field> {
+        find_field(&self.fields, name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum Field {
     Field {
         name: Cow<'static, str>,
+
+        /// The field's type as written in the source, like `Vec<T>`.
         ty: Cow<'static, str>,
+
+        /// The field's type with generic parameters filled in, as reported by
+        /// [`std::any::type_name`], like `alloc::vec::Vec<i32>`. This is
+        /// preferred over `ty` when rendering.
+        ///
+        /// ```rust
+        /// use type_layout::{Field, TypeLayout};
+        ///
+        /// #[derive(TypeLayout)]
+        /// struct Wrapper<T> {
+        ///     items: Vec<T>,
+        /// }
+        ///
+        /// fn types(field: Option<&Field>) -> (&str, &str) {
+        ///     match field {
+        ///         Some(Field::Field {
+        ///             ty,
+        ///             resolved_ty: Some(resolved_ty),
+        ///             ..
+        ///         }) => (ty, resolved_ty),
+        ///         _ => panic!("expected a field with a resolved type"),
+        ///     }
+        /// }
+        ///
+        /// let ints = Wrapper::<i32>::type_layout();
+        /// assert_eq!(types(ints.field("items")), ("Vec<T>", "alloc::vec::Vec<i32>"));
+        ///
+        /// let bytes = Wrapper::<u8>::type_layout();
+        /// assert_eq!(types(bytes.field("items")), ("Vec<T>", "alloc::vec::Vec<u8>"));
+        /// ```
+        #[cfg_attr(feature = "serde1", serde(default))]
+        resolved_ty: Option<Cow<'static, str>>,
+
+        /// The field's [`TypeId`], if its type is `'static`. See
+        /// [`TypeLayoutInfo::fields_of`].
+        ///
+        /// The derive fills this in when the field's type doesn't mention any
+        /// generic parameters or lifetimes other than `'static`. Fields with
+        /// other types can opt in with `#[layout(type_id)]`, which requires
+        /// their type to be `'static`.
+        ///
+        /// This isn't serialized, so a layout read back with Serde has `None`
+        /// here and won't compare equal to a layout that has a `TypeId`.
+        #[cfg_attr(feature = "serde1", serde(skip))]
+        type_id: Option<TypeId>,
+
+        offset: usize,
         size: usize,
+
+        /// The alignment of the field's type, if it's known.
+        #[cfg_attr(feature = "serde1", serde(default))]
+        alignment: Option<usize>,
+
+        /// Set when the field is an array, describing its elements.
+        array: Option<ArrayInfo>,
+
+        /// Whether dropping this field runs any code, as reported by
+        /// [`std::mem::needs_drop`]. These fields are marked with `*` in the
+        /// rendered table.
+        needs_drop: bool,
+
+        /// The size of `Option` of this field's type, if it's known.
+        #[cfg_attr(feature = "serde1", serde(default))]
+        option_size: Option<usize>,
+
+        /// The layout of this field's type, for fields marked with
+        /// `#[layout(nested)]`. Its offsets are from the start of the field.
+        /// Nested fields are rendered indented below the field.
+        ///
+        /// For array fields, this is the layout of the element type, which
+        /// describes the first element. The other elements have the same
+        /// layout, `stride` bytes apart, so they aren't repeated.
+        ///
+        /// ```rust
+        /// use type_layout::TypeLayout;
+        ///
+        /// #[derive(TypeLayout)]
+        /// #[repr(C)]
+        /// struct Point {
+        ///     x: u16,
+        ///     y: u32,
+        /// }
+        ///
+        /// #[derive(TypeLayout)]
+        /// #[repr(C)]
+        /// struct Line {
+        ///     id: u8,
+        ///     #[layout(nested)]
+        ///     start: Point,
+        ///     #[layout(nested)]
+        ///     end: Point,
+        /// }
+        ///
+        /// println!("{}", Line::type_layout());
+        /// // prints:
+        /// // Line (size 20, alignment 4)
+        /// // | Offset | Name        | Size |
+        /// // | ------ | ----------- | ---- |
+        /// // | 0      | id          | 1    |
+        /// // | 1      | [padding]   | 3    |
+        /// // | 4      | start       | 8    |
+        /// // | 4      |   x         | 2    |
+        /// // | 6      |   [padding] | 2    |
+        /// // | 8      |   y         | 4    |
+        /// // | 12     | end         | 8    |
+        /// // | 12     |   x         | 2    |
+        /// // | 14     |   [padding] | 2    |
+        /// // | 16     |   y         | 4    |
+        /// // Option<Line> is 24 bytes (no niche)
+        /// ```
+        #[cfg_attr(feature = "serde1", serde(default))]
+        nested: Option<Box<TypeLayoutInfo>>,
+
+        /// Whether the field is marked with `#[layout(contended)]`, meaning
+        /// that it's written to often, possibly from several threads. See
+        /// [`CacheLineReport::false_sharing`].
+        #[cfg_attr(feature = "serde1", serde(default))]
+        contended: bool,
+
+        /// Whether the field is an `Option` that stores whether it's `Some` in
+        /// a niche of its payload, like `Option<&T>` or `Option<NonZeroU32>`,
+        /// so that it takes no more space than the payload. These fields are
+        /// marked with `(niche)` in the rendered table.
+        ///
+        /// ```rust
+        /// use std::num::NonZeroU32;
+        /// use type_layout::{Field, TypeLayout};
+        ///
+        /// #[derive(TypeLayout)]
+        /// #[repr(C)]
+        /// struct Handles {
+        ///     id: Option<NonZeroU32>,
+        ///     count: Option<u32>,
+        /// }
+        ///
+        /// let layout = Handles::type_layout();
+        /// let niche = |name| match layout.field(name) {
+        ///     Some(Field::Field { niche, .. }) => *niche,
+        ///     _ => panic!("no field named {}", name),
+        /// };
+        /// assert!(niche("id"));
+        /// assert!(!niche("count"));
+        ///
+        /// println!("{}", layout);
+        /// // prints:
+        /// // Handles (size 12, alignment 4)
+        /// // | Offset | Name       | Size |
+        /// // | ------ | ---------- | ---- |
+        /// // | 0      | id (niche) | 4    |
+        /// // | 4      | count      | 8    |
+        /// // (niche) the Option's tag is stored in its payload, using no extra space
+        /// // Option<Handles> is 12 bytes (has a niche)
+        /// ```
+        #[cfg_attr(feature = "serde1", serde(default))]
+        niche: bool,
     },
     Padding {
+        offset: usize,
         size: usize,
+
+        #[cfg_attr(feature = "serde1", serde(default))]
+        kind: PaddingKind,
     },
 }
 
-impl fmt::Display for TypeLayoutInfo {
-    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        writeln!(
-            formatter,
-            "{} (size {}, alignment {})",
-            self.name, self.size, self.alignment
-        )?;
+/// The options given in a type's `#[repr(...)]` attribute. Types without one
+/// have the default representation, whose layout the compiler is free to
+/// choose.
+///
+/// ```rust
+/// use type_layout::TypeLayout;
+///
+/// #[derive(TypeLayout)]
+/// #[repr(C, align(16))]
+/// struct Vector {
+///     x: f32,
+///     y: f32,
+///     z: f32,
+/// }
+///
+/// let repr = Vector::type_layout().repr;
+/// assert!(repr.c);
+/// assert_eq!(repr.align, Some(16));
+/// assert_eq!(repr.packed, None);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Repr {
+    /// Whether the type is `#[repr(C)]`.
+    #[cfg_attr(feature = "serde1", serde(default))]
+    pub c: bool,
 
-        let longest_name = self
-            .fields
-            .iter()
-            .map(|field| match field {
-                Field::Field { name, .. } => name.len(),
-                Field::Padding { .. } => "[padding]".len(),
-            })
-            .max()
-            .unwrap_or(1);
-
-        let widths = RowWidths {
-            offset: "Offset".len(),
-            name: longest_name,
-            size: "Size".len(),
-        };
-
-        write_row(
-            formatter,
-            widths,
-            Row {
-                offset: "Offset",
-                name: "Name",
-                size: "Size",
-            },
-        )?;
-
-        write_row(
-            formatter,
-            widths,
-            Row {
-                offset: "------",
-                name: str::repeat("-", longest_name),
-                size: "----",
-            },
-        )?;
-
-        let mut offset = 0;
-
-        for field in &self.fields {
-            match field {
-                Field::Field { name, size, .. } => {
-                    write_row(formatter, widths, Row { offset, name, size })?;
-
-                    offset += size;
-                }
-                Field::Padding { size } => {
-                    write_row(
-                        formatter,
-                        widths,
-                        Row {
-                            offset,
-                            name: "[padding]",
-                            size,
-                        },
-                    )?;
-
-                    offset += size;
-                }
-            }
+    /// Whether the type is `#[repr(transparent)]`.
+    #[cfg_attr(feature = "serde1", serde(default))]
+    pub transparent: bool,
+
+    /// The primitive representation of an enum, like `u8` in `#[repr(u8)]`.
+    #[cfg_attr(feature = "serde1", serde(default))]
+    pub int: Option<Cow<'static, str>>,
+
+    /// The `N` in `#[repr(packed(N))]`, which is 1 for `#[repr(packed)]`.
+    ///
+    /// The fields of a packed type are reported with their alignment lowered
+    /// to at most `N`, since that's all the type guarantees them. Offsets are
+    /// found without creating references to fields, so deriving `TypeLayout`
+    /// on a packed type is sound.
+    ///
+    /// ```rust
+    /// use type_layout::TypeLayout;
+    ///
+    /// #[derive(TypeLayout)]
+    /// #[repr(C, packed)]
+    /// struct Wire {
+    ///     kind: u8,
+    ///     len: u32,
+    /// }
+    ///
+    /// let layout = Wire::type_layout();
+    /// assert_eq!(layout.repr.packed, Some(1));
+    /// assert_eq!(layout.size, 5);
+    ///
+    /// let len = layout.field("len").unwrap();
+    /// assert_eq!((len.offset(), len.alignment()), (1, Some(1)));
+    /// ```
+    #[cfg_attr(feature = "serde1", serde(default))]
+    pub packed: Option<usize>,
+
+    /// The `N` in `#[repr(align(N))]`.
+    #[cfg_attr(feature = "serde1", serde(default))]
+    pub align: Option<usize>,
+}
+
+/// Where padding falls within a type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum PaddingKind {
+    /// Padding before a field, which aligns the field.
+    Interior,
+
+    /// Padding after the last field, which rounds the type's size up to a
+    /// multiple of its alignment. This is shown as `[tail padding]`.
+    ///
+    /// Unlike interior padding, tail padding isn't always copied along with
+    /// the type. C code that writes `sizeof(T)` bytes covers it, but Rust may
+    /// store other data in it when the type is a field of another type, so
+    /// FFI code shouldn't expect it to be preserved.
+    Trailing,
+}
+
+impl Default for PaddingKind {
+    fn default() -> Self {
+        PaddingKind::Interior
+    }
+}
+
+/// The elements of an array field.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ArrayInfo {
+    pub elem_ty: Cow<'static, str>,
+    pub len: usize,
+
+    /// The distance between the start of each element, in bytes. This is the
+    /// size of the element type.
+    pub stride: usize,
+}
+
+/// A dynamically sized field at the end of a struct. Structs like this can't
+/// be created directly, but are common as headers of variable-length data.
+///
+/// ```rust
+/// use type_layout::TypeLayout;
+///
+/// #[derive(TypeLayout)]
+/// #[repr(C)]
+/// struct Packet {
+///     kind: u8,
+///     len: u16,
+///     data: [u32],
+/// }
+///
+/// let layout = Packet::type_layout();
+/// let tail = layout.unsized_tail.as_ref().unwrap();
+/// assert_eq!((tail.offset, tail.stride), (4, Some(4)));
+/// assert_eq!(layout.size, 4);
+///
+/// println!("{}", layout);
+/// // prints:
+/// // Packet (size 4, alignment 4)
+/// // | Offset | Name                   | Size    |
+/// // | ------ | ---------------------- | ------- |
+/// // | 0      | kind                   | 1       |
+/// // | 1      | [padding]              | 1       |
+/// // | 2      | len                    | 2       |
+/// // | 4      | data: [u32] (stride 4) | unsized |
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct UnsizedTail {
+    pub name: Cow<'static, str>,
+
+    /// The type of the field as written in the source code.
+    pub ty: Cow<'static, str>,
+
+    /// The offset of the field. A trait object is placed at this offset
+    /// rounded up to the alignment of the concrete type behind it, so for
+    /// those this is the lowest offset it can have.
+    pub offset: usize,
+
+    /// The distance between the start of each element of a `[T]` or `str`
+    /// field, in bytes. This is `None` for trait objects, whose size depends
+    /// on the concrete type.
+    pub stride: Option<usize>,
+}
+
+impl TypeLayoutInfo {
+    /// The name of the type along with its generic parameters, like
+    /// `Buffer<u64, 4>`. This is the same as `name` for types that aren't
+    /// generic.
+    ///
+    /// ```rust
+    /// use type_layout::TypeLayout;
+    ///
+    /// #[derive(TypeLayout)]
+    /// struct Buffer<'a, T, const N: usize> {
+    ///     items: [T; N],
+    ///     label: &'a str,
+    /// }
+    ///
+    /// let layout = Buffer::<u64, 4>::type_layout();
+    /// assert_eq!(layout.name, "Buffer");
+    /// assert_eq!(layout.generics, ["u64", "4"]);
+    /// assert_eq!(layout.full_name(), "Buffer<u64, 4>");
+    /// assert!(layout.to_string().starts_with("Buffer<u64, 4> (size"));
+    /// ```
+    pub fn full_name(&self) -> Cow<'_, str> {
+        if self.generics.is_empty() {
+            Cow::Borrowed(&self.name)
+        } else {
+            Cow::Owned(format!("{}<{}>", self.name, self.generics.join(", ")))
+        }
+    }
+
+    /// Whether `Option<Self>` is the same size as this type, which means that
+    /// the type has a niche. Returns `None` if the size of `Option<Self>`
+    /// isn't known.
+    ///
+    /// ```rust
+    /// use std::num::NonZeroU32;
+    /// use type_layout::TypeLayout;
+    ///
+    /// #[derive(TypeLayout)]
+    /// struct Handle<'a> {
+    ///     owner: &'a str,
+    ///     id: NonZeroU32,
+    ///     generation: u32,
+    /// }
+    ///
+    /// #[derive(TypeLayout)]
+    /// struct Plain {
+    ///     id: u32,
+    /// }
+    ///
+    /// let handle = Handle::type_layout();
+    /// assert_eq!(handle.has_niche(), Some(true));
+    /// assert_eq!(handle.field("owner").unwrap().has_niche(), Some(true));
+    /// assert_eq!(handle.field("id").unwrap().has_niche(), Some(true));
+    /// assert_eq!(handle.field("generation").unwrap().has_niche(), Some(false));
+    ///
+    /// let plain = Plain::type_layout();
+    /// assert_eq!(plain.has_niche(), Some(false));
+    /// assert_eq!(plain.option_size, Some(8));
+    /// ```
+    pub fn has_niche(&self) -> Option<bool> {
+        self.option_size.map(|option_size| option_size == self.size)
+    }
+
+    /// Iterates over the fields whose type is `T`, matched by [`TypeId`] so
+    /// that type aliases and differently written paths are found too.
+    ///
+    /// ```rust
+    /// use std::time::Instant;
+    /// use type_layout::{Field, TypeLayout};
+    ///
+    /// type Timestamp = Instant;
+    ///
+    /// #[derive(TypeLayout)]
+    /// struct Request<'a> {
+    ///     started: Instant,
+    ///     finished: Timestamp,
+    ///     deadline: std::time::Instant,
+    ///     path: &'a str,
+    ///     id: u64,
+    /// }
+    ///
+    /// let layout = Request::type_layout();
+    /// let names: Vec<_> = layout
+    ///     .fields_of::<Instant>()
+    ///     .map(|field| match field {
+    ///         Field::Field { name, .. } => name.as_ref(),
+    ///         Field::Padding { .. } => unreachable!(),
+    ///     })
+    ///     .collect();
+    ///
+    /// assert_eq!(names, ["started", "finished", "deadline"]);
+    ///
+    /// // `&'a str` isn't `'static`, so it has no `TypeId`.
+    /// match layout.field("path") {
+    ///     Some(Field::Field { type_id, .. }) => assert!(type_id.is_none()),
+    ///     _ => unreachable!(),
+    /// }
+    /// ```
+    ///
+    /// Generic fields opt in with `#[layout(type_id)]`, which makes the
+    /// `TypeLayout` implementation require that their type is `'static`:
+    ///
+    /// ```rust
+    /// use type_layout::TypeLayout;
+    ///
+    /// #[derive(TypeLayout)]
+    /// struct Slot<T> {
+    ///     #[layout(type_id)]
+    ///     value: T,
+    ///     untracked: Option<T>,
+    /// }
+    ///
+    /// let layout = Slot::<u32>::type_layout();
+    /// assert_eq!(layout.fields_of::<u32>().count(), 1);
+    /// assert_eq!(layout.fields_of::<Option<u32>>().count(), 0);
+    /// ```
+    pub fn fields_of<T: ?Sized + 'static>(&self) -> impl Iterator<Item = &Field> {
+        let wanted = TypeId::of::<T>();
+
+        self.fields.iter().filter(move |field| match field {
+            Field::Field { type_id, .. } => *type_id == Some(wanted),
+            Field::Padding { .. } => false,
+        })
+    }
+
+    /// Finds the field with the given name, if there is one.
+    pub fn field(&self, name: &str) -> Option<&Field> {
+        find_field(&self.fields, name)
+    }
+
+    /// The offset of the field with the given name, if there is one.
+    ///
+    /// ```rust
+    /// use type_layout::TypeLayout;
+    ///
+    /// #[derive(TypeLayout)]
+    /// #[repr(C)]
+    /// struct Header {
+    ///     kind: u8,
+    ///     len: u32,
+    /// }
+    ///
+    /// let layout = Header::type_layout();
+    /// assert_eq!(layout.offset_of("len"), Some(4));
+    /// assert_eq!(layout.size_of_field("len"), Some(4));
+    /// assert_eq!(layout.offset_of("checksum"), None);
+    /// ```
+    pub fn offset_of(&self, name: &str) -> Option<usize> {
+        self.field(name).map(Field::offset)
+    }
+
+    /// The size of the field with the given name, if there is one.
+    pub fn size_of_field(&self, name: &str) -> Option<usize> {
+        self.field(name).map(Field::size)
+    }
+
+    /// Finds the variant with the given name, if this is an enum that has one.
+    pub fn variant(&self, name: &str) -> Option<&Variant> {
+        self.variants.iter().find(|variant| variant.name == name)
+    }
+
+    /// Iterates over the fields of a struct, or of every variant of an enum.
+    fn all_fields(&self) -> impl Iterator<Item = &Field> {
+        self.fields.iter().chain(
+            self.variants
+                .iter()
+                .flat_map(|variant| variant.fields.iter()),
+        )
+    }
+}
+
+impl Field {
+    /// The offset of this field or padding from the start of the type, in
+    /// bytes.
+    pub fn offset(&self) -> usize {
+        match self {
+            Field::Field { offset, .. } | Field::Padding { offset, .. } => *offset,
+        }
+    }
+
+    /// The size of this field or padding, in bytes.
+    pub fn size(&self) -> usize {
+        match self {
+            Field::Field { size, .. } | Field::Padding { size, .. } => *size,
+        }
+    }
+
+    /// The alignment of this field's type, if it's known. Returns `None` for
+    /// padding.
+    pub fn alignment(&self) -> Option<usize> {
+        match self {
+            Field::Field { alignment, .. } => *alignment,
+            Field::Padding { .. } => None,
+        }
+    }
+
+    pub fn is_padding(&self) -> bool {
+        matches!(self, Field::Padding { .. })
+    }
+
+    /// Whether `Option` of this field's type is the same size as the field.
+    /// Returns `None` for padding, or if the size of the `Option` isn't known.
+    pub fn has_niche(&self) -> Option<bool> {
+        match self {
+            Field::Field {
+                size, option_size, ..
+            } => option_size.map(|option_size| option_size == *size),
+            Field::Padding { .. } => None,
         }
+    }
+}
 
-        Ok(())
+/// The prefix of the names of the fields nested in a field called `name`,
+/// like `outer.`. The nested layout of an array describes its first element,
+/// so its fields are named like `outer[0].`.
+pub(crate) fn nested_prefix(name: &str, array: &Option<ArrayInfo>) -> String {
+    match array {
+        Some(_) => format!("{}[0].", name),
+        None => format!("{}.", name),
     }
 }
 
+fn find_field<'a>(fields: &'a [Field], name: &str) -> Option<&'a Field> {
+    fields.iter().find(|field| match field {
+        Field::Field {
+            name: field_name, ..
+        } => field_name == name,
+        Field::Padding { .. } => false,
+    })
+}
+
+/// One entry of the table of fields that `#[derive(TypeLayout)]` generates.
+/// Everything that only depends on the field's type is in `info`, so that the
+/// code generated for each field stays small, and [`__fields`] turns the table
+/// into `Field`s.
+#[doc(hidden)]
+pub struct __FieldDesc {
+    pub name: &'static str,
+    pub ty: Cow<'static, str>,
+    pub offset: usize,
+    pub info: __TypeInfo,
+    pub type_id: Option<TypeId>,
+    pub array: Option<ArrayInfo>,
+    pub nested: Option<fn() -> TypeLayoutInfo>,
+    pub contended: bool,
+}
+
+/// The details of a field that only depend on its type.
+#[doc(hidden)]
 #[derive(Clone, Copy)]
-struct RowWidths {
-    offset: usize,
-    name: usize,
+pub struct __TypeInfo {
+    resolved_ty: Option<&'static str>,
     size: usize,
+    alignment: usize,
+    needs_drop: bool,
+    option_size: usize,
+    niche: bool,
+}
+
+impl __TypeInfo {
+    pub fn of<T>() -> Self {
+        Self {
+            resolved_ty: Some(core::any::type_name::<T>()),
+            size: core::mem::size_of::<T>(),
+            alignment: core::mem::align_of::<T>(),
+            needs_drop: core::mem::needs_drop::<T>(),
+            option_size: core::mem::size_of::<Option<T>>(),
+            niche: false,
+        }
+    }
+
+    /// Marks an `Option` field whose payload has the type `T` as using a
+    /// niche, if it's no larger than `T`.
+    pub fn option_of<T>(mut self) -> Self {
+        self.niche = self.size == core::mem::size_of::<T>();
+        self
+    }
+
+    pub fn packed(mut self, packed: usize) -> Self {
+        self.alignment = self.alignment.min(packed);
+        self
+    }
+
+    /// Hides the type's name, for fields whose type is skipped or renamed.
+    pub fn opaque(mut self) -> Self {
+        self.resolved_ty = None;
+        self
+    }
+}
+
+#[doc(hidden)]
+pub fn __fields(table: Vec<__FieldDesc>) -> Vec<Field> {
+    table
+        .into_iter()
+        .map(|desc| Field::Field {
+            name: Cow::Borrowed(desc.name),
+            ty: desc.ty,
+            resolved_ty: desc.info.resolved_ty.map(Cow::Borrowed),
+            type_id: desc.type_id,
+            offset: desc.offset,
+            size: desc.info.size,
+            alignment: Some(desc.info.alignment),
+            array: desc.array,
+            needs_drop: desc.info.needs_drop,
+            option_size: Some(desc.info.option_size),
+            nested: desc.nested.map(|type_layout| Box::new(type_layout())),
+            contended: desc.contended,
+            niche: desc.info.niche,
+        })
+        .collect()
+}
+
+/// Describes the tag of an enum, whose type `T` is a fieldless enum with the
+/// same representation.
+#[doc(hidden)]
+pub fn __tag_field<T>(ty: &'static str) -> Field {
+    Field::Field {
+        name: Cow::Borrowed("[tag]"),
+        ty: Cow::Borrowed(ty),
+        resolved_ty: None,
+        type_id: None,
+        offset: 0,
+        size: core::mem::size_of::<T>(),
+        alignment: Some(core::mem::align_of::<T>()),
+        array: None,
+        needs_drop: false,
+        option_size: None,
+        nested: None,
+        contended: false,
+        niche: false,
+    }
 }
 
-struct Row<O, N, S> {
-    offset: O,
-    name: N,
-    size: S,
-}
-
-fn write_row<O: Display, N: Display, S: Display>(
-    formatter: &mut fmt::Formatter,
-    widths: RowWidths,
-    row: Row<O, N, S>,
-) -> fmt::Result {
-    writeln!(
-        formatter,
-        "| {:<offset_width$} | {:<name_width$} | {:<size_width$} |",
-        row.offset,
-        row.name,
-        row.size,
-        offset_width = widths.offset,
-        name_width = widths.name,
-        size_width = widths.size
-    )
+/// Lays out one variant of a `#[repr(C)]` or `#[repr(u8)]`-style enum. The
+/// variant's fields are placed one after the other starting at
+/// `payload_start`, the same way `#[repr(C)]` places the fields of a struct.
+#[doc(hidden)]
+pub fn __layout_variant(
+    name: &'static str,
+    discriminant: i128,
+    tag: Field,
+    payload_start: usize,
+    enum_size: usize,
+    fields: Vec<Field>,
+) -> Variant {
+    let mut placed = Vec::with_capacity(fields.len() + 1);
+    placed.push(tag);
+
+    let mut end = payload_start;
+    for mut field in fields {
+        let alignment = field.alignment().unwrap_or(1);
+        let offset = (end + alignment - 1) / alignment * alignment;
+        if let Field::Field {
+            offset: field_offset,
+            ..
+        } = &mut field
+        {
+            *field_offset = offset;
+        }
+
+        end = offset + field.size();
+        placed.push(field);
+    }
+
+    Variant {
+        name: Cow::Borrowed(name),
+        discriminant,
+        fields: __insert_padding(placed, enum_size),
+    }
+}
+
+/// Sorts fields by offset and fills the gaps between them, and between the
+/// last field and the end of the type, with padding.
+#[doc(hidden)]
+pub fn __insert_padding(mut fields: Vec<Field>, size: usize) -> Vec<Field> {
+    fields.sort_by_key(Field::offset);
+
+    let mut padded = Vec::with_capacity(fields.len());
+    let mut last_field_end = 0;
+
+    for field in fields {
+        if field.offset() > last_field_end {
+            padded.push(Field::Padding {
+                offset: last_field_end,
+                size: field.offset() - last_field_end,
+                kind: PaddingKind::Interior,
+            });
+        }
+
+        // Zero-sized fields can share an offset with, or sit inside of, the
+        // field before them, so the end can't be allowed to move backwards.
+        last_field_end = last_field_end.max(field.offset() + field.size());
+        padded.push(field);
+    }
+
+    if size > last_field_end {
+        padded.push(Field::Padding {
+            offset: last_field_end,
+            size: size - last_field_end,
+            kind: PaddingKind::Trailing,
+        });
+    }
+
+    padded
+}
+
+/// Like [`__insert_padding`], for the sized fields of a struct with an unsized
+/// tail. Padding before the tail is between fields, not after them.
+#[doc(hidden)]
+pub fn __unsized_fields(fields: Vec<Field>, tail_offset: usize) -> Vec<Field> {
+    let mut fields = __insert_padding(fields, tail_offset);
+
+    if let Some(Field::Padding { kind, .. }) = fields.last_mut() {
+        *kind = PaddingKind::Interior;
+    }
+
+    fields
+}
+
+/// An allocation to find field offsets in, for structs with an unsized tail.
+/// Those can't be placed in a `MaybeUninit` like `offset_of!` does for sized
+/// types, so instead a pointer to one with an empty tail is made from a
+/// pointer to an empty slice, which has the same kind of metadata.
+#[doc(hidden)]
+pub struct __UnsizedBase {
+    ptr: *mut u8,
+    layout: alloc::alloc::Layout,
+}
+
+impl __UnsizedBase {
+    /// Allocates `size` bytes, which must be enough to hold the struct with
+    /// an empty tail.
+    pub fn new(size: usize, alignment: usize) -> Self {
+        let layout = alloc::alloc::Layout::from_size_align(size.max(1), alignment)
+            .expect("type-layout computed an invalid layout for an unsized struct");
+
+        // SAFETY: The layout has a non-zero size.
+        let ptr = unsafe { alloc::alloc::alloc_zeroed(layout) };
+        if ptr.is_null() {
+            alloc::alloc::handle_alloc_error(layout);
+        }
+
+        Self { ptr, layout }
+    }
+
+    /// A pointer to an empty slice at the start of the allocation, to be cast
+    /// to a pointer to the struct.
+    pub fn empty_tail(&self) -> *const [()] {
+        core::ptr::slice_from_raw_parts(self.ptr as *const (), 0)
+    }
+
+    /// The offset of a pointer into the allocation from its start.
+    pub fn offset_of<T: ?Sized>(&self, field: *const T) -> usize {
+        field as *const u8 as usize - self.ptr as usize
+    }
+}
+
+impl Drop for __UnsizedBase {
+    fn drop(&mut self) {
+        // SAFETY: `ptr` was allocated in `new` with this layout.
+        unsafe { alloc::alloc::dealloc(self.ptr, self.layout) }
+    }
+}
+
+/// Finds the offset of a field of a struct with an unsized tail, given the
+/// `__UnsizedBase` the pointer to the struct was made from.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __unsized_offset_of {
+    ($base:expr, $ptr:expr, $field:tt) => {
+        // SAFETY: The pointer points into an allocation that's large enough
+        // for the struct with an empty tail, and `addr_of!` doesn't read the
+        // field.
+        $base.offset_of(unsafe { ::core::ptr::addr_of!((*$ptr).$field) })
+    };
+}
+
+impl TypeLayoutInfo {
+    /// Renders the layout table, the same as `Display` does, into any
+    /// [`fmt::Write`].
+    pub fn write_text<W: fmt::Write + ?Sized>(&self, writer: &mut W) -> fmt::Result {
+        self.fmt_to(writer, &TableOptions::new())
+    }
+
+    /// Renders the layout table with the given options into any
+    /// [`fmt::Write`], like a `String` or a `Formatter`, without building the
+    /// table in a `String` of its own first.
+    pub fn fmt_to<W: fmt::Write + ?Sized>(
+        &self,
+        writer: &mut W,
+        options: &TableOptions,
+    ) -> fmt::Result {
+        self.write_table(writer, options)
+    }
+
+    /// Renders the layout table, the same as `Display` does, into any
+    /// [`io::Write`].
+    ///
+    /// If the writer fails, rendering stops and its error is returned.
+    ///
+    /// This method is only available with the `std` feature.
+    #[cfg(feature = "std")]
+    pub fn write_io<W: io::Write + ?Sized>(&self, writer: &mut W) -> io::Result<()> {
+        self.write_to(writer, &TableOptions::new())
+    }
+
+    /// Renders the layout table with the given options into any
+    /// [`io::Write`], like a file or a log sink, as it's formatted.
+    ///
+    /// If the writer fails, rendering stops and its error is returned.
+    ///
+    /// ```rust
+    /// use type_layout::{TableOptions, TypeLayout};
+    ///
+    /// #[derive(TypeLayout)]
+    /// #[repr(C)]
+    /// struct Header {
+    ///     kind: u8,
+    ///     len: u32,
+    /// }
+    ///
+    /// let mut out = Vec::new();
+    /// let options = TableOptions::new().types(true);
+    /// Header::type_layout().write_to(&mut out, &options).unwrap();
+    ///
+    /// let text = String::from_utf8(out).unwrap();
+    /// assert!(text.contains("| 4      | len       | u32  | 4    |"));
+    /// ```
+    ///
+    /// This method is only available with the `std` feature.
+    #[cfg(feature = "std")]
+    pub fn write_to<W: io::Write + ?Sized>(
+        &self,
+        writer: &mut W,
+        options: &TableOptions,
+    ) -> io::Result<()> {
+        let mut adapter = WriteFmtAdapter::new(writer);
+        let result = self.fmt_to(&mut adapter, options);
+        adapter.finish(result)
+    }
+}
+
+impl fmt::Display for TypeLayoutInfo {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.write_text(formatter)
+    }
+}
+
+/// Adapts an [`io::Write`] into a [`fmt::Write`], keeping the underlying I/O
+/// error so that it isn't lost to [`fmt::Error`].
+///
+/// ```rust
+/// use std::fmt::Write;
+/// use type_layout::WriteFmtAdapter;
+///
+/// let mut buffer = Vec::new();
+/// let mut adapter = WriteFmtAdapter::new(&mut buffer);
+/// let result = write!(adapter, "{} + {}", 1, 2);
+/// adapter.finish(result).unwrap();
+///
+/// assert_eq!(buffer, b"1 + 2");
+/// ```
+///
+/// This type is only available with the `std` feature.
+#[cfg(feature = "std")]
+pub struct WriteFmtAdapter<'a, W: io::Write + ?Sized> {
+    writer: &'a mut W,
+    error: Option<io::Error>,
+}
+
+#[cfg(feature = "std")]
+impl<'a, W: io::Write + ?Sized> WriteFmtAdapter<'a, W> {
+    pub fn new(writer: &'a mut W) -> Self {
+        Self {
+            writer,
+            error: None,
+        }
+    }
+
+    /// Converts the result of formatting into this adapter into an
+    /// [`io::Result`], recovering the I/O error that caused it to fail.
+    pub fn finish(self, result: fmt::Result) -> io::Result<()> {
+        match (result, self.error) {
+            (_, Some(err)) => Err(err),
+            (Ok(()), None) => Ok(()),
+            (Err(fmt::Error), None) => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "formatter returned an error",
+            )),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: io::Write + ?Sized> fmt::Write for WriteFmtAdapter<'_, W> {
+    fn write_str(&mut self, text: &str) -> fmt::Result {
+        // Once a write has failed, later writes would produce output with a
+        // gap in it, so refuse them.
+        if self.error.is_some() {
+            return Err(fmt::Error);
+        }
+
+        self.writer.write_all(text.as_bytes()).map_err(|err| {
+            self.error = Some(err);
+            fmt::Error
+        })
+    }
 }
@@ -0,0 +1,465 @@
+//! Lock files that record the expected layouts of types, so that tests fail
+//! when a layout changes unexpectedly.
+//!
+//! This module is only available with the `serde1` feature. A lock file is a
+//! pretty-printed JSON document that is meant to be checked into version
+//! control next to the tests that verify it.
+//!
+//! The first time [`verify`] runs against a path that doesn't exist, it
+//! records the given layouts and succeeds. After that, it compares against the
+//! recorded layouts. To accept a change, run the tests again with the
+//! `TYPE_LAYOUT_UPDATE` environment variable set to `1`. With the `registry`
+//! feature, [`verify_registered`] checks every type that derives `TypeLayout`
+//! at once.
+//!
+//! ```rust
+//! use type_layout::{lockfile, TypeLayout};
+//!
+//! #[derive(TypeLayout)]
+//! #[repr(C)]
+//! struct Header {
+//!     kind: u8,
+//!     len: u32,
+//! }
+//!
+//! # let dir = std::env::temp_dir().join(format!("type-layout-doctest-{}", std::process::id()));
+//! # std::fs::create_dir_all(&dir).unwrap();
+//! # let path = dir.join("layout.lock");
+//! # std::fs::remove_file(&path).ok();
+//! lockfile::verify(&path, &[Header::type_layout()]).unwrap();
+//! # std::fs::remove_dir_all(&dir).unwrap();
+//! ```
+
+use alloc::borrow::Cow;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use std::error::Error;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::assert::{diff_lines, normalize_lines, update_requested};
+use crate::TypeLayoutInfo;
+
+/// When this environment variable is set to anything other than `0`,
+/// [`verify`] records the given layouts instead of comparing against them.
+/// [`assert_layout_snapshot!`](crate::assert_layout_snapshot) also uses it to
+/// update snapshot files.
+pub const UPDATE_ENV_VAR: &str = crate::assert::UPDATE_ENV_VAR;
+
+const VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct LockFile {
+    version: u32,
+    layouts: Vec<TypeLayoutInfo>,
+}
+
+/// Compares `layouts` against the layouts recorded in the lock file at `path`.
+///
+/// Types are matched by name. Every type that was added, removed, or whose
+/// layout changed is reported. If the lock file doesn't exist yet, or the
+/// [`UPDATE_ENV_VAR`] environment variable is set, the layouts are recorded
+/// with [`record`] instead.
+///
+/// ```rust
+/// use type_layout::{lockfile, TypeLayout};
+///
+/// #[derive(TypeLayout)]
+/// #[repr(C)]
+/// struct Header {
+///     kind: u8,
+///     len: u32,
+/// }
+///
+/// # let dir = std::env::temp_dir().join(format!("type-layout-verify-{}", std::process::id()));
+/// # std::fs::create_dir_all(&dir).unwrap();
+/// # let path = dir.join("layout.lock");
+/// lockfile::record(&path, &[Header::type_layout()]).unwrap();
+///
+/// let mut grown = Header::type_layout();
+/// grown.size = 12;
+///
+/// let err = lockfile::verify(&path, &[grown]).unwrap_err();
+/// assert!(err.to_string().contains("+ Header (size 12, alignment 4)"));
+/// # std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+pub fn verify<P: AsRef<Path>>(path: P, layouts: &[TypeLayoutInfo]) -> Result<(), LockfileError> {
+    let path = path.as_ref();
+
+    if update_requested() || !path.exists() {
+        return record(path, layouts);
+    }
+
+    let recorded = read(path)?;
+    let mut diffs = Vec::new();
+
+    for layout in layouts {
+        match recorded
+            .iter()
+            .find(|old| old.full_name() == layout.full_name())
+        {
+            Some(old) => {
+                if !same_layout(old, layout)? {
+                    diffs.push(LayoutDiffReport::Changed {
+                        expected: old.clone(),
+                        actual: layout.clone(),
+                    });
+                }
+            }
+            None => diffs.push(LayoutDiffReport::Added(layout.clone())),
+        }
+    }
+
+    for old in &recorded {
+        if !layouts
+            .iter()
+            .any(|layout| layout.full_name() == old.full_name())
+        {
+            diffs.push(LayoutDiffReport::Removed(old.clone()));
+        }
+    }
+
+    if diffs.is_empty() {
+        Ok(())
+    } else {
+        Err(LockfileError::Mismatch(diffs))
+    }
+}
+
+/// Verifies the layout of every type in the [`registry`](crate::registry)
+/// against the lock file at `path`, like [`verify`].
+///
+/// This only needs to be called from one test to check every type in the
+/// program that derives `TypeLayout`, which makes it a good fit for catching
+/// accidental ABI breaks in CI. Types are recorded in order of name.
+///
+/// This function is only available with the `registry` feature.
+///
+/// ```rust
+/// use type_layout::{lockfile, TypeLayout};
+///
+/// #[derive(TypeLayout)]
+/// #[repr(C)]
+/// pub struct PluginVTable {
+///     pub version: u32,
+///     pub init: extern "C" fn() -> i32,
+/// }
+///
+/// # let dir = std::env::temp_dir().join(format!("type-layout-registered-{}", std::process::id()));
+/// # std::fs::create_dir_all(&dir).unwrap();
+/// # let path = dir.join("layout.lock");
+/// lockfile::verify_registered(&path).unwrap();
+/// # std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+#[cfg(feature = "registry")]
+pub fn verify_registered<P: AsRef<Path>>(path: P) -> Result<(), LockfileError> {
+    let mut layouts: Vec<TypeLayoutInfo> = crate::registry::iter()
+        .map(|registration| (registration.type_layout)())
+        .collect();
+    layouts.sort_by(|a, b| a.full_name().cmp(&b.full_name()));
+
+    verify(path, &layouts)
+}
+
+/// Writes `layouts` to the lock file at `path`.
+///
+/// Types that are already in the lock file keep their position, and new types
+/// are appended after them, so that changes show up as small diffs. Types that
+/// are in the lock file but not in `layouts` are dropped.
+///
+/// If there's already a file at `path` that can't be read as a lock file,
+/// like one that's corrupt or from an incompatible version of type-layout,
+/// the error is returned and the file is left alone. Delete it to record a
+/// new lock file in its place.
+pub fn record<P: AsRef<Path>>(path: P, layouts: &[TypeLayoutInfo]) -> Result<(), LockfileError> {
+    let path = path.as_ref();
+
+    let recorded = match read(path) {
+        Ok(recorded) => recorded,
+        Err(LockfileError::Io(err)) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+        Err(err) => return Err(err),
+    };
+
+    let mut ordered: Vec<TypeLayoutInfo> = recorded
+        .iter()
+        .filter_map(|old| {
+            layouts
+                .iter()
+                .find(|layout| layout.full_name() == old.full_name())
+        })
+        .cloned()
+        .collect();
+
+    for layout in layouts {
+        if !ordered
+            .iter()
+            .any(|existing| existing.full_name() == layout.full_name())
+        {
+            ordered.push(layout.clone());
+        }
+    }
+
+    let file = LockFile {
+        version: VERSION,
+        layouts: ordered,
+    };
+
+    let mut contents = serde_json::to_string_pretty(&file).map_err(LockfileError::Json)?;
+    contents.push('\n');
+
+    fs::write(path, contents).map_err(LockfileError::Io)
+}
+
+fn read(path: &Path) -> Result<Vec<TypeLayoutInfo>, LockfileError> {
+    let contents = fs::read_to_string(path).map_err(LockfileError::Io)?;
+    let file: LockFile = serde_json::from_str(&contents).map_err(LockfileError::Json)?;
+
+    if file.version != VERSION {
+        return Err(LockfileError::UnsupportedVersion(file.version));
+    }
+
+    Ok(file.layouts)
+}
+
+/// Compares layouts as they're recorded, except for their targets, so that a
+/// lock file can be verified on every target that lays the types out the
+/// same way, and lock files from before targets were recorded still match.
+fn same_layout(a: &TypeLayoutInfo, b: &TypeLayoutInfo) -> Result<bool, LockfileError> {
+    let without_target = |layout: &TypeLayoutInfo| {
+        let mut value = serde_json::to_value(layout).map_err(LockfileError::Json)?;
+        if let Some(object) = value.as_object_mut() {
+            object.remove("target");
+        }
+        Ok(value)
+    };
+
+    Ok(without_target(a)? == without_target(b)?)
+}
+
+/// How the layout of one type differs from what was recorded in a lock file.
+// Reports are only made when verification fails, so their size doesn't matter.
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone)]
+pub enum LayoutDiffReport {
+    /// The type isn't in the lock file yet.
+    Added(TypeLayoutInfo),
+
+    /// The type is in the lock file, but wasn't verified.
+    Removed(TypeLayoutInfo),
+
+    /// The type's layout is different from the recorded one.
+    Changed {
+        expected: TypeLayoutInfo,
+        actual: TypeLayoutInfo,
+    },
+}
+
+impl LayoutDiffReport {
+    /// The name of the type this report is about.
+    pub fn name(&self) -> Cow<'_, str> {
+        match self {
+            LayoutDiffReport::Added(layout) | LayoutDiffReport::Removed(layout) => {
+                layout.full_name()
+            }
+            LayoutDiffReport::Changed { actual, .. } => actual.full_name(),
+        }
+    }
+}
+
+impl fmt::Display for LayoutDiffReport {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LayoutDiffReport::Added(layout) => {
+                writeln!(
+                    formatter,
+                    "`{}` is not in the lock file",
+                    layout.full_name()
+                )
+            }
+            LayoutDiffReport::Removed(layout) => {
+                writeln!(
+                    formatter,
+                    "`{}` is in the lock file but was not verified",
+                    layout.full_name()
+                )
+            }
+            LayoutDiffReport::Changed { expected, actual } => {
+                writeln!(formatter, "layout of `{}` changed:", actual.full_name())?;
+
+                let old = expected.to_string();
+                let new = actual.to_string();
+
+                // Some details, like field types, aren't part of the rendered
+                // table. Fall back to diffing the recorded form in that case.
+                let (old, new) = if old == new {
+                    (pretty(expected)?, pretty(actual)?)
+                } else {
+                    (old, new)
+                };
+
+                write!(
+                    formatter,
+                    "{}",
+                    diff_lines(&normalize_lines(&old), &normalize_lines(&new))
+                )
+            }
+        }
+    }
+}
+
+impl fmt::Debug for LayoutDiffReport {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, formatter)
+    }
+}
+
+/// The recorded form of a layout. Failing to serialize it fails formatting,
+/// rather than showing an empty diff.
+fn pretty(layout: &TypeLayoutInfo) -> Result<String, fmt::Error> {
+    serde_json::to_string_pretty(layout).map_err(|_| fmt::Error)
+}
+
+/// An error from reading, writing, or verifying a lock file.
+///
+/// The `Debug` output is the same as the `Display` output, so that unwrapping
+/// the result of [`verify`] in a test prints a readable diff.
+pub enum LockfileError {
+    /// The lock file couldn't be read or written.
+    Io(io::Error),
+
+    /// The lock file isn't valid JSON, or doesn't describe layouts.
+    Json(serde_json::Error),
+
+    /// The lock file was written by an incompatible version of type-layout.
+    UnsupportedVersion(u32),
+
+    /// Some layouts don't match the lock file.
+    Mismatch(Vec<LayoutDiffReport>),
+}
+
+impl fmt::Display for LockfileError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LockfileError::Io(err) => write!(formatter, "could not access lock file: {}", err),
+            LockfileError::Json(err) => write!(formatter, "invalid lock file: {}", err),
+            LockfileError::UnsupportedVersion(version) => {
+                write!(formatter, "lock file has unsupported version {}", version)
+            }
+            LockfileError::Mismatch(diffs) => {
+                writeln!(
+                    formatter,
+                    "{} layout(s) do not match the lock file; set {}=1 to update it",
+                    diffs.len(),
+                    UPDATE_ENV_VAR
+                )?;
+
+                for diff in diffs {
+                    writeln!(formatter)?;
+                    write!(formatter, "{}", diff)?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+impl fmt::Debug for LockfileError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, formatter)
+    }
+}
+
+impl Error for LockfileError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LockfileError::Io(err) => Some(err),
+            LockfileError::Json(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::format;
+
+    use super::*;
+
+    fn layout(name: &'static str) -> TypeLayoutInfo {
+        TypeLayoutInfo::builder(name)
+            .field("a", "u8", 1, 1)
+            .build()
+            .unwrap()
+    }
+
+    fn temp_dir(test: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "type-layout-lockfile-{}-{}",
+            std::process::id(),
+            test
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn record_creates_missing_file() {
+        let dir = temp_dir("missing");
+        let path = dir.join("layout.lock");
+
+        record(&path, &[layout("A")]).unwrap();
+        assert_eq!(read(&path).unwrap().len(), 1);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn record_keeps_order_of_existing_entries() {
+        let dir = temp_dir("order");
+        let path = dir.join("layout.lock");
+
+        record(&path, &[layout("B"), layout("A")]).unwrap();
+        record(&path, &[layout("A"), layout("C"), layout("B")]).unwrap();
+
+        let names: Vec<_> = read(&path)
+            .unwrap()
+            .iter()
+            .map(|layout| layout.full_name().into_owned())
+            .collect();
+        assert_eq!(names, ["B", "A", "C"]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn record_refuses_to_overwrite_corrupt_file() {
+        let dir = temp_dir("corrupt");
+        let path = dir.join("layout.lock");
+        fs::write(&path, "{ not json").unwrap();
+
+        let err = record(&path, &[layout("A")]).unwrap_err();
+        assert!(matches!(err, LockfileError::Json(_)));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{ not json");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn record_refuses_to_overwrite_other_version() {
+        let dir = temp_dir("version");
+        let path = dir.join("layout.lock");
+        let contents = "{\"version\": 999, \"layouts\": []}";
+        fs::write(&path, contents).unwrap();
+
+        let err = record(&path, &[layout("A")]).unwrap_err();
+        assert!(matches!(err, LockfileError::UnsupportedVersion(999)));
+        assert_eq!(fs::read_to_string(&path).unwrap(), contents);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
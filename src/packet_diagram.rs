@@ -0,0 +1,210 @@
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::byte_map::collect_leaves;
+use crate::{Field, TypeLayoutInfo};
+
+/// Bits in each row of the diagram, as in most RFCs.
+const ROW_BITS: usize = 32;
+
+impl TypeLayoutInfo {
+    /// Renders the layout as the packet diagram that RFCs use to describe
+    /// wire formats, with a ruler numbering the bits of each 32-bit row and a
+    /// box for each field. Fields are in the order of their bytes in memory,
+    /// so this is the wire format of a type that's sent as its bytes, like a
+    /// `#[repr(C, packed)]` header with big-endian fields.
+    ///
+    /// Nested fields are shown as their own fields, named like
+    /// `outer.inner`, and padding is shown as `padding`. Names that don't fit
+    /// in their box are cut short. When fields share bytes, like the fields
+    /// of a union, only the first is shown, and each variant of an enum gets
+    /// its own diagram.
+    ///
+    /// ```rust
+    /// use type_layout::TypeLayout;
+    ///
+    /// #[derive(TypeLayout)]
+    /// #[repr(C, packed)]
+    /// struct UdpHeader {
+    ///     source_port: [u8; 2],
+    ///     dest_port: [u8; 2],
+    ///     length: [u8; 2],
+    ///     checksum: [u8; 2],
+    /// }
+    ///
+    /// print!("{}", UdpHeader::type_layout().packet_diagram());
+    /// // prints:
+    /// //  0                   1                   2                   3
+    /// //  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+    /// // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    /// // |          source_port          |           dest_port           |
+    /// // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    /// // |            length             |           checksum            |
+    /// // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    /// ```
+    pub fn packet_diagram(&self) -> PacketDiagram<'_> {
+        PacketDiagram { layout: self }
+    }
+}
+
+/// Renders a layout as an RFC-style packet diagram. Created by
+/// [`TypeLayoutInfo::packet_diagram`].
+pub struct PacketDiagram<'a> {
+    layout: &'a TypeLayoutInfo,
+}
+
+impl fmt::Display for PacketDiagram<'_> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let layout = self.layout;
+
+        if layout.variants.is_empty() {
+            write_diagram(formatter, &layout.fields, layout.size)
+        } else {
+            for variant in &layout.variants {
+                writeln!(
+                    formatter,
+                    "Variant {} = {}",
+                    variant.name, variant.discriminant
+                )?;
+                write_diagram(formatter, &variant.fields, layout.size)?;
+            }
+
+            Ok(())
+        }
+    }
+}
+
+/// Draws the diagram for one set of fields, which is either the fields of a
+/// struct or those of one variant of an enum.
+fn write_diagram(formatter: &mut fmt::Formatter, fields: &[Field], size: usize) -> fmt::Result {
+    let mut leaves = Vec::new();
+    collect_leaves(fields, 0, "", &mut leaves);
+
+    // The box each byte belongs to. Padding gets boxes of its own, so that
+    // every byte has one.
+    let mut names: Vec<Cow<'_, str>> = Vec::new();
+    let mut owners: Vec<Option<usize>> = vec![None; size];
+    for leaf in leaves {
+        let index = names.len();
+        names.push(leaf.name);
+
+        for owner in owners.iter_mut().skip(leaf.offset).take(leaf.field.size()) {
+            owner.get_or_insert(index);
+        }
+    }
+
+    let mut owners_filled = Vec::with_capacity(size);
+    for (byte, owner) in owners.iter().enumerate() {
+        let owner = match owner {
+            Some(owner) => *owner,
+            None if byte > 0 && owners[byte - 1].is_none() => owners_filled[byte - 1],
+            None => {
+                names.push(Cow::Borrowed("padding"));
+                names.len() - 1
+            }
+        };
+        owners_filled.push(owner);
+    }
+
+    let owners = owners_filled;
+    let row_bytes = ROW_BITS / 8;
+    let rows: Vec<&[usize]> = owners.chunks(row_bytes).collect();
+
+    write_ruler(formatter)?;
+    write_separator(formatter, None, rows.first().copied())?;
+
+    for (index, row) in rows.iter().enumerate() {
+        write_row(formatter, row, &names, &owners[..index * row_bytes])?;
+        write_separator(formatter, Some(row), rows.get(index + 1).copied())?;
+    }
+
+    Ok(())
+}
+
+fn write_ruler(formatter: &mut fmt::Formatter) -> fmt::Result {
+    let mut tens = String::new();
+    let mut ones = String::new();
+
+    for bit in 0..ROW_BITS {
+        if bit % 10 == 0 {
+            tens.push_str(&format!(" {}", bit / 10));
+        } else {
+            tens.push_str("  ");
+        }
+        ones.push_str(&format!(" {}", bit % 10));
+    }
+
+    writeln!(formatter, "{}", tens.trim_end())?;
+    writeln!(formatter, "{}", ones)
+}
+
+/// Draws one row of boxes. `before` holds the owners of the bytes of the
+/// rows above, so a box that continues from them isn't labeled again.
+fn write_row(
+    formatter: &mut fmt::Formatter,
+    row: &[usize],
+    names: &[Cow<'_, str>],
+    before: &[usize],
+) -> fmt::Result {
+    let mut line = String::from("|");
+    let mut start = 0;
+
+    while start < row.len() {
+        let owner = row[start];
+        let end = row[start..]
+            .iter()
+            .position(|other| *other != owner)
+            .map_or(row.len(), |len| start + len);
+
+        let width = (end - start) * 16 - 1;
+        let label = if before.contains(&owner) {
+            ""
+        } else {
+            let name: &str = &names[owner];
+            match name.char_indices().nth(width) {
+                Some((cut, _)) => &name[..cut],
+                None => name,
+            }
+        };
+
+        line.push_str(&format!("{:^width$}|", label, width = width));
+        start = end;
+    }
+
+    writeln!(formatter, "{}", line)
+}
+
+/// Draws the line between two rows, leaving out the parts of it that a box
+/// spanning both rows crosses.
+fn write_separator(
+    formatter: &mut fmt::Formatter,
+    above: Option<&[usize]>,
+    below: Option<&[usize]>,
+) -> fmt::Result {
+    let bits = above
+        .map_or(0, |row| row.len())
+        .max(below.map_or(0, |row| row.len()))
+        * 8;
+    let owner = |row: Option<&[usize]>, bit: usize| row.and_then(|row| row.get(bit / 8)).copied();
+    let crossed = |bit: usize| {
+        let above = owner(above, bit);
+        above.is_some() && above == owner(below, bit)
+    };
+
+    let mut line = String::new();
+    for bit in 0..bits {
+        let inside = bit > 0
+            && crossed(bit - 1)
+            && crossed(bit)
+            && owner(above, bit - 1) == owner(above, bit);
+        line.push(if inside { ' ' } else { '+' });
+        line.push(if crossed(bit) { ' ' } else { '-' });
+    }
+    line.push('+');
+
+    writeln!(formatter, "{}", line)
+}
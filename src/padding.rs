@@ -0,0 +1,308 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{Field, PaddingKind, TypeLayout, TypeLayoutInfo};
+
+/// A run of padding bytes in a type. See [`TypeLayoutInfo::padding`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct PaddingRegion {
+    pub offset: usize,
+    pub size: usize,
+    pub kind: PaddingKind,
+}
+
+impl TypeLayoutInfo {
+    /// Returns one entry for each byte of the type, which is `true` if that
+    /// byte is padding.
+    ///
+    /// Only padding between and after this type's own fields is included. Any
+    /// padding inside of a field, like a field that is itself a struct with
+    /// padding, is reported as part of that field.
+    ///
+    /// For enums, a byte is only padding if it's padding in every variant. For
+    /// unions, only the bytes after the largest field are padding.
+    pub fn padding_mask(&self) -> Vec<bool> {
+        let mut mask = vec![true; self.size];
+
+        for field in self.all_fields() {
+            if let Field::Field { offset, size, .. } = field {
+                let start = (*offset).min(self.size);
+                let end = offset.saturating_add(*size).min(self.size);
+
+                for byte in &mut mask[start..end] {
+                    *byte = false;
+                }
+            }
+        }
+
+        mask
+    }
+
+    /// The runs of padding bytes in this type, in order of offset. These are
+    /// the same bytes that [`TypeLayoutInfo::padding_mask`] marks.
+    ///
+    /// For structs and unions, these match the `Field::Padding` entries in
+    /// `fields`. For enums, only bytes that are padding in every variant are
+    /// included, while each variant's own padding is in its `fields`.
+    ///
+    /// ```rust
+    /// use type_layout::{PaddingKind, PaddingRegion, TypeLayout};
+    ///
+    /// #[derive(TypeLayout)]
+    /// #[repr(C)]
+    /// struct Record {
+    ///     tag: u8,
+    ///     value: u32,
+    ///     flag: u8,
+    /// }
+    ///
+    /// let padding: Vec<_> = Record::type_layout().padding().collect();
+    /// assert_eq!(
+    ///     padding,
+    ///     [
+    ///         PaddingRegion {
+    ///             offset: 1,
+    ///             size: 3,
+    ///             kind: PaddingKind::Interior,
+    ///         },
+    ///         PaddingRegion {
+    ///             offset: 9,
+    ///             size: 3,
+    ///             kind: PaddingKind::Trailing,
+    ///         },
+    ///     ]
+    /// );
+    /// ```
+    pub fn padding(&self) -> impl Iterator<Item = PaddingRegion> {
+        let mask = self.padding_mask();
+        let mut regions = Vec::new();
+        let mut start = None;
+
+        for (index, is_padding) in mask.iter().copied().chain(Some(false)).enumerate() {
+            match (is_padding, start) {
+                (true, None) => start = Some(index),
+                (false, Some(offset)) => {
+                    let kind = if index == self.size {
+                        PaddingKind::Trailing
+                    } else {
+                        PaddingKind::Interior
+                    };
+
+                    regions.push(PaddingRegion {
+                        offset,
+                        size: index - offset,
+                        kind,
+                    });
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+
+        regions.into_iter()
+    }
+
+    /// The number of padding bytes in this type, counting the same bytes as
+    /// [`TypeLayoutInfo::padding_mask`].
+    ///
+    /// ```rust
+    /// use type_layout::TypeLayout;
+    ///
+    /// #[derive(TypeLayout)]
+    /// #[repr(C)]
+    /// struct Sparse {
+    ///     a: u8,
+    ///     b: u32,
+    ///     c: u8,
+    ///     d: u16,
+    /// }
+    ///
+    /// let layout = Sparse::type_layout();
+    /// assert_eq!(layout.padding_bytes(), 4);
+    /// assert!(layout.padding_ratio() < 0.4);
+    ///
+    /// let hole = layout.largest_hole().unwrap();
+    /// assert_eq!((hole.offset, hole.size), (1, 3));
+    /// ```
+    pub fn padding_bytes(&self) -> usize {
+        self.padding_mask()
+            .into_iter()
+            .filter(|&is_padding| is_padding)
+            .count()
+    }
+
+    /// The fraction of this type's size that is padding, from `0.0` to `1.0`.
+    /// Zero-sized types have no padding.
+    pub fn padding_ratio(&self) -> f64 {
+        if self.size == 0 {
+            0.0
+        } else {
+            self.padding_bytes() as f64 / self.size as f64
+        }
+    }
+
+    /// The largest run of interior padding, which is padding between fields
+    /// rather than after the last one. If several are the same size, the first
+    /// is returned.
+    pub fn largest_hole(&self) -> Option<PaddingRegion> {
+        self.padding()
+            .filter(|region| region.kind == PaddingKind::Interior)
+            .fold(
+                None,
+                |largest: Option<PaddingRegion>, region| match largest {
+                    Some(largest) if largest.size >= region.size => Some(largest),
+                    _ => Some(region),
+                },
+            )
+    }
+}
+
+/// Why a run of padding is there. Recorded on each [`PaddingRow`] returned by
+/// [`TypeLayoutInfo::rows`], and shown in tables with
+/// [`TableOptions::padding_causes`](crate::TableOptions::padding_causes).
+///
+/// Its `Display` is a short explanation, like ``align for `len: u32` ``.
+///
+/// [`PaddingRow`]: crate::PaddingRow
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PaddingCause<'a> {
+    /// The padding moves a field up to a multiple of its alignment.
+    Field {
+        name: &'a str,
+
+        /// The field's type, resolved to a concrete type when the field's
+        /// type is a generic parameter.
+        ty: &'a str,
+
+        /// The field's alignment, when it's known.
+        alignment: Option<usize>,
+    },
+
+    /// The padding rounds the size of the type up to a multiple of its
+    /// alignment, so that each element of an array of it is aligned.
+    Alignment(usize),
+
+    /// The padding fills the rest of a type of this size, like a variant of
+    /// an enum that's smaller than the enum's largest variant.
+    Size(usize),
+}
+
+impl fmt::Display for PaddingCause<'_> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PaddingCause::Field { name, ty, .. } => {
+                write!(formatter, "align for `{}: {}`", name, ty)
+            }
+            PaddingCause::Alignment(alignment) => {
+                write!(formatter, "round size to alignment {}", alignment)
+            }
+            PaddingCause::Size(size) => write!(formatter, "fill to size {}", size),
+        }
+    }
+}
+
+/// The cause of the padding at `fields[index]`, where `fields` are the fields
+/// of `layout` or of one of its variants.
+pub(crate) fn padding_cause<'a>(
+    layout: &TypeLayoutInfo,
+    fields: &'a [Field],
+    index: usize,
+) -> PaddingCause<'a> {
+    let next = fields[index + 1..].iter().find_map(|field| match field {
+        Field::Field {
+            name,
+            ty,
+            resolved_ty,
+            alignment,
+            ..
+        } => Some(PaddingCause::Field {
+            name,
+            ty: resolved_ty.as_ref().unwrap_or(ty),
+            alignment: *alignment,
+        }),
+        Field::Padding { .. } => None,
+    });
+    if let Some(cause) = next {
+        return cause;
+    }
+
+    let end = fields[index].offset() + fields[index].size();
+    let unpadded = fields[..index]
+        .iter()
+        .map(|field| field.offset() + field.size())
+        .max()
+        .unwrap_or(0);
+
+    let rounded = match layout.alignment {
+        0 => unpadded,
+        alignment => (unpadded + alignment - 1) / alignment * alignment,
+    };
+
+    // The padding of a variant is up to the size of the whole enum, which
+    // may be set by another variant.
+    if layout.variants.is_empty() && end == rounded {
+        PaddingCause::Alignment(layout.alignment)
+    } else {
+        PaddingCause::Size(layout.size)
+    }
+}
+
+/// Compares the bytes of two values, skipping over their padding.
+///
+/// This is useful for comparing `#[repr(C)]` values whose padding bytes may
+/// hold garbage, where a plain byte comparison would fail spuriously.
+///
+/// # Safety
+///
+/// Every byte that isn't padding according to
+/// [`TypeLayoutInfo::padding_mask`] must be initialized in both values. In
+/// particular, fields of `T` must not contain padding of their own, like a
+/// field whose type is a struct with padding, since reading those bytes is
+/// undefined behavior. For enums, bytes that are padding in the variant that
+/// either value holds, but not in every variant, must be initialized too.
+/// For unions, both values must have initialized every byte of the largest
+/// field, not just those of the field they hold.
+///
+/// ```rust
+/// use std::mem::{size_of, MaybeUninit};
+/// use std::ptr::addr_of_mut;
+/// use type_layout::{eq_ignoring_padding, TypeLayout};
+///
+/// #[derive(TypeLayout)]
+/// #[repr(C)]
+/// struct Foo {
+///     a: u8,
+///     b: u32,
+/// }
+///
+/// fn make(a: u8, b: u32, scribble: u8) -> Foo {
+///     let mut value = MaybeUninit::<Foo>::uninit();
+///     let ptr = value.as_mut_ptr();
+///
+///     unsafe {
+///         ptr.cast::<u8>().write_bytes(scribble, size_of::<Foo>());
+///         addr_of_mut!((*ptr).a).write(a);
+///         addr_of_mut!((*ptr).b).write(b);
+///         value.assume_init()
+///     }
+/// }
+///
+/// unsafe {
+///     assert!(eq_ignoring_padding(&make(1, 2, 0xAA), &make(1, 2, 0x55)));
+///     assert!(!eq_ignoring_padding(&make(1, 2, 0xAA), &make(1, 3, 0xAA)));
+/// }
+/// ```
+pub unsafe fn eq_ignoring_padding<T: TypeLayout>(a: &T, b: &T) -> bool {
+    let mask = T::type_layout().padding_mask();
+    let a = a as *const T as *const u8;
+    let b = b as *const T as *const u8;
+
+    mask.iter().enumerate().all(|(index, is_padding)| {
+        // SAFETY: The index is within the type because the mask has one entry
+        // per byte, and the caller promised that non-padding bytes are
+        // initialized.
+        *is_padding || unsafe { a.add(index).read() == b.add(index).read() }
+    })
+}
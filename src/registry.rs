@@ -0,0 +1,63 @@
+//! A list of every type in the program that derives `TypeLayout`.
+//!
+//! This module is only available with the `registry` feature. Generic types
+//! aren't registered, since there's no way to know which instantiations of
+//! them exist.
+//!
+//! ```rust
+//! use type_layout::TypeLayout;
+//!
+//! #[derive(TypeLayout)]
+//! struct Apple {
+//!     weight: u32,
+//! }
+//!
+//! #[derive(TypeLayout)]
+//! struct Banana {
+//!     length: u16,
+//! }
+//!
+//! #[derive(TypeLayout)]
+//! struct Cherry {
+//!     pit: bool,
+//! }
+//!
+//! let mut names: Vec<_> = type_layout::registry::iter()
+//!     .map(|registration| registration.name)
+//!     .collect();
+//! names.sort();
+//!
+//! assert_eq!(names, ["Apple", "Banana", "Cherry"]);
+//! ```
+
+use crate::TypeLayoutInfo;
+
+/// A type that was registered by `#[derive(TypeLayout)]`.
+#[derive(Debug)]
+pub struct Registration {
+    /// The name of the type, without its module path.
+    pub name: &'static str,
+
+    /// The type's implementation of `TypeLayout::type_layout`.
+    pub type_layout: fn() -> TypeLayoutInfo,
+}
+
+inventory::collect!(Registration);
+
+/// Iterates over every registered type, in no particular order.
+pub fn iter() -> impl Iterator<Item = &'static Registration> {
+    inventory::iter::<Registration>.into_iter()
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __register_type_layout {
+    ($ty:ident) => {
+        $crate::inventory::submit! {
+            $crate::registry::Registration {
+                name: ::core::stringify!($ty),
+                type_layout: <$ty as $crate::TypeLayout>::type_layout,
+            }
+        }
+    };
+}
@@ -0,0 +1,505 @@
+use alloc::borrow::{Cow, ToOwned};
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Reverse;
+use core::fmt;
+
+use crate::{Field, Repr, TypeLayoutInfo};
+
+impl TypeLayoutInfo {
+    /// Finds the order of this struct's fields that makes it as small as
+    /// possible under `#[repr(C)]` rules, which place fields in the order
+    /// they're declared.
+    ///
+    /// Placing fields from most to least aligned leaves no padding between
+    /// them, since the size of every type is a multiple of its alignment, so
+    /// that's the order suggested. Fields with the same alignment keep their
+    /// current order.
+    ///
+    /// Returns `None` for enums and unions, and for layouts where the
+    /// alignment of a field isn't known.
+    ///
+    /// ```rust
+    /// use type_layout::TypeLayout;
+    ///
+    /// #[derive(TypeLayout)]
+    /// #[repr(C)]
+    /// struct GameState {
+    ///     paused: bool,
+    ///     score: u64,
+    ///     lives: u8,
+    ///     level: u32,
+    /// }
+    ///
+    /// let order = GameState::type_layout().optimize_order().unwrap();
+    /// assert_eq!(order.fields, ["score", "level", "paused", "lives"]);
+    /// assert_eq!((order.current_size, order.optimized_size), (24, 16));
+    /// assert_eq!(order.savings(), 8);
+    ///
+    /// println!("{}", order);
+    /// // prints:
+    /// // Reordering the fields of `GameState` as score, level, paused, lives would save 8 bytes (24 -> 16)
+    /// ```
+    pub fn optimize_order(&self) -> Option<OptimizedOrder> {
+        if self.is_union || !self.variants.is_empty() {
+            return None;
+        }
+
+        let mut fields = Vec::new();
+        for field in &self.fields {
+            if let Field::Field { name, size, .. } = field {
+                // Packing lowers the alignment of every field to at most that
+                // of the struct, and `align(N)` only ever raises the struct's.
+                let alignment = field.alignment()?.min(self.alignment);
+                fields.push((name.clone(), *size, alignment));
+            }
+        }
+
+        // Stable, so fields with the same alignment stay in order.
+        fields.sort_by_key(|&(_, _, alignment)| Reverse(alignment));
+
+        let mut end = 0;
+        for (_, size, alignment) in &fields {
+            end = round_up(end, *alignment) + size;
+        }
+
+        Some(OptimizedOrder {
+            name: Cow::Owned(self.full_name().into_owned()),
+            fields: fields.into_iter().map(|(name, _, _)| name).collect(),
+            current_size: self.size,
+            optimized_size: round_up(end, self.alignment),
+        })
+    }
+
+    /// Computes the layout this struct would have under `#[repr(C)]` rules
+    /// with its fields in a different order, to try out orders without
+    /// editing the struct and compiling it again.
+    ///
+    /// `order` lists the index of each field in its new position, counting
+    /// the fields in their current order and skipping padding, so `[1, 0]`
+    /// swaps the fields of a struct with two of them. The size and offsets
+    /// are recomputed, while the alignment stays the same, including any
+    /// raised by `#[repr(align(N))]` or lowered by `#[repr(packed)]`.
+    ///
+    /// Returns `None` for enums, unions, and types with an unsized tail, and
+    /// for layouts where the alignment of a field isn't known.
+    ///
+    /// ```rust
+    /// use type_layout::TypeLayout;
+    ///
+    /// #[derive(TypeLayout)]
+    /// #[repr(C)]
+    /// struct Header {
+    ///     kind: u8,
+    ///     len: u32,
+    ///     flags: u8,
+    /// }
+    ///
+    /// let reordered = Header::type_layout().with_field_order(&[1, 0, 2]).unwrap();
+    /// assert_eq!(reordered.size, 8);
+    /// assert_eq!(reordered.offset_of("flags"), Some(5));
+    ///
+    /// println!("{}", reordered);
+    /// // prints:
+    /// // Header (size 8, alignment 4)
+    /// // | Offset | Name           | Size |
+    /// // | ------ | -------------- | ---- |
+    /// // | 0      | len            | 4    |
+    /// // | 4      | kind           | 1    |
+    /// // | 5      | flags          | 1    |
+    /// // | 6      | [tail padding] | 2    |
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` doesn't list the index of every field exactly once.
+    pub fn with_field_order(&self, order: &[usize]) -> Option<TypeLayoutInfo> {
+        if self.is_union || !self.variants.is_empty() || self.unsized_tail.is_some() {
+            return None;
+        }
+
+        let fields: Vec<&Field> = self
+            .fields
+            .iter()
+            .filter(|field| !field.is_padding())
+            .collect();
+
+        let mut seen = vec![false; fields.len()];
+        let is_permutation = order.len() == fields.len()
+            && order
+                .iter()
+                .all(|&index| index < fields.len() && !core::mem::replace(&mut seen[index], true));
+        assert!(
+            is_permutation,
+            "field order must list the index of each of the {} fields exactly once",
+            fields.len()
+        );
+
+        let mut reordered = Vec::with_capacity(fields.len());
+        let mut end = 0;
+        for &index in order {
+            let mut field = fields[index].clone();
+            let alignment = field.alignment()?.min(self.alignment);
+
+            if let Field::Field { offset, size, .. } = &mut field {
+                *offset = round_up(end, alignment);
+                end = *offset + *size;
+            }
+            reordered.push(field);
+        }
+
+        let size = round_up(end, self.alignment);
+
+        Some(TypeLayoutInfo {
+            name: self.name.clone(),
+            generics: self.generics.clone(),
+            size,
+            alignment: self.alignment,
+            natural_alignment: self.natural_alignment,
+            needs_drop: self.needs_drop,
+            option_size: None,
+            is_union: false,
+            repr: Repr {
+                c: true,
+                ..self.repr.clone()
+            },
+            guaranteed: true,
+            fields: crate::__insert_padding(reordered, size),
+            variants: Vec::new(),
+            unsized_tail: None,
+            tag: None,
+            target: self.target.clone(),
+        })
+    }
+
+    /// Compares the size of this struct with the sizes it would have with its
+    /// fields reordered by [`optimize_order`](Self::optimize_order), and
+    /// under `#[repr(C, packed)]`, which leaves out all padding at the cost
+    /// of fields that may be unaligned, to judge whether changing the struct
+    /// is worth it.
+    ///
+    /// Returns `None` in the same cases as `optimize_order`.
+    ///
+    /// ```rust
+    /// use type_layout::TypeLayout;
+    ///
+    /// #[derive(TypeLayout)]
+    /// #[repr(C)]
+    /// struct GameState {
+    ///     paused: bool,
+    ///     score: u64,
+    ///     lives: u8,
+    ///     level: u32,
+    /// }
+    ///
+    /// let savings = GameState::type_layout().projected_savings().unwrap();
+    /// assert_eq!(savings.packed_size, 14);
+    ///
+    /// print!("{}", savings);
+    /// // prints:
+    /// // Projected sizes of `GameState`
+    /// // | Layout          | Size | Savings |
+    /// // | --------------- | ---- | ------- |
+    /// // | current         | 24   | 0       |
+    /// // | reordered       | 16   | 8       |
+    /// // | repr(C, packed) | 14   | 10      |
+    /// // Reordered as score, level, paused, lives
+    /// ```
+    pub fn projected_savings(&self) -> Option<ProjectedSavings> {
+        let reordered = self.optimize_order()?;
+        let packed_size = self
+            .fields
+            .iter()
+            .filter(|field| !field.is_padding())
+            .map(Field::size)
+            .sum();
+
+        Some(ProjectedSavings {
+            name: reordered.name,
+            order: reordered.fields,
+            current_size: self.size,
+            reordered_size: reordered.optimized_size,
+            packed_size,
+        })
+    }
+}
+
+/// An order of a struct's fields with as little padding as possible. Created
+/// by [`TypeLayoutInfo::optimize_order`].
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct OptimizedOrder {
+    pub name: Cow<'static, str>,
+
+    /// The names of the fields, in the suggested order.
+    pub fields: Vec<Cow<'static, str>>,
+
+    pub current_size: usize,
+
+    /// The size of the struct with its fields in the suggested order.
+    pub optimized_size: usize,
+}
+
+impl OptimizedOrder {
+    /// The number of bytes that reordering the fields would save.
+    pub fn savings(&self) -> usize {
+        self.current_size.saturating_sub(self.optimized_size)
+    }
+}
+
+impl fmt::Display for OptimizedOrder {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        if self.savings() == 0 {
+            return writeln!(
+                formatter,
+                "The fields of `{}` are already in an order of minimal size ({} bytes)",
+                self.name, self.current_size
+            );
+        }
+
+        writeln!(
+            formatter,
+            "Reordering the fields of `{}` as {} would save {} bytes ({} -> {})",
+            self.name,
+            self.fields.join(", "),
+            self.savings(),
+            self.current_size,
+            self.optimized_size
+        )
+    }
+}
+
+/// Rounds `offset` up to a multiple of `alignment`, treating an alignment of
+/// zero, which only hand-built layouts can have, as 1.
+fn round_up(offset: usize, alignment: usize) -> usize {
+    let alignment = alignment.max(1);
+    (offset + alignment - 1) / alignment * alignment
+}
+
+/// The sizes a struct would have with its fields reordered or packed. Created
+/// by [`TypeLayoutInfo::projected_savings`].
+///
+/// Its `Display` renders a table of the sizes and how much each saves.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProjectedSavings {
+    pub name: Cow<'static, str>,
+
+    /// The names of the fields in the order that gives `reordered_size`.
+    pub order: Vec<Cow<'static, str>>,
+
+    pub current_size: usize,
+
+    /// The size of the struct with its fields in the order of `order`.
+    pub reordered_size: usize,
+
+    /// The size of the struct under `#[repr(C, packed)]`, which is the sum
+    /// of the sizes of its fields.
+    pub packed_size: usize,
+}
+
+impl ProjectedSavings {
+    /// The number of bytes that reordering the fields would save.
+    pub fn reordered_savings(&self) -> usize {
+        self.current_size.saturating_sub(self.reordered_size)
+    }
+
+    /// The number of bytes that packing the struct would save.
+    pub fn packed_savings(&self) -> usize {
+        self.current_size.saturating_sub(self.packed_size)
+    }
+}
+
+impl fmt::Display for ProjectedSavings {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let rows = [
+            [
+                "current".to_owned(),
+                self.current_size.to_string(),
+                "0".to_owned(),
+            ],
+            [
+                "reordered".to_owned(),
+                self.reordered_size.to_string(),
+                self.reordered_savings().to_string(),
+            ],
+            [
+                "repr(C, packed)".to_owned(),
+                self.packed_size.to_string(),
+                self.packed_savings().to_string(),
+            ],
+        ];
+
+        let headers = ["Layout", "Size", "Savings"].map(String::from);
+        let mut widths = headers.clone().map(|header| header.len());
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.len());
+            }
+        }
+
+        writeln!(formatter, "Projected sizes of `{}`", self.name)?;
+        write_row(formatter, &widths, &headers)?;
+        write_row(formatter, &widths, &widths.map(|width| "-".repeat(width)))?;
+        for row in &rows {
+            write_row(formatter, &widths, row)?;
+        }
+
+        if self.reordered_savings() > 0 {
+            writeln!(formatter, "Reordered as {}", self.order.join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
+fn write_row(
+    formatter: &mut fmt::Formatter,
+    widths: &[usize; 3],
+    cells: &[String; 3],
+) -> fmt::Result {
+    for (width, cell) in widths.iter().zip(cells) {
+        write!(formatter, "| {:<width$} ", cell, width = width)?;
+    }
+
+    writeln!(formatter, "|")
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::format;
+
+    use super::*;
+
+    fn header() -> TypeLayoutInfo {
+        TypeLayoutInfo::builder("Header")
+            .field("kind", "u8", 1, 1)
+            .field("len", "u32", 4, 4)
+            .field("flags", "u8", 1, 1)
+            .build()
+            .unwrap()
+    }
+
+    fn offsets(layout: &TypeLayoutInfo) -> Vec<(&str, usize)> {
+        layout
+            .fields
+            .iter()
+            .filter_map(|field| match field {
+                Field::Field { name, offset, .. } => Some((&**name, *offset)),
+                Field::Padding { .. } => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "field order must list the index of each of the 3 fields exactly once"
+    )]
+    fn order_too_short() {
+        header().with_field_order(&[1, 0]);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "field order must list the index of each of the 3 fields exactly once"
+    )]
+    fn order_too_long() {
+        header().with_field_order(&[0, 1, 2, 3]);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "field order must list the index of each of the 3 fields exactly once"
+    )]
+    fn order_with_duplicates() {
+        header().with_field_order(&[1, 1, 0]);
+    }
+
+    #[test]
+    fn packed() {
+        let packed = TypeLayoutInfo::builder("Packed")
+            .field("kind", "u8", 1, 1)
+            .field("len", "u32", 4, 4)
+            .field("flags", "u8", 1, 1)
+            .packed(2)
+            .build()
+            .unwrap();
+        assert_eq!((packed.size, packed.alignment), (8, 2));
+
+        let order = packed.optimize_order().unwrap();
+        assert_eq!(order.fields, ["len", "kind", "flags"]);
+        assert_eq!((order.current_size, order.optimized_size), (8, 6));
+
+        // Fields are only aligned to 2, so `len` isn't moved to offset 4.
+        let reordered = packed.with_field_order(&[0, 2, 1]).unwrap();
+        assert_eq!(offsets(&reordered), [("kind", 0), ("flags", 1), ("len", 2)]);
+        assert_eq!((reordered.size, reordered.alignment), (6, 2));
+
+        let savings = packed.projected_savings().unwrap();
+        assert_eq!(savings.packed_size, 6);
+        assert_eq!(savings.reordered_savings(), 2);
+    }
+
+    #[test]
+    fn zero_sized_fields() {
+        let layout = TypeLayoutInfo::builder("Tagged")
+            .field("tag", "u8", 1, 1)
+            .field("marker", "PhantomData<u64>", 0, 1)
+            .field("align", "[u64; 0]", 0, 8)
+            .field("value", "u32", 4, 4)
+            .build()
+            .unwrap();
+        assert_eq!(layout.size, 16);
+
+        let order = layout.optimize_order().unwrap();
+        assert_eq!(order.fields, ["align", "value", "tag", "marker"]);
+        assert_eq!(order.optimized_size, 8);
+
+        let reordered = layout.with_field_order(&[2, 3, 0, 1]).unwrap();
+        assert_eq!(
+            offsets(&reordered),
+            [("align", 0), ("value", 0), ("tag", 4), ("marker", 5)]
+        );
+        assert_eq!(reordered.size, 8);
+
+        assert_eq!(layout.projected_savings().unwrap().packed_size, 5);
+    }
+
+    #[test]
+    fn already_optimal() {
+        let layout = header().with_field_order(&[1, 0, 2]).unwrap();
+
+        let order = layout.optimize_order().unwrap();
+        assert_eq!(order.fields, ["len", "kind", "flags"]);
+        assert_eq!(order.savings(), 0);
+        assert_eq!(
+            format!("{}", order),
+            "The fields of `Header` are already in an order of minimal size (8 bytes)\n"
+        );
+
+        let savings = layout.projected_savings().unwrap();
+        assert_eq!(savings.reordered_savings(), 0);
+        assert_eq!(savings.packed_savings(), 2);
+        assert_eq!(
+            format!("{}", savings),
+            "\
+Projected sizes of `Header`
+| Layout          | Size | Savings |
+| --------------- | ---- | ------- |
+| current         | 8    | 0       |
+| reordered       | 8    | 0       |
+| repr(C, packed) | 6    | 2       |
+"
+        );
+    }
+
+    #[test]
+    fn unions() {
+        let mut union = header();
+        union.is_union = true;
+        assert!(union.optimize_order().is_none());
+        assert!(union.with_field_order(&[0, 1, 2]).is_none());
+        assert!(union.projected_savings().is_none());
+    }
+}
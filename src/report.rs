@@ -0,0 +1,159 @@
+use alloc::format;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use core::cmp::Reverse;
+use core::fmt;
+
+// The `JsonSchema` derive expects these from the standard prelude.
+#[cfg(feature = "schemars")]
+use alloc::borrow::ToOwned;
+
+use crate::{TypeLayout, TypeLayoutInfo};
+
+/// A collection of layouts that are rendered together, followed by a summary
+/// of how much padding each type contains.
+///
+/// The summary lists the size and alignment of each type, along with its
+/// padding in bytes and as a percentage of its size. Types with the most
+/// padding come first.
+///
+/// ```rust
+/// use type_layout::{LayoutReport, TypeLayout};
+///
+/// #[derive(TypeLayout)]
+/// #[repr(C)]
+/// struct Small {
+///     a: u8,
+///     b: u16,
+/// }
+///
+/// #[derive(TypeLayout)]
+/// #[repr(C)]
+/// struct Wasteful {
+///     a: u8,
+///     b: u64,
+///     c: u8,
+/// }
+///
+/// let mut report = LayoutReport::new();
+/// report.add::<Small>();
+/// report.add::<Wasteful>();
+/// report.add::<Small>();
+///
+/// assert_eq!(report.layouts().len(), 2);
+/// println!("{}", report);
+/// ```
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct LayoutReport {
+    layouts: Vec<TypeLayoutInfo>,
+}
+
+impl LayoutReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the layout of `T`, unless a layout with the same name has already
+    /// been added.
+    pub fn add<T: TypeLayout>(&mut self) -> &mut Self {
+        let layout = T::type_layout();
+
+        if !self
+            .layouts
+            .iter()
+            .any(|existing| existing.full_name() == layout.full_name())
+        {
+            self.layouts.push(layout);
+        }
+
+        self
+    }
+
+    /// Adds a layout, even if one with the same name has already been added.
+    pub fn push(&mut self, layout: TypeLayoutInfo) -> &mut Self {
+        self.layouts.push(layout);
+        self
+    }
+
+    /// The layouts in this report, in the order they were added.
+    pub fn layouts(&self) -> &[TypeLayoutInfo] {
+        &self.layouts
+    }
+
+    pub fn into_layouts(self) -> Vec<TypeLayoutInfo> {
+        self.layouts
+    }
+}
+
+impl fmt::Display for LayoutReport {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        for layout in &self.layouts {
+            layout.write_text(formatter)?;
+            writeln!(formatter)?;
+        }
+
+        let mut summary: Vec<_> = self
+            .layouts
+            .iter()
+            .map(|layout| {
+                let name = layout.full_name();
+                let size = layout.size.to_string();
+                let alignment = layout.alignment.to_string();
+                let padding = layout.padding_bytes();
+                let ratio = format!("{:.1}", layout.padding_ratio() * 100.0);
+                (name, size, alignment, padding, ratio)
+            })
+            .collect();
+
+        // Stable, so types with the same amount of padding stay in the order
+        // they were added.
+        summary.sort_by_key(|row| Reverse(row.3));
+
+        let summary: Vec<_> = summary
+            .into_iter()
+            .map(|(name, size, alignment, padding, ratio)| {
+                [
+                    name.into_owned(),
+                    size,
+                    alignment,
+                    padding.to_string(),
+                    ratio,
+                ]
+            })
+            .collect();
+
+        let headers = ["Type", "Size", "Alignment", "Padding", "Padding %"];
+        let mut widths = headers.map(str::len);
+        for row in &summary {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.len());
+            }
+        }
+
+        writeln!(formatter, "Summary")?;
+        write_summary_row(formatter, &widths, &headers)?;
+
+        let dashes = widths.map(|width| "-".repeat(width));
+        write_summary_row(formatter, &widths, &dashes)?;
+
+        for row in &summary {
+            write_summary_row(formatter, &widths, row)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn write_summary_row<S: AsRef<str>>(
+    formatter: &mut fmt::Formatter,
+    widths: &[usize; 5],
+    cells: &[S; 5],
+) -> fmt::Result {
+    for (width, cell) in widths.iter().zip(cells) {
+        write!(formatter, "| {:<width$} ", cell.as_ref(), width = width)?;
+    }
+
+    writeln!(formatter, "|")
+}
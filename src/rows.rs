@@ -0,0 +1,173 @@
+use alloc::vec::Vec;
+
+use crate::padding::padding_cause;
+use crate::{Field, PaddingCause, PaddingKind, TypeLayoutInfo, UnsizedTail, Variant};
+
+impl TypeLayoutInfo {
+    /// Iterates over the rows of the layout table, in the order `Display`
+    /// shows them, so that tools can use the same fields and padding without
+    /// rendering the table and parsing it again.
+    ///
+    /// Each nested field is followed by the rows of its fields, whose
+    /// [`depth`](FieldRow::depth) is one more than the field's, and every
+    /// offset is from the start of this type. The rows of an enum are those
+    /// of each of its variants in turn, and an unsized tail comes last.
+    ///
+    /// ```rust
+    /// use type_layout::{Row, TypeLayout};
+    ///
+    /// #[derive(TypeLayout)]
+    /// #[repr(C)]
+    /// struct Header {
+    ///     kind: u8,
+    ///     len: u32,
+    /// }
+    ///
+    /// for row in Header::type_layout().rows() {
+    ///     match row {
+    ///         Row::Field(field) => println!("{} at {}", field.name, field.offset),
+    ///         Row::Padding(padding) => println!("{} bytes of padding", padding.size),
+    ///         Row::UnsizedTail(_) => {}
+    ///     }
+    /// }
+    /// // prints:
+    /// // kind at 0
+    /// // 3 bytes of padding
+    /// // len at 4
+    /// ```
+    pub fn rows(&self) -> impl Iterator<Item = Row<'_>> {
+        let mut rows = Vec::new();
+
+        if self.variants.is_empty() {
+            collect_rows(self, &self.fields, 0, 0, None, &mut rows);
+        } else {
+            for variant in &self.variants {
+                collect_rows(self, &variant.fields, 0, 0, Some(variant), &mut rows);
+            }
+        }
+
+        if let Some(tail) = &self.unsized_tail {
+            rows.push(Row::UnsizedTail(tail));
+        }
+
+        rows.into_iter()
+    }
+}
+
+/// One row of a layout table. Returned by [`TypeLayoutInfo::rows`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Row<'a> {
+    Field(FieldRow<'a>),
+    Padding(PaddingRow<'a>),
+
+    /// The unsized last field of a struct, like a slice.
+    UnsizedTail(&'a UnsizedTail),
+}
+
+impl Row<'_> {
+    /// The offset of the row from the start of the type, in bytes.
+    pub fn offset(&self) -> usize {
+        match self {
+            Row::Field(field) => field.offset,
+            Row::Padding(padding) => padding.offset,
+            Row::UnsizedTail(tail) => tail.offset,
+        }
+    }
+
+    /// The size of the row, in bytes, or `None` for an unsized tail.
+    pub fn size(&self) -> Option<usize> {
+        match self {
+            Row::Field(field) => Some(field.field.size()),
+            Row::Padding(padding) => Some(padding.size),
+            Row::UnsizedTail(_) => None,
+        }
+    }
+}
+
+/// A row for a field.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FieldRow<'a> {
+    pub name: &'a str,
+
+    /// The field's type, resolved to a concrete type when the field's type
+    /// is a generic parameter.
+    pub ty: &'a str,
+
+    /// The offset of the field from the start of the outermost type.
+    pub offset: usize,
+
+    /// How many nested fields this field is inside of.
+    pub depth: usize,
+
+    /// The variant the field belongs to, if the type is an enum.
+    pub variant: Option<&'a Variant>,
+
+    /// The field itself, for the rest of its details. Its offset is relative
+    /// to the type it's directly in.
+    pub field: &'a Field,
+}
+
+/// A row for a run of padding.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PaddingRow<'a> {
+    /// The offset of the padding from the start of the outermost type.
+    pub offset: usize,
+    pub size: usize,
+    pub kind: PaddingKind,
+
+    /// How many nested fields this padding is inside of.
+    pub depth: usize,
+
+    /// The variant the padding belongs to, if the type is an enum.
+    pub variant: Option<&'a Variant>,
+
+    /// Why the padding is there.
+    pub cause: PaddingCause<'a>,
+}
+
+/// Flattens fields into rows like the table does. `fields` are those of
+/// `layout` or of one of its variants, and `base` is the offset of their
+/// parent from the start of the outermost type.
+fn collect_rows<'a>(
+    layout: &'a TypeLayoutInfo,
+    fields: &'a [Field],
+    base: usize,
+    depth: usize,
+    variant: Option<&'a Variant>,
+    rows: &mut Vec<Row<'a>>,
+) {
+    for (index, field) in fields.iter().enumerate() {
+        let offset = base + field.offset();
+
+        match field {
+            Field::Field {
+                name,
+                ty,
+                resolved_ty,
+                nested,
+                ..
+            } => {
+                rows.push(Row::Field(FieldRow {
+                    name,
+                    ty: resolved_ty.as_ref().unwrap_or(ty),
+                    offset,
+                    depth,
+                    variant,
+                    field,
+                }));
+
+                if let Some(nested) = nested {
+                    collect_rows(nested, &nested.fields, offset, depth + 1, variant, rows);
+                }
+            }
+            Field::Padding { size, kind, .. } => rows.push(Row::Padding(PaddingRow {
+                offset,
+                size: *size,
+                kind: *kind,
+                depth,
+                variant,
+                cause: padding_cause(layout, fields, index),
+            })),
+        }
+    }
+}
@@ -0,0 +1,775 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use crate::gpu::{self, GpuLayout, GpuType, Scalar};
+use crate::{Field, TypeLayoutInfo};
+
+impl TypeLayoutInfo {
+    /// Generates WGSL struct declarations with this type's layout, so a
+    /// shader can share a buffer with Rust without declaring the struct
+    /// twice by hand.
+    ///
+    /// `_padN` members spell out any padding the Rust type has beyond what
+    /// WGSL inserts, so the shader reads each field at the same offset as
+    /// Rust writes it. Pass [`GpuLayout::Std430`] for storage buffers, and
+    /// [`GpuLayout::Std140`] for uniform buffers, which WGSL lays out the same
+    /// way but with stricter rules, checked with `// warning:` comments. See
+    /// the [`gpu`](crate::gpu) module for how the type of each field is
+    /// chosen. Nested fields refer to structs declared before this one.
+    ///
+    /// Fields with no WGSL equivalent, like `bool` and `f64`, are left out,
+    /// and their bytes become padding. Where a field can't be placed at its
+    /// Rust offset, like a `[f32; 3]` that isn't 16-byte aligned, a
+    /// `// warning:` comment says so; [`check_gpu_layout`] lists the same
+    /// problems.
+    ///
+    /// [`check_gpu_layout`]: TypeLayoutInfo::check_gpu_layout
+    ///
+    /// ```rust
+    /// use type_layout::gpu::GpuLayout;
+    /// use type_layout::TypeLayout;
+    ///
+    /// #[derive(TypeLayout)]
+    /// #[repr(C)]
+    /// struct Light {
+    ///     position: [f32; 3],
+    ///     range: f32,
+    ///     color: [f32; 4],
+    ///     #[layout(nested)]
+    ///     shadow: Shadow,
+    /// }
+    ///
+    /// #[derive(TypeLayout)]
+    /// #[repr(C)]
+    /// struct Shadow {
+    ///     cascades: [u32; 2],
+    ///     bias: f32,
+    ///     enabled: bool,
+    /// }
+    ///
+    /// print!("{}", Light::type_layout().to_wgsl(GpuLayout::Std430));
+    /// // prints:
+    /// // struct Shadow {
+    /// //     cascades: vec2<u32>,
+    /// //     bias: f32,
+    /// //     // `enabled` has type `bool`, which has no WGSL equivalent
+    /// //     _pad0: u32,
+    /// // }
+    /// //
+    /// // struct Light {
+    /// //     position: vec3<f32>,
+    /// //     range: f32,
+    /// //     color: vec4<f32>,
+    /// //     shadow: Shadow,
+    /// // }
+    /// ```
+    pub fn to_wgsl(&self, layout: GpuLayout) -> String {
+        let mut out = String::new();
+        declare(&mut out, self, layout, Language::Wgsl, &mut Vec::new());
+        out
+    }
+
+    /// Generates GLSL struct declarations with this type's layout, for use
+    /// in a block declared with `layout(std140)` or `layout(std430)`,
+    /// matching `layout`.
+    ///
+    /// This works like [`to_wgsl`](TypeLayoutInfo::to_wgsl): padding is
+    /// spelled out as `_padN` members, fields with no GLSL equivalent like
+    /// `bool` become padding, and fields that can't be placed at their Rust
+    /// offset get a `// warning:` comment.
+    ///
+    /// ```rust
+    /// use type_layout::gpu::GpuLayout;
+    /// use type_layout::TypeLayout;
+    ///
+    /// #[derive(TypeLayout)]
+    /// #[repr(C)]
+    /// struct Particle {
+    ///     mass: f32,
+    ///     alive: bool,
+    ///     velocity: [f32; 2],
+    /// }
+    ///
+    /// print!("{}", Particle::type_layout().to_glsl(GpuLayout::Std140));
+    /// // prints:
+    /// // struct Particle {
+    /// //     float mass;
+    /// //     // `alive` has type `bool`, which has no GLSL equivalent
+    /// //     uint _pad0;
+    /// //     vec2 velocity;
+    /// // };
+    /// ```
+    pub fn to_glsl(&self, layout: GpuLayout) -> String {
+        let mut out = String::new();
+        declare(&mut out, self, layout, Language::Glsl, &mut Vec::new());
+        out
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Language {
+    Wgsl,
+    Glsl,
+}
+
+impl Language {
+    fn name(self) -> &'static str {
+        match self {
+            Language::Wgsl => "WGSL",
+            Language::Glsl => "GLSL",
+        }
+    }
+
+    fn member(self, name: &str, ty: &ShaderType) -> String {
+        match self {
+            Language::Wgsl => format!("{}: {},", name, ty.name),
+            Language::Glsl => format!("{} {}{};", ty.name, name, ty.suffix),
+        }
+    }
+}
+
+/// A shader type, with the array lengths that GLSL writes after the name.
+struct ShaderType {
+    name: String,
+    suffix: String,
+}
+
+/// A struct that's been declared, with its size and alignment in the shader.
+struct Declared {
+    name: String,
+    size: usize,
+    alignment: usize,
+}
+
+/// Declares a struct for `layout`, after the structs of its nested fields,
+/// and returns its size and alignment in the shader. `declared` holds the
+/// structs that have been declared so far.
+fn declare(
+    out: &mut String,
+    layout: &TypeLayoutInfo,
+    rules: GpuLayout,
+    language: Language,
+    declared: &mut Vec<Declared>,
+) -> (usize, usize) {
+    let name = identifier(&layout.full_name(), language);
+    if let Some(existing) = declared.iter().find(|existing| existing.name == name) {
+        return (existing.size, existing.alignment);
+    }
+
+    // WGSL lays out uniform buffers like storage buffers, and rejects the
+    // types that std140 would lay out differently instead.
+    let uniform = language == Language::Wgsl && rules == GpuLayout::Std140;
+    let placement = if language == Language::Wgsl {
+        GpuLayout::Std430
+    } else {
+        rules
+    };
+
+    let mut lines = Vec::new();
+    let mut padding = 0;
+    let mut end = 0;
+    let mut alignment = 1;
+
+    if !layout.variants.is_empty() || layout.is_union {
+        let kind = if layout.is_union {
+            "a union"
+        } else {
+            "an enum"
+        };
+        lines.push(format!(
+            "// warning: `{}` is {}, which {} has no equivalent for",
+            layout.full_name(),
+            kind,
+            language.name()
+        ));
+    }
+
+    for field in &layout.fields {
+        let (field_name, ty, offset, array, nested) = match field {
+            Field::Field {
+                name,
+                ty,
+                offset,
+                size,
+                array,
+                nested,
+                ..
+            } if *size > 0 && layout.variants.is_empty() && !layout.is_union => {
+                (name, ty, *offset, array, nested)
+            }
+            _ => continue,
+        };
+
+        let (shader_type, size, field_alignment, stride) = match nested {
+            Some(nested) => {
+                // The size of a struct is a multiple of its alignment, so it's
+                // also the stride of an array of them.
+                let (size, field_alignment) = declare(out, nested, rules, language, declared);
+                let name = identifier(&nested.full_name(), language);
+                match (array, language) {
+                    (Some(array), Language::Wgsl) => {
+                        let ty = ShaderType {
+                            name: format!("array<{}, {}>", name, array.len),
+                            suffix: String::new(),
+                        };
+                        (ty, size * array.len, field_alignment, Some(size))
+                    }
+                    (Some(array), Language::Glsl) => {
+                        let ty = ShaderType {
+                            name,
+                            suffix: format!("[{}]", array.len),
+                        };
+                        (ty, size * array.len, field_alignment, Some(size))
+                    }
+                    (None, _) => {
+                        let ty = ShaderType {
+                            name,
+                            suffix: String::new(),
+                        };
+                        (ty, size, field_alignment, None)
+                    }
+                }
+            }
+            None => {
+                let gpu_type = gpu::gpu_type_of_field(field);
+                match shader_type(&gpu_type, language) {
+                    Some(ty) => {
+                        let (size, field_alignment) = gpu_type.size_align(placement);
+                        (ty, size, field_alignment, gpu_type.array_stride(placement))
+                    }
+                    None => {
+                        // The field's bytes are covered by the padding before
+                        // the next field.
+                        lines.push(format!(
+                            "// `{}` has type `{}`, which has no {} equivalent",
+                            field_name,
+                            ty,
+                            language.name()
+                        ));
+                        continue;
+                    }
+                }
+            }
+        };
+
+        let placed = gpu::round_up(end, field_alignment);
+        if offset >= end && offset % field_alignment == 0 && (offset - end) % 4 == 0 {
+            pad(&mut lines, &mut padding, end, offset, rules, language);
+            end = offset;
+        } else {
+            lines.push(format!(
+                "// warning: `{}` is at offset {} in Rust, but at offset {} here",
+                field_name, offset, placed
+            ));
+            end = placed;
+        }
+
+        let aggregate = nested.is_some() || stride.is_some();
+        if uniform && (end % 16 != 0 && aggregate || stride.map_or(false, |s| s % 16 != 0)) {
+            lines.push(format!(
+                "// warning: `{}` can't be in a uniform buffer, where arrays and structs \
+                 need 16-byte alignment and array elements need to be 16 bytes apart",
+                field_name
+            ));
+        }
+
+        lines.push(language.member(&identifier(field_name, language), &shader_type));
+        end += size;
+        alignment = alignment.max(field_alignment);
+    }
+
+    if layout.size > end && (layout.size - end) % 4 == 0 {
+        pad(&mut lines, &mut padding, end, layout.size, rules, language);
+        end = layout.size;
+    }
+
+    let alignment = match placement {
+        GpuLayout::Std140 => alignment.max(16),
+        GpuLayout::Std430 => alignment,
+    };
+    let size = gpu::round_up(end, alignment);
+    if size != layout.size {
+        lines.push(format!(
+            "// warning: `{}` is {} bytes in Rust, but {} bytes here",
+            layout.full_name(),
+            layout.size,
+            size
+        ));
+    }
+
+    if let Some(tail) = &layout.unsized_tail {
+        lines.push(format!(
+            "// warning: the unsized tail `{}: {}` isn't declared",
+            tail.name, tail.ty
+        ));
+    }
+
+    declared.push(Declared {
+        name: name.clone(),
+        size,
+        alignment,
+    });
+
+    if !out.is_empty() {
+        let _ = writeln!(out);
+    }
+
+    let _ = writeln!(out, "struct {} {{", name);
+    for line in &lines {
+        let _ = writeln!(out, "    {}", line.trim_end());
+    }
+    match language {
+        Language::Wgsl => {
+            let _ = writeln!(out, "}}");
+        }
+        Language::Glsl => {
+            let _ = writeln!(out, "}};");
+        }
+    }
+
+    (size, alignment)
+}
+
+/// Adds `_padN` members covering `start..end`, which are both multiples of
+/// 4. std140 puts the elements of every array 16 bytes apart, so padding is
+/// made of single `u32`s there.
+fn pad(
+    lines: &mut Vec<String>,
+    padding: &mut usize,
+    start: usize,
+    end: usize,
+    rules: GpuLayout,
+    language: Language,
+) {
+    let words = (end - start) / 4;
+    let uint = match language {
+        Language::Wgsl => "u32",
+        Language::Glsl => "uint",
+    };
+
+    let (count, ty) = match (rules, language) {
+        (GpuLayout::Std430, Language::Wgsl) if words > 1 => (
+            1,
+            ShaderType {
+                name: format!("array<u32, {}>", words),
+                suffix: String::new(),
+            },
+        ),
+        (GpuLayout::Std430, Language::Glsl) if words > 1 => (
+            1,
+            ShaderType {
+                name: String::from(uint),
+                suffix: format!("[{}]", words),
+            },
+        ),
+        _ => (
+            words,
+            ShaderType {
+                name: String::from(uint),
+                suffix: String::new(),
+            },
+        ),
+    };
+
+    for _ in 0..count {
+        lines.push(language.member(&format!("_pad{}", padding), &ty));
+        *padding += 1;
+    }
+}
+
+/// The name of `ty` in the shader, if it has one.
+fn shader_type(ty: &GpuType, language: Language) -> Option<ShaderType> {
+    let plain = |name: String| {
+        Some(ShaderType {
+            name,
+            suffix: String::new(),
+        })
+    };
+
+    match (ty, language) {
+        (GpuType::Scalar(scalar), Language::Wgsl) => plain(String::from(match scalar {
+            Scalar::F32 => "f32",
+            Scalar::I32 => "i32",
+            Scalar::U32 => "u32",
+            Scalar::F64 => return None,
+        })),
+        (GpuType::Scalar(scalar), Language::Glsl) => plain(String::from(match scalar {
+            Scalar::F32 => "float",
+            Scalar::I32 => "int",
+            Scalar::U32 => "uint",
+            Scalar::F64 => "double",
+        })),
+        (GpuType::Vector(scalar, count), Language::Wgsl) => {
+            let component = shader_type(&GpuType::Scalar(*scalar), language)?.name;
+            plain(format!("vec{}<{}>", count, component))
+        }
+        (GpuType::Vector(scalar, count), Language::Glsl) => {
+            plain(format!("{}vec{}", glsl_prefix(*scalar), count))
+        }
+        (GpuType::Matrix(scalar, count), Language::Wgsl) => {
+            let component = shader_type(&GpuType::Scalar(*scalar), language)?.name;
+            plain(format!("mat{}x{}<{}>", count, count, component))
+        }
+        (GpuType::Matrix(scalar, count), Language::Glsl) => {
+            plain(format!("{}mat{}", glsl_prefix(*scalar), count))
+        }
+        (GpuType::Array(elem, len), Language::Wgsl) => {
+            let elem = shader_type(elem, language)?.name;
+            plain(format!("array<{}, {}>", elem, len))
+        }
+        (GpuType::Array(elem, len), Language::Glsl) => {
+            let elem = shader_type(elem, language)?;
+            Some(ShaderType {
+                name: elem.name,
+                suffix: format!("[{}]{}", len, elem.suffix),
+            })
+        }
+        (GpuType::Struct(_), _) | (GpuType::Unsupported(..), _) => None,
+    }
+}
+
+fn glsl_prefix(scalar: Scalar) -> &'static str {
+    match scalar {
+        Scalar::F32 => "",
+        Scalar::I32 => "i",
+        Scalar::U32 => "u",
+        Scalar::F64 => "d",
+    }
+}
+
+/// Turns a Rust name into a shader identifier. Both languages reserve names
+/// with two underscores in a row.
+fn identifier(name: &str, language: Language) -> String {
+    let mut identifier = crate::c_header::sanitize(name);
+    while identifier.contains("__") {
+        identifier = identifier.replace("__", "_");
+    }
+
+    let keyword = match language {
+        Language::Wgsl => is_wgsl_keyword(&identifier),
+        Language::Glsl => is_glsl_keyword(&identifier),
+    };
+    if keyword {
+        identifier.push('_');
+    }
+
+    identifier
+}
+
+fn is_wgsl_keyword(name: &str) -> bool {
+    [
+        "alias",
+        "array",
+        "bool",
+        "break",
+        "case",
+        "const",
+        "continue",
+        "continuing",
+        "default",
+        "discard",
+        "else",
+        "enable",
+        "f16",
+        "f32",
+        "false",
+        "fn",
+        "for",
+        "i32",
+        "if",
+        "let",
+        "loop",
+        "override",
+        "return",
+        "struct",
+        "switch",
+        "true",
+        "u32",
+        "var",
+        "while",
+    ]
+    .contains(&name)
+}
+
+fn is_glsl_keyword(name: &str) -> bool {
+    [
+        "bool", "break", "buffer", "case", "const", "continue", "default", "discard", "do",
+        "double", "else", "false", "flat", "float", "for", "if", "in", "inout", "input", "int",
+        "layout", "out", "output", "return", "sample", "shared", "struct", "switch", "true",
+        "uint", "uniform", "void", "while",
+    ]
+    .contains(&name)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::boxed::Box;
+
+    use super::*;
+
+    /// Sets `nested` on the field called `name`, like `#[layout(nested)]`.
+    fn nest(mut layout: TypeLayoutInfo, name: &str, inner: &TypeLayoutInfo) -> TypeLayoutInfo {
+        for field in &mut layout.fields {
+            if let Field::Field {
+                name: field_name,
+                nested,
+                ..
+            } = field
+            {
+                if field_name == name {
+                    *nested = Some(Box::new(inner.clone()));
+                }
+            }
+        }
+
+        layout
+    }
+
+    fn lines(source: &str) -> Vec<&str> {
+        source.lines().collect()
+    }
+
+    #[test]
+    fn vectors_and_matrices() {
+        let vertex = TypeLayoutInfo::builder("Vertex")
+            .field("position", "[f32; 3]", 12, 4)
+            .field("id", "u32", 4, 4)
+            .field("color", "[f32; 4]", 16, 4)
+            .field("uv", "[f32; 2]", 8, 4)
+            .field("cell", "[i32; 2]", 8, 4)
+            .field("transform", "glam::Mat4", 64, 16)
+            .field("normal", "DMat3", 72, 8)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            lines(&vertex.to_wgsl(GpuLayout::Std430)),
+            [
+                "struct Vertex {",
+                "    position: vec3<f32>,",
+                "    id: u32,",
+                "    color: vec4<f32>,",
+                "    uv: vec2<f32>,",
+                "    cell: vec2<i32>,",
+                "    transform: mat4x4<f32>,",
+                "    // `normal` has type `DMat3`, which has no WGSL equivalent",
+                "    _pad0: array<u32, 20>,",
+                "}",
+            ]
+        );
+
+        // dvec3 columns are 32-byte aligned, so a `DMat3` is 96 bytes.
+        assert_eq!(
+            lines(&vertex.to_glsl(GpuLayout::Std430)),
+            [
+                "struct Vertex {",
+                "    vec3 position;",
+                "    uint id;",
+                "    vec4 color;",
+                "    vec2 uv;",
+                "    ivec2 cell;",
+                "    mat4 transform;",
+                "    // warning: `normal` is at offset 112 in Rust, but at offset 128 here",
+                "    dmat3 normal;",
+                "    // warning: `Vertex` is 192 bytes in Rust, but 224 bytes here",
+                "};",
+            ]
+        );
+    }
+
+    #[test]
+    fn arrays() {
+        let arrays = TypeLayoutInfo::builder("Arrays")
+            .field("weights", "[f32; 8]", 32, 4)
+            .field("rows", "[[f32; 3]; 3]", 36, 4)
+            .field("tail", "u32", 4, 4)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            lines(&arrays.to_wgsl(GpuLayout::Std430)),
+            [
+                "struct Arrays {",
+                "    weights: array<f32, 8>,",
+                "    rows: array<vec3<f32>, 3>,",
+                "    // warning: `tail` is at offset 68 in Rust, but at offset 80 here",
+                "    tail: u32,",
+                "    // warning: `Arrays` is 72 bytes in Rust, but 96 bytes here",
+                "}",
+            ]
+        );
+
+        // WGSL places uniform buffer members like std430, but rejects
+        // arrays with elements less than 16 bytes apart.
+        assert_eq!(
+            lines(&arrays.to_wgsl(GpuLayout::Std140))[1],
+            "    // warning: `weights` can't be in a uniform buffer, where arrays and structs \
+             need 16-byte alignment and array elements need to be 16 bytes apart"
+        );
+
+        // GLSL places them 16 bytes apart instead.
+        assert_eq!(
+            lines(&arrays.to_glsl(GpuLayout::Std140)),
+            [
+                "struct Arrays {",
+                "    float weights[8];",
+                "    // warning: `rows` is at offset 32 in Rust, but at offset 128 here",
+                "    vec3 rows[3];",
+                "    // warning: `tail` is at offset 68 in Rust, but at offset 176 here",
+                "    uint tail;",
+                "    // warning: `Arrays` is 72 bytes in Rust, but 192 bytes here",
+                "};",
+            ]
+        );
+    }
+
+    #[test]
+    fn nested_structs() {
+        let inner = TypeLayoutInfo::builder("Inner")
+            .field("x", "[f32; 2]", 8, 4)
+            .field("y", "u32", 4, 4)
+            .build()
+            .unwrap();
+        let outer = TypeLayoutInfo::builder("Outer")
+            .field("a", "f32", 4, 4)
+            .field("first", "Inner", 12, 4)
+            .field("second", "Inner", 12, 4)
+            .build()
+            .unwrap();
+        let outer = nest(nest(outer, "first", &inner), "second", &inner);
+
+        // `Inner` is only declared once.
+        assert_eq!(
+            lines(&outer.to_wgsl(GpuLayout::Std430)),
+            [
+                "struct Inner {",
+                "    x: vec2<f32>,",
+                "    y: u32,",
+                "    // warning: `Inner` is 12 bytes in Rust, but 16 bytes here",
+                "}",
+                "",
+                "struct Outer {",
+                "    a: f32,",
+                "    // warning: `first` is at offset 4 in Rust, but at offset 8 here",
+                "    first: Inner,",
+                "    // warning: `second` is at offset 16 in Rust, but at offset 24 here",
+                "    second: Inner,",
+                "    // warning: `Outer` is 28 bytes in Rust, but 40 bytes here",
+                "}",
+            ]
+        );
+
+        // std140 aligns structs to 16 bytes.
+        assert_eq!(
+            lines(&outer.to_glsl(GpuLayout::Std140)),
+            [
+                "struct Inner {",
+                "    vec2 x;",
+                "    uint y;",
+                "    // warning: `Inner` is 12 bytes in Rust, but 16 bytes here",
+                "};",
+                "",
+                "struct Outer {",
+                "    float a;",
+                "    // warning: `first` is at offset 4 in Rust, but at offset 16 here",
+                "    Inner first;",
+                "    // warning: `second` is at offset 16 in Rust, but at offset 32 here",
+                "    Inner second;",
+                "    // warning: `Outer` is 28 bytes in Rust, but 48 bytes here",
+                "};",
+            ]
+        );
+    }
+
+    #[test]
+    fn arrays_of_nested_structs() {
+        let sample = TypeLayoutInfo::builder("Sample")
+            .field("time", "u32", 4, 4)
+            .field("value", "f32", 4, 4)
+            .build()
+            .unwrap();
+        let recording = TypeLayoutInfo::builder("Recording")
+            .field("id", "u32", 4, 4)
+            .field("samples", "[Sample; 3]", 24, 4)
+            .build()
+            .unwrap();
+        let mut recording = nest(recording, "samples", &sample);
+        if let Field::Field { array, .. } = &mut recording.fields[1] {
+            *array = Some(crate::ArrayInfo {
+                elem_ty: "Sample".into(),
+                len: 3,
+                stride: 8,
+            });
+        }
+
+        assert_eq!(
+            lines(&recording.to_wgsl(GpuLayout::Std430)),
+            [
+                "struct Sample {",
+                "    time: u32,",
+                "    value: f32,",
+                "}",
+                "",
+                "struct Recording {",
+                "    id: u32,",
+                "    samples: array<Sample, 3>,",
+                "}",
+            ]
+        );
+
+        assert_eq!(
+            lines(&recording.to_glsl(GpuLayout::Std140))[6..],
+            [
+                "struct Recording {",
+                "    uint id;",
+                "    // warning: `samples` is at offset 4 in Rust, but at offset 16 here",
+                "    Sample samples[3];",
+                "    // warning: `Recording` is 28 bytes in Rust, but 64 bytes here",
+                "};",
+            ]
+        );
+    }
+
+    #[test]
+    fn unsupported_fields_become_padding() {
+        let flags = TypeLayoutInfo::builder("Flags")
+            .field("enabled", "bool", 1, 1)
+            .field("scale", "f64", 8, 8)
+            .field("loop", "u32", 4, 4)
+            .field("a__b", "i32", 4, 4)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            lines(&flags.to_wgsl(GpuLayout::Std430)),
+            [
+                "struct Flags {",
+                "    // `enabled` has type `bool`, which has no WGSL equivalent",
+                "    // `scale` has type `f64`, which has no WGSL equivalent",
+                "    _pad0: array<u32, 4>,",
+                "    loop_: u32,",
+                "    a_b: i32,",
+                "}",
+            ]
+        );
+
+        // std140 puts array elements 16 bytes apart, so padding is made of
+        // single words.
+        assert_eq!(
+            lines(&flags.to_glsl(GpuLayout::Std140)),
+            [
+                "struct Flags {",
+                "    // `enabled` has type `bool`, which has no GLSL equivalent",
+                "    uint _pad0;",
+                "    uint _pad1;",
+                "    double scale;",
+                "    uint loop;",
+                "    int a_b;",
+                "    // warning: `Flags` is 24 bytes in Rust, but 32 bytes here",
+                "};",
+            ]
+        );
+    }
+}
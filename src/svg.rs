@@ -0,0 +1,200 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use crate::byte_map::collect_leaves;
+use crate::html::escape;
+use crate::{Field, TypeLayoutInfo};
+
+/// Bytes in each row of the diagram.
+const ROW_BYTES: usize = 16;
+
+const BYTE_WIDTH: usize = 36;
+const ROW_HEIGHT: usize = 40;
+
+/// Room to the left of the rows for their offsets.
+const MARGIN: usize = 48;
+
+/// Height of a line of heading text.
+const HEADING: usize = 28;
+
+/// Roughly how wide a character of the labels is.
+const CHAR_WIDTH: usize = 7;
+
+/// Fill colors for fields, used in turn.
+const COLORS: &[&str] = &[
+    "#8dd3c7", "#ffffb3", "#bebada", "#fb8072", "#80b1d3", "#fdb462", "#b3de69", "#fccde5",
+];
+
+impl TypeLayoutInfo {
+    /// Renders the layout as an SVG image, for documentation of binary
+    /// formats. Each field is a colored block whose width is proportional to
+    /// its size, in rows of 16 bytes, and padding is hatched. Hovering over a
+    /// block shows the field's name, type, offset, and size, which is useful
+    /// when the name doesn't fit inside it.
+    ///
+    /// Nested fields are shown as their own fields, named like
+    /// `outer.inner`. When fields share bytes, like the fields of a union,
+    /// only the first is shown, and each variant of an enum gets its own
+    /// diagram.
+    ///
+    /// ```rust
+    /// use type_layout::TypeLayout;
+    ///
+    /// #[derive(TypeLayout)]
+    /// #[repr(C)]
+    /// struct Header {
+    ///     kind: u8,
+    ///     len: u32,
+    ///     checksum: u64,
+    /// }
+    ///
+    /// let svg = Header::type_layout().to_svg();
+    /// assert!(svg.starts_with("<svg"));
+    /// assert!(svg.contains(">checksum</text>"));
+    /// ```
+    pub fn to_svg(&self) -> String {
+        let mut body = String::new();
+        let mut y = HEADING;
+
+        let kind = if self.is_union { "union, " } else { "" };
+        let _ = writeln!(
+            body,
+            "<text x=\"0\" y=\"{}\" font-weight=\"bold\">{} ({}size {}, alignment {})</text>",
+            HEADING - 10,
+            escape(&self.full_name()),
+            kind,
+            self.size,
+            self.alignment
+        );
+
+        if self.variants.is_empty() {
+            y = write_rows(&mut body, &self.fields, self.size, y);
+        } else {
+            for variant in &self.variants {
+                y += HEADING;
+                let _ = writeln!(
+                    body,
+                    "<text x=\"0\" y=\"{}\">Variant {} = {}</text>",
+                    y - 10,
+                    escape(&variant.name),
+                    variant.discriminant
+                );
+                y = write_rows(&mut body, &variant.fields, self.size, y);
+            }
+        }
+
+        let width = MARGIN + self.size.clamp(1, ROW_BYTES) * BYTE_WIDTH + 1;
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" \
+             font-family=\"monospace\" font-size=\"12\">\n",
+            width,
+            y + 1
+        );
+        svg.push_str(
+            "<defs><pattern id=\"hatch\" width=\"6\" height=\"6\" \
+             patternUnits=\"userSpaceOnUse\" patternTransform=\"rotate(45)\">\
+             <rect width=\"6\" height=\"6\" fill=\"#eee\"/>\
+             <line x1=\"0\" y1=\"0\" x2=\"0\" y2=\"6\" stroke=\"#999\" stroke-width=\"2\"/>\
+             </pattern></defs>\n",
+        );
+        svg.push_str(&body);
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+/// Draws the rows for one set of fields, which is either the fields of a
+/// struct or those of one variant of an enum, starting at `y`. Returns the
+/// `y` below the last row.
+fn write_rows(out: &mut String, fields: &[Field], size: usize, mut y: usize) -> usize {
+    let mut leaves = Vec::new();
+    collect_leaves(fields, 0, "", &mut leaves);
+
+    let mut owners = vec![None; size];
+    for (index, leaf) in leaves.iter().enumerate() {
+        for owner in owners.iter_mut().skip(leaf.offset).take(leaf.field.size()) {
+            owner.get_or_insert(index);
+        }
+    }
+
+    for (row, chunk) in owners.chunks(ROW_BYTES).enumerate() {
+        let _ = writeln!(
+            out,
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"end\" fill=\"#666\">{}</text>",
+            MARGIN - 8,
+            y + ROW_HEIGHT / 2 + 4,
+            row * ROW_BYTES
+        );
+
+        let mut start = 0;
+        while start < chunk.len() {
+            let owner = chunk[start];
+            let end = chunk[start..]
+                .iter()
+                .position(|other| *other != owner)
+                .map_or(chunk.len(), |len| start + len);
+
+            let x = MARGIN + start * BYTE_WIDTH;
+            let width = (end - start) * BYTE_WIDTH;
+            let offset = row * ROW_BYTES + start;
+
+            let (fill, label, title) = match owner {
+                Some(index) => {
+                    let leaf = &leaves[index];
+                    let ty = match leaf.field {
+                        Field::Field {
+                            ty, resolved_ty, ..
+                        } => resolved_ty.as_ref().unwrap_or(ty).as_ref(),
+                        Field::Padding { .. } => "",
+                    };
+                    let title = format!(
+                        "{}: {} (offset {}, size {})",
+                        leaf.name,
+                        ty,
+                        leaf.offset,
+                        leaf.field.size()
+                    );
+                    (COLORS[index % COLORS.len()], leaf.name.as_ref(), title)
+                }
+                None => (
+                    "url(#hatch)",
+                    "",
+                    format!("padding (offset {}, size {})", offset, end - start),
+                ),
+            };
+
+            let _ = writeln!(
+                out,
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\" \
+                 stroke=\"#333\"><title>{}</title></rect>",
+                x,
+                y,
+                width,
+                ROW_HEIGHT,
+                fill,
+                escape(&title)
+            );
+
+            // Only the first block of a field that spans rows is labeled.
+            let first = owner.map_or(false, |index| leaves[index].offset == offset);
+            if first && label.len() * CHAR_WIDTH + 6 <= width {
+                let _ = writeln!(
+                    out,
+                    "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\">{}</text>",
+                    x + width / 2,
+                    y + ROW_HEIGHT / 2 + 4,
+                    escape(label)
+                );
+            }
+
+            start = end;
+        }
+
+        y += ROW_HEIGHT;
+    }
+
+    y
+}
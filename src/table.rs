@@ -0,0 +1,711 @@
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::padding::padding_cause;
+use crate::{Field, PaddingKind, TypeLayoutInfo, UnsizedTail};
+
+/// Options for rendering a layout table. Created with [`TableOptions::new`],
+/// which gives the same table that `Display` renders, and used with
+/// [`TypeLayoutInfo::display_with`].
+///
+/// ```rust
+/// use type_layout::{TableOptions, TypeLayout};
+///
+/// #[derive(TypeLayout)]
+/// #[repr(C)]
+/// struct Header {
+///     kind: u8,
+///     len: u32,
+///     flags: [u16; 2],
+/// }
+///
+/// let options = TableOptions::new().types(true).alignments(true);
+/// println!("{}", Header::type_layout().display_with(options));
+/// // prints:
+/// // Header (size 12, alignment 4)
+/// // | Offset | Name             | Type     | Size | Align |
+/// // | ------ | ---------------- | -------- | ---- | ----- |
+/// // | 0      | kind             | u8       | 1    | 1     |
+/// // | 1      | [padding]        |          | 3    |       |
+/// // | 4      | len              | u32      | 4    | 4     |
+/// // | 8      | flags (stride 2) | [u16; 2] | 4    | 2     |
+/// // Option<Header> is 16 bytes (no niche)
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TableOptions {
+    types: bool,
+    alignments: bool,
+    cache_line_size: Option<usize>,
+    box_drawing: bool,
+    color: bool,
+    hex_offsets: bool,
+    offset_ranges: bool,
+    hide_padding: bool,
+    padding_causes: bool,
+    max_name_width: Option<usize>,
+}
+
+impl TableOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a column with the type of each field.
+    pub fn types(mut self, show: bool) -> Self {
+        self.types = show;
+        self
+    }
+
+    /// Adds a column with the alignment of each field, when it's known.
+    pub fn alignments(mut self, show: bool) -> Self {
+        self.alignments = show;
+        self
+    }
+
+    /// Shows offsets in hexadecimal, like `0x1c`, to match debuggers and
+    /// hex dumps.
+    pub fn hex_offsets(mut self, enabled: bool) -> Self {
+        self.hex_offsets = enabled;
+        self
+    }
+
+    /// Shows the range of bytes each row covers, like `4..8`, instead of
+    /// just its offset. The end of the range is exclusive, like Rust's
+    /// ranges.
+    ///
+    /// ```rust
+    /// use type_layout::{TableOptions, TypeLayout};
+    ///
+    /// #[derive(TypeLayout)]
+    /// #[repr(C)]
+    /// struct Header {
+    ///     kind: u8,
+    ///     len: u32,
+    ///     checksum: u64,
+    /// }
+    ///
+    /// let options = TableOptions::new()
+    ///     .hex_offsets(true)
+    ///     .offset_ranges(true)
+    ///     .padding(false);
+    /// println!("{}", Header::type_layout().display_with(options));
+    /// // prints:
+    /// // Header (size 16, alignment 8)
+    /// // | Offset    | Name     | Size |
+    /// // | --------- | -------- | ---- |
+    /// // | 0x0..0x1  | kind     | 1    |
+    /// // | 0x4..0x8  | len      | 4    |
+    /// // | 0x8..0x10 | checksum | 8    |
+    /// // Option<Header> is 24 bytes (no niche)
+    /// ```
+    pub fn offset_ranges(mut self, enabled: bool) -> Self {
+        self.offset_ranges = enabled;
+        self
+    }
+
+    /// Whether to show rows for padding, which is on by default. Hiding them
+    /// makes tables of large types shorter, at the cost of the gaps between
+    /// offsets being the only sign of padding.
+    pub fn padding(mut self, show: bool) -> Self {
+        self.hide_padding = !show;
+        self
+    }
+
+    /// Shows why each run of padding is there next to it, like the field
+    /// whose alignment it's for, which points at the fields to move to get
+    /// rid of it. See [`PaddingCause`](crate::PaddingCause).
+    ///
+    /// ```rust
+    /// use type_layout::{TableOptions, TypeLayout};
+    ///
+    /// #[derive(TypeLayout)]
+    /// #[repr(C)]
+    /// struct Header {
+    ///     kind: u8,
+    ///     len: u32,
+    ///     flags: u8,
+    /// }
+    ///
+    /// let options = TableOptions::new().padding_causes(true);
+    /// println!("{}", Header::type_layout().display_with(options));
+    /// // prints:
+    /// // Header (size 12, alignment 4)
+    /// // | Offset | Name                                       | Size |
+    /// // | ------ | ------------------------------------------ | ---- |
+    /// // | 0      | kind                                       | 1    |
+    /// // | 1      | [padding] (align for `len: u32`)           | 3    |
+    /// // | 4      | len                                        | 4    |
+    /// // | 8      | flags                                      | 1    |
+    /// // | 9      | [tail padding] (round size to alignment 4) | 3    |
+    /// // Option<Header> is 16 bytes (no niche)
+    /// ```
+    pub fn padding_causes(mut self, show: bool) -> Self {
+        self.padding_causes = show;
+        self
+    }
+
+    /// Shortens names longer than `width` characters, ending them with `...`,
+    /// so that long generic names or deep nesting don't push the other
+    /// columns off the screen. Indentation of nested fields counts towards
+    /// the width.
+    pub fn max_name_width(mut self, width: usize) -> Self {
+        self.max_name_width = Some(width);
+        self
+    }
+
+    /// Draws a separator row before each row that starts on a new cache line
+    /// of `line_size` bytes. See [`TypeLayoutInfo::with_cache_lines`].
+    ///
+    /// # Panics
+    ///
+    /// Rendering panics if `line_size` is zero.
+    pub fn cache_lines(mut self, line_size: usize) -> Self {
+        self.cache_line_size = Some(line_size);
+        self
+    }
+
+    /// Draws the table with box-drawing characters instead of Markdown
+    /// pipes, for terminals and docs that aren't rendered as Markdown.
+    ///
+    /// ```rust
+    /// use type_layout::{TableOptions, TypeLayout};
+    ///
+    /// #[derive(TypeLayout)]
+    /// #[repr(C)]
+    /// struct Header {
+    ///     kind: u8,
+    ///     len: u32,
+    /// }
+    ///
+    /// let options = TableOptions::new().box_drawing(true);
+    /// println!("{}", Header::type_layout().display_with(options));
+    /// // prints:
+    /// // Header (size 8, alignment 4)
+    /// // ┌────────┬───────────┬──────┐
+    /// // │ Offset │ Name      │ Size │
+    /// // ├────────┼───────────┼──────┤
+    /// // │ 0      │ kind      │ 1    │
+    /// // │ 1      │ [padding] │ 3    │
+    /// // │ 4      │ len       │ 4    │
+    /// // └────────┴───────────┴──────┘
+    /// // Option<Header> is 12 bytes (no niche)
+    /// ```
+    pub fn box_drawing(mut self, enabled: bool) -> Self {
+        self.box_drawing = enabled;
+        self
+    }
+
+    /// Colors rows with ANSI escape codes: padding in dim red, and fields
+    /// with padding inside them in yellow, so wasted space stands out when
+    /// scanning many layouts. Only fields marked `#[layout(nested)]` are
+    /// known to have padding inside them.
+    ///
+    /// This method is only available with the `color` feature.
+    #[cfg(feature = "color")]
+    pub fn color(mut self, enabled: bool) -> Self {
+        self.color = enabled;
+        self
+    }
+
+    /// Colors rows like [`color`](TableOptions::color) when `stream` is a
+    /// terminal and the `NO_COLOR` environment variable isn't set, so output
+    /// piped to a file or another program stays plain.
+    ///
+    /// ```rust
+    /// use type_layout::{TableOptions, TypeLayout};
+    ///
+    /// #[derive(TypeLayout)]
+    /// #[repr(C)]
+    /// struct Header {
+    ///     kind: u8,
+    ///     len: u32,
+    /// }
+    ///
+    /// let options = TableOptions::new().color_if_terminal(&std::io::stdout());
+    /// println!("{}", Header::type_layout().display_with(options));
+    /// ```
+    ///
+    /// This method is only available with the `color` feature, which needs
+    /// Rust 1.70 or newer.
+    #[cfg(feature = "color")]
+    #[clippy::msrv = "1.70"]
+    pub fn color_if_terminal<T: std::io::IsTerminal>(self, stream: &T) -> Self {
+        let enabled = stream.is_terminal() && std::env::var_os("NO_COLOR").is_none();
+        self.color(enabled)
+    }
+}
+
+impl TypeLayoutInfo {
+    /// Renders the layout table with the given options.
+    pub fn display_with(&self, options: TableOptions) -> DisplayWith<'_> {
+        DisplayWith {
+            layout: self,
+            options,
+        }
+    }
+
+    pub(crate) fn write_table<W: fmt::Write + ?Sized>(
+        &self,
+        writer: &mut W,
+        options: &TableOptions,
+    ) -> fmt::Result {
+        if let Some(line_size) = options.cache_line_size {
+            assert!(line_size > 0, "cache line size must be greater than zero");
+        }
+
+        let kind = if self.is_union { "union, " } else { "" };
+
+        write!(
+            writer,
+            "{} ({}size {}, alignment {}",
+            self.full_name(),
+            kind,
+            self.size,
+            self.alignment
+        )?;
+
+        if self.natural_alignment != self.alignment {
+            write!(writer, " (natural {})", self.natural_alignment)?;
+        }
+
+        writeln!(writer, ")")?;
+
+        if self.variants.is_empty() {
+            write_fields(
+                writer,
+                self,
+                &self.fields,
+                self.unsized_tail.as_ref(),
+                options,
+            )?;
+        } else {
+            for variant in &self.variants {
+                writeln!(
+                    writer,
+                    "Variant {} = {}",
+                    variant.name, variant.discriminant
+                )?;
+                write_fields(writer, self, &variant.fields, None, options)?;
+            }
+        }
+
+        let any_needs_drop = self.all_fields().any(|field| match field {
+            Field::Field { needs_drop, .. } => *needs_drop,
+            Field::Padding { .. } => false,
+        });
+
+        if any_needs_drop {
+            writeln!(writer, "* needs drop")?;
+        }
+
+        let any_niche = self.all_fields().any(|field| match field {
+            Field::Field { niche, .. } => *niche,
+            Field::Padding { .. } => false,
+        });
+
+        if any_niche {
+            writeln!(
+                writer,
+                "(niche) the Option's tag is stored in its payload, using no extra space"
+            )?;
+        }
+
+        if let Some(option_size) = self.option_size {
+            let niche = if option_size == self.size {
+                "has a niche"
+            } else {
+                "no niche"
+            };
+
+            writeln!(
+                writer,
+                "Option<{}> is {} bytes ({})",
+                self.full_name(),
+                option_size,
+                niche
+            )?;
+        }
+
+        if !self.guaranteed {
+            writeln!(
+                writer,
+                "warning: {} has no #[repr(C)], so the compiler may reorder its fields",
+                self.full_name()
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Renders a layout table with options. Created by
+/// [`TypeLayoutInfo::display_with`].
+pub struct DisplayWith<'a> {
+    layout: &'a TypeLayoutInfo,
+    options: TableOptions,
+}
+
+impl fmt::Display for DisplayWith<'_> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.layout.write_table(formatter, &self.options)
+    }
+}
+
+/// One row of a table, with a cell for each column.
+struct Row<'a> {
+    offset: usize,
+    cells: Vec<Cow<'a, str>>,
+
+    /// The escape code to color the row with, if any.
+    color: Option<&'static str>,
+}
+
+/// Dim red, for padding.
+const PADDING_COLOR: &str = "\x1b[2;31m";
+
+/// Yellow, for fields with padding inside them.
+const PADDED_FIELD_COLOR: &str = "\x1b[33m";
+
+const RESET: &str = "\x1b[0m";
+
+/// Renders one table of fields, which is either the fields of a struct or
+/// those of one variant of an enum. An unsized tail is shown after the fields.
+fn write_fields<W: fmt::Write + ?Sized>(
+    writer: &mut W,
+    layout: &TypeLayoutInfo,
+    fields: &[Field],
+    tail: Option<&UnsizedTail>,
+    options: &TableOptions,
+) -> fmt::Result {
+    let mut headers = vec!["Offset", "Name"];
+    if options.types {
+        headers.push("Type");
+    }
+    headers.push("Size");
+    if options.alignments {
+        headers.push("Align");
+    }
+
+    let mut rows = Vec::new();
+    collect_rows(layout, fields, 0, 0, options, &mut rows);
+    if let Some(tail) = tail {
+        rows.push(tail_row(tail, options));
+    }
+
+    let mut widths: Vec<usize> = headers.iter().map(|header| header.len()).collect();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(&row.cells) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    let style = if options.box_drawing {
+        &BOX_DRAWING
+    } else {
+        &MARKDOWN
+    };
+
+    write_border(writer, &widths, style.top)?;
+    write_row(writer, &widths, &headers, style.vertical)?;
+    write_border(writer, &widths, style.header)?;
+
+    let mut current_line = 0;
+
+    for row in &rows {
+        if let Some(line_size) = options.cache_line_size {
+            let line = row.offset / line_size;
+
+            if line > current_line {
+                current_line = line;
+                write_border(writer, &widths, style.cache_line)?;
+            }
+        }
+
+        match row.color {
+            Some(color) => {
+                write!(writer, "{}", color)?;
+                write_cells(writer, &widths, &row.cells, style.vertical)?;
+                writeln!(writer, "{}", RESET)?;
+            }
+            None => write_row(writer, &widths, &row.cells, style.vertical)?,
+        }
+    }
+
+    write_border(writer, &widths, style.bottom)
+}
+
+/// The characters a table is drawn with.
+struct Style {
+    vertical: char,
+    top: Option<Border>,
+    header: Option<Border>,
+    cache_line: Option<Border>,
+    bottom: Option<Border>,
+}
+
+/// A horizontal line across the table: its left end, the line, the crossing
+/// with each column separator, and its right end.
+type Border = (char, char, char, char);
+
+const MARKDOWN: Style = Style {
+    vertical: '|',
+    top: None,
+    header: Some(('|', '-', '|', '|')),
+    cache_line: Some(('|', '=', '|', '|')),
+    bottom: None,
+};
+
+const BOX_DRAWING: Style = Style {
+    vertical: '│',
+    top: Some(('┌', '─', '┬', '┐')),
+    header: Some(('├', '─', '┼', '┤')),
+    cache_line: Some(('╞', '═', '╪', '╡')),
+    bottom: Some(('└', '─', '┴', '┘')),
+};
+
+/// Flattens fields into table rows, following each field's nested layout so
+/// that its fields appear indented below it. `fields` are those of `layout`
+/// or of one of its variants, and `base` is the offset of their parent from
+/// the start of the outermost type.
+fn collect_rows<'a>(
+    layout: &TypeLayoutInfo,
+    fields: &'a [Field],
+    base: usize,
+    depth: usize,
+    options: &TableOptions,
+    rows: &mut Vec<Row<'a>>,
+) {
+    for (index, field) in fields.iter().enumerate() {
+        if options.hide_padding && field.is_padding() {
+            continue;
+        }
+
+        let offset = base + field.offset();
+
+        let mut name = display_name(field, options);
+        if options.padding_causes && field.is_padding() {
+            let cause = padding_cause(layout, fields, index);
+            name = Cow::Owned(format!("{} ({})", name, cause));
+        }
+        let name = if depth == 0 {
+            name
+        } else {
+            Cow::Owned(format!("{}{}", "  ".repeat(depth), name))
+        };
+
+        let mut cells = vec![
+            Cow::Owned(offset_cell(offset, Some(field.size()), options)),
+            truncate(name, options),
+        ];
+
+        if options.types {
+            cells.push(match field {
+                Field::Field {
+                    ty, resolved_ty, ..
+                } => Cow::Borrowed(resolved_ty.as_ref().unwrap_or(ty).as_ref()),
+                Field::Padding { .. } => Cow::Borrowed(""),
+            });
+        }
+
+        cells.push(Cow::Owned(field.size().to_string()));
+
+        if options.alignments {
+            cells.push(match field.alignment() {
+                Some(alignment) => Cow::Owned(alignment.to_string()),
+                None => Cow::Borrowed(""),
+            });
+        }
+
+        let color = match field {
+            _ if !options.color => None,
+            Field::Padding { .. } => Some(PADDING_COLOR),
+            Field::Field {
+                nested: Some(nested),
+                ..
+            } if nested.fields.iter().any(|field| field.is_padding()) => Some(PADDED_FIELD_COLOR),
+            Field::Field { .. } => None,
+        };
+
+        rows.push(Row {
+            offset,
+            cells,
+            color,
+        });
+
+        if let Field::Field {
+            nested: Some(nested),
+            ..
+        } = field
+        {
+            collect_rows(nested, &nested.fields, offset, depth + 1, options, rows);
+        }
+    }
+}
+
+/// The row for an unsized tail, whose size is shown as `unsized`.
+fn tail_row<'a>(tail: &'a UnsizedTail, options: &TableOptions) -> Row<'a> {
+    let mut name = tail.name.to_string();
+    if !options.types {
+        name.push_str(&format!(": {}", tail.ty));
+    }
+    if let Some(stride) = tail.stride {
+        name.push_str(&format!(" (stride {})", stride));
+    }
+
+    let mut cells = vec![
+        Cow::Owned(offset_cell(tail.offset, None, options)),
+        truncate(Cow::Owned(name), options),
+    ];
+    if options.types {
+        cells.push(Cow::Borrowed(tail.ty.as_ref()));
+    }
+    cells.push(Cow::Borrowed("unsized"));
+    if options.alignments {
+        cells.push(Cow::Borrowed(""));
+    }
+
+    Row {
+        offset: tail.offset,
+        cells,
+        color: None,
+    }
+}
+
+/// The offset column of a row. Unsized tails have no size, so their range
+/// has no end.
+fn offset_cell(offset: usize, size: Option<usize>, options: &TableOptions) -> String {
+    let format = |value: usize| {
+        if options.hex_offsets {
+            format!("{:#x}", value)
+        } else {
+            value.to_string()
+        }
+    };
+
+    if !options.offset_ranges {
+        return format(offset);
+    }
+
+    match size {
+        Some(size) => format!("{}..{}", format(offset), format(offset + size)),
+        None => format!("{}..", format(offset)),
+    }
+}
+
+/// Shortens a name to the maximum width from the options, if there is one.
+fn truncate<'a>(name: Cow<'a, str>, options: &TableOptions) -> Cow<'a, str> {
+    let width = match options.max_name_width {
+        Some(width) if name.chars().count() > width => width,
+        _ => return name,
+    };
+
+    let kept = width.saturating_sub(3);
+    let mut truncated: String = name.chars().take(kept).collect();
+    truncated.push_str(&"..."[..width - kept]);
+    Cow::Owned(truncated)
+}
+
+/// The name of a field as it's shown in the table. When there's no type
+/// column, the type of an array is shown next to its name.
+fn display_name<'a>(field: &'a Field, options: &TableOptions) -> Cow<'a, str> {
+    match field {
+        Field::Field {
+            name,
+            ty,
+            resolved_ty,
+            array,
+            needs_drop,
+            niche,
+            ..
+        } => {
+            if array.is_none() && !needs_drop && !niche {
+                return Cow::Borrowed(name);
+            }
+
+            let mut display = name.to_string();
+            if *needs_drop {
+                display.push('*');
+            }
+            if *niche {
+                display.push_str(" (niche)");
+            }
+            if let Some(array) = array {
+                if options.types {
+                    display.push_str(&format!(" (stride {})", array.stride));
+                } else {
+                    let ty = resolved_ty.as_ref().unwrap_or(ty);
+                    display.push_str(&format!(": {} (stride {})", ty, array.stride));
+                }
+            }
+
+            Cow::Owned(display)
+        }
+        Field::Padding {
+            kind: PaddingKind::Interior,
+            ..
+        } => Cow::Borrowed("[padding]"),
+        Field::Padding {
+            kind: PaddingKind::Trailing,
+            ..
+        } => Cow::Borrowed("[tail padding]"),
+    }
+}
+
+fn write_row<W: fmt::Write + ?Sized, S: AsRef<str>>(
+    writer: &mut W,
+    widths: &[usize],
+    cells: &[S],
+    vertical: char,
+) -> fmt::Result {
+    write_cells(writer, widths, cells, vertical)?;
+    writeln!(writer)
+}
+
+/// Writes a row without ending the line.
+fn write_cells<W: fmt::Write + ?Sized, S: AsRef<str>>(
+    writer: &mut W,
+    widths: &[usize],
+    cells: &[S],
+    vertical: char,
+) -> fmt::Result {
+    for (width, cell) in widths.iter().zip(cells) {
+        write!(
+            writer,
+            "{} {:<width$} ",
+            vertical,
+            cell.as_ref(),
+            width = width
+        )?;
+    }
+
+    write!(writer, "{}", vertical)
+}
+
+/// Draws a horizontal line across the table. The Markdown separator keeps a
+/// space on either side of each line, like the cells of [`write_row`].
+fn write_border<W: fmt::Write + ?Sized>(
+    writer: &mut W,
+    widths: &[usize],
+    border: Option<Border>,
+) -> fmt::Result {
+    let (left, line, cross, right) = match border {
+        Some(border) => border,
+        None => return Ok(()),
+    };
+
+    let padding = if left == '|' { ' ' } else { line };
+    for (index, width) in widths.iter().enumerate() {
+        let start = if index == 0 { left } else { cross };
+        write!(writer, "{}{}", start, padding)?;
+        for _ in 0..*width {
+            write!(writer, "{}", line)?;
+        }
+        write!(writer, "{}", padding)?;
+    }
+
+    writeln!(writer, "{}", right)
+}
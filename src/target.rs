@@ -0,0 +1,89 @@
+use alloc::borrow::Cow;
+use core::fmt;
+
+// The `JsonSchema` derive expects these from the standard prelude.
+#[cfg(feature = "schemars")]
+use alloc::{borrow::ToOwned, vec};
+
+/// The target a layout was computed for. Layouts depend on the target, so
+/// this tells layouts that were serialized on different machines apart.
+///
+/// ```rust
+/// use type_layout::{Endianness, TypeLayout};
+///
+/// #[derive(TypeLayout)]
+/// #[repr(C)]
+/// struct Header {
+///     kind: u8,
+///     len: usize,
+/// }
+///
+/// let target = Header::type_layout().target.unwrap();
+/// assert_eq!(target.pointer_width, usize::BITS as usize);
+/// if cfg!(target_endian = "little") {
+///     assert_eq!(target.endianness, Endianness::Little);
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct TargetInfo {
+    /// The size of a pointer, in bits.
+    pub pointer_width: usize,
+
+    pub endianness: Endianness,
+
+    /// The target triple, like `x86_64-unknown-linux-gnu`, if it's known.
+    #[cfg_attr(feature = "serde1", serde(default))]
+    pub triple: Option<Cow<'static, str>>,
+}
+
+impl TargetInfo {
+    /// The target this crate was compiled for, which is the target of every
+    /// layout that `#[derive(TypeLayout)]` reports.
+    pub fn current() -> Self {
+        let endianness = if cfg!(target_endian = "big") {
+            Endianness::Big
+        } else {
+            Endianness::Little
+        };
+
+        TargetInfo {
+            pointer_width: core::mem::size_of::<usize>() * 8,
+            endianness,
+            triple: option_env!("TYPE_LAYOUT_TARGET").map(Cow::Borrowed),
+        }
+    }
+}
+
+impl fmt::Display for TargetInfo {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(triple) = &self.triple {
+            write!(formatter, "{} ", triple)?;
+        }
+
+        write!(
+            formatter,
+            "({}-bit, {} endian)",
+            self.pointer_width, self.endianness
+        )
+    }
+}
+
+/// The order of the bytes of a number in memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum Endianness {
+    Little,
+    Big,
+}
+
+impl fmt::Display for Endianness {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Endianness::Little => write!(formatter, "little"),
+            Endianness::Big => write!(formatter, "big"),
+        }
+    }
+}
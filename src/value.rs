@@ -0,0 +1,77 @@
+use core::fmt;
+
+use crate::{TypeLayout, TypeLayoutInfo, Variant};
+
+/// The layout of a value's type, along with the variant the value holds if
+/// the type is an enum. Created by [`layout_of_value`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct ValueLayout {
+    pub layout: TypeLayoutInfo,
+
+    /// The index in `layout.variants` of the variant the value holds, or
+    /// `None` if the type isn't an enum.
+    pub variant_index: Option<usize>,
+}
+
+impl ValueLayout {
+    /// The variant the value holds, if its type is an enum.
+    pub fn variant(&self) -> Option<&Variant> {
+        self.variant_index
+            .and_then(|index| self.layout.variants.get(index))
+    }
+}
+
+/// Renders the layout like [`TypeLayoutInfo`] does, except that enums only
+/// show the variant the value holds.
+impl fmt::Display for ValueLayout {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        if self.layout.variants.is_empty() {
+            return fmt::Display::fmt(&self.layout, formatter);
+        }
+
+        let mut layout = self.layout.clone();
+        layout.variants = self.variant().cloned().into_iter().collect();
+        fmt::Display::fmt(&layout, formatter)
+    }
+}
+
+/// Returns the layout of a value's type, along with the variant it holds if
+/// it's an enum. This is useful for inspecting live data, where the variant
+/// matters more than the layout of every other one.
+///
+/// ```rust
+/// use type_layout::{layout_of_value, TypeLayout};
+///
+/// #[derive(TypeLayout)]
+/// #[repr(u8)]
+/// enum Shape {
+///     Empty,
+///     Circle { radius: f32 },
+///     Rect(u16, u16),
+/// }
+///
+/// let value = layout_of_value(&Shape::Rect(3, 4));
+/// let variant = value.variant().unwrap();
+/// assert_eq!(variant.name, "Rect");
+/// assert_eq!(variant.field("1").unwrap().offset(), 4);
+///
+/// println!("{}", value);
+/// // prints:
+/// // Shape (size 8, alignment 4)
+/// // Variant Rect = 2
+/// // | Offset | Name           | Size |
+/// // | ------ | -------------- | ---- |
+/// // | 0      | [tag]          | 1    |
+/// // | 1      | [padding]      | 1    |
+/// // | 2      | 0              | 2    |
+/// // | 4      | 1              | 2    |
+/// // | 6      | [tail padding] | 2    |
+/// // Option<Shape> is 8 bytes (has a niche)
+/// ```
+pub fn layout_of_value<T: TypeLayout + ?Sized>(value: &T) -> ValueLayout {
+    ValueLayout {
+        layout: T::type_layout(),
+        variant_index: value.__variant_index(),
+    }
+}
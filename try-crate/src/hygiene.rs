@@ -0,0 +1,139 @@
+//! Derives in a module without the prelude, and with a `std` module that
+//! shadows the real one, so that any path in the generated code that isn't
+//! fully qualified fails to build.
+
+#![no_implicit_prelude]
+#![allow(dead_code)]
+
+use ::type_layout::{document_layout, TypeLayout};
+
+mod std {}
+mod core {}
+
+#[derive(TypeLayout)]
+#[layout(max_padding = 3, max_size = 8, size = 8, align = 4, offset_consts)]
+#[repr(C)]
+pub struct Named {
+    tag: u8,
+    #[layout(offset = 4)]
+    value: u32,
+}
+
+#[derive(TypeLayout)]
+#[layout(offset_consts, const_layout)]
+pub struct Tuple(u8, u16);
+
+#[derive(TypeLayout)]
+pub struct Unit;
+
+#[derive(TypeLayout)]
+#[layout(max_size = 72, const_layout)]
+pub struct Generic<T, const N: usize> {
+    #[layout(type_id)]
+    items: [T; N],
+    literal: [u8; 4],
+    #[layout(nested)]
+    named: Named,
+}
+
+#[document_layout]
+#[layout(embed)]
+#[repr(C)]
+pub struct Documented {
+    a: u8,
+    b: u16,
+    #[cfg(any())]
+    c: Missing,
+}
+
+#[derive(TypeLayout)]
+#[layout(max_size = 16)]
+#[repr(C, u8)]
+pub enum Tagged<T> {
+    Empty,
+    One(T),
+    Two { first: T, second: u16 },
+}
+
+#[derive(TypeLayout)]
+#[layout(max_padding = 0)]
+#[repr(C)]
+pub union Overlapping {
+    pub small: u8,
+    pub large: u64,
+}
+
+::type_layout::assert_layout!(Named: size == 8, align == 4, tag @ 0, value @ 4);
+
+#[derive(TypeLayout)]
+#[layout(offset_consts, const_layout)]
+#[repr(C, packed(2))]
+pub struct Packed {
+    tag: u8,
+    value: u64,
+}
+
+#[derive(TypeLayout)]
+pub struct Slice<T> {
+    len: u16,
+    items: [T],
+}
+
+#[derive(TypeLayout)]
+#[repr(C)]
+pub struct Object {
+    tag: u8,
+    rest: dyn ::core::fmt::Debug,
+}
+
+#[derive(TypeLayout)]
+#[layout(bound = "T: ::type_layout::TypeLayout + 'static")]
+#[repr(C)]
+pub struct Bounded<T> {
+    #[layout(type_id, nested)]
+    value: T,
+    #[layout(nested)]
+    named: Named,
+}
+
+#[derive(TypeLayout)]
+#[layout(no_bound)]
+pub struct Unbounded {
+    #[layout(nested)]
+    named: Named,
+}
+
+pub mod facade {
+    pub use ::type_layout::*;
+}
+
+#[derive(TypeLayout)]
+#[layout(crate = "self::facade", offset_consts, const_layout, embed)]
+#[repr(C)]
+pub struct Reexported {
+    #[layout(rename = "kind", ty = "uint8_t")]
+    a: u8,
+    #[layout(offset = 2)]
+    b: u16,
+    #[layout(skip)]
+    hidden: [u8; 4],
+}
+
+pub fn check() {
+    let _ = <Named as TypeLayout>::type_layout();
+    let _ = <Tuple as TypeLayout>::type_layout();
+    let _ = <Unit as TypeLayout>::type_layout();
+    let _ = <Generic<u16, 3> as TypeLayout>::type_layout();
+    let _ = <Documented as TypeLayout>::type_layout();
+    let _ = <Tagged<u32> as TypeLayout>::type_layout();
+    let _ = ::type_layout::layout_of_value(&Tagged::One(1u32));
+    let _ = <Slice<u64> as TypeLayout>::type_layout();
+    let _ = <Object as TypeLayout>::type_layout();
+    let _ = <Bounded<u32> as TypeLayout>::type_layout();
+    let _ = <Unbounded as TypeLayout>::type_layout();
+    let _ = <Reexported as TypeLayout>::type_layout();
+    let _ = <Overlapping as TypeLayout>::type_layout();
+    let _ = <Packed as TypeLayout>::type_layout();
+    let _ = <Tuple as ::type_layout::ConstTypeLayout>::LAYOUT;
+    let _ = <Generic<u16, 3> as ::type_layout::ConstTypeLayout>::LAYOUT;
+}
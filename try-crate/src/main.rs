@@ -1,4 +1,8 @@
-use type_layout::TypeLayout;
+mod hygiene;
+mod offsets;
+mod stress;
+
+use type_layout::{document_layout, TypeLayout};
 
 #[repr(C)]
 #[derive(TypeLayout)]
@@ -13,7 +17,63 @@ struct Bar {
     b: u16,
 }
 
+/// A header whose docs list its fields, since `u32` and `u16` are aligned
+/// differently on some targets.
+#[document_layout]
+#[layout(embed)]
+#[repr(C)]
+struct Header {
+    tag: u8,
+    len: u32,
+    flags: [u16; 3],
+}
+
+/// A struct whose docs can only list its fields.
+#[document_layout]
+#[derive(TypeLayout)]
+struct Listed {
+    a: u64,
+    b: usize,
+}
+
+#[derive(TypeLayout)]
+#[layout(max_padding = 3, max_size = 8)]
+#[repr(C)]
+struct Budgeted<T> {
+    tag: u8,
+    value: T,
+}
+
+#[derive(TypeLayout)]
+#[repr(C)]
+struct Samples<const N: usize> {
+    count: u8,
+    samples: [f32; 16],
+    scratch: [u16; N],
+    empty: [u64; 0],
+}
+
 fn main() {
+    offsets::check();
+    hygiene::check();
+
     println!("{}", Foo::type_layout());
     println!("{}", Bar::type_layout());
+    println!("{}", Header::type_layout());
+    println!("{}", Listed::type_layout());
+    println!("{}", Budgeted::<u32>::type_layout());
+    println!("{}", Samples::<3>::type_layout());
+
+    let stress = stress::Stress::type_layout();
+    println!(
+        "Stress (size {}, {} rows)",
+        stress.size,
+        stress.fields.len()
+    );
+
+    let mut registered: Vec<_> = type_layout::registry::iter()
+        .map(|registration| registration.name)
+        .collect();
+    registered.sort();
+    println!("Registered types: {}", registered.join(", "));
 }
@@ -0,0 +1,61 @@
+//! Checks that the derive reports the same offsets as `core::mem::offset_of!`,
+//! whichever `offset_of!` type-layout is configured to use. Build with and
+//! without the `memoffset` feature of this crate to cover both.
+
+use core::mem::offset_of;
+
+use type_layout::TypeLayout;
+
+#[derive(TypeLayout)]
+#[layout(offset_consts)]
+#[repr(C)]
+pub struct Pair(u8, u64);
+
+#[derive(TypeLayout)]
+#[layout(offset_consts)]
+pub struct Reordered(u8, u32, u16);
+
+#[derive(TypeLayout)]
+#[layout(offset_consts)]
+#[repr(C)]
+pub struct Wrapped<T>(u16, T);
+
+#[derive(TypeLayout)]
+#[layout(offset_consts)]
+#[repr(C, packed)]
+pub struct Packed {
+    tag: u8,
+    value: u32,
+}
+
+const _: () = assert!(Pair::OFFSET_OF_0 == offset_of!(Pair, 0));
+const _: () = assert!(Pair::OFFSET_OF_1 == offset_of!(Pair, 1));
+const _: () = assert!(Reordered::OFFSET_OF_0 == offset_of!(Reordered, 0));
+const _: () = assert!(Reordered::OFFSET_OF_1 == offset_of!(Reordered, 1));
+const _: () = assert!(Reordered::OFFSET_OF_2 == offset_of!(Reordered, 2));
+const _: () = assert!(Wrapped::<u64>::OFFSET_OF_1 == offset_of!(Wrapped<u64>, 1));
+const _: () = assert!(Packed::OFFSET_OF_TAG == offset_of!(Packed, tag));
+const _: () = assert!(Packed::OFFSET_OF_VALUE == offset_of!(Packed, value));
+
+/// Panics if any offset reported by `type_layout()` differs from
+/// `core::mem::offset_of!`.
+pub fn check() {
+    fn offset(layout: &type_layout::TypeLayoutInfo, field: &str) -> usize {
+        layout.field(field).unwrap().offset()
+    }
+
+    let pair = Pair::type_layout();
+    assert_eq!(offset(&pair, "0"), offset_of!(Pair, 0));
+    assert_eq!(offset(&pair, "1"), offset_of!(Pair, 1));
+
+    let reordered = Reordered::type_layout();
+    assert_eq!(offset(&reordered, "0"), offset_of!(Reordered, 0));
+    assert_eq!(offset(&reordered, "1"), offset_of!(Reordered, 1));
+    assert_eq!(offset(&reordered, "2"), offset_of!(Reordered, 2));
+
+    let wrapped = Wrapped::<[u8; 3]>::type_layout();
+    assert_eq!(offset(&wrapped, "1"), offset_of!(Wrapped<[u8; 3]>, 1));
+
+    let packed = Packed::type_layout();
+    assert_eq!(offset(&packed, "value"), offset_of!(Packed, value));
+}
@@ -0,0 +1,329 @@
+//! A struct with a few hundred fields, to keep an eye on how much code the
+//! derive generates for large types like bindgen output.
+
+use type_layout::TypeLayout;
+
+#[derive(TypeLayout)]
+#[repr(C)]
+pub struct Stress {
+    pub field_0: u8,
+    pub field_1: u16,
+    pub field_2: u32,
+    pub field_3: u64,
+    pub field_4: f32,
+    pub field_5: f64,
+    pub field_6: [u8; 3],
+    pub field_7: bool,
+    pub field_8: u8,
+    pub field_9: u16,
+    pub field_10: u32,
+    pub field_11: u64,
+    pub field_12: f32,
+    pub field_13: f64,
+    pub field_14: [u8; 3],
+    pub field_15: bool,
+    pub field_16: u8,
+    pub field_17: u16,
+    pub field_18: u32,
+    pub field_19: u64,
+    pub field_20: f32,
+    pub field_21: f64,
+    pub field_22: [u8; 3],
+    pub field_23: bool,
+    pub field_24: u8,
+    pub field_25: u16,
+    pub field_26: u32,
+    pub field_27: u64,
+    pub field_28: f32,
+    pub field_29: f64,
+    pub field_30: [u8; 3],
+    pub field_31: bool,
+    pub field_32: u8,
+    pub field_33: u16,
+    pub field_34: u32,
+    pub field_35: u64,
+    pub field_36: f32,
+    pub field_37: f64,
+    pub field_38: [u8; 3],
+    pub field_39: bool,
+    pub field_40: u8,
+    pub field_41: u16,
+    pub field_42: u32,
+    pub field_43: u64,
+    pub field_44: f32,
+    pub field_45: f64,
+    pub field_46: [u8; 3],
+    pub field_47: bool,
+    pub field_48: u8,
+    pub field_49: u16,
+    pub field_50: u32,
+    pub field_51: u64,
+    pub field_52: f32,
+    pub field_53: f64,
+    pub field_54: [u8; 3],
+    pub field_55: bool,
+    pub field_56: u8,
+    pub field_57: u16,
+    pub field_58: u32,
+    pub field_59: u64,
+    pub field_60: f32,
+    pub field_61: f64,
+    pub field_62: [u8; 3],
+    pub field_63: bool,
+    pub field_64: u8,
+    pub field_65: u16,
+    pub field_66: u32,
+    pub field_67: u64,
+    pub field_68: f32,
+    pub field_69: f64,
+    pub field_70: [u8; 3],
+    pub field_71: bool,
+    pub field_72: u8,
+    pub field_73: u16,
+    pub field_74: u32,
+    pub field_75: u64,
+    pub field_76: f32,
+    pub field_77: f64,
+    pub field_78: [u8; 3],
+    pub field_79: bool,
+    pub field_80: u8,
+    pub field_81: u16,
+    pub field_82: u32,
+    pub field_83: u64,
+    pub field_84: f32,
+    pub field_85: f64,
+    pub field_86: [u8; 3],
+    pub field_87: bool,
+    pub field_88: u8,
+    pub field_89: u16,
+    pub field_90: u32,
+    pub field_91: u64,
+    pub field_92: f32,
+    pub field_93: f64,
+    pub field_94: [u8; 3],
+    pub field_95: bool,
+    pub field_96: u8,
+    pub field_97: u16,
+    pub field_98: u32,
+    pub field_99: u64,
+    pub field_100: f32,
+    pub field_101: f64,
+    pub field_102: [u8; 3],
+    pub field_103: bool,
+    pub field_104: u8,
+    pub field_105: u16,
+    pub field_106: u32,
+    pub field_107: u64,
+    pub field_108: f32,
+    pub field_109: f64,
+    pub field_110: [u8; 3],
+    pub field_111: bool,
+    pub field_112: u8,
+    pub field_113: u16,
+    pub field_114: u32,
+    pub field_115: u64,
+    pub field_116: f32,
+    pub field_117: f64,
+    pub field_118: [u8; 3],
+    pub field_119: bool,
+    pub field_120: u8,
+    pub field_121: u16,
+    pub field_122: u32,
+    pub field_123: u64,
+    pub field_124: f32,
+    pub field_125: f64,
+    pub field_126: [u8; 3],
+    pub field_127: bool,
+    pub field_128: u8,
+    pub field_129: u16,
+    pub field_130: u32,
+    pub field_131: u64,
+    pub field_132: f32,
+    pub field_133: f64,
+    pub field_134: [u8; 3],
+    pub field_135: bool,
+    pub field_136: u8,
+    pub field_137: u16,
+    pub field_138: u32,
+    pub field_139: u64,
+    pub field_140: f32,
+    pub field_141: f64,
+    pub field_142: [u8; 3],
+    pub field_143: bool,
+    pub field_144: u8,
+    pub field_145: u16,
+    pub field_146: u32,
+    pub field_147: u64,
+    pub field_148: f32,
+    pub field_149: f64,
+    pub field_150: [u8; 3],
+    pub field_151: bool,
+    pub field_152: u8,
+    pub field_153: u16,
+    pub field_154: u32,
+    pub field_155: u64,
+    pub field_156: f32,
+    pub field_157: f64,
+    pub field_158: [u8; 3],
+    pub field_159: bool,
+    pub field_160: u8,
+    pub field_161: u16,
+    pub field_162: u32,
+    pub field_163: u64,
+    pub field_164: f32,
+    pub field_165: f64,
+    pub field_166: [u8; 3],
+    pub field_167: bool,
+    pub field_168: u8,
+    pub field_169: u16,
+    pub field_170: u32,
+    pub field_171: u64,
+    pub field_172: f32,
+    pub field_173: f64,
+    pub field_174: [u8; 3],
+    pub field_175: bool,
+    pub field_176: u8,
+    pub field_177: u16,
+    pub field_178: u32,
+    pub field_179: u64,
+    pub field_180: f32,
+    pub field_181: f64,
+    pub field_182: [u8; 3],
+    pub field_183: bool,
+    pub field_184: u8,
+    pub field_185: u16,
+    pub field_186: u32,
+    pub field_187: u64,
+    pub field_188: f32,
+    pub field_189: f64,
+    pub field_190: [u8; 3],
+    pub field_191: bool,
+    pub field_192: u8,
+    pub field_193: u16,
+    pub field_194: u32,
+    pub field_195: u64,
+    pub field_196: f32,
+    pub field_197: f64,
+    pub field_198: [u8; 3],
+    pub field_199: bool,
+    pub field_200: u8,
+    pub field_201: u16,
+    pub field_202: u32,
+    pub field_203: u64,
+    pub field_204: f32,
+    pub field_205: f64,
+    pub field_206: [u8; 3],
+    pub field_207: bool,
+    pub field_208: u8,
+    pub field_209: u16,
+    pub field_210: u32,
+    pub field_211: u64,
+    pub field_212: f32,
+    pub field_213: f64,
+    pub field_214: [u8; 3],
+    pub field_215: bool,
+    pub field_216: u8,
+    pub field_217: u16,
+    pub field_218: u32,
+    pub field_219: u64,
+    pub field_220: f32,
+    pub field_221: f64,
+    pub field_222: [u8; 3],
+    pub field_223: bool,
+    pub field_224: u8,
+    pub field_225: u16,
+    pub field_226: u32,
+    pub field_227: u64,
+    pub field_228: f32,
+    pub field_229: f64,
+    pub field_230: [u8; 3],
+    pub field_231: bool,
+    pub field_232: u8,
+    pub field_233: u16,
+    pub field_234: u32,
+    pub field_235: u64,
+    pub field_236: f32,
+    pub field_237: f64,
+    pub field_238: [u8; 3],
+    pub field_239: bool,
+    pub field_240: u8,
+    pub field_241: u16,
+    pub field_242: u32,
+    pub field_243: u64,
+    pub field_244: f32,
+    pub field_245: f64,
+    pub field_246: [u8; 3],
+    pub field_247: bool,
+    pub field_248: u8,
+    pub field_249: u16,
+    pub field_250: u32,
+    pub field_251: u64,
+    pub field_252: f32,
+    pub field_253: f64,
+    pub field_254: [u8; 3],
+    pub field_255: bool,
+    pub field_256: u8,
+    pub field_257: u16,
+    pub field_258: u32,
+    pub field_259: u64,
+    pub field_260: f32,
+    pub field_261: f64,
+    pub field_262: [u8; 3],
+    pub field_263: bool,
+    pub field_264: u8,
+    pub field_265: u16,
+    pub field_266: u32,
+    pub field_267: u64,
+    pub field_268: f32,
+    pub field_269: f64,
+    pub field_270: [u8; 3],
+    pub field_271: bool,
+    pub field_272: u8,
+    pub field_273: u16,
+    pub field_274: u32,
+    pub field_275: u64,
+    pub field_276: f32,
+    pub field_277: f64,
+    pub field_278: [u8; 3],
+    pub field_279: bool,
+    pub field_280: u8,
+    pub field_281: u16,
+    pub field_282: u32,
+    pub field_283: u64,
+    pub field_284: f32,
+    pub field_285: f64,
+    pub field_286: [u8; 3],
+    pub field_287: bool,
+    pub field_288: u8,
+    pub field_289: u16,
+    pub field_290: u32,
+    pub field_291: u64,
+    pub field_292: f32,
+    pub field_293: f64,
+    pub field_294: [u8; 3],
+    pub field_295: bool,
+    pub field_296: u8,
+    pub field_297: u16,
+    pub field_298: u32,
+    pub field_299: u64,
+    pub field_300: f32,
+    pub field_301: f64,
+    pub field_302: [u8; 3],
+    pub field_303: bool,
+    pub field_304: u8,
+    pub field_305: u16,
+    pub field_306: u32,
+    pub field_307: u64,
+    pub field_308: f32,
+    pub field_309: f64,
+    pub field_310: [u8; 3],
+    pub field_311: bool,
+    pub field_312: u8,
+    pub field_313: u16,
+    pub field_314: u32,
+    pub field_315: u64,
+    pub field_316: f32,
+    pub field_317: f64,
+    pub field_318: [u8; 3],
+    pub field_319: bool,
+}
@@ -0,0 +1,112 @@
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::{DeriveInput, LitInt};
+
+/// A property of the derived type's layout that's checked at compile time.
+pub struct Assertion {
+    pub kind: AssertionKind,
+
+    /// The value given in the attribute. Errors are reported at its span.
+    pub expected: LitInt,
+
+    /// A const expression producing the actual value. Generic parameters of
+    /// the type are in scope.
+    pub actual: TokenStream,
+
+    pub message: String,
+}
+
+pub enum AssertionKind {
+    AtMost,
+    Equals,
+}
+
+impl AssertionKind {
+    /// A const expression that's true when the assertion holds.
+    fn check(&self, actual: &TokenStream, expected: &LitInt) -> TokenStream {
+        match self {
+            AssertionKind::AtMost => quote!(#actual <= #expected),
+            AssertionKind::Equals => quote!(#actual == #expected),
+        }
+    }
+}
+
+/// Generated code enforcing a list of assertions.
+pub struct Expanded {
+    /// Items to emit next to the `TypeLayout` impl.
+    pub items: TokenStream,
+
+    /// A statement to put in `type_layout()` for assertions that can only be
+    /// checked once the type is monomorphized.
+    pub trigger: TokenStream,
+}
+
+/// Non-generic types are checked with free-standing constants. Alongside the
+/// assertion, which names the type, a mismatched array length makes rustc
+/// print the actual value.
+///
+/// Constants can't refer to generic parameters, so generic types are instead
+/// checked by an associated constant that `type_layout()` evaluates, which
+/// happens whenever a particular instantiation's `type_layout()` is used.
+pub fn expand(input: &DeriveInput, assertions: &[Assertion]) -> Expanded {
+    if assertions.is_empty() {
+        return Expanded {
+            items: TokenStream::new(),
+            trigger: TokenStream::new(),
+        };
+    }
+
+    if input.generics.params.is_empty() {
+        let items = assertions.iter().map(|assertion| {
+            let expected = &assertion.expected;
+            let actual = &assertion.actual;
+            let message = &assertion.message;
+
+            let check = assertion.kind.check(actual, expected);
+            let clamped = match assertion.kind {
+                AssertionKind::AtMost => quote! {
+                    if #actual > #expected { #actual } else { #expected }
+                },
+                AssertionKind::Equals => actual.clone(),
+            };
+
+            quote_spanned! { expected.span() =>
+                const _: () = ::core::assert!(#check, #message);
+                const _: [(); #expected] = [(); #clamped];
+            }
+        });
+
+        Expanded {
+            items: quote!(#(#items)*),
+            trigger: TokenStream::new(),
+        }
+    } else {
+        let name = &input.ident;
+        let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+        let checks = assertions.iter().map(|assertion| {
+            let expected = &assertion.expected;
+            let actual = &assertion.actual;
+            let message = &assertion.message;
+            let check = assertion.kind.check(actual, expected);
+
+            quote_spanned! { expected.span() =>
+                ::core::assert!(#check, #message);
+            }
+        });
+
+        Expanded {
+            items: quote! {
+                impl #impl_generics #name #ty_generics #where_clause {
+                    #[doc(hidden)]
+                    const __TYPE_LAYOUT_ASSERTIONS: () = {
+                        #(#checks)*
+                    };
+                }
+            },
+            trigger: quote! {
+                let () = Self::__TYPE_LAYOUT_ASSERTIONS;
+            },
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use proc_macro2::Span;
+use syn::{
+    punctuated::Punctuated, spanned::Spanned, Attribute, LitInt, LitStr, Path, Token,
+    WherePredicate,
+};
+
+/// Options given to the derive through `#[layout(...)]` on the type itself.
+#[derive(Default)]
+pub struct ContainerAttrs {
+    pub max_padding: Option<LitInt>,
+    pub max_size: Option<LitInt>,
+    pub size: Option<LitInt>,
+    pub align: Option<LitInt>,
+
+    /// Set to the span of `embed` when it's present.
+    pub embed: Option<Span>,
+
+    /// Set to the span of `offset_consts` when it's present.
+    pub offset_consts: Option<Span>,
+
+    /// Set to the span of `const_layout` when it's present.
+    pub const_layout: Option<Span>,
+
+    /// Set to the span of `report` when it's present.
+    pub report: Option<Span>,
+
+    /// The path to the type-layout crate, from `crate = "..."`.
+    pub krate: Option<Path>,
+
+    /// Where clauses to use instead of the ones the derive infers, from
+    /// `bound = "..."`.
+    pub bound: Option<Punctuated<WherePredicate, Token![,]>>,
+
+    /// Set to the span of `no_bound` when it's present.
+    pub no_bound: Option<Span>,
+}
+
+impl ContainerAttrs {
+    pub fn from_attrs(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut container = ContainerAttrs::default();
+
+        for attr in attrs {
+            if !attr.path().is_ident("layout") {
+                continue;
+            }
+
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("max_padding") {
+                    container.max_padding = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("max_size") {
+                    container.max_size = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("size") {
+                    container.size = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("align") {
+                    container.align = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("embed") {
+                    container.embed = Some(meta.path.span());
+                } else if meta.path.is_ident("offset_consts") {
+                    container.offset_consts = Some(meta.path.span());
+                } else if meta.path.is_ident("const_layout") {
+                    container.const_layout = Some(meta.path.span());
+                } else if meta.path.is_ident("report") {
+                    container.report = Some(meta.path.span());
+                } else if meta.path.is_ident("crate") {
+                    let path: LitStr = meta.value()?.parse()?;
+                    container.krate = Some(path.parse()?);
+                } else if meta.path.is_ident("bound") {
+                    let bound: LitStr = meta.value()?.parse()?;
+                    container.bound = Some(bound.parse_with(Punctuated::parse_terminated)?);
+                } else if meta.path.is_ident("no_bound") {
+                    container.no_bound = Some(meta.path.span());
+                } else {
+                    return Err(meta.error("unknown type-layout attribute"));
+                }
+
+                Ok(())
+            })?;
+        }
+
+        if let (Some(bound), Some(_)) = (&container.bound, container.no_bound) {
+            return Err(syn::Error::new(
+                bound.span(),
+                "#[layout(bound)] and #[layout(no_bound)] can't be used together",
+            ));
+        }
+
+        Ok(container)
+    }
+}
+
+/// Options given to the derive through `#[layout(...)]` on a field.
+#[derive(Default)]
+pub struct FieldAttrs {
+    /// Set to the span of `type_id` when it's present.
+    pub type_id: Option<Span>,
+
+    /// Set to the span of `nested` when it's present.
+    pub nested: Option<Span>,
+
+    /// Set to the span of `contended` when it's present.
+    pub contended: Option<Span>,
+
+    /// The offset the field is expected to have, from `offset = N`.
+    pub offset: Option<LitInt>,
+
+    /// Set to the span of `skip` when it's present.
+    pub skip: Option<Span>,
+
+    /// The name to report the field under, from `rename = "..."`.
+    pub rename: Option<LitStr>,
+
+    /// The type to report the field as, from `ty = "..."`.
+    pub ty: Option<LitStr>,
+}
+
+impl FieldAttrs {
+    pub fn from_attrs(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut field = FieldAttrs::default();
+
+        for attr in attrs {
+            if !attr.path().is_ident("layout") {
+                continue;
+            }
+
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("type_id") {
+                    field.type_id = Some(meta.path.span());
+                } else if meta.path.is_ident("nested") {
+                    field.nested = Some(meta.path.span());
+                } else if meta.path.is_ident("contended") {
+                    field.contended = Some(meta.path.span());
+                } else if meta.path.is_ident("offset") {
+                    field.offset = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("skip") {
+                    field.skip = Some(meta.path.span());
+                } else if meta.path.is_ident("rename") {
+                    field.rename = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("ty") {
+                    field.ty = Some(meta.value()?.parse()?);
+                } else {
+                    return Err(meta.error("unknown type-layout field attribute"));
+                }
+
+                Ok(())
+            })?;
+        }
+
+        Ok(field)
+    }
+}
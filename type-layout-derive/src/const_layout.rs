@@ -0,0 +1,73 @@
+use proc_macro2::{Literal, Span, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::{spanned::Spanned, Data, DeriveInput};
+
+use crate::repr::Repr;
+
+/// Implements `#[layout(const_layout)]`, which implements `ConstTypeLayout`
+/// with a constant describing each field of the struct.
+pub fn expand(input: &DeriveInput, span: Span) -> syn::Result<TokenStream> {
+    let data = match &input.data {
+        Data::Struct(data) => data,
+        Data::Enum(_) | Data::Union(_) => {
+            return Err(syn::Error::new(
+                span,
+                "#[layout(const_layout)] only supports structs",
+            ))
+        }
+    };
+
+    let name = &input.ident;
+    let krate = crate::crate_path(input)?;
+    let name_str = Literal::string(&name.to_string());
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let repr = Repr::from_attrs(&input.attrs)?;
+    let packed = repr.packed;
+    let guaranteed = repr.is_guaranteed();
+
+    let fields = data
+        .fields
+        .iter()
+        .zip(data.fields.members())
+        .map(|(field, member)| {
+            let (field_name, field_ty_str) = crate::reported_field(field, &member)?;
+            let field_name_str = Literal::string(&field_name);
+            let field_ty = &field.ty;
+            let field_ty_str = Literal::string(&field_ty_str);
+            let alignment = match packed {
+                Some(packed) => {
+                    let packed = Literal::u64_unsuffixed(packed);
+                    quote! {{
+                        let alignment = ::core::mem::align_of::<#field_ty>();
+                        if alignment < #packed { alignment } else { #packed }
+                    }}
+                }
+                None => quote!(::core::mem::align_of::<#field_ty>()),
+            };
+
+            Ok(quote_spanned! { field.span() =>
+                #krate::ConstField {
+                    name: #field_name_str,
+                    ty: #field_ty_str,
+                    offset: #krate::__offset_of!(Self, #member),
+                    size: ::core::mem::size_of::<#field_ty>(),
+                    alignment: #alignment,
+                    needs_drop: ::core::mem::needs_drop::<#field_ty>(),
+                }
+            })
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    Ok(quote! {
+        impl #impl_generics #krate::ConstTypeLayout for #name #ty_generics #where_clause {
+            const LAYOUT: #krate::ConstLayout = #krate::ConstLayout {
+                name: #name_str,
+                size: ::core::mem::size_of::<Self>(),
+                alignment: ::core::mem::align_of::<Self>(),
+                needs_drop: ::core::mem::needs_drop::<Self>(),
+                guaranteed: #guaranteed,
+                fields: &[#(#fields),*],
+            };
+        }
+    })
+}
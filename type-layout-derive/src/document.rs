@@ -0,0 +1,718 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    parse::Parser, punctuated::Punctuated, spanned::Spanned, Attribute, Data, DataStruct,
+    DeriveInput, Expr, ExprLit, Fields, FieldsNamed, Lit, LitStr, Member, Path, Token, Type,
+};
+
+use crate::repr::Repr;
+use crate::type_name::type_string;
+
+/// Implements `#[document_layout]`, which appends a "Layout" section to the
+/// doc comment of a struct and adds `#[derive(TypeLayout)]` to it if the
+/// struct does not already have it. With `path = "..."`, it also generates a
+/// doctest that checks the section against the layout the compiler picked.
+pub fn expand(attr: TokenStream, mut input: DeriveInput) -> syn::Result<TokenStream> {
+    let mut path = None;
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("path") {
+            let lit: LitStr = meta.value()?.parse()?;
+            lit.parse::<Path>()?;
+            path = Some(lit.value());
+            Ok(())
+        } else {
+            Err(meta.error("unknown #[document_layout] argument"))
+        }
+    });
+    parser.parse2(attr)?;
+
+    let fields = match &input.data {
+        Data::Struct(DataStruct {
+            fields: Fields::Named(fields),
+            ..
+        }) => fields,
+        _ => {
+            return Err(syn::Error::new(
+                input.ident.span(),
+                "#[document_layout] only supports structs with named fields",
+            ))
+        }
+    };
+
+    if path.is_some() && !input.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            input.generics.span(),
+            "#[document_layout(path)] can't check generic structs",
+        ));
+    }
+
+    let repr = Repr::from_attrs(&input.attrs)?;
+    let computed = if input.generics.params.is_empty() {
+        compute_layout(&repr, fields)
+    } else {
+        None
+    };
+
+    let name = input.ident.to_string();
+    let doc = match &computed {
+        Some(layout) => lines(&document_computed(&name, layout)),
+        None => document_fields(&name, fields)?,
+    };
+
+    // The table was computed without asking the compiler, so double check it
+    // against the real thing.
+    let check = computed.as_ref().map(|layout| {
+        let name = &input.ident;
+        let size = layout.size as usize;
+        let align = layout.align as usize;
+        let message = format!(
+            "type-layout computed the wrong layout for `{}` in #[document_layout]",
+            name
+        );
+
+        quote! {
+            const _: () = ::core::assert!(
+                ::core::mem::size_of::<#name>() == #size
+                    && ::core::mem::align_of::<#name>() == #align,
+                #message
+            );
+        }
+    });
+
+    let krate = crate::crate_path(&input)?;
+
+    // Doctests are compiled as their own crate, so they can only name the
+    // struct by a path the user gives.
+    let doctest = match &path {
+        Some(path) => {
+            let krate = quote!(#krate).to_string();
+            let lines = match &computed {
+                Some(layout) => lines(&doctest_computed(path, &krate, layout)),
+                None => doctest_fields(path, &krate, fields)?,
+            };
+            let attrs = doc_attrs(lines);
+
+            Some(quote! {
+                #[cfg(doctest)]
+                #(#attrs)*
+                const _: () = ();
+            })
+        }
+        None => None,
+    };
+
+    // Adding the derive rather than expanding it here means the compiler
+    // removes fields that are configured out before the derive sees them.
+    if !derives_type_layout(&input.attrs) {
+        input
+            .attrs
+            .insert(0, syn::parse_quote!(#[derive(#krate::TypeLayout)]));
+    }
+
+    input.attrs.extend(doc_attrs(doc));
+
+    Ok(quote! {
+        #input
+        #check
+        #doctest
+    })
+}
+
+/// Turns lines of documentation into `#[doc]` attributes, wrapping the ones
+/// that come with a condition in `#[cfg_attr]`.
+fn doc_attrs(lines: Vec<(String, Option<TokenStream>)>) -> Vec<Attribute> {
+    lines
+        .into_iter()
+        .map(|(line, cfg)| {
+            let line = format!(" {}", line);
+            match cfg {
+                Some(cfg) => syn::parse_quote!(#[cfg_attr(#cfg, doc = #line)]),
+                None => syn::parse_quote!(#[doc = #line]),
+            }
+        })
+        .collect()
+}
+
+fn lines(text: &str) -> Vec<(String, Option<TokenStream>)> {
+    text.lines().map(|line| (line.to_owned(), None)).collect()
+}
+
+fn derives_type_layout(attrs: &[Attribute]) -> bool {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("derive"))
+        .filter_map(|attr| {
+            attr.parse_args_with(Punctuated::<Path, Token![,]>::parse_terminated)
+                .ok()
+        })
+        .flatten()
+        .any(|path| {
+            path.segments
+                .last()
+                .map_or(false, |segment| segment.ident == "TypeLayout")
+        })
+}
+
+struct ComputedLayout {
+    size: u64,
+    align: u64,
+    rows: Vec<Row>,
+}
+
+enum Row {
+    Field {
+        offset: u64,
+        name: String,
+        ty: String,
+        size: u64,
+    },
+    Padding {
+        offset: u64,
+        size: u64,
+    },
+}
+
+/// Lays out the struct the way rustc would, which is only possible when the
+/// layout is fully specified by `#[repr(C)]` and every field has the same size
+/// and alignment on every target. Fields behind `#[cfg(...)]` may not exist,
+/// so structs with any aren't computed either.
+fn compute_layout(repr: &Repr, fields: &FieldsNamed) -> Option<ComputedLayout> {
+    if !repr.c || fields.named.iter().any(|field| field_cfg(field).is_some()) {
+        return None;
+    }
+
+    let mut rows = Vec::new();
+    let mut offset = 0;
+    let mut align = 1;
+
+    for field in &fields.named {
+        let (field_size, mut field_align) = portable_layout(&field.ty)?;
+        if let Some(packed) = repr.packed {
+            field_align = field_align.min(packed);
+        }
+
+        let field_offset = align_up(offset, field_align);
+        if field_offset > offset {
+            rows.push(Row::Padding {
+                offset,
+                size: field_offset - offset,
+            });
+        }
+
+        let member = Member::Named(field.ident.clone().unwrap());
+        let (name, ty) = crate::reported_field(field, &member).ok()?;
+        rows.push(Row::Field {
+            offset: field_offset,
+            name,
+            ty,
+            size: field_size,
+        });
+
+        offset = field_offset + field_size;
+        align = align.max(field_align);
+    }
+
+    if let Some(min_align) = repr.align {
+        align = align.max(min_align);
+    }
+
+    let size = align_up(offset, align);
+    if size > offset {
+        rows.push(Row::Padding {
+            offset,
+            size: size - offset,
+        });
+    }
+
+    Some(ComputedLayout { size, align, rows })
+}
+
+/// Returns the size and alignment of types whose layout doesn't vary between
+/// targets. Only single bytes qualify: every wider primitive is less aligned
+/// on some target, like `u16` and `u32` on AVR and `u32` on MSP430, and 64-bit
+/// types on many 32-bit targets.
+fn portable_layout(ty: &Type) -> Option<(u64, u64)> {
+    match ty {
+        Type::Path(path) if path.qself.is_none() => {
+            let ident = path.path.get_ident()?.to_string();
+            match ident.as_str() {
+                "u8" | "i8" | "bool" => Some((1, 1)),
+                _ => None,
+            }
+        }
+        Type::Array(array) => {
+            let (size, align) = portable_layout(&array.elem)?;
+            let len = match &array.len {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Int(len), ..
+                }) => len.base10_parse::<u64>().ok()?,
+                _ => return None,
+            };
+
+            Some((size.checked_mul(len)?, align))
+        }
+        Type::Paren(paren) => portable_layout(&paren.elem),
+        Type::Group(group) => portable_layout(&group.elem),
+        _ => None,
+    }
+}
+
+fn align_up(offset: u64, align: u64) -> u64 {
+    (offset + align - 1) / align * align
+}
+
+fn document_computed(name: &str, layout: &ComputedLayout) -> String {
+    let mut doc = String::from("# Layout\n\n");
+    doc.push_str(&format!(
+        "`{}` has size {} and alignment {}.\n\n",
+        name, layout.size, layout.align
+    ));
+    doc.push_str("| Offset | Name | Type | Size |\n");
+    doc.push_str("| ------ | ---- | ---- | ---- |\n");
+
+    for row in &layout.rows {
+        match row {
+            Row::Field {
+                offset, ty, size, ..
+            } if ty.is_empty() => {
+                doc.push_str(&format!("| {} | *opaque* | | {} |\n", offset, size))
+            }
+            Row::Field {
+                offset,
+                name,
+                ty,
+                size,
+            } => doc.push_str(&format!(
+                "| {} | `{}` | `{}` | {} |\n",
+                offset,
+                name,
+                escape_cell(ty),
+                size
+            )),
+            Row::Padding { offset, size } => {
+                let name = if offset + size == layout.size {
+                    "*tail padding*"
+                } else {
+                    "*padding*"
+                };
+
+                doc.push_str(&format!("| {} | {} | | {} |\n", offset, name, size))
+            }
+        }
+    }
+
+    doc
+}
+
+/// Lists each field with its offset and size written as `offset_of!` and
+/// `size_of` expressions, since the numbers depend on the target. Rows for
+/// fields behind `#[cfg(...)]` come with the condition, so that they're only
+/// documented when the field exists.
+fn document_fields(
+    name: &str,
+    fields: &FieldsNamed,
+) -> syn::Result<Vec<(String, Option<TokenStream>)>> {
+    let mut doc = lines(
+        "# Layout\n\n\
+         | Offset | Name | Type | Size |\n\
+         | ------ | ---- | ---- | ---- |",
+    );
+
+    for field in &fields.named {
+        let member = Member::Named(field.ident.clone().unwrap());
+        let (field_name, ty) = crate::reported_field(field, &member)?;
+
+        let row = if ty.is_empty() {
+            String::from("| | *opaque* | | |")
+        } else {
+            format!(
+                "| `offset_of!({}, {})` | `{}` | `{}` | `size_of::<{}>()` |",
+                name,
+                field.ident.as_ref().unwrap(),
+                field_name,
+                escape_cell(&ty),
+                escape_cell(&type_string(&field.ty))
+            )
+        };
+        doc.push((row, field_cfg(field)));
+    }
+
+    let note = "\nThe offsets and sizes of these fields depend on the target, so they \
+                can only\nbe given as expressions here. Call `TypeLayout::type_layout()` \
+                to get them as\nnumbers.";
+    doc.extend(lines(note));
+
+    Ok(doc)
+}
+
+/// A doctest that checks the size, alignment, and every row of a computed
+/// table against the struct's `TypeLayout` implementation.
+fn doctest_computed(path: &str, krate: &str, layout: &ComputedLayout) -> String {
+    let mut test = doctest_start(path, krate);
+    test.push_str(&format!(
+        "assert_eq!((layout.size, layout.alignment), ({}, {}));\n",
+        layout.size, layout.align
+    ));
+    test.push_str("let expected: &[(usize, &str, &str, usize)] = &[\n");
+
+    for row in &layout.rows {
+        let (offset, name, ty, size) = match row {
+            Row::Field {
+                offset,
+                name,
+                ty,
+                size,
+            } => (offset, name.as_str(), ty.as_str(), size),
+            Row::Padding { offset, size } => (offset, "", "", size),
+        };
+        test.push_str(&format!(
+            "    ({}, {:?}, {:?}, {}),\n",
+            offset, name, ty, size
+        ));
+    }
+
+    test.push_str("];\nassert_eq!(rows, expected);\n```");
+    test
+}
+
+/// A doctest that checks the names and types of the listed fields, in order,
+/// against the struct's `TypeLayout` implementation.
+fn doctest_fields(
+    path: &str,
+    krate: &str,
+    fields: &FieldsNamed,
+) -> syn::Result<Vec<(String, Option<TokenStream>)>> {
+    let mut test = doctest_start(path, krate);
+    test.push_str(
+        "let fields: Vec<_> = rows\n    \
+         .iter()\n    \
+         .filter(|row| !row.1.is_empty())\n    \
+         .map(|row| (row.1, row.2))\n    \
+         .collect();\n\
+         let expected: &[(&str, &str)] = &[",
+    );
+    let mut doc = lines(&test);
+
+    for field in &fields.named {
+        let member = Member::Named(field.ident.clone().unwrap());
+        let (name, ty) = crate::reported_field(field, &member)?;
+        doc.push((format!("    ({:?}, {:?}),", name, ty), field_cfg(field)));
+    }
+
+    doc.extend(lines("];\nassert_eq!(fields, expected);\n```"));
+    Ok(doc)
+}
+
+fn doctest_start(path: &str, krate: &str) -> String {
+    format!(
+        "```\n\
+         let layout = <{} as {}::TypeLayout>::type_layout();\n\
+         let rows = {}::__private::document_rows(&layout);\n",
+        path, krate, krate
+    )
+}
+
+/// The condition under which a field exists, combining all of its
+/// `#[cfg(...)]` attributes, or `None` if it always does.
+fn field_cfg(field: &syn::Field) -> Option<TokenStream> {
+    let predicates: Vec<TokenStream> = field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("cfg"))
+        .filter_map(|attr| attr.parse_args().ok())
+        .collect();
+
+    match predicates.len() {
+        0 => None,
+        1 => predicates.into_iter().next(),
+        _ => Some(quote!(all(#(#predicates),*))),
+    }
+}
+
+fn escape_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proc_macro2::TokenTree;
+    use syn::{parse::ParseStream, parse_quote, Meta};
+
+    /// The struct and the attributes of each constant after it.
+    fn expand_items(attr: TokenStream, input: DeriveInput) -> (DeriveInput, Vec<Vec<Attribute>>) {
+        let parser = |input: ParseStream| {
+            let item: DeriveInput = input.parse()?;
+            let mut consts = Vec::new();
+            while !input.is_empty() {
+                consts.push(input.call(Attribute::parse_outer)?);
+                while !matches!(input.parse()?, TokenTree::Punct(punct) if punct.as_char() == ';') {
+                }
+            }
+            Ok((item, consts))
+        };
+
+        parser.parse2(expand(attr, input).unwrap()).unwrap()
+    }
+
+    fn tokens(attr: &Attribute) -> String {
+        quote!(#attr).to_string()
+    }
+
+    fn contains(attrs: &[Attribute], expected: Attribute) -> bool {
+        attrs.iter().any(|attr| tokens(attr) == tokens(&expected))
+    }
+
+    /// The text of the plain `#[doc]` attributes, one line per attribute.
+    fn doc_text(attrs: &[Attribute]) -> String {
+        attrs
+            .iter()
+            .filter_map(|attr| match &attr.meta {
+                Meta::NameValue(meta) if meta.path.is_ident("doc") => match &meta.value {
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Str(line),
+                        ..
+                    }) => Some(line.value() + "\n"),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn computed_table() {
+        let (item, consts) = expand_items(
+            TokenStream::new(),
+            parse_quote! {
+                #[repr(C, align(4))]
+                struct Packet {
+                    kind: u8,
+                    #[layout(rename = "payload")]
+                    data: [u8; 2],
+                    #[layout(skip)]
+                    secret: bool,
+                    last: i8,
+                }
+            },
+        );
+
+        assert_eq!(
+            doc_text(&item.attrs),
+            " # Layout
+ 
+ `Packet` has size 8 and alignment 4.
+ 
+ | Offset | Name | Type | Size |
+ | ------ | ---- | ---- | ---- |
+ | 0 | `kind` | `u8` | 1 |
+ | 1 | `payload` | `[u8; 2]` | 2 |
+ | 3 | *opaque* | | 1 |
+ | 4 | `last` | `i8` | 1 |
+ | 5 | *tail padding* | | 3 |
+"
+        );
+
+        // The derive is added, and the size is checked at compile time.
+        assert_eq!(
+            tokens(&item.attrs[0]),
+            "# [derive (:: type_layout :: TypeLayout)]"
+        );
+        assert_eq!(consts.len(), 1);
+    }
+
+    #[test]
+    fn field_table() {
+        let (item, consts) = expand_items(
+            TokenStream::new(),
+            parse_quote! {
+                #[derive(Clone, type_layout::TypeLayout)]
+                struct Entry<T> {
+                    id: u64,
+                    #[layout(ty = "Name")]
+                    r#ref: Option<&'static str>,
+                    value: Vec<T>,
+                    #[layout(skip)]
+                    hidden: u32,
+                    flags: [fn(u8) -> u8; 2],
+                }
+            },
+        );
+
+        assert_eq!(
+            doc_text(&item.attrs),
+            " # Layout
+ 
+ | Offset | Name | Type | Size |
+ | ------ | ---- | ---- | ---- |
+ | `offset_of!(Entry, id)` | `id` | `u64` | `size_of::<u64>()` |
+ | `offset_of!(Entry, r#ref)` | `ref` | `Name` | `size_of::<Option<&'static str>>()` |
+ | `offset_of!(Entry, value)` | `value` | `Vec<T>` | `size_of::<Vec<T>>()` |
+ | | *opaque* | | |
+ | `offset_of!(Entry, flags)` | `flags` | `[fn(u8) -> u8; 2]` | `size_of::<[fn(u8) -> u8; 2]>()` |
+ 
+ The offsets and sizes of these fields depend on the target, so they can only
+ be given as expressions here. Call `TypeLayout::type_layout()` to get them as
+ numbers.
+"
+        );
+
+        // The existing derive isn't repeated, and there's nothing to check.
+        assert_eq!(
+            tokens(&item.attrs[0]),
+            "# [derive (Clone , type_layout :: TypeLayout)]"
+        );
+        assert!(consts.is_empty());
+    }
+
+    #[test]
+    fn cfg_fields() {
+        let (item, _) = expand_items(
+            TokenStream::new(),
+            parse_quote! {
+                #[repr(C)]
+                struct Config {
+                    always: u8,
+                    #[cfg(feature = "a")]
+                    #[cfg(unix)]
+                    sometimes: u8,
+                }
+            },
+        );
+        let attrs = &item.attrs;
+
+        // Not computed, since the field may not exist.
+        assert!(doc_text(attrs).contains("| `offset_of!(Config, always)` |"));
+        assert!(!doc_text(attrs).contains("sometimes"));
+
+        let expected: Attribute = parse_quote! {
+            #[cfg_attr(
+                all(feature = "a", unix),
+                doc = " | `offset_of!(Config, sometimes)` | `sometimes` | `u8` | `size_of::<u8>()` |"
+            )]
+        };
+        assert!(contains(attrs, expected));
+    }
+
+    #[test]
+    fn computed_doctest() {
+        let (_, consts) = expand_items(
+            quote!(path = "my_crate::Pair"),
+            parse_quote! {
+                #[repr(C)]
+                struct Pair {
+                    a: u8,
+                    b: bool,
+                }
+            },
+        );
+
+        let doctest = &consts[1];
+        assert_eq!(tokens(&doctest[0]), "# [cfg (doctest)]");
+        assert_eq!(
+            doc_text(doctest),
+            " ```
+ let layout = <my_crate::Pair as :: type_layout::TypeLayout>::type_layout();
+ let rows = :: type_layout::__private::document_rows(&layout);
+ assert_eq!((layout.size, layout.alignment), (2, 1));
+ let expected: &[(usize, &str, &str, usize)] = &[
+     (0, \"a\", \"u8\", 1),
+     (1, \"b\", \"bool\", 1),
+ ];
+ assert_eq!(rows, expected);
+ ```
+"
+        );
+    }
+
+    #[test]
+    fn field_doctest() {
+        let (_, consts) = expand_items(
+            quote!(path = "my_crate::Entry"),
+            parse_quote! {
+                #[layout(crate = "tl")]
+                struct Entry {
+                    id: u64,
+                    #[cfg(unix)]
+                    fd: i32,
+                    #[layout(skip)]
+                    hidden: u32,
+                }
+            },
+        );
+
+        let doctest = &consts[0];
+        assert_eq!(
+            doc_text(doctest),
+            " ```
+ let layout = <my_crate::Entry as tl::TypeLayout>::type_layout();
+ let rows = tl::__private::document_rows(&layout);
+ let fields: Vec<_> = rows
+     .iter()
+     .filter(|row| !row.1.is_empty())
+     .map(|row| (row.1, row.2))
+     .collect();
+ let expected: &[(&str, &str)] = &[
+     (\"id\", \"u64\"),
+     (\"[opaque]\", \"\"),
+ ];
+ assert_eq!(fields, expected);
+ ```
+"
+        );
+
+        let expected: Attribute = parse_quote!(#[cfg_attr(unix, doc = "     (\"fd\", \"i32\"),")]);
+        assert!(contains(doctest, expected));
+    }
+
+    #[test]
+    fn errors() {
+        let error = |attr: TokenStream, input: DeriveInput| match expand(attr, input) {
+            Ok(_) => panic!("expected an error"),
+            Err(err) => err.to_string(),
+        };
+
+        assert_eq!(
+            error(
+                quote!(check),
+                parse_quote!(
+                    struct A {
+                        a: u8,
+                    }
+                )
+            ),
+            "unknown #[document_layout] argument"
+        );
+        assert_eq!(
+            error(
+                quote!(path = "not a path"),
+                parse_quote!(
+                    struct A {
+                        a: u8,
+                    }
+                )
+            ),
+            "unexpected token"
+        );
+        assert_eq!(
+            error(
+                quote!(path = "a::B"),
+                parse_quote!(
+                    struct B<T> {
+                        a: T,
+                    }
+                )
+            ),
+            "#[document_layout(path)] can't check generic structs"
+        );
+        assert_eq!(
+            error(
+                TokenStream::new(),
+                parse_quote!(
+                    struct C(u8);
+                )
+            ),
+            "#[document_layout] only supports structs with named fields"
+        );
+    }
+}
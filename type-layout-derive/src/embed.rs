@@ -0,0 +1,108 @@
+use proc_macro2::{Literal, Span, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::{Data, DeriveInput, Fields, Member};
+
+use crate::repr::Repr;
+
+/// Implements `#[layout(embed)]`, which places an encoded record of the type's
+/// layout in a link section. See `type_layout::embedded` for the encoding.
+pub fn expand(input: &DeriveInput, span: Span) -> syn::Result<TokenStream> {
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            span,
+            "#[layout(embed)] is not supported on generic types",
+        ));
+    }
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new(
+                    span,
+                    "#[layout(embed)] only supports structs with named fields",
+                ))
+            }
+        },
+        Data::Enum(_) | Data::Union(_) => {
+            return Err(syn::Error::new(
+                span,
+                "#[layout(embed)] only supports structs",
+            ))
+        }
+    };
+
+    let name = &input.ident;
+    let krate = crate::crate_path(input)?;
+    let name_str = Literal::string(&name.to_string());
+    let guaranteed = Repr::from_attrs(&input.attrs)?.is_guaranteed();
+
+    let field_count = fields.len() as u32;
+    let field_strs = fields
+        .iter()
+        .map(|field| {
+            let member = Member::Named(field.ident.clone().unwrap());
+            let (field_name, field_ty) = crate::reported_field(field, &member)?;
+            Ok((Literal::string(&field_name), Literal::string(&field_ty)))
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let lengths = field_strs
+        .iter()
+        .map(|(field_name, field_ty)| quote!((#field_name, #field_ty)));
+
+    let encoded_fields = fields.iter().zip(&field_strs).map(|(field, strs)| {
+        let (field_name_str, field_ty_str) = strs;
+        let field_name = field.ident.as_ref().unwrap();
+        let field_ty = &field.ty;
+
+        quote! {
+            .flags(::core::mem::needs_drop::<#field_ty>())
+            .usize(#krate::__offset_of!(#name, #field_name))
+            .usize(::core::mem::size_of::<#field_ty>())
+            .str(#field_name_str)
+            .str(#field_ty_str)
+        }
+    });
+
+    let field_alignments = fields.iter().map(|field| {
+        let field_ty = &field.ty;
+        quote!(let alignment = max(alignment, ::core::mem::align_of::<#field_ty>());)
+    });
+
+    Ok(quote_spanned! { span =>
+        const _: () = {
+            const fn max(a: usize, b: usize) -> usize {
+                if a > b { a } else { b }
+            }
+
+            const NATURAL_ALIGNMENT: usize = {
+                let alignment = 1;
+                #(#field_alignments)*
+                alignment
+            };
+
+            const LEN: usize = #krate::embedded::record_len(#name_str, &[#(#lengths),*]);
+
+            #[used]
+            #[cfg_attr(
+                any(target_os = "macos", target_os = "ios"),
+                link_section = "__DATA,__type_layout"
+            )]
+            #[cfg_attr(windows, link_section = ".tylay")]
+            #[cfg_attr(
+                not(any(target_os = "macos", target_os = "ios", windows)),
+                link_section = ".type_layout"
+            )]
+            static RECORD: [u8; LEN] = #krate::embedded::Encoder::<LEN>::new()
+                .record_flags(::core::mem::needs_drop::<#name>(), #guaranteed)
+                .usize(::core::mem::size_of::<#name>())
+                .usize(::core::mem::align_of::<#name>())
+                .usize(NATURAL_ALIGNMENT)
+                .str(#name_str)
+                .u32(#field_count)
+                #(#encoded_fields)*
+                .finish();
+        };
+    })
+}
@@ -0,0 +1,137 @@
+use proc_macro2::{Literal, TokenStream};
+use quote::quote;
+use syn::{DataEnum, DeriveInput};
+
+use crate::repr::Repr;
+use crate::Layout;
+
+/// Lays out an enum with `#[repr(C)]` or a primitive representation like
+/// `#[repr(u8)]`, following the rules in RFC 2195:
+///
+/// - With only a primitive representation, each variant is laid out like a
+///   `#[repr(C)]` struct whose first field is the tag.
+/// - With `#[repr(C)]`, the enum is laid out like a `#[repr(C)]` struct of
+///   the tag followed by a union of `#[repr(C)]` structs, one per variant.
+///
+/// The layout of other enums is unspecified, so they're rejected.
+///
+/// The tag's size and each variant's discriminant come from a fieldless copy
+/// of the enum with the same representation, declared in `type_layout()`.
+pub fn expand(input: &DeriveInput, data: &DataEnum) -> syn::Result<Layout> {
+    let krate = crate::crate_path(input)?;
+    let repr = Repr::from_attrs(&input.attrs)?;
+
+    let tag_repr = match (&repr.int, repr.c) {
+        (Some(int), _) => quote!(#[repr(#int)]),
+        (None, true) => quote!(#[repr(C)]),
+        (None, false) => {
+            return Err(syn::Error::new(
+                input.ident.span(),
+                "type-layout only supports enums with #[repr(C)] or a primitive \
+                 representation like #[repr(u8)], since the layout of other enums \
+                 is unspecified",
+            ))
+        }
+    };
+
+    let tag_ty_str = match &repr.int {
+        Some(int) => Literal::string(&int.to_string()),
+        None => Literal::string("C enum"),
+    };
+
+    let tag_variants = data.variants.iter().map(|variant| {
+        let ident = &variant.ident;
+        match &variant.discriminant {
+            Some((_, discriminant)) => quote!(#ident = #discriminant),
+            None => quote!(#ident),
+        }
+    });
+
+    let payload_start = if repr.c {
+        let field_alignments = data
+            .variants
+            .iter()
+            .flat_map(|variant| variant.fields.iter())
+            .map(|field| {
+                let ty = &field.ty;
+                quote! {
+                    let alignment = ::core::cmp::Ord::max(alignment, ::core::mem::align_of::<#ty>());
+                }
+            });
+
+        quote! {{
+            let alignment = 1usize;
+            #(#field_alignments)*
+            (tag_size + alignment - 1) & !(alignment - 1)
+        }}
+    } else {
+        quote!(tag_size)
+    };
+
+    let mut variants = Vec::new();
+
+    for variant in &data.variants {
+        let ident = &variant.ident;
+        let name_str = Literal::string(&ident.to_string());
+
+        let mut fields = Vec::new();
+        for (field, member) in variant.fields.iter().zip(variant.fields.members()) {
+            fields.push(crate::field_value(input, field, &member, quote!(0usize))?);
+        }
+
+        let table = crate::fields_table(&fields, &krate);
+
+        variants.push(quote! {
+            #krate::__layout_variant(
+                #name_str,
+                __TypeLayoutTag::#ident as i128,
+                #krate::__tag_field::<__TypeLayoutTag>(#tag_ty_str),
+                payload_start,
+                ::core::mem::size_of::<Self>(),
+                #table,
+            )
+        });
+    }
+
+    Ok(Layout {
+        setup: quote! {
+            #[allow(dead_code)]
+            #tag_repr
+            enum __TypeLayoutTag {
+                #(#tag_variants),*
+            }
+
+            let tag_size = ::core::mem::size_of::<__TypeLayoutTag>();
+            let payload_start: usize = #payload_start;
+        },
+        fields: quote!(#krate::__private::Vec::new()),
+        variants: quote!(#krate::__private::vec![#(#variants),*]),
+        alignments: vec![quote!(::core::mem::align_of::<__TypeLayoutTag>())],
+        unsized_tail: None,
+        tag: Some(quote! {
+            #krate::EnumTag {
+                ty: #krate::__private::Cow::Borrowed(#tag_ty_str),
+                offset: 0,
+                size: tag_size,
+                payload_offset: payload_start,
+            }
+        }),
+    })
+}
+
+/// Generates the override of `TypeLayout::__variant_index`, which finds the
+/// variant a value holds by matching on it, for `type_layout::layout_of_value`.
+pub fn variant_index(data: &DataEnum) -> TokenStream {
+    let arms = data.variants.iter().enumerate().map(|(index, variant)| {
+        let ident = &variant.ident;
+        quote!(Self::#ident { .. } => ::core::option::Option::Some(#index),)
+    });
+
+    quote! {
+        fn __variant_index(&self) -> ::core::option::Option<usize> {
+            match self {
+                #(#arms)*
+            }
+        }
+    }
+}
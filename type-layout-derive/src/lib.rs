@@ -2,92 +2,684 @@ extern crate proc_macro;
 
 use proc_macro::TokenStream;
 
-use proc_macro2::{Ident, Literal};
-use quote::{quote, quote_spanned, ToTokens};
-use syn::{parse_macro_input, spanned::Spanned, Data, DeriveInput, Fields};
+use proc_macro2::Literal;
+use quote::{format_ident, quote, quote_spanned};
+use syn::{
+    ext::IdentExt, parse_macro_input, parse_quote, spanned::Spanned, Data, DataStruct, DataUnion,
+    DeriveInput, Expr, ExprLit, GenericArgument, GenericParam, Generics, Lit, Member, Path,
+    PathArguments, Type, TypeArray, WherePredicate,
+};
 
-#[proc_macro_derive(TypeLayout)]
+use crate::assertions::{Assertion, AssertionKind};
+use crate::attr::{ContainerAttrs, FieldAttrs};
+use crate::repr::Repr;
+use crate::type_name::type_string;
+
+mod assertions;
+mod attr;
+mod const_layout;
+mod document;
+mod embed;
+mod enums;
+mod offsets;
+mod report;
+mod repr;
+mod type_id;
+mod type_name;
+mod unsized_tail;
+mod validate;
+
+#[proc_macro_derive(TypeLayout, attributes(layout))]
 pub fn derive_type_layout(input: TokenStream) -> TokenStream {
     // Parse the input tokens into a syntax tree
     let input = parse_macro_input!(input as DeriveInput);
 
+    // Hand the output tokens back to the compiler
+    match expand_type_layout(&input) {
+        Ok(expanded) => TokenStream::from(expanded),
+        Err(err) => TokenStream::from(err.to_compile_error()),
+    }
+}
+
+#[proc_macro_attribute]
+pub fn document_layout(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let attr = proc_macro2::TokenStream::from(attr);
+    let input = parse_macro_input!(item as DeriveInput);
+
+    match document::expand(attr, input) {
+        Ok(expanded) => TokenStream::from(expanded),
+        Err(err) => TokenStream::from(err.to_compile_error()),
+    }
+}
+
+/// The name reported for fields marked with `#[layout(skip)]`.
+const OPAQUE_NAME: &str = "[opaque]";
+
+fn expand_type_layout(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
     // Used in the quasi-quotation below as `#name`.
-    let name = input.ident;
+    let name = &input.ident;
     let name_str = Literal::string(&name.to_string());
 
-    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
-    let layout = layout_of_type(&name, &input.data);
+    validate::check_fields(&input.data)?;
+    unsized_tail::check(input)?;
+
+    let krate = crate_path(input)?;
+
+    // Fields that ask for a `TypeId` need their type to be `'static`, and
+    // nested fields need their type to implement `TypeLayout`. These bounds
+    // can be replaced with `#[layout(bound = "...")]` or left out with
+    // `#[layout(no_bound)]`.
+    let mut bounds: Vec<WherePredicate> = Vec::new();
+    for field in data_fields(&input.data) {
+        let attrs = FieldAttrs::from_attrs(&field.attrs)?;
+        let ty = &field.ty;
+
+        if let (Some(skip), Some(_)) = (attrs.skip, attrs.type_id.or(attrs.nested)) {
+            return Err(syn::Error::new(
+                skip,
+                "#[layout(skip)] can't be combined with `type_id` or `nested`, \
+                 which would report the field's type",
+            ));
+        }
+
+        if let (Some(skip), true) = (attrs.skip, attrs.rename.is_some() || attrs.ty.is_some()) {
+            return Err(syn::Error::new(
+                skip,
+                "#[layout(skip)] can't be combined with `rename` or `ty`, since skipped \
+                 fields aren't reported under any name",
+            ));
+        }
 
-    // Build the output, possibly using quasi-quotation
-    let expanded = quote! {
-        impl #impl_generics ::type_layout::TypeLayout for #name #ty_generics #where_clause {
-            fn type_layout() -> ::type_layout::TypeLayoutInfo {
-                let mut last_field_end = 0;
-                let mut fields = Vec::new();
+        if attrs.type_id.is_some() {
+            bounds.push(parse_quote!(#ty: 'static));
+        }
 
-                #layout
+        if attrs.nested.is_some() {
+            let ty = nested_type(ty, &attrs);
+            bounds.push(parse_quote!(#ty: #krate::TypeLayout));
+        }
+    }
 
-                ::type_layout::TypeLayoutInfo {
-                    name: ::std::borrow::Cow::Borrowed(#name_str),
-                    size: std::mem::size_of::<#name>(),
-                    alignment: ::std::mem::align_of::<#name>(),
-                    fields,
+    let container = ContainerAttrs::from_attrs(&input.attrs)?;
+    if let Some(bound) = &container.bound {
+        bounds = bound.iter().cloned().collect();
+    } else if container.no_bound.is_some() {
+        bounds.clear();
+    }
+
+    let mut generics = input.generics.clone();
+    if !bounds.is_empty() {
+        generics.make_where_clause().predicates.extend(bounds);
+    }
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let layout = match &input.data {
+        Data::Struct(data) => layout_of_struct(input, data)?,
+        Data::Enum(data) => enums::expand(input, data)?,
+        Data::Union(data) => layout_of_union(input, data)?,
+    };
+    let variant_index = match &input.data {
+        Data::Enum(data) => Some(enums::variant_index(data)),
+        Data::Struct(_) | Data::Union(_) => None,
+    };
+    let is_union = matches!(input.data, Data::Union(_));
+    let repr = Repr::from_attrs(&input.attrs)?;
+    let guaranteed = repr.is_guaranteed();
+    let repr_value = repr.expand(&krate);
+    let generic_names = generic_names(&input.generics, &krate);
+    let setup = &layout.setup;
+    let tag = match &layout.tag {
+        Some(tag) => quote!(::core::option::Option::Some(#tag)),
+        None => quote!(::core::option::Option::None),
+    };
+    let fields = &layout.fields;
+    let variants = &layout.variants;
+
+    // Structs with an unsized tail don't have a size; their layout's setup
+    // finds the size and alignment with an empty tail instead.
+    let (size, alignment, option_size, unsized_tail) = match &layout.unsized_tail {
+        Some(unsized_tail) => (
+            quote!((__tail_offset + __tail_alignment - 1) & !(__tail_alignment - 1)),
+            quote!(__tail_alignment),
+            quote!(::core::option::Option::None),
+            quote!(::core::option::Option::Some(#unsized_tail)),
+        ),
+        None => (
+            quote!(::core::mem::size_of::<Self>()),
+            quote!(::core::mem::align_of::<Self>()),
+            quote! {
+                ::core::option::Option::Some(
+                    ::core::mem::size_of::<::core::option::Option<Self>>(),
+                )
+            },
+            quote!(::core::option::Option::None),
+        ),
+    };
+
+    let tail = unsized_tail::tail_field(&input.data);
+    let field_alignments = data_fields(&input.data)
+        .filter(|field| tail.map_or(true, |tail| !std::ptr::eq(*field, tail)))
+        .map(|field| &field.ty)
+        .map(|ty| quote!(::core::mem::align_of::<#ty>()))
+        .chain(layout.alignments.iter().cloned())
+        .map(|alignment| quote!(let alignment = ::core::cmp::Ord::max(alignment, #alignment);));
+
+    if let (Some(max_padding), Data::Enum(_)) = (&container.max_padding, &input.data) {
+        return Err(syn::Error::new(
+            max_padding.span(),
+            "#[layout(max_padding)] is only supported on structs and unions",
+        ));
+    }
+
+    let assertions = assertions::expand(input, &container_assertions(input, &container)?);
+    let assertion_items = assertions.items;
+    let assertion_trigger = assertions.trigger;
+
+    let embedded = match container.embed {
+        Some(span) => Some(embed::expand(input, span)?),
+        None => None,
+    };
+
+    let offset_consts = match container.offset_consts {
+        Some(span) => Some(offsets::expand(input, span)?),
+        None => None,
+    };
+
+    let const_layout = match container.const_layout {
+        Some(span) => Some(const_layout::expand(input, span)?),
+        None => None,
+    };
+
+    let report = match container.report {
+        Some(span) => Some(report::expand(input, span)?),
+        None => None,
+    };
+
+    // Generic types can't be registered because we don't know which
+    // instantiations of them exist.
+    let registration = if input.generics.params.is_empty() {
+        Some(quote!(#krate::__register_type_layout!(#name);))
+    } else {
+        None
+    };
+
+    // With the `layout-tests` feature, each type gets a test that compares
+    // its layout against a snapshot file, which catches changes from new
+    // compilers or targets. Like registration, this needs a concrete type.
+    // The test sits next to the type, so the `module_path!()` that the macro
+    // expands to keys the snapshot by the type's module, and types with the
+    // same name in different modules get separate snapshots.
+    let layout_test = if cfg!(feature = "layout-tests") && input.generics.params.is_empty() {
+        let test_name = format_ident!("type_layout_test_{}", name.unraw());
+        Some(quote! {
+            #[cfg(test)]
+            #[test]
+            #[allow(non_snake_case)]
+            fn #test_name() {
+                #krate::assert_layout_snapshot!(#name);
+            }
+        })
+    } else {
+        None
+    };
+
+    Ok(quote! {
+        impl #impl_generics #krate::TypeLayout for #name #ty_generics #where_clause {
+            fn type_layout() -> #krate::TypeLayoutInfo {
+                #assertion_trigger
+                #setup
+
+                #krate::TypeLayoutInfo {
+                    name: #krate::__private::Cow::Borrowed(#name_str),
+                    generics: #krate::__private::vec![#(#generic_names),*],
+                    size: #size,
+                    alignment: #alignment,
+                    natural_alignment: {
+                        let alignment = 1usize;
+                        #(#field_alignments)*
+                        alignment
+                    },
+                    needs_drop: ::core::mem::needs_drop::<Self>(),
+                    option_size: #option_size,
+                    is_union: #is_union,
+                    repr: #repr_value,
+                    guaranteed: #guaranteed,
+                    fields: #fields,
+                    variants: #variants,
+                    unsized_tail: #unsized_tail,
+                    tag: #tag,
+                    target: ::core::option::Option::Some(#krate::TargetInfo::current()),
+                }
+            }
+
+            #variant_index
+        }
+
+        #assertion_items
+        #offset_consts
+        #const_layout
+        #report
+        #registration
+        #embedded
+        #layout_test
+    })
+}
+
+fn container_assertions(
+    input: &DeriveInput,
+    container: &ContainerAttrs,
+) -> syn::Result<Vec<Assertion>> {
+    let name = &input.ident;
+    let krate = crate_path(input)?;
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let size = quote!(::core::mem::size_of::<#name #ty_generics>());
+
+    let mut assertions = Vec::new();
+
+    if let Some(max_padding) = &container.max_padding {
+        let field_sizes = field_types(&input.data).map(|ty| quote!(::core::mem::size_of::<#ty>()));
+
+        // The fields of a union overlap, so only the largest one takes up
+        // space that would otherwise be padding.
+        let used = match &input.data {
+            Data::Union(_) => quote! {{
+                let largest = 0usize;
+                #(
+                    let size = #field_sizes;
+                    let largest = if size > largest { size } else { largest };
+                )*
+                largest
+            }},
+            _ => quote!((0 #(+ #field_sizes)*)),
+        };
+
+        assertions.push(Assertion {
+            kind: AssertionKind::AtMost,
+            expected: max_padding.clone(),
+            actual: quote!((#size - #used)),
+            message: format!(
+                "`{}` has more than {} bytes of padding, exceeding #[layout(max_padding = {})]",
+                name, max_padding, max_padding
+            ),
+        });
+    }
+
+    if let Some(max_size) = &container.max_size {
+        assertions.push(Assertion {
+            kind: AssertionKind::AtMost,
+            expected: max_size.clone(),
+            actual: size.clone(),
+            message: format!(
+                "`{}` is larger than {} bytes, exceeding #[layout(max_size = {})]",
+                name, max_size, max_size
+            ),
+        });
+    }
+
+    if let Some(expected) = &container.size {
+        assertions.push(Assertion {
+            kind: AssertionKind::Equals,
+            expected: expected.clone(),
+            actual: size,
+            message: format!(
+                "`{}` is not {} bytes, as required by #[layout(size = {})]",
+                name, expected, expected
+            ),
+        });
+    }
+
+    if let Some(expected) = &container.align {
+        assertions.push(Assertion {
+            kind: AssertionKind::Equals,
+            expected: expected.clone(),
+            actual: quote!(::core::mem::align_of::<#name #ty_generics>()),
+            message: format!(
+                "`{}` does not have an alignment of {}, as required by #[layout(align = {})]",
+                name, expected, expected
+            ),
+        });
+    }
+
+    let data = match &input.data {
+        Data::Struct(data) => data,
+        Data::Enum(_) | Data::Union(_) => {
+            for field in data_fields(&input.data) {
+                if let Some(offset) = FieldAttrs::from_attrs(&field.attrs)?.offset {
+                    return Err(syn::Error::new(
+                        offset.span(),
+                        "#[layout(offset)] is only supported on fields of structs",
+                    ));
                 }
             }
+
+            return Ok(assertions);
         }
     };
 
-    // Hand the output tokens back to the compiler
-    TokenStream::from(expanded)
-}
-
-fn layout_of_type(struct_name: &Ident, data: &Data) -> proc_macro2::TokenStream {
-    match data {
-        Data::Struct(data) => match &data.fields {
-            Fields::Named(fields) => {
-                let values = fields.named.iter().map(|field| {
-                    let field_name = field.ident.as_ref().unwrap();
-                    let field_name_str = Literal::string(&field_name.to_string());
-                    let field_ty = &field.ty;
-                    let field_ty_str = Literal::string(&field_ty.to_token_stream().to_string());
-
-                    quote_spanned! { field.span() =>
-                        #[allow(unused_assignments)]
-                        {
-                            let size = ::std::mem::size_of::<#field_ty>();
-                            let offset = ::type_layout::memoffset::offset_of!(#struct_name, #field_name);
-
-                            if offset > last_field_end {
-                                fields.push(::type_layout::Field::Padding {
-                                    size: offset - last_field_end
-                                });
-                            }
-
-                            fields.push(::type_layout::Field::Field {
-                                name: ::std::borrow::Cow::Borrowed(#field_name_str),
-                                ty: ::std::borrow::Cow::Borrowed(#field_ty_str),
-                                size,
-                            });
-
-                            last_field_end = offset + size;
-                        }
-                    }
-                });
+    for (field, member) in data.fields.iter().zip(data.fields.members()) {
+        if let Some(expected) = FieldAttrs::from_attrs(&field.attrs)?.offset {
+            let message = format!(
+                "`{}::{}` is not at offset {}, as required by #[layout(offset = {})]",
+                name,
+                member_name(&member),
+                expected,
+                expected
+            );
 
-                quote! {
-                    #(#values)*
+            assertions.push(Assertion {
+                kind: AssertionKind::Equals,
+                expected,
+                actual: quote!(#krate::__offset_of!(#name #ty_generics, #member)),
+                message,
+            });
+        }
+    }
 
-                    let struct_size = ::std::mem::size_of::<#struct_name>();
-                    if struct_size > last_field_end {
-                        fields.push(::type_layout::Field::Padding {
-                            size: struct_size - last_field_end,
-                        });
+    Ok(assertions)
+}
+
+/// Generates the names of the type and const parameters that a generic type
+/// was instantiated with.
+fn generic_names(generics: &Generics, krate: &Path) -> Vec<proc_macro2::TokenStream> {
+    generics
+        .params
+        .iter()
+        .filter_map(|param| match param {
+            GenericParam::Type(param) => {
+                let ident = &param.ident;
+                Some(quote! {
+                    #krate::__private::Cow::Borrowed(::core::any::type_name::<#ident>())
+                })
+            }
+            GenericParam::Const(param) => {
+                let ident = &param.ident;
+                Some(quote! {
+                    #krate::__private::Cow::Owned(
+                        #krate::__private::format!("{}", #ident),
+                    )
+                })
+            }
+            GenericParam::Lifetime(_) => None,
+        })
+        .collect()
+}
+
+/// The path generated code uses to reach this crate, which is `::type_layout`
+/// unless it's overridden with `#[layout(crate = "...")]`.
+fn crate_path(input: &DeriveInput) -> syn::Result<Path> {
+    Ok(ContainerAttrs::from_attrs(&input.attrs)?
+        .krate
+        .unwrap_or_else(|| parse_quote!(::type_layout)))
+}
+
+/// The fields of a struct or union, or of every variant of an enum.
+fn data_fields(data: &Data) -> impl Iterator<Item = &syn::Field> {
+    let (fields, variants) = match data {
+        Data::Struct(data) => (Some(data.fields.iter()), None),
+        Data::Enum(data) => (None, Some(data.variants.iter())),
+        Data::Union(data) => (Some(data.fields.named.iter()), None),
+    };
+
+    let variant_fields = variants
+        .into_iter()
+        .flatten()
+        .flat_map(|variant| variant.fields.iter());
+
+    fields.into_iter().flatten().chain(variant_fields)
+}
+
+fn field_types(data: &Data) -> impl Iterator<Item = &syn::Type> {
+    data_fields(data).map(|field| &field.ty)
+}
+
+/// Generated code describing the fields or variants of a type.
+struct Layout {
+    /// Statements to run at the start of `type_layout()`.
+    setup: proc_macro2::TokenStream,
+
+    /// Expressions producing `TypeLayoutInfo::fields` and `variants`.
+    fields: proc_macro2::TokenStream,
+    variants: proc_macro2::TokenStream,
+
+    /// Alignments other than those of the fields that count toward the
+    /// type's natural alignment, like that of an enum's tag.
+    alignments: Vec<proc_macro2::TokenStream>,
+
+    /// An expression producing `TypeLayoutInfo::unsized_tail`, for structs
+    /// whose last field is unsized. See `unsized_tail::layout_of_struct`.
+    unsized_tail: Option<proc_macro2::TokenStream>,
+
+    /// An expression producing `TypeLayoutInfo::tag`, for enums.
+    tag: Option<proc_macro2::TokenStream>,
+}
+
+fn layout_of_struct(input: &DeriveInput, data: &DataStruct) -> syn::Result<Layout> {
+    if let Some(tail) = unsized_tail::tail_field(&input.data) {
+        return unsized_tail::layout_of_struct(input, data, tail);
+    }
+
+    let krate = crate_path(input)?;
+    let mut values = Vec::new();
+
+    for (field, member) in data.fields.iter().zip(data.fields.members()) {
+        let offset = quote!(#krate::__offset_of!(Self, #member));
+        values.push(field_value(input, field, &member, offset)?);
+    }
+
+    let table = fields_table(&values, &krate);
+
+    Ok(Layout {
+        setup: proc_macro2::TokenStream::new(),
+        fields: quote! {
+            #krate::__insert_padding(#table, ::core::mem::size_of::<Self>())
+        },
+        variants: quote!(#krate::__private::Vec::new()),
+        alignments: Vec::new(),
+        unsized_tail: None,
+        tag: None,
+    })
+}
+
+/// Every field of a union starts at offset 0.
+fn layout_of_union(input: &DeriveInput, data: &DataUnion) -> syn::Result<Layout> {
+    let krate = crate_path(input)?;
+    let mut values = Vec::new();
+
+    for field in &data.fields.named {
+        let member = Member::Named(field.ident.clone().unwrap());
+        values.push(field_value(input, field, &member, quote!(0usize))?);
+    }
+
+    let table = fields_table(&values, &krate);
+
+    Ok(Layout {
+        setup: proc_macro2::TokenStream::new(),
+        fields: quote! {
+            #krate::__insert_padding(#table, ::core::mem::size_of::<Self>())
+        },
+        variants: quote!(#krate::__private::Vec::new()),
+        alignments: Vec::new(),
+        unsized_tail: None,
+        tag: None,
+    })
+}
+
+/// Generates a `type_layout::__FieldDesc` describing one field, placed at the
+/// given offset. A list of these is turned into fields by `fields_table`.
+fn field_value(
+    input: &DeriveInput,
+    field: &syn::Field,
+    member: &Member,
+    offset: proc_macro2::TokenStream,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let krate = crate_path(input)?;
+    let attrs = FieldAttrs::from_attrs(&field.attrs)?;
+    let (field_name, field_ty_str) = reported_field(field, member)?;
+    let field_name_str = Literal::string(&field_name);
+    let field_ty = &field.ty;
+    let field_ty_str = Literal::string(&field_ty_str);
+    // A hidden or overridden type can't be described as an array.
+    let hide_type = attrs.skip.is_some() || attrs.ty.is_some();
+    let (field_ty_str, array) = match array_of_type(field_ty).filter(|_| !hide_type) {
+        Some(array) => array_info(array, field_ty_str, &krate),
+        None => (
+            quote!(#krate::__private::Cow::Borrowed(#field_ty_str)),
+            quote!(::core::option::Option::None),
+        ),
+    };
+    let type_id = match attrs.skip {
+        Some(_) => quote!(::core::option::Option::None),
+        None => type_id::expand(field_ty, &attrs, &input.generics),
+    };
+    let contended = attrs.contended.is_some();
+    let nested = match attrs.nested {
+        Some(span) => {
+            let nested_ty = nested_type(field_ty, &attrs);
+            quote_spanned! { span =>
+                ::core::option::Option::Some(<#nested_ty as #krate::TypeLayout>::type_layout)
+            }
+        }
+        None => quote!(::core::option::Option::None),
+    };
+    // Fields of a packed type are only as aligned as the packing allows.
+    let mut info = match Repr::from_attrs(&input.attrs)?.packed {
+        Some(packed) => {
+            let packed = Literal::u64_unsuffixed(packed);
+            quote!(#krate::__TypeInfo::of::<#field_ty>().packed(#packed))
+        }
+        None => quote!(#krate::__TypeInfo::of::<#field_ty>()),
+    };
+    if hide_type {
+        info = quote!(#info.opaque());
+    } else if let Some(payload) = option_payload(field_ty) {
+        info = quote!(#info.option_of::<#payload>());
+    }
+
+    Ok(quote_spanned! { field.span() =>
+        #krate::__FieldDesc {
+            name: #field_name_str,
+            ty: #field_ty_str,
+            offset: #offset,
+            info: #info,
+            type_id: #type_id,
+            array: #array,
+            nested: #nested,
+            contended: #contended,
+        }
+    })
+}
+
+/// Generates the fields of a struct, union, or enum variant from the
+/// descriptions made by `field_value`.
+fn fields_table(values: &[proc_macro2::TokenStream], krate: &Path) -> proc_macro2::TokenStream {
+    quote! {
+        #krate::__fields(#krate::__private::vec![#(#values),*])
+    }
+}
+
+/// The name and type a field is reported under, which can be overridden with
+/// `#[layout(rename = "...", ty = "...")]`. Fields marked with
+/// `#[layout(skip)]` are reported as an opaque region, without either.
+fn reported_field(field: &syn::Field, member: &Member) -> syn::Result<(String, String)> {
+    let attrs = FieldAttrs::from_attrs(&field.attrs)?;
+
+    if attrs.skip.is_some() {
+        return Ok((OPAQUE_NAME.to_owned(), String::new()));
+    }
+
+    let name = match &attrs.rename {
+        Some(rename) => rename.value(),
+        None => member_name(member),
+    };
+    let ty = match &attrs.ty {
+        Some(ty) => ty.value(),
+        None => type_string(&field.ty),
+    };
+
+    Ok((name, ty))
+}
+
+/// The name a field is reported under: its identifier without any `r#`
+/// prefix, or its index in a tuple struct.
+fn member_name(member: &Member) -> String {
+    match member {
+        Member::Named(ident) => ident.unraw().to_string(),
+        Member::Unnamed(index) => index.index.to_string(),
+    }
+}
+
+fn array_of_type(ty: &Type) -> Option<&TypeArray> {
+    match ty {
+        Type::Array(array) => Some(array),
+        Type::Paren(paren) => array_of_type(&paren.elem),
+        Type::Group(group) => array_of_type(&group.elem),
+        _ => None,
+    }
+}
+
+/// The type whose layout is shown under a `#[layout(nested)]` field. For
+/// arrays, that's the element type, so the fields of the elements are only
+/// shown once, for the first element.
+fn nested_type<'a>(ty: &'a Type, attrs: &FieldAttrs) -> &'a Type {
+    match array_of_type(ty) {
+        Some(array) if attrs.ty.is_none() => &array.elem,
+        _ => ty,
+    }
+}
+
+/// The `T` in a field of type `Option<T>`, which is checked for a niche.
+fn option_payload(ty: &Type) -> Option<&Type> {
+    match ty {
+        Type::Path(path) if path.qself.is_none() => {
+            let segment = path.path.segments.last()?;
+            if segment.ident != "Option" {
+                return None;
+            }
+
+            match &segment.arguments {
+                PathArguments::AngleBracketed(args) if args.args.len() == 1 => {
+                    match args.args.first()? {
+                        GenericArgument::Type(payload) => Some(payload),
+                        _ => None,
                     }
                 }
+                _ => None,
             }
-            Fields::Unnamed(_) => unimplemented!(),
-            Fields::Unit => unimplemented!(),
-        },
-        Data::Enum(_) | Data::Union(_) => unimplemented!("type-layout only supports structs"),
+        }
+        Type::Paren(paren) => option_payload(&paren.elem),
+        Type::Group(group) => option_payload(&group.elem),
+        _ => None,
     }
 }
+
+/// Generates the type name and `ArrayInfo` of an array field. Lengths that
+/// aren't literals, like const generic parameters, are substituted into the
+/// type name at runtime so that it shows the actual length.
+fn array_info(
+    array: &TypeArray,
+    ty_str: Literal,
+    krate: &Path,
+) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    let elem = &array.elem;
+    let elem_str = Literal::string(&type_string(elem));
+    let len = &array.len;
+
+    let ty_str = match len {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(_), ..
+        }) => quote!(#krate::__private::Cow::Borrowed(#ty_str)),
+        _ => quote! {
+            #krate::__private::Cow::Owned(#krate::__private::format!("[{}; {}]", #elem_str, #len))
+        },
+    };
+
+    let info = quote! {
+        ::core::option::Option::Some(#krate::ArrayInfo {
+            elem_ty: #krate::__private::Cow::Borrowed(#elem_str),
+            len: #len,
+            stride: ::core::mem::size_of::<#elem>(),
+        })
+    };
+
+    (ty_str, info)
+}
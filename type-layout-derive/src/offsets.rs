@@ -0,0 +1,62 @@
+use std::collections::HashMap;
+
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::{spanned::Spanned, Data, DeriveInput, LitStr};
+
+/// Implements `#[layout(offset_consts)]`, which adds an `OFFSET_OF_<FIELD>`
+/// associated constant to the type for each of its fields.
+pub fn expand(input: &DeriveInput, span: Span) -> syn::Result<TokenStream> {
+    let data = match &input.data {
+        Data::Struct(data) => data,
+        Data::Enum(_) | Data::Union(_) => {
+            return Err(syn::Error::new(
+                span,
+                "#[layout(offset_consts)] only supports structs",
+            ))
+        }
+    };
+
+    let krate = crate::crate_path(input)?;
+    let mut seen: HashMap<String, Span> = HashMap::new();
+    let mut consts = Vec::new();
+
+    for (field, member) in data.fields.iter().zip(data.fields.members()) {
+        let field_name = crate::member_name(&member);
+        let const_name = format!("OFFSET_OF_{}", field_name.to_uppercase());
+
+        if let Some(previous) = seen.get(&const_name) {
+            let mut error = syn::Error::new(
+                field.span(),
+                format!(
+                    "#[layout(offset_consts)] would generate `{}` for more than one field",
+                    const_name
+                ),
+            );
+            error.combine(syn::Error::new(*previous, "first generated here"));
+            return Err(error);
+        }
+        seen.insert(const_name.clone(), field.span());
+
+        let const_ident = Ident::new(&const_name, field.span());
+        let doc = LitStr::new(
+            &format!("The offset of `{}` in bytes.", field_name),
+            field.span(),
+        );
+
+        consts.push(quote_spanned! { field.span() =>
+            #[doc = #doc]
+            pub const #const_ident: usize = #krate::__offset_of!(Self, #member);
+        });
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        #[allow(dead_code)]
+        impl #impl_generics #name #ty_generics #where_clause {
+            #(#consts)*
+        }
+    })
+}
@@ -0,0 +1,58 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{quote_spanned, ToTokens};
+use syn::DeriveInput;
+
+/// Implements `#[layout(report)]`, which makes the compiler print the type's
+/// size and alignment as a warning.
+///
+/// There's no stable way for a derive to emit a warning of its own, let alone
+/// one holding a value that's only known after the derive has run. Instead,
+/// this defines an `extern "C"` function taking a `LayoutOf<Type, SIZE,
+/// ALIGN>`, which isn't FFI-safe, and rustc's `improper_ctypes_definitions`
+/// warning names the type with its constants evaluated. The function is
+/// defined inside an unnamed constant rather than declared in an `extern`
+/// block, so it has no symbol name that could clash with another type's.
+pub fn expand(input: &DeriveInput, span: Span) -> syn::Result<TokenStream> {
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            span,
+            "#[layout(report)] is not supported on generic types",
+        ));
+    }
+
+    if crate::unsized_tail::tail_field(&input.data).is_some() {
+        return Err(syn::Error::new(
+            span,
+            "#[layout(report)] is not supported on types with an unsized tail",
+        ));
+    }
+
+    let name = &input.ident;
+
+    // rustc doesn't report `improper_ctypes_definitions` in code from
+    // macros, so every token of the type, including the path to this crate,
+    // has to look like it was written at the attribute.
+    let krate: TokenStream = crate::crate_path(input)?
+        .into_token_stream()
+        .into_iter()
+        .map(|mut token| {
+            token.set_span(span);
+            token
+        })
+        .collect();
+
+    Ok(quote_spanned! { span =>
+        const _: () = {
+            #[allow(dead_code)]
+            #[warn(improper_ctypes_definitions)]
+            extern "C" fn __type_layout_report(
+                _layout: #krate::__private::LayoutOf<
+                    #name,
+                    { ::core::mem::size_of::<#name>() },
+                    { ::core::mem::align_of::<#name>() },
+                >,
+            ) {
+            }
+        };
+    })
+}
@@ -0,0 +1,116 @@
+use proc_macro2::{Literal, TokenStream};
+use quote::quote;
+use syn::{Attribute, Ident, LitInt, Path};
+
+const INTS: &[&str] = &[
+    "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize",
+];
+
+/// The subset of `#[repr(...)]` that affects how a type is laid out.
+#[derive(Debug, Default)]
+pub struct Repr {
+    pub c: bool,
+    pub transparent: bool,
+
+    /// The integer type of an enum's tag, like `u8` in `#[repr(u8)]`.
+    pub int: Option<Ident>,
+
+    pub packed: Option<u64>,
+    pub align: Option<u64>,
+}
+
+impl Repr {
+    pub fn from_attrs(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut repr = Repr::default();
+
+        for attr in attrs {
+            if !attr.path().is_ident("repr") {
+                continue;
+            }
+
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("C") {
+                    repr.c = true;
+                } else if meta.path.is_ident("transparent") {
+                    repr.transparent = true;
+                } else if let Some(int) = meta
+                    .path
+                    .get_ident()
+                    .filter(|ident| INTS.iter().any(|int| *ident == int))
+                {
+                    repr.int = Some(int.clone());
+                } else if meta.path.is_ident("packed") {
+                    repr.packed = Some(parse_optional_int(&meta)?.unwrap_or(1));
+                } else if meta.path.is_ident("align") {
+                    repr.align = parse_optional_int(&meta)?;
+                } else if meta.input.peek(syn::token::Paren) {
+                    // Unknown reprs with arguments are left for rustc to
+                    // diagnose; skip over their contents.
+                    let content;
+                    syn::parenthesized!(content in meta.input);
+                    content.parse::<proc_macro2::TokenStream>()?;
+                }
+
+                Ok(())
+            })?;
+        }
+
+        Ok(repr)
+    }
+
+    /// Whether this representation guarantees the order and offsets of the
+    /// type's fields.
+    pub fn is_guaranteed(&self) -> bool {
+        self.c || self.transparent || self.int.is_some()
+    }
+}
+
+impl Repr {
+    /// Generates the `type_layout::Repr` describing this representation.
+    pub fn expand(&self, krate: &Path) -> TokenStream {
+        let c = self.c;
+        let transparent = self.transparent;
+        let int = match &self.int {
+            Some(int) => {
+                let int = Literal::string(&int.to_string());
+                quote! {
+                    ::core::option::Option::Some(#krate::__private::Cow::Borrowed(#int))
+                }
+            }
+            None => quote!(::core::option::Option::None),
+        };
+        let packed = optional_usize(self.packed);
+        let align = optional_usize(self.align);
+
+        quote! {
+            #krate::Repr {
+                c: #c,
+                transparent: #transparent,
+                int: #int,
+                packed: #packed,
+                align: #align,
+            }
+        }
+    }
+}
+
+fn optional_usize(value: Option<u64>) -> TokenStream {
+    match value {
+        Some(value) => {
+            let value = Literal::u64_unsuffixed(value);
+            quote!(::core::option::Option::Some(#value))
+        }
+        None => quote!(::core::option::Option::None),
+    }
+}
+
+fn parse_optional_int(meta: &syn::meta::ParseNestedMeta) -> syn::Result<Option<u64>> {
+    if !meta.input.peek(syn::token::Paren) {
+        return Ok(None);
+    }
+
+    let content;
+    syn::parenthesized!(content in meta.input);
+    let value: LitInt = content.parse()?;
+    value.base10_parse().map(Some)
+}
@@ -0,0 +1,62 @@
+use proc_macro2::{TokenStream, TokenTree};
+use quote::{quote, ToTokens};
+use syn::{Generics, Type};
+
+use crate::attr::FieldAttrs;
+
+/// Generates the `type_id` of a field. `TypeId` is only available for types
+/// that are `'static`, which the derive can't check directly. Instead, a field
+/// gets a `TypeId` when its type can't name a generic parameter or a lifetime
+/// other than `'static`, or when it asks for one with `#[layout(type_id)]`.
+pub fn expand(ty: &Type, attrs: &FieldAttrs, generics: &Generics) -> TokenStream {
+    if attrs.type_id.is_some() || is_static(ty, generics) {
+        quote!(::core::option::Option::Some(::core::any::TypeId::of::<#ty>()))
+    } else {
+        quote!(::core::option::Option::None)
+    }
+}
+
+fn is_static(ty: &Type, generics: &Generics) -> bool {
+    let params: Vec<String> = generics
+        .type_params()
+        .map(|param| param.ident.to_string())
+        .collect();
+
+    // Macros can expand to anything, including generic parameters.
+    if matches!(ty, Type::Macro(_)) {
+        return false;
+    }
+
+    is_static_tokens(ty.to_token_stream(), &params, !generics.params.is_empty())
+}
+
+fn is_static_tokens(tokens: TokenStream, params: &[String], is_generic: bool) -> bool {
+    let mut tokens = tokens.into_iter().peekable();
+
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Group(group) => {
+                if !is_static_tokens(group.stream(), params, is_generic) {
+                    return false;
+                }
+            }
+            TokenTree::Punct(punct) if punct.as_char() == '\'' => {
+                if let Some(TokenTree::Ident(lifetime)) = tokens.next() {
+                    if lifetime != "static" {
+                        return false;
+                    }
+                }
+            }
+            TokenTree::Ident(ident) => {
+                if (is_generic && ident == "Self")
+                    || params.iter().any(|param| ident == param.as_str())
+                {
+                    return false;
+                }
+            }
+            TokenTree::Punct(_) | TokenTree::Literal(_) => {}
+        }
+    }
+
+    true
+}
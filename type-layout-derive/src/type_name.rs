@@ -0,0 +1,152 @@
+use proc_macro2::{Delimiter, Spacing, TokenStream, TokenTree};
+use quote::ToTokens;
+
+/// Renders a type the way it would usually be written, like `Vec<Box<dyn
+/// Error>>`, instead of the spaced-out `Vec < Box < dyn Error > >` that
+/// `TokenStream`'s `Display` produces.
+pub fn type_string<T: ToTokens>(ty: &T) -> String {
+    let mut printer = Printer {
+        output: String::new(),
+        last: Last::Start,
+    };
+    printer.tokens(ty.to_token_stream());
+    printer.output
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Last {
+    /// Nothing has been written yet, or an opening delimiter was just written.
+    Start,
+
+    /// An identifier, literal, or closing delimiter.
+    Word,
+
+    /// A keyword like `mut` or `dyn`, or a lifetime, which is separated by a
+    /// space from whatever follows it, like `*const [u8]` or `&'a &'b u8`.
+    Keyword,
+
+    /// Punctuation that the next token should follow without a space.
+    Tight,
+
+    /// Punctuation that the next token should be separated from by a space.
+    Spaced,
+}
+
+/// Keywords that can be followed by punctuation or a group in a type, like
+/// `dyn ::core::any::Any` or `<T as ::core::ops::Deref>`. `for` isn't one,
+/// since it hugs the `<` after it.
+const KEYWORDS: &[&str] = &["as", "const", "dyn", "impl", "mut"];
+
+struct Printer {
+    output: String,
+    last: Last,
+}
+
+impl Printer {
+    fn tokens(&mut self, tokens: TokenStream) {
+        let mut tokens = tokens.into_iter().peekable();
+
+        while let Some(token) = tokens.next() {
+            match token {
+                TokenTree::Group(group) => {
+                    let (open, close) = match group.delimiter() {
+                        Delimiter::Parenthesis => ("(", ")"),
+                        Delimiter::Bracket => ("[", "]"),
+                        Delimiter::Brace => ("{ ", " }"),
+                        Delimiter::None => {
+                            self.tokens(group.stream());
+                            continue;
+                        }
+                    };
+
+                    // Calls like `Fn(u8)` hug the name before them, but
+                    // `&mut [u8]` and `*const (u8, u8)` don't.
+                    if matches!(self.last, Last::Spaced | Last::Keyword) {
+                        self.output.push(' ');
+                    }
+
+                    self.output.push_str(open);
+                    self.last = Last::Start;
+                    self.tokens(group.stream());
+                    self.output.push_str(close);
+                    self.last = Last::Word;
+                }
+                TokenTree::Ident(_) | TokenTree::Literal(_) => {
+                    if matches!(self.last, Last::Word | Last::Spaced | Last::Keyword) {
+                        self.output.push(' ');
+                    }
+
+                    let lifetime = self.output.ends_with('\'');
+                    let word = token.to_string();
+                    self.output.push_str(&word);
+                    self.last = if lifetime || KEYWORDS.contains(&word.as_str()) {
+                        Last::Keyword
+                    } else {
+                        Last::Word
+                    };
+                }
+                TokenTree::Punct(punct) => {
+                    let ch = punct.as_char();
+                    let joint = punct.spacing() == Spacing::Joint;
+                    let next = match tokens.peek() {
+                        Some(TokenTree::Punct(next)) => Some(next.as_char()),
+                        _ => None,
+                    };
+
+                    match ch {
+                        ',' | ';' => {
+                            self.output.push(ch);
+                            self.last = Last::Spaced;
+                        }
+
+                        // `->` and `=>`, along with `+` and `=` on their own,
+                        // are surrounded by spaces.
+                        '-' | '=' | '+' => {
+                            let arrow = ch != '+' && joint && next == Some('>');
+
+                            if self.last != Last::Start {
+                                self.output.push(' ');
+                            }
+
+                            self.output.push(ch);
+
+                            if arrow {
+                                self.output.push('>');
+                                tokens.next();
+                            }
+
+                            self.last = Last::Spaced;
+                        }
+
+                        // Lifetimes follow the word before them with a space,
+                        // like `dyn Trait + 'a` or `for<'a> fn(&'a u8)`.
+                        '\'' => {
+                            if matches!(self.last, Last::Word | Last::Spaced | Last::Keyword) {
+                                self.output.push(' ');
+                            }
+
+                            self.output.push(ch);
+                            self.last = Last::Tight;
+                        }
+
+                        // Closing angle brackets act like words, so that
+                        // `for<'a> fn()` keeps its space.
+                        '>' => {
+                            self.output.push(ch);
+                            self.last = Last::Word;
+                        }
+
+                        _ => {
+                            if matches!(self.last, Last::Spaced | Last::Keyword) {
+                                self.output.push(' ');
+                            }
+
+                            self.output.push(ch);
+                            self.last = Last::Tight;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
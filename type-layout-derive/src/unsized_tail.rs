@@ -0,0 +1,241 @@
+use proc_macro2::{Literal, Span};
+use quote::quote;
+use syn::{spanned::Spanned, Data, DataStruct, DeriveInput, Type};
+
+use crate::attr::{ContainerAttrs, FieldAttrs};
+use crate::repr::Repr;
+use crate::Layout;
+
+/// A dynamically sized type that a struct can end with.
+pub enum Tail<'a> {
+    /// `[T]`, holding the element type.
+    Slice(&'a Type),
+    Str,
+    Dyn,
+}
+
+impl<'a> Tail<'a> {
+    pub fn of_type(ty: &'a Type) -> Option<Self> {
+        match ty {
+            Type::Slice(slice) => Some(Tail::Slice(&slice.elem)),
+            Type::Path(path) if path.qself.is_none() && path.path.is_ident("str") => {
+                Some(Tail::Str)
+            }
+            Type::TraitObject(_) => Some(Tail::Dyn),
+            Type::Paren(paren) => Self::of_type(&paren.elem),
+            Type::Group(group) => Self::of_type(&group.elem),
+            _ => None,
+        }
+    }
+}
+
+/// The last field of a struct, if its type is dynamically sized.
+pub fn tail_field(data: &Data) -> Option<&syn::Field> {
+    match data {
+        Data::Struct(data) => data
+            .fields
+            .iter()
+            .last()
+            .filter(|field| Tail::of_type(&field.ty).is_some()),
+        Data::Enum(_) | Data::Union(_) => None,
+    }
+}
+
+/// Rejects options that need the size of the type, which structs with an
+/// unsized tail don't have.
+pub fn check(input: &DeriveInput) -> syn::Result<()> {
+    let tail = match tail_field(&input.data) {
+        Some(tail) => tail,
+        None => return Ok(()),
+    };
+
+    let container = ContainerAttrs::from_attrs(&input.attrs)?;
+    let spans = [
+        container.max_padding.as_ref().map(Spanned::span),
+        container.max_size.as_ref().map(Spanned::span),
+        container.size.as_ref().map(Spanned::span),
+        container.align.as_ref().map(Spanned::span),
+        container.embed,
+        container.offset_consts,
+        container.const_layout,
+    ];
+
+    if let Some(span) = spans.iter().flatten().next() {
+        return Err(syn::Error::new(
+            *span,
+            "this option isn't supported on structs with an unsized field",
+        ));
+    }
+
+    for field in crate::data_fields(&input.data) {
+        if let Some(offset) = FieldAttrs::from_attrs(&field.attrs)?.offset {
+            return Err(syn::Error::new(
+                offset.span(),
+                "#[layout(offset)] isn't supported on structs with an unsized field",
+            ));
+        }
+    }
+
+    let attrs = FieldAttrs::from_attrs(&tail.attrs)?;
+    if let Some(span) = attrs
+        .type_id
+        .or(attrs.nested)
+        .or(attrs.contended)
+        .or(attrs.skip)
+    {
+        return Err(syn::Error::new(
+            span,
+            "#[layout(...)] isn't supported on unsized fields",
+        ));
+    }
+
+    if let Some(Tail::Dyn) = Tail::of_type(&tail.ty) {
+        if !Repr::from_attrs(&input.attrs)?.c {
+            return Err(syn::Error::new(
+                tail.ty.span(),
+                "type-layout requires #[repr(C)] on structs ending in a trait object, \
+                 since the offsets of their fields can't be found otherwise",
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Lays out a struct whose last field is unsized. Offsets are found through a
+/// pointer to the struct with an empty tail, except for trait objects, whose
+/// metadata can't be made up; those structs must be `#[repr(C)]`, so their
+/// offsets are computed instead.
+///
+/// The setup defines `__tail_offset` and `__tail_alignment`, which the
+/// generated `type_layout()` uses for the type's size and alignment.
+pub fn layout_of_struct(
+    input: &DeriveInput,
+    data: &DataStruct,
+    tail: &syn::Field,
+) -> syn::Result<Layout> {
+    let krate = crate::crate_path(input)?;
+    let repr = Repr::from_attrs(&input.attrs)?;
+    let kind = Tail::of_type(&tail.ty).unwrap();
+    let members: Vec<_> = data.fields.members().collect();
+    let (tail_member, prefix_members) = members.split_last().unwrap();
+    let prefix: Vec<_> = data.fields.iter().zip(prefix_members).collect();
+
+    let elem_alignment = match kind {
+        Tail::Slice(elem) => Some(quote!(::core::mem::align_of::<#elem>())),
+        Tail::Str => Some(quote!(1usize)),
+        Tail::Dyn => None,
+    };
+    let stride = match kind {
+        Tail::Slice(elem) => {
+            quote!(::core::option::Option::Some(::core::mem::size_of::<#elem>()))
+        }
+        Tail::Str => quote!(::core::option::Option::Some(1usize)),
+        Tail::Dyn => quote!(::core::option::Option::None),
+    };
+
+    let alignments = prefix
+        .iter()
+        .map(|(field, _)| {
+            let ty = &field.ty;
+            quote!(::core::mem::align_of::<#ty>())
+        })
+        .chain(elem_alignment.clone())
+        .map(|alignment| quote!(let alignment = ::core::cmp::Ord::max(alignment, #alignment);));
+    let adjust_alignment = match (repr.packed, repr.align) {
+        (Some(packed), _) => {
+            let packed = Literal::u64_unsuffixed(packed);
+            quote!(::core::cmp::Ord::min(alignment, #packed))
+        }
+        (None, Some(align)) => {
+            let align = Literal::u64_unsuffixed(align);
+            quote!(::core::cmp::Ord::max(alignment, #align))
+        }
+        (None, None) => quote!(alignment),
+    };
+
+    let mut setup = quote! {
+        let __tail_alignment: usize = {
+            let alignment = 1usize;
+            #(#alignments)*
+            #adjust_alignment
+        };
+    };
+    let mut offsets = Vec::new();
+
+    match kind {
+        Tail::Slice(_) | Tail::Str => {
+            let sizes = prefix.iter().map(|(field, _)| {
+                let ty = &field.ty;
+                quote!(+ ::core::mem::size_of::<#ty>() + ::core::mem::align_of::<#ty>())
+            });
+
+            setup.extend(quote! {
+                let __base = #krate::__UnsizedBase::new(
+                    0usize #(#sizes)* + __tail_alignment,
+                    __tail_alignment,
+                );
+                let __ptr = __base.empty_tail() as *const Self;
+                let __tail_offset = #krate::__unsized_offset_of!(__base, __ptr, #tail_member);
+            });
+
+            for (_, member) in &prefix {
+                offsets.push(quote!(
+                    #krate::__unsized_offset_of!(__base, __ptr, #member)
+                ));
+            }
+        }
+        Tail::Dyn => {
+            setup.extend(quote!(let __tail_offset = 0usize;));
+
+            for (index, (field, _)) in prefix.iter().enumerate() {
+                let ty = &field.ty;
+                let offset = syn::Ident::new(&format!("__offset_{}", index), Span::call_site());
+                let alignment = match repr.packed {
+                    Some(packed) => {
+                        let packed = Literal::u64_unsuffixed(packed);
+                        quote!(::core::cmp::Ord::min(::core::mem::align_of::<#ty>(), #packed))
+                    }
+                    None => quote!(::core::mem::align_of::<#ty>()),
+                };
+
+                setup.extend(quote! {
+                    let #offset = {
+                        let alignment = #alignment;
+                        (__tail_offset + alignment - 1) & !(alignment - 1)
+                    };
+                    let __tail_offset = #offset + ::core::mem::size_of::<#ty>();
+                });
+                offsets.push(quote!(#offset));
+            }
+        }
+    }
+
+    let mut values = Vec::new();
+    for ((field, member), offset) in prefix.iter().zip(offsets) {
+        values.push(crate::field_value(input, field, member, offset)?);
+    }
+
+    let table = crate::fields_table(&values, &krate);
+    let (name, ty) = crate::reported_field(tail, tail_member)?;
+    let name_str = Literal::string(&name);
+    let ty_str = Literal::string(&ty);
+
+    Ok(Layout {
+        setup,
+        fields: quote! {
+            #krate::__unsized_fields(#table, __tail_offset)
+        },
+        variants: quote!(#krate::__private::Vec::new()),
+        alignments: elem_alignment.into_iter().collect(),
+        unsized_tail: Some(quote! {
+            #krate::UnsizedTail {
+                name: #krate::__private::Cow::Borrowed(#name_str),
+                ty: #krate::__private::Cow::Borrowed(#ty_str),
+                offset: __tail_offset,
+                stride: #stride,
+            }
+        }),
+        tag: None,
+    })
+}
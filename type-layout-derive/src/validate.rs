@@ -0,0 +1,60 @@
+use syn::{Data, Type};
+
+/// Rejects fields whose types the derive can't lay out. Without this, rustc
+/// reports these problems from inside the generated code, which reads like a
+/// bug in type-layout rather than in the type being derived.
+pub fn check_fields(data: &Data) -> syn::Result<()> {
+    let mut errors: Option<syn::Error> = None;
+
+    // The last field of a struct can be unsized; see `unsized_tail`.
+    let tail = crate::unsized_tail::tail_field(data);
+
+    for field in crate::data_fields(data) {
+        if tail.map_or(false, |tail| std::ptr::eq(field, tail)) {
+            continue;
+        }
+
+        let ty = &field.ty;
+        if let Some(message) = unsupported_reason(ty) {
+            let error = syn::Error::new_spanned(ty, message);
+
+            match &mut errors {
+                Some(errors) => errors.combine(error),
+                None => errors = Some(error),
+            }
+        }
+    }
+
+    match errors {
+        Some(errors) => Err(errors),
+        None => Ok(()),
+    }
+}
+
+fn unsupported_reason(ty: &Type) -> Option<&'static str> {
+    match ty {
+        Type::Slice(_) => Some(
+            "type-layout requires field types to have a size known at compile time; \
+             put this slice behind a pointer, like `Box<[T]>`",
+        ),
+        Type::Path(path) if path.qself.is_none() && path.path.is_ident("str") => Some(
+            "type-layout requires field types to have a size known at compile time; \
+             put this `str` behind a pointer, like `Box<str>`",
+        ),
+        Type::TraitObject(_) => Some(
+            "type-layout requires field types to have a size known at compile time; \
+             put this trait object behind a pointer, like `Box<dyn Trait>`",
+        ),
+        Type::ImplTrait(_) => Some("`impl Trait` can't be used as the type of a field"),
+        Type::Infer(_) => Some("`_` can't be used as the type of a field; write out the type"),
+        Type::Paren(paren) => unsupported_reason(&paren.elem),
+        Type::Group(group) => unsupported_reason(&group.elem),
+        // Macros are expanded after the derive runs, so what they expand to
+        // can't be checked here. That's fine: the generated code names the
+        // macro, which expands to a sized type that lays out like any other,
+        // and a macro that expands to an unsized type is already rejected by
+        // rustc's own check of the struct, pointing at the field.
+        Type::Macro(_) => None,
+        _ => None,
+    }
+}
@@ -0,0 +1,10 @@
+use type_layout::TypeLayout;
+
+#[derive(TypeLayout)]
+struct Message {
+    name: str,
+    body: [u8],
+    len: usize,
+}
+
+fn main() {}
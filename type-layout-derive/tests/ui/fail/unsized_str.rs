@@ -0,0 +1,9 @@
+use type_layout::TypeLayout;
+
+#[derive(TypeLayout)]
+struct Message {
+    body: str,
+    len: usize,
+}
+
+fn main() {}
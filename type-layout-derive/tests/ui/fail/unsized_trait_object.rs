@@ -0,0 +1,11 @@
+use std::fmt::Debug;
+
+use type_layout::TypeLayout;
+
+#[derive(TypeLayout)]
+struct Message {
+    body: dyn Debug,
+    len: usize,
+}
+
+fn main() {}
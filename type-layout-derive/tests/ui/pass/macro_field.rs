@@ -0,0 +1,23 @@
+use type_layout::TypeLayout;
+
+macro_rules! id_type {
+    () => {
+        u32
+    };
+}
+
+#[derive(TypeLayout)]
+#[repr(C)]
+struct Message {
+    kind: u8,
+    id: id_type!(),
+}
+
+fn main() {
+    let layout = Message::type_layout();
+    assert_eq!(layout.size, 8);
+
+    let id = layout.field("id").unwrap();
+    assert_eq!(id.offset(), 4);
+    assert_eq!(id.size(), 4);
+}
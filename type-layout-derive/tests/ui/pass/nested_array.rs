@@ -0,0 +1,58 @@
+// `#[layout(nested)]` on an array shows the fields of its first element.
+use type_layout::{Field, TypeLayout};
+
+#[derive(TypeLayout)]
+#[repr(C)]
+struct Sample {
+    time: u32,
+    value: f64,
+}
+
+#[derive(TypeLayout)]
+#[repr(C)]
+struct Recording {
+    id: u8,
+    #[layout(nested)]
+    samples: [Sample; 16],
+}
+
+fn main() {
+    let layout = Recording::type_layout();
+
+    let samples = layout.field("samples").unwrap();
+    match samples {
+        Field::Field {
+            array: Some(array),
+            nested: Some(nested),
+            ..
+        } => {
+            assert_eq!((array.len, array.stride), (16, 16));
+            assert_eq!(nested.name, "Sample");
+            assert_eq!(nested.field("value").unwrap().offset(), 8);
+        }
+        _ => panic!("`samples` should be a nested array: {:?}", samples),
+    }
+
+    // The rows after `samples` are the fields of `samples[0]`. The cells are
+    // compared without their padding, since the width of the table depends
+    // on the crate name in the type of `samples`.
+    let table = layout.to_string();
+    let rows: Vec<Vec<&str>> = table
+        .lines()
+        .skip(6)
+        .take(3)
+        .map(|line| line.split('|').map(str::trim).collect())
+        .collect();
+    assert_eq!(
+        rows,
+        [
+            ["", "8", "time", "4", ""],
+            ["", "12", "[padding]", "4", ""],
+            ["", "16", "value", "8", ""],
+        ]
+    );
+    assert!(table.lines().nth(5).unwrap().contains("| samples: ["));
+
+    let csv = layout.to_csv();
+    assert!(csv.contains("Recording,samples[0].value,f64,16,8,field\n"));
+}
@@ -0,0 +1,39 @@
+// Types with the same name in different modules, even with identical
+// definitions, each get their own report without clashing.
+#![deny(clashing_extern_declarations)]
+
+mod a {
+    use type_layout::TypeLayout;
+
+    #[derive(TypeLayout)]
+    #[layout(report)]
+    #[repr(C)]
+    pub struct Packet {
+        kind: u8,
+    }
+}
+
+mod b {
+    use type_layout::TypeLayout;
+
+    #[derive(TypeLayout)]
+    #[layout(report)]
+    #[repr(C)]
+    pub struct Packet {
+        kind: u8,
+        len: u32,
+    }
+}
+
+mod c {
+    use type_layout::TypeLayout;
+
+    #[derive(TypeLayout)]
+    #[layout(report)]
+    #[repr(C)]
+    pub struct Packet {
+        kind: u8,
+    }
+}
+
+fn main() {}